@@ -0,0 +1,724 @@
+//! Shared definitions for the Gorbagana (`gorbagana_bridge`) and Solana
+//! (`solana_bridge`) bridge programs.
+//!
+//! Only what is genuinely identical on both sides lives here: the error
+//! enum, order limits, PDA seeds, and validation helpers. `#[account]`
+//! types and events stay in each program because their layouts differ.
+
+use anchor_lang::prelude::*;
+
+// ── Constants ────────────────────────────────────────────────────────
+/// Minimum order size in token base units (or lamports), independent of
+/// the mint's decimals.
+pub const MIN_ORDER_AMOUNT: u64 = 100_000;
+
+/// Maximum order lifetime in slots (~400ms/slot → ~24 hours)
+pub const MAX_EXPIRY_SLOTS: u64 = 216_000;
+
+/// Maximum order lifetime for timestamp expiries: 24 hours, the wall-clock
+/// equivalent of `MAX_EXPIRY_SLOTS`.
+pub const MAX_EXPIRY_SECONDS: u64 = 86_400;
+
+/// Seed prefix for order PDAs. The rest of the seeds differ per program:
+/// `[ORDER_SEED, maker, seed_amount_le]` on Gorbagana,
+/// `[ORDER_SEED, maker, order_id_le]` on Solana.
+pub const ORDER_SEED: &[u8] = b"order";
+
+/// Seed prefix for escrow token accounts. The rest of the seeds differ per
+/// program: `[ESCROW_SEED, maker, seed_amount_le]` on Gorbagana,
+/// `[ESCROW_SEED, maker, order_id_le, mint]` on Solana.
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// Size of an order memo: one length byte followed by up to 63 bytes of UTF-8
+pub const MEMO_LEN: usize = 64;
+
+/// Size of an HTLC hashlock (a sha256 digest) and of its preimage
+pub const HASHLOCK_LEN: usize = 32;
+
+// ═══════════════════════════════════════════════════════════════════════
+// PDA DERIVATION
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Gorbagana order PDA for `maker` and the amount the order was created
+/// with.
+pub fn find_order_address(program_id: &Pubkey, maker: &Pubkey, seed_amount: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ORDER_SEED, maker.as_ref(), &seed_amount.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Gorbagana escrow token account PDA paired with `find_order_address`.
+pub fn find_escrow_address(program_id: &Pubkey, maker: &Pubkey, seed_amount: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ESCROW_SEED, maker.as_ref(), &seed_amount.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Solana order PDA for `maker` and its maker-chosen `order_id`.
+pub fn find_solana_order_address(
+    program_id: &Pubkey,
+    maker: &Pubkey,
+    order_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ORDER_SEED, maker.as_ref(), &order_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Solana escrow token account PDA paired with `find_solana_order_address`.
+/// Orders created before the mint joined the seeds store their escrow
+/// instead (see `Order::escrow_token_account` in `solana_bridge`).
+pub fn find_solana_escrow_address(
+    program_id: &Pubkey,
+    maker: &Pubkey,
+    order_id: u64,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ESCROW_SEED,
+            maker.as_ref(),
+            &order_id.to_le_bytes(),
+            mint.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// VALIDATION
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Rejects orders below `MIN_ORDER_AMOUNT`.
+pub fn validate_amount(amount: u64) -> Result<()> {
+    validate_amount_at_least(amount, MIN_ORDER_AMOUNT)
+}
+
+/// Rejects orders below `min_order_amount`, for programs whose minimum is
+/// configurable.
+pub fn validate_amount_at_least(amount: u64, min_order_amount: u64) -> Result<()> {
+    require!(amount >= min_order_amount, BridgeError::InvalidAmount);
+    Ok(())
+}
+
+/// Requires `expiration_slot` to be after `current_slot` and no more than
+/// `MAX_EXPIRY_SLOTS` ahead of it.
+pub fn validate_expiration(current_slot: u64, expiration_slot: u64) -> Result<()> {
+    validate_expiration_within(current_slot, expiration_slot, MAX_EXPIRY_SLOTS)
+}
+
+/// Like `validate_expiration`, with the maximum lifetime supplied by the
+/// caller.
+pub fn validate_expiration_within(
+    current_slot: u64,
+    expiration_slot: u64,
+    max_expiry_slots: u64,
+) -> Result<()> {
+    require!(
+        expiration_slot > current_slot,
+        BridgeError::ExpirationInPast
+    );
+    require!(
+        expiration_slot <= current_slot.saturating_add(max_expiry_slots),
+        BridgeError::ExpirationTooFar
+    );
+    Ok(())
+}
+
+/// Timestamp counterpart of `validate_expiration_within`: requires
+/// `expiration_ts` to be after `current_ts` and no more than
+/// `max_expiry_seconds` ahead of it.
+pub fn validate_expiration_timestamp_within(
+    current_ts: i64,
+    expiration_ts: i64,
+    max_expiry_seconds: u64,
+) -> Result<()> {
+    require!(expiration_ts > current_ts, BridgeError::ExpirationInPast);
+    let max = i64::try_from(max_expiry_seconds).unwrap_or(i64::MAX);
+    require!(
+        expiration_ts <= current_ts.saturating_add(max),
+        BridgeError::ExpirationTooFar
+    );
+    Ok(())
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// PRICING
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Expected gGOR at `slot` for a Dutch-auction order whose price moves
+/// linearly from `start_expected` at `start_slot` to `end_expected` at
+/// `end_slot`, clamped to the endpoints outside that range. Rounds toward
+/// `start_expected`, so a decaying price never undercuts the line.
+///
+/// Pure, so the programs and any off-chain quote agree on the price for
+/// a given slot.
+pub fn dutch_expected_at(
+    start_expected: u64,
+    end_expected: u64,
+    start_slot: u64,
+    end_slot: u64,
+    slot: u64,
+) -> u64 {
+    if slot >= end_slot {
+        return end_expected;
+    }
+    if slot <= start_slot {
+        return start_expected;
+    }
+    let elapsed = (slot - start_slot) as u128;
+    let duration = (end_slot - start_slot) as u128;
+    // |start - end| * elapsed < 2^128, and the step never exceeds the spread
+    if start_expected >= end_expected {
+        let step = (start_expected - end_expected) as u128 * elapsed / duration;
+        start_expected - step as u64
+    } else {
+        let step = (end_expected - start_expected) as u128 * elapsed / duration;
+        start_expected + step as u64
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// MEMOS
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Packs a UTF-8 string into the on-chain memo layout:
+/// `[len, bytes[..len], 0, 0, ...]`. Fails if it exceeds `MEMO_LEN - 1` bytes.
+pub fn pack_memo(text: &str) -> Result<[u8; MEMO_LEN]> {
+    let bytes = text.as_bytes();
+    require!(bytes.len() < MEMO_LEN, BridgeError::InvalidMemo);
+
+    let mut memo = [0u8; MEMO_LEN];
+    memo[0] = bytes.len() as u8;
+    memo[1..=bytes.len()].copy_from_slice(bytes);
+    Ok(memo)
+}
+
+/// Returns the text of a packed memo, or `None` if it is malformed or not
+/// valid UTF-8. An all-zero memo unpacks to `""`.
+pub fn unpack_memo(memo: &[u8; MEMO_LEN]) -> Option<&str> {
+    let len = memo[0] as usize;
+    if len >= MEMO_LEN {
+        return None;
+    }
+    core::str::from_utf8(&memo[1..=len]).ok()
+}
+
+/// Checks the memo layout only: the length fits and every byte past it is
+/// zero. The content is opaque to the programs and never inspected.
+pub fn validate_memo(memo: &[u8; MEMO_LEN]) -> Result<()> {
+    let len = memo[0] as usize;
+    require!(len < MEMO_LEN, BridgeError::InvalidMemo);
+    require!(
+        memo[len + 1..].iter().all(|b| *b == 0),
+        BridgeError::InvalidMemo
+    );
+    Ok(())
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// HASHLOCKS
+// ═══════════════════════════════════════════════════════════════════════
+
+/// sha256 of `preimage`, the hashlock a maker commits to for an HTLC order.
+pub fn hashlock_of(preimage: &[u8; HASHLOCK_LEN]) -> [u8; HASHLOCK_LEN] {
+    anchor_lang::solana_program::hash::hash(preimage).to_bytes()
+}
+
+/// Requires `preimage` to hash to `hashlock`. Both programs release an HTLC
+/// escrow only through this check, so one revealed preimage opens both legs.
+pub fn verify_preimage(
+    hashlock: &[u8; HASHLOCK_LEN],
+    preimage: &[u8; HASHLOCK_LEN],
+) -> Result<()> {
+    require!(hashlock_of(preimage) == *hashlock, BridgeError::InvalidPreimage);
+    Ok(())
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// ERRORS
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Error codes for both bridge programs. Append new variants at the end so
+/// existing codes stay stable for clients.
+///
+/// The order is the Gorbagana program's original one. The Solana program's
+/// first seven codes moved when it adopted this enum (e.g. `InvalidMint`
+/// 6001 → 6002, `ExpirationTooFar` 6006 → 6008); see the bridge-solana
+/// README for the full mapping.
+#[error_code]
+pub enum BridgeError {
+    #[msg("Amount must be >= minimum order size.")]
+    InvalidAmount,
+
+    #[msg("Invalid direction. Must be 0 (sGOR→gGOR) or 1 (gGOR→sGOR).")]
+    InvalidDirection,
+
+    #[msg("Invalid token mint. Must be sGOR.")]
+    InvalidMint,
+
+    #[msg("Order has expired (past expiration_slot + fill grace window).")]
+    OrderExpired,
+
+    #[msg("Order has already been filled.")]
+    OrderAlreadyFilled,
+
+    #[msg("Insufficient sGOR balance in the source token account.")]
+    InsufficientFunds,
+
+    #[msg("Unauthorized — only the maker can perform this action.")]
+    Unauthorized,
+
+    #[msg("Expiration slot is in the past.")]
+    ExpirationInPast,
+
+    #[msg("Expiration too far in the future (max ~24 hours).")]
+    ExpirationTooFar,
+
+    #[msg("Missing escrow token account (required for SPL direction).")]
+    MissingEscrowTokenAccount,
+
+    #[msg("Missing maker token account (required for SPL direction).")]
+    MissingMakerTokenAccount,
+
+    #[msg("Missing taker token account.")]
+    MissingTakerTokenAccount,
+
+    #[msg("Missing taker receive token account.")]
+    MissingTakerReceiveTokenAccount,
+
+    #[msg("Missing maker receive token account.")]
+    MissingMakerReceiveTokenAccount,
+
+    #[msg("Maker account must be a system-owned wallet.")]
+    MakerNotSystemAccount,
+
+    #[msg("Order is not in a valid state for this action.")]
+    InvalidOrderState,
+
+    #[msg("Remaining accounts must be non-empty groups of 4 per order.")]
+    InvalidRemainingAccounts,
+
+    #[msg("max_total is too low to fill the first order.")]
+    FillLimitTooLow,
+
+    #[msg("Arithmetic overflow.")]
+    Overflow,
+
+    #[msg("Order is in its post-expiry fill grace window; retry after it ends.")]
+    OrderInGracePeriod,
+
+    #[msg("Fill grace window exceeds the maximum allowed.")]
+    GracePeriodTooLong,
+
+    #[msg("Unauthorized — only the bridge admin can perform this action.")]
+    UnauthorizedAdmin,
+
+    #[msg("Refund token account must be owned by the maker.")]
+    InvalidRefundAccount,
+
+    #[msg("Escrow token account is not controlled by this order.")]
+    InvalidEscrowAccount,
+
+    #[msg("Source and destination token accounts must both hold sGOR.")]
+    TokenMintMismatch,
+
+    #[msg("Source token account is frozen.")]
+    SourceAccountFrozen,
+
+    #[msg("Destination token account is frozen.")]
+    DestinationAccountFrozen,
+
+    #[msg("Memo must be a length byte followed by at most 63 bytes, zero-padded.")]
+    InvalidMemo,
+
+    #[msg("Maker wallet would stay below rent-exemption after receiving this payment.")]
+    MakerBelowRentExempt,
+
+    #[msg("Order is not fillable until its activation slot.")]
+    OrderNotActive,
+
+    #[msg("Activation slot must be before the expiration slot.")]
+    InvalidActivationSlot,
+
+    #[msg("Orders fill in full; fill_amount must equal the order amount.")]
+    PartialFillNotSupported,
+
+    #[msg("Signer is not a registered relayer.")]
+    UnauthorizedRelayer,
+
+    #[msg("Relayer is already registered.")]
+    RelayerAlreadyRegistered,
+
+    #[msg("Relayer is not registered.")]
+    RelayerNotRegistered,
+
+    #[msg("Relayer registry is full.")]
+    RelayerRegistryFull,
+
+    #[msg("Account is not a legacy (pre-order_id) order.")]
+    NotLegacyOrder,
+
+    #[msg("Order is locked by a taker.")]
+    OrderLocked,
+
+    #[msg("Lock duration must be between 1 and the maximum lock slots.")]
+    InvalidLockDuration,
+
+    #[msg("Order lock has lapsed.")]
+    LockExpired,
+
+    #[msg("Order lock has not lapsed yet.")]
+    LockNotExpired,
+
+    #[msg("Gorbagana transaction signature must not be all zeros.")]
+    InvalidTxSignature,
+
+    #[msg("Gorbagana address must not be the default pubkey.")]
+    InvalidGorbaganaAddress,
+
+    #[msg("Fee exceeds the maximum allowed basis points.")]
+    FeeTooHigh,
+
+    #[msg("Fee token account does not belong to the configured fee recipient.")]
+    InvalidFeeRecipient,
+
+    #[msg("Config values must be non-zero.")]
+    InvalidConfig,
+
+    #[msg("Bridge is paused.")]
+    BridgePaused,
+
+    #[msg("Order has not expired yet.")]
+    OrderNotExpired,
+
+    #[msg("Order is reserved for a different taker.")]
+    TakerNotAllowed,
+
+    #[msg("Order cannot be cancelled this soon after creation.")]
+    CancelTooEarly,
+
+    #[msg("Escrow received less than the order amount; fee-bearing mints are not supported.")]
+    TransferFeeNotSupported,
+
+    #[msg("Taker token account is not owned by the taker.")]
+    InvalidTakerTokenAccount,
+
+    #[msg("Maker already has the maximum number of open orders.")]
+    TooManyOpenOrders,
+
+    #[msg("Batch must hold between one and the maximum number of orders.")]
+    BatchTooLarge,
+
+    #[msg("Batch accounts do not match the expected order and escrow PDAs.")]
+    InvalidBatchAccounts,
+
+    #[msg("Address is on the bridge denylist.")]
+    AddressDenied,
+
+    #[msg("Order account version is not supported; migrate it first.")]
+    UnsupportedOrderVersion,
+
+    #[msg("Order no longer matches the amount or recipient the taker quoted.")]
+    OrderMismatch,
+
+    #[msg("Taker token account does not name the relayer as delegate.")]
+    DelegateNotApproved,
+
+    #[msg("Missing or invalid ed25519 signature from the taker.")]
+    InvalidTakerSignature,
+
+    #[msg("Signed nonce does not match the taker's next nonce.")]
+    InvalidNonce,
+
+    #[msg("Hash-locked orders must name their taker.")]
+    HtlcRequiresTaker,
+
+    #[msg("Hash-locked orders are filled with claim_htlc_order.")]
+    HtlcClaimRequired,
+
+    #[msg("Order has no hashlock.")]
+    NotHashLocked,
+
+    #[msg("Preimage does not hash to the order's hashlock.")]
+    InvalidPreimage,
+
+    #[msg("Hash-locked order cannot be refunded before it expires.")]
+    HtlcNotExpired,
+
+    #[msg("Hash-locked order terms cannot be changed.")]
+    HtlcTermsFixed,
+
+    // Reserved for partial fills, which neither program supports yet
+    #[msg("Fill would leave a remainder below the order's minimum fill.")]
+    FillLeavesDust,
+
+    #[msg("Order cannot be filled or locked this soon after creation.")]
+    FillTooEarly,
+
+    #[msg("Makers cannot fill their own orders.")]
+    SelfFillNotAllowed,
+
+    #[msg("Payout splits must be at most four non-zero shares summing to 10000 bps.")]
+    InvalidPayoutSplits,
+
+    #[msg("Payout token accounts do not match the order's payout splits.")]
+    InvalidPayoutAccounts,
+
+    #[msg("Orders with payout splits are released by fill_order or settle_order.")]
+    PayoutSplitsUnsupported,
+
+    #[msg("Too many orders created by this maker in the current window.")]
+    RateLimited,
+
+    #[msg("The settlement's challenge window has not passed yet.")]
+    ChallengeWindowOpen,
+
+    #[msg("The settlement's challenge window has already passed.")]
+    ChallengeWindowClosed,
+
+    #[msg("This mint is not on the bridge's allowlist.")]
+    MintNotAllowed,
+
+    #[msg("The escrow holds less than the order amount.")]
+    EscrowBalanceMismatch,
+
+    #[msg("The order has not been expired for the full quarantine period.")]
+    QuarantineNotElapsed,
+
+    #[msg("The fill's deadline slot has passed.")]
+    FillDeadlineExceeded,
+
+    #[msg("Order is not open.")]
+    OrderNotOpen,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(err: anchor_lang::error::Error) -> u32 {
+        match err {
+            anchor_lang::error::Error::AnchorError(e) => e.error_code_number,
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    fn expected(err: BridgeError) -> u32 {
+        // Includes ERROR_CODE_OFFSET
+        u32::from(err)
+    }
+
+    #[test]
+    fn error_codes_are_stable() {
+        // Clients map these numbers; reordering the enum breaks them
+        assert_eq!(expected(BridgeError::InvalidAmount), 6000);
+        assert_eq!(expected(BridgeError::InvalidMint), 6002);
+        assert_eq!(expected(BridgeError::OrderExpired), 6003);
+        assert_eq!(expected(BridgeError::OrderAlreadyFilled), 6004);
+        assert_eq!(expected(BridgeError::Unauthorized), 6006);
+        assert_eq!(expected(BridgeError::ExpirationInPast), 6007);
+        assert_eq!(expected(BridgeError::ExpirationTooFar), 6008);
+        assert_eq!(expected(BridgeError::DestinationAccountFrozen), 6026);
+        assert_eq!(expected(BridgeError::FillDeadlineExceeded), 6079);
+    }
+
+    #[test]
+    fn amount_at_minimum_is_accepted() {
+        assert!(validate_amount(MIN_ORDER_AMOUNT).is_ok());
+        assert!(validate_amount(u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn amount_below_minimum_is_rejected() {
+        let err = validate_amount(MIN_ORDER_AMOUNT - 1).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::InvalidAmount));
+    }
+
+    #[test]
+    fn expiration_must_be_in_the_future() {
+        let err = validate_expiration(100, 100).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::ExpirationInPast));
+        assert!(validate_expiration(100, 101).is_ok());
+    }
+
+    #[test]
+    fn expiration_is_capped_at_max_lifetime() {
+        assert!(validate_expiration(100, 100 + MAX_EXPIRY_SLOTS).is_ok());
+        let err = validate_expiration(100, 101 + MAX_EXPIRY_SLOTS).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::ExpirationTooFar));
+    }
+
+    #[test]
+    fn expiration_near_slot_limit_does_not_overflow() {
+        assert!(validate_expiration(u64::MAX - 1, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn configured_limits_override_defaults() {
+        assert!(validate_amount_at_least(500, 500).is_ok());
+        let err = validate_amount_at_least(499, 500).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::InvalidAmount));
+
+        assert!(validate_expiration_within(100, 110, 10).is_ok());
+        let err = validate_expiration_within(100, 111, 10).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::ExpirationTooFar));
+    }
+
+    #[test]
+    fn timestamp_expiration_is_bounded() {
+        let now = 1_700_000_000;
+        let err = validate_expiration_timestamp_within(now, now, MAX_EXPIRY_SECONDS).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::ExpirationInPast));
+        assert!(validate_expiration_timestamp_within(now, now + 1, MAX_EXPIRY_SECONDS).is_ok());
+        assert!(
+            validate_expiration_timestamp_within(now, now + 86_400, MAX_EXPIRY_SECONDS).is_ok()
+        );
+        let err = validate_expiration_timestamp_within(now, now + 86_401, MAX_EXPIRY_SECONDS)
+            .unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::ExpirationTooFar));
+    }
+
+    #[test]
+    fn timestamp_expiration_near_limit_does_not_overflow() {
+        assert!(validate_expiration_timestamp_within(i64::MAX - 1, i64::MAX, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn dutch_price_holds_the_endpoints() {
+        assert_eq!(dutch_expected_at(1_000, 400, 100, 200, 99), 1_000);
+        assert_eq!(dutch_expected_at(1_000, 400, 100, 200, 100), 1_000);
+        assert_eq!(dutch_expected_at(1_000, 400, 100, 200, 200), 400);
+        assert_eq!(dutch_expected_at(1_000, 400, 100, 200, 201), 400);
+    }
+
+    #[test]
+    fn dutch_price_interpolates_and_rounds_toward_start() {
+        assert_eq!(dutch_expected_at(1_000, 400, 100, 200, 150), 700);
+        // 600 * 1 / 3 = 200 exactly
+        assert_eq!(dutch_expected_at(1_000, 400, 0, 3, 1), 800);
+        // 10 * 1 / 3 = 3.33 -> step 3, price stays above the line
+        assert_eq!(dutch_expected_at(10, 0, 0, 3, 1), 7);
+        assert_eq!(dutch_expected_at(0, 10, 0, 3, 1), 3);
+    }
+
+    #[test]
+    fn dutch_price_at_u64_extremes_does_not_overflow() {
+        let mid = dutch_expected_at(u64::MAX, 0, 0, u64::MAX, u64::MAX / 2);
+        assert_eq!(mid, u64::MAX - u64::MAX / 2);
+        assert_eq!(dutch_expected_at(u64::MAX, 1, 0, u64::MAX, u64::MAX - 1), 2);
+    }
+
+    #[test]
+    fn flat_dutch_price_is_constant() {
+        assert_eq!(dutch_expected_at(500, 500, 0, 100, 50), 500);
+    }
+
+    #[test]
+    fn order_and_escrow_addresses_differ() {
+        let program_id = Pubkey::new_unique();
+        let maker = Pubkey::new_unique();
+        let (order, _) = find_order_address(&program_id, &maker, MIN_ORDER_AMOUNT);
+        let (escrow, _) = find_escrow_address(&program_id, &maker, MIN_ORDER_AMOUNT);
+        assert_ne!(order, escrow);
+        assert_eq!(
+            order,
+            find_order_address(&program_id, &maker, MIN_ORDER_AMOUNT).0
+        );
+    }
+
+    #[test]
+    fn solana_escrow_depends_on_the_mint() {
+        let program_id = Pubkey::new_unique();
+        let maker = Pubkey::new_unique();
+        let (order, _) = find_solana_order_address(&program_id, &maker, 7);
+        let (escrow, _) = find_solana_escrow_address(&program_id, &maker, 7, &Pubkey::new_unique());
+        let (other, _) = find_solana_escrow_address(&program_id, &maker, 7, &Pubkey::new_unique());
+        assert_ne!(order, escrow);
+        assert_ne!(escrow, other);
+    }
+
+    #[test]
+    fn memo_round_trips() {
+        let memo = pack_memo("deal-42").unwrap();
+        assert_eq!(memo[0], 7);
+        assert!(validate_memo(&memo).is_ok());
+        assert_eq!(unpack_memo(&memo), Some("deal-42"));
+    }
+
+    #[test]
+    fn empty_memo_is_all_zero() {
+        let memo = pack_memo("").unwrap();
+        assert_eq!(memo, [0u8; MEMO_LEN]);
+        assert!(validate_memo(&memo).is_ok());
+        assert_eq!(unpack_memo(&memo), Some(""));
+    }
+
+    #[test]
+    fn memo_at_capacity_is_accepted() {
+        let text = "x".repeat(MEMO_LEN - 1);
+        let memo = pack_memo(&text).unwrap();
+        assert!(validate_memo(&memo).is_ok());
+        assert_eq!(unpack_memo(&memo), Some(text.as_str()));
+    }
+
+    #[test]
+    fn oversized_memo_is_rejected() {
+        let err = pack_memo(&"x".repeat(MEMO_LEN)).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::InvalidMemo));
+    }
+
+    #[test]
+    fn memo_with_trailing_garbage_is_rejected() {
+        let mut memo = pack_memo("abc").unwrap();
+        memo[10] = 1;
+        let err = validate_memo(&memo).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::InvalidMemo));
+    }
+
+    #[test]
+    fn memo_with_bad_length_is_rejected() {
+        let mut memo = [0u8; MEMO_LEN];
+        memo[0] = MEMO_LEN as u8;
+        assert!(validate_memo(&memo).is_err());
+        assert_eq!(unpack_memo(&memo), None);
+    }
+
+    #[test]
+    fn preimage_matching_hashlock_is_accepted() {
+        let preimage = [7u8; HASHLOCK_LEN];
+        assert!(verify_preimage(&hashlock_of(&preimage), &preimage).is_ok());
+    }
+
+    #[test]
+    fn wrong_preimage_is_rejected() {
+        let hashlock = hashlock_of(&[7u8; HASHLOCK_LEN]);
+        let err = verify_preimage(&hashlock, &[8u8; HASHLOCK_LEN]).unwrap_err();
+        assert_eq!(code(err), expected(BridgeError::InvalidPreimage));
+    }
+
+    #[test]
+    fn hashlock_is_not_the_preimage() {
+        // A maker who passes the secret itself as the hashlock must not be
+        // able to claim with it
+        let preimage = [7u8; HASHLOCK_LEN];
+        assert!(verify_preimage(&preimage, &preimage).is_err());
+    }
+
+    #[test]
+    fn hashlock_is_sha256() {
+        // Off-chain tooling computes hashlocks with plain sha256
+        let hashlock = hashlock_of(&[0u8; HASHLOCK_LEN]);
+        assert_eq!(hashlock[..8], [0x66, 0x68, 0x7a, 0xad, 0xf8, 0x62, 0xbd, 0x77]);
+    }
+
+    #[test]
+    fn existing_error_codes_are_stable() {
+        assert_eq!(expected(BridgeError::InvalidAmount), 6000);
+        assert_eq!(expected(BridgeError::Unauthorized), 6006);
+        assert_eq!(expected(BridgeError::InvalidEscrowAccount), 6023);
+    }
+}
@@ -0,0 +1,199 @@
+//! Minimal CPI client of `solana_bridge`. It makes and cancels bridge
+//! orders with one of its own PDAs as the maker, so the integration tests
+//! can check that the bridge works for program-owned makers. Only deployed
+//! to test validators.
+
+use anchor_lang::prelude::*;
+use solana_bridge::cpi::accounts::{CancelOrder, CreateOrder};
+use solana_bridge::program::SolanaBridge;
+use solana_bridge::{CreateOrderParams, Expiry};
+
+declare_id!("DvdC7WA6YT2ntXKBKWq8E8etJw1nbGby9djdJQJpJQV7");
+
+/// Seed of the PDA that makes orders on the program's behalf
+pub const MAKER_SEED: &[u8] = b"maker";
+
+#[program]
+pub mod order_mirror {
+    use super::*;
+
+    /// Opens a public, fixed-price order with the maker PDA as maker. The
+    /// PDA pays the order's rent, so it must be funded beforehand, and its
+    /// token account must hold `amount`.
+    pub fn create_order(
+        ctx: Context<MirrorCreateOrder>,
+        order_id: u64,
+        amount: u64,
+        expected_ggor_amount: u64,
+        expiry: Expiry,
+        gorbagana_recipient: Pubkey,
+    ) -> Result<()> {
+        let bump = [ctx.bumps.maker];
+        let signer_seeds: &[&[&[u8]]] = &[&[MAKER_SEED, &bump]];
+        let accounts = &ctx.accounts;
+
+        solana_bridge::cpi::create_order(
+            CpiContext::new_with_signer(
+                accounts.bridge_program.to_account_info(),
+                CreateOrder {
+                    maker: accounts.maker.to_account_info(),
+                    order: accounts.order.to_account_info(),
+                    maker_registry: accounts.maker_registry.to_account_info(),
+                    stats: accounts.stats.to_account_info(),
+                    escrow_token_account: accounts.escrow_token_account.to_account_info(),
+                    maker_token_account: accounts.maker_token_account.to_account_info(),
+                    sgor_mint: accounts.sgor_mint.to_account_info(),
+                    mint_allow: accounts.mint_allow.to_account_info(),
+                    config: accounts.config.to_account_info(),
+                    maker_deny: accounts.maker_deny.to_account_info(),
+                    token_program: accounts.token_program.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                    rent: accounts.rent.to_account_info(),
+                    event_authority: accounts.event_authority.to_account_info(),
+                    program: accounts.bridge_program.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            CreateOrderParams {
+                order_id,
+                amount,
+                expected_ggor_amount,
+                expiry,
+                gorbagana_recipient,
+                allowed_taker: None,
+                end_expected_ggor_amount: None,
+                client_order_id: 0,
+                hashlock: None,
+                activation_slot: None,
+                operator: None,
+                payout_splits: Vec::new(),
+            },
+        )
+    }
+
+    /// Cancels an order made by the maker PDA, which signs as both the
+    /// authority and the refund destination.
+    pub fn cancel_order(ctx: Context<MirrorCancelOrder>) -> Result<()> {
+        let bump = [ctx.bumps.maker];
+        let signer_seeds: &[&[&[u8]]] = &[&[MAKER_SEED, &bump]];
+        let accounts = &ctx.accounts;
+
+        solana_bridge::cpi::cancel_order(CpiContext::new_with_signer(
+            accounts.bridge_program.to_account_info(),
+            CancelOrder {
+                authority: accounts.maker.to_account_info(),
+                maker: accounts.maker.to_account_info(),
+                order: accounts.order.to_account_info(),
+                maker_registry: accounts.maker_registry.to_account_info(),
+                stats: accounts.stats.to_account_info(),
+                escrow_token_account: accounts.escrow_token_account.to_account_info(),
+                maker_token_account: accounts.maker_token_account.to_account_info(),
+                config: accounts.config.to_account_info(),
+                sgor_mint: accounts.sgor_mint.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                event_authority: accounts.event_authority.to_account_info(),
+                program: accounts.bridge_program.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+}
+
+// The bridge validates every forwarded account itself; only the maker PDA
+// is checked here.
+
+#[derive(Accounts)]
+pub struct MirrorCreateOrder<'info> {
+    /// CHECK: System-owned PDA of this program; signs via its seeds
+    #[account(mut, seeds = [MAKER_SEED], bump)]
+    pub maker: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub order: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub maker_registry: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub stats: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub escrow_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub maker_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub sgor_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub mint_allow: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub maker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub token_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Validated by the bridge
+    pub rent: UncheckedAccount<'info>,
+
+    /// CHECK: The bridge's event authority PDA, validated by the bridge
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub bridge_program: Program<'info, SolanaBridge>,
+}
+
+#[derive(Accounts)]
+pub struct MirrorCancelOrder<'info> {
+    /// CHECK: System-owned PDA of this program; signs via its seeds
+    #[account(mut, seeds = [MAKER_SEED], bump)]
+    pub maker: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub order: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub maker_registry: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub stats: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub escrow_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    #[account(mut)]
+    pub maker_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub sgor_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bridge
+    pub token_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The bridge's event authority PDA, validated by the bridge
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub bridge_program: Program<'info, SolanaBridge>,
+}
@@ -1,18 +1,121 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as SplTransfer};
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::program::MAX_RETURN_DATA;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{
+    self, CloseAccount, InitializeAccount3, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+// Imported rather than written as a path in the config initializers: the
+// IDL build resolves `Program<'info, T>` by `T`'s last segment alone
+use crate::program::SolanaBridge;
+pub use bridge_common::{
+    dutch_expected_at, hashlock_of, validate_amount_at_least, validate_expiration_timestamp_within,
+    validate_expiration_within, verify_preimage, BridgeError, ESCROW_SEED, HASHLOCK_LEN,
+    MAX_EXPIRY_SECONDS, MAX_EXPIRY_SLOTS, MIN_ORDER_AMOUNT, ORDER_SEED,
+};
 
 // TODO: Replace with actual program ID after `anchor keys list`
 declare_id!("8FeLhyHHxKtnaKBQ2xZJ8Mwq5R8UqPvfBF9CL1BdH67T");
 
 // ── Hardcoded Constants ──────────────────────────────────────────────
-/// sGOR SPL Token mint on Solana Mainnet
+/// sGOR SPL Token mint on Solana Mainnet, the expected
+/// `BridgeConfig.sgor_mint`.
 pub const SGOR_MINT: Pubkey = pubkey!("71Jvq4Epe2FCJ7JFSF7jLXdNk1Wy4Bhqd9iL6bEFELvg");
 
-/// Minimum order size in token base units (6 decimals for sGOR)
-pub const MIN_ORDER_AMOUNT: u64 = 100_000; // 0.1 sGOR
+/// Length of a Gorbagana (ed25519) transaction signature
+pub const TX_SIG_LEN: usize = 64;
 
-/// Maximum order lifetime in slots (~400ms/slot → ~24 hours)
-pub const MAX_EXPIRY_SLOTS: u64 = 216_000;
+/// Longest a taker may reserve an order with `lock_order` (~10 minutes).
+/// Suggested `BridgeConfig.max_lock_slots` for `initialize_config`.
+pub const MAX_LOCK_SLOTS: u64 = 1_500;
+
+/// Slots after creation during which the maker cannot cancel (~1 minute),
+/// so a taker who saw the order has time to lock it. Suggested
+/// `BridgeConfig.cancel_delay_slots` for `initialize_config`.
+pub const DEFAULT_CANCEL_DELAY_SLOTS: u64 = 150;
+
+/// Native SOL a taker posts with `lock_order` (0.1 SOL). Suggested
+/// `BridgeConfig.lock_bond_lamports` for `initialize_config`.
+pub const DEFAULT_LOCK_BOND_LAMPORTS: u64 = 100_000_000;
+
+/// Share of a lapsed lock's bond paid to whoever calls `expire_lock` (10%);
+/// the maker gets the rest. Suggested `BridgeConfig.keeper_share_bps` for
+/// `initialize_config`.
+pub const DEFAULT_KEEPER_SHARE_BPS: u16 = 1_000;
+
+/// Hard cap on the protocol fee (3%)
+pub const MAX_FEE_BPS: u16 = 300;
+
+/// Basis-point denominator
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Lamports paid from the order's rent to whoever closes an expired order.
+/// Default for `BridgeConfig.expiry_bounty_lamports`.
+pub const DEFAULT_EXPIRY_BOUNTY_LAMPORTS: u64 = 100_000;
+
+/// Hard cap on `BridgeConfig.expiry_bounty_lamports` (0.001 SOL), well
+/// under an order's rent so the maker always gets most of it back.
+pub const MAX_EXPIRY_BOUNTY_LAMPORTS: u64 = 1_000_000;
+
+/// Slots that must pass between an order's creation and its first fill or
+/// lock, so an order cannot be created and taken in the same slot. Default
+/// for `BridgeConfig.min_fill_delay_slots`.
+pub const DEFAULT_MIN_FILL_DELAY_SLOTS: u64 = 1;
+
+/// Default length of a maker's order-creation window (~1 minute). Only
+/// matters once `BridgeConfig.max_creates_per_window` is set.
+pub const DEFAULT_CREATE_WINDOW_SLOTS: u64 = 150;
+
+/// Default for `BridgeConfig.challenge_slots`: settlements release
+/// immediately, with no challenge window.
+pub const DEFAULT_CHALLENGE_SLOTS: u64 = 0;
+
+/// Defaults for `BridgeConfig.quarantine_slots` / `quarantine_seconds`:
+/// how long an order must have been expired before the admin may move its
+/// escrow to the recovery vault. About 90 days either way.
+pub const DEFAULT_QUARANTINE_SLOTS: u64 = 19_440_000;
+pub const DEFAULT_QUARANTINE_SECONDS: u64 = 7_776_000;
+
+/// Slots in a maker's open-order registry
+pub const MAX_OPEN_ORDERS: usize = 32;
+
+/// Most orders one `create_orders` call may open. Each order costs two
+/// account creations, a token-account init, and a transfer (~30k CU), so
+/// a full batch needs a raised compute limit (~400k CU) but stays well
+/// inside the 1.4M CU transaction cap.
+pub const MAX_BATCH_ORDERS: usize = 10;
+
+/// Accounts per order in `cancel_orders` remaining_accounts
+pub const CANCEL_GROUP_LEN: usize = 3;
+
+/// Most `OrderSummary` entries `get_maker_orders` returns: as many as fit
+/// in the runtime's 1024-byte return data after the `MakerOrders` header.
+pub const MAX_LISTED_ORDERS: usize = (MAX_RETURN_DATA - MakerOrders::HEADER_LEN) / OrderSummary::LEN;
+
+/// Size of an SPL token account without extensions, as created for
+/// escrows by `create_orders`.
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Current `Order` layout. Handlers refuse any other value; v0 accounts
+/// (no version byte), v1 accounts (no stored escrow), v2 accounts (no
+/// payout splits), v3 accounts (no mint), and v4 accounts (24 reserved
+/// bytes) are upgraded with `migrate_order`.
+pub const ORDER_VERSION: u8 = 5;
+
+/// Zeroed bytes at the end of `Order`, from which new fields are carved.
+/// Every write keeps the account zero past the order's encoding (see
+/// `Order::zero_tail`), so a field taken from here reads as its default on
+/// existing orders without a `realloc`. Versions up to v4 grew the account
+/// instead (v1 318 bytes, v2 392, v3 572, v4 604, now `Order::LEN` 644).
+pub const ORDER_RESERVED_LEN: usize = 64;
+
+/// Most recipients an order's escrow release can be split between
+pub const MAX_PAYOUT_SPLITS: usize = 4;
 
 /// Direction for this program (Solana side - handles sGOR only)
 /// This program ONLY handles sGOR escrow. gGOR is handled by Gorbagana program.
@@ -24,74 +127,825 @@ pub const MAX_EXPIRY_SLOTS: u64 = 216_000;
 /// When someone wants to trade gGOR → sGOR:
 /// 1. They create order on Gorbagana locking gGOR
 /// 2. Counterparty fills HERE (Solana) sending sGOR
+///
+/// Order lifecycle events (`OrderCreated`, `OrderFilled`, `OrderCancelled`)
+/// are emitted twice: through a self-CPI (`emit_cpi!`), which survives log
+/// truncation when fills are wrapped by other programs, and as a plain log
+/// (`emit!`) for indexers that have not switched yet. The self-CPI needs
+/// the `event_authority` PDA (`[b"__event_authority"]`) and this program's
+/// account on `create_order`, `fill_order`, `settle_order`,
+/// `claim_htlc_order`, and `cancel_order`. The log copy will be dropped in
+/// the next release.
 
 #[program]
 pub mod solana_bridge {
     use super::*;
 
     // ═══════════════════════════════════════════════════════════════════
-    // CREATE ORDER (Maker locks sGOR on Solana)
+    // RELAYER REGISTRY — Admin-managed cross-chain attestors
     // ═══════════════════════════════════════════════════════════════════
-    /// Creates an sGOR escrow order on Solana.
-    /// Maker deposits sGOR into escrow, expecting gGOR on Gorbagana in return.
-    pub fn create_order(
-        ctx: Context<CreateOrder>,
-        amount: u64,
-        expiration_slot: u64,
-        gorbagana_recipient: Pubkey, // Maker's Gorbagana address to receive gGOR
+    /// Authorizes `relayer` to attest Gorbagana payments by creating its
+    /// `[b"relayer", relayer]` entry, active and with no attestations.
+    /// Config admin only; the admin is fixed by `initialize_config`, which
+    /// only the upgrade authority can call, so the registry has no separate
+    /// init that could be front-run.
+    pub fn add_relayer(ctx: Context<AddRelayer>, relayer: Pubkey) -> Result<()> {
+        let entry = &mut ctx.accounts.relayer_entry;
+        entry.relayer = relayer;
+        entry.active = true;
+        entry.attestations = 0;
+        entry.bump = ctx.bumps.relayer_entry;
+
+        emit!(RelayerAdded { relayer });
+        Ok(())
+    }
+
+    /// Revokes `relayer` and closes its entry, returning the rent to the
+    /// admin. Its attestation count is reported in `RelayerRemoved`.
+    /// Config admin only.
+    pub fn remove_relayer(ctx: Context<RemoveRelayer>, relayer: Pubkey) -> Result<()> {
+        emit!(RelayerRemoved {
+            relayer,
+            attestations: ctx.accounts.relayer_entry.attestations,
+        });
+        Ok(())
+    }
+
+    /// Suspends or resumes `relayer` without losing its entry or counters,
+    /// e.g. while its key is rotated or an incident is investigated.
+    /// Config admin only.
+    pub fn set_relayer_paused(
+        ctx: Context<SetRelayerPaused>,
+        relayer: Pubkey,
+        paused: bool,
     ) -> Result<()> {
-        // ── Validation ───────────────────────────────────────────────
-        require!(amount >= MIN_ORDER_AMOUNT, BridgeError::InvalidAmount);
+        ctx.accounts.relayer_entry.active = !paused;
 
-        let clock = Clock::get()?;
+        emit!(RelayerPauseSet { relayer, paused });
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // BRIDGE CONFIG — Admin-tunable mint and order limits
+    // ═══════════════════════════════════════════════════════════════════
+    /// Creates the singleton bridge config. Signed by the program's upgrade
+    /// authority, which becomes the admin.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        params.validate()?;
+        let ConfigParams {
+            sgor_mint,
+            min_order_amount,
+            max_expiry_slots,
+            max_expiry_seconds,
+            cancel_delay_slots,
+            max_lock_slots,
+            lock_bond_lamports,
+            keeper_share_bps,
+        } = params;
+
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.sgor_mint = sgor_mint;
+        config.min_order_amount = min_order_amount;
+        config.max_expiry_slots = max_expiry_slots;
+        config.max_expiry_seconds = max_expiry_seconds;
+        config.cancel_delay_slots = cancel_delay_slots;
+        config.max_lock_slots = max_lock_slots;
+        config.lock_bond_lamports = lock_bond_lamports;
+        config.keeper_share_bps = keeper_share_bps;
+        config.expiry_bounty_lamports = DEFAULT_EXPIRY_BOUNTY_LAMPORTS;
+        config.min_fill_delay_slots = DEFAULT_MIN_FILL_DELAY_SLOTS;
+        config.allow_self_fill = false;
+        config.max_creates_per_window = 0;
+        config.window_slots = DEFAULT_CREATE_WINDOW_SLOTS;
+        config.challenge_slots = DEFAULT_CHALLENGE_SLOTS;
+        config.quarantine_slots = DEFAULT_QUARANTINE_SLOTS;
+        config.quarantine_seconds = DEFAULT_QUARANTINE_SECONDS;
+        config.paused = false;
+        config.bump = ctx.bumps.config;
+
+        emit!(ConfigInitialized {
+            admin: config.admin,
+            sgor_mint,
+            min_order_amount,
+            max_expiry_slots,
+            max_expiry_seconds,
+            cancel_delay_slots,
+            max_lock_slots,
+            lock_bond_lamports,
+            keeper_share_bps,
+        });
+        Ok(())
+    }
+
+    /// Changes the mint, order limits, lock/cancel timing, and lock bond.
+    /// Admin only. A new bond applies to locks taken afterwards.
+    /// The mint applies to orders created afterwards; open orders keep the
+    /// mint their escrow holds.
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        params.validate()?;
+        let ConfigParams {
+            sgor_mint,
+            min_order_amount,
+            max_expiry_slots,
+            max_expiry_seconds,
+            cancel_delay_slots,
+            max_lock_slots,
+            lock_bond_lamports,
+            keeper_share_bps,
+        } = params;
+
+        let config = &mut ctx.accounts.config;
+        config.sgor_mint = sgor_mint;
+        config.min_order_amount = min_order_amount;
+        config.max_expiry_slots = max_expiry_slots;
+        config.max_expiry_seconds = max_expiry_seconds;
+        config.cancel_delay_slots = cancel_delay_slots;
+        config.max_lock_slots = max_lock_slots;
+        config.lock_bond_lamports = lock_bond_lamports;
+        config.keeper_share_bps = keeper_share_bps;
+
+        emit!(ConfigUpdated {
+            sgor_mint,
+            min_order_amount,
+            max_expiry_slots,
+            max_expiry_seconds,
+            cancel_delay_slots,
+            max_lock_slots,
+            lock_bond_lamports,
+            keeper_share_bps,
+        });
+        Ok(())
+    }
+
+    /// Sets the bounty `close_expired_order` pays its caller, up to
+    /// `MAX_EXPIRY_BOUNTY_LAMPORTS`. Applies to orders closed afterwards.
+    /// Admin only.
+    pub fn set_expiry_bounty(
+        ctx: Context<UpdateConfig>,
+        expiry_bounty_lamports: u64,
+    ) -> Result<()> {
         require!(
-            expiration_slot > clock.slot,
-            BridgeError::ExpirationInPast
+            expiry_bounty_lamports <= MAX_EXPIRY_BOUNTY_LAMPORTS,
+            BridgeError::InvalidConfig
         );
+
+        let config = &mut ctx.accounts.config;
+        let old_expiry_bounty_lamports = config.expiry_bounty_lamports;
+        config.expiry_bounty_lamports = expiry_bounty_lamports;
+
+        emit!(ExpiryBountyUpdated {
+            old_expiry_bounty_lamports,
+            expiry_bounty_lamports,
+        });
+        Ok(())
+    }
+
+    /// Sets how many slots must pass after an order is created before it
+    /// can be filled or locked, and whether makers may fill their own
+    /// orders. Self-fills are only meant for test deployments. Admin only.
+    pub fn set_fill_policy(
+        ctx: Context<UpdateConfig>,
+        min_fill_delay_slots: u64,
+        allow_self_fill: bool,
+    ) -> Result<()> {
         require!(
-            expiration_slot <= clock.slot.checked_add(MAX_EXPIRY_SLOTS).unwrap(),
-            BridgeError::ExpirationTooFar
+            min_fill_delay_slots <= ctx.accounts.config.max_expiry_slots,
+            BridgeError::InvalidConfig
         );
 
-        // ── Populate order state ─────────────────────────────────────
-        {
-            let order = &mut ctx.accounts.order;
-            order.maker = ctx.accounts.maker.key();
-            order.amount = amount;
-            order.expiration_slot = expiration_slot;
-            order.gorbagana_recipient = gorbagana_recipient;
-            order.is_filled = false;
-            order.bump = ctx.bumps.order;
+        let config = &mut ctx.accounts.config;
+        let old_min_fill_delay_slots = config.min_fill_delay_slots;
+        let old_allow_self_fill = config.allow_self_fill;
+        config.min_fill_delay_slots = min_fill_delay_slots;
+        config.allow_self_fill = allow_self_fill;
+
+        emit!(FillPolicyUpdated {
+            old_min_fill_delay_slots,
+            min_fill_delay_slots,
+            old_allow_self_fill,
+            allow_self_fill,
+        });
+        Ok(())
+    }
+
+    /// Caps how many orders one maker may create within `window_slots`
+    /// slots. `max_creates_per_window = 0` lifts the cap. A maker's window
+    /// restarts on their first create after it ends. Admin only.
+    pub fn set_create_rate_limit(
+        ctx: Context<UpdateConfig>,
+        max_creates_per_window: u32,
+        window_slots: u64,
+    ) -> Result<()> {
+        require!(
+            window_slots > 0 && window_slots <= ctx.accounts.config.max_expiry_slots,
+            BridgeError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_max_creates_per_window = config.max_creates_per_window;
+        let old_window_slots = config.window_slots;
+        config.max_creates_per_window = max_creates_per_window;
+        config.window_slots = window_slots;
+
+        emit!(CreateRateLimitUpdated {
+            old_max_creates_per_window,
+            max_creates_per_window,
+            old_window_slots,
+            window_slots,
+        });
+        Ok(())
+    }
+
+    /// Sets how long an attested `settle_order` waits before its escrow can
+    /// be released by `finalize_settlement`, giving the maker or another
+    /// relayer time to `dispute_settlement`. 0 releases at settle, as
+    /// before. Admin only.
+    pub fn set_challenge_period(ctx: Context<UpdateConfig>, challenge_slots: u64) -> Result<()> {
+        require!(
+            challenge_slots <= ctx.accounts.config.max_expiry_slots,
+            BridgeError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_challenge_slots = config.challenge_slots;
+        config.challenge_slots = challenge_slots;
+
+        emit!(ChallengePeriodUpdated {
+            old_challenge_slots,
+            challenge_slots,
+        });
+        Ok(())
+    }
+
+    /// Sets how long an order must have been expired before
+    /// `quarantine_recover` may touch it: `quarantine_slots` past a slot
+    /// deadline, `quarantine_seconds` past a timestamp one. Neither may be
+    /// shorter than the longest order lifetime. Admin only.
+    pub fn set_quarantine_period(
+        ctx: Context<UpdateConfig>,
+        quarantine_slots: u64,
+        quarantine_seconds: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(
+            quarantine_slots >= config.max_expiry_slots
+                && quarantine_seconds >= config.max_expiry_seconds,
+            BridgeError::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_quarantine_slots = config.quarantine_slots;
+        let old_quarantine_seconds = config.quarantine_seconds;
+        config.quarantine_slots = quarantine_slots;
+        config.quarantine_seconds = quarantine_seconds;
+
+        emit!(QuarantinePeriodUpdated {
+            old_quarantine_slots,
+            quarantine_slots,
+            old_quarantine_seconds,
+            quarantine_seconds,
+        });
+        Ok(())
+    }
+
+    /// Halts new orders and fills. Cancels and expiry cleanup stay open so
+    /// makers can always reclaim escrowed sGOR. Admin only.
+    pub fn pause(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.config.paused = true;
+
+        emit!(Paused {
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Lifts a pause. Admin only.
+    pub fn unpause(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.config.paused = false;
+
+        emit!(Unpaused {
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // DENYLIST — Compliance blocks on creating and filling orders
+    // ═══════════════════════════════════════════════════════════════════
+    /// Lists `address`, so it can no longer create, fill, or lock orders,
+    /// nor have its orders filled or locked. Cancels and expiry sweeps stay
+    /// open to it so escrowed sGOR is never trapped. Config admin only.
+    pub fn deny_address(ctx: Context<DenyAddress>, address: Pubkey) -> Result<()> {
+        let entry = &mut ctx.accounts.deny_entry;
+        entry.address = address;
+        entry.bump = ctx.bumps.deny_entry;
+
+        emit!(AddressDenylisted {
+            address,
+            admin: ctx.accounts.admin.key(),
+        });
+        Ok(())
+    }
+
+    /// Removes `address` from the denylist and returns the entry's rent to
+    /// the admin. Config admin only.
+    pub fn allow_address(ctx: Context<AllowAddress>, address: Pubkey) -> Result<()> {
+        emit!(AddressAllowed {
+            address,
+            admin: ctx.accounts.admin.key(),
+        });
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // MINT ALLOWLIST — Admin-managed escrow mints
+    // ═══════════════════════════════════════════════════════════════════
+    /// Allows orders to escrow `mint` alongside the config's `sgor_mint`,
+    /// which is always allowed. Config admin only.
+    pub fn add_mint(ctx: Context<AddMint>, mint: Pubkey) -> Result<()> {
+        let entry = &mut ctx.accounts.mint_allow;
+        entry.mint = mint;
+        entry.bump = ctx.bumps.mint_allow;
+
+        emit!(MintAdded {
+            mint,
+            admin: ctx.accounts.admin.key(),
+        });
+        Ok(())
+    }
+
+    /// Stops new orders in `mint` and returns the entry's rent to the
+    /// admin. Open orders in the mint can still be filled, cancelled, and
+    /// swept. Config admin only.
+    pub fn remove_mint(ctx: Context<RemoveMint>, mint: Pubkey) -> Result<()> {
+        emit!(MintRemoved {
+            mint,
+            admin: ctx.accounts.admin.key(),
+        });
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // FEE CONFIG — Protocol fee taken from the escrow on fill
+    // ═══════════════════════════════════════════════════════════════════
+    /// Creates the singleton fee config. Signed by the program's upgrade
+    /// authority, which becomes the admin.
+    pub fn initialize_fee_config(
+        ctx: Context<InitializeFeeConfig>,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, BridgeError::FeeTooHigh);
+
+        let fee_config = &mut ctx.accounts.fee_config;
+        fee_config.admin = ctx.accounts.admin.key();
+        fee_config.fee_bps = fee_bps;
+        fee_config.fee_recipient = fee_recipient;
+        fee_config.relayer_tip_bps = 0;
+        fee_config.bump = ctx.bumps.fee_config;
+
+        emit!(FeeConfigInitialized {
+            admin: fee_config.admin,
+            fee_bps,
+            fee_recipient,
+        });
+        Ok(())
+    }
+
+    /// Changes the fee rate and recipient. Admin only.
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfig>,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, BridgeError::FeeTooHigh);
+
+        let fee_config = &mut ctx.accounts.fee_config;
+        let old_fee_bps = fee_config.fee_bps;
+        fee_config.fee_bps = fee_bps;
+        fee_config.fee_recipient = fee_recipient;
+
+        emit!(FeeConfigUpdated {
+            old_fee_bps,
+            fee_bps,
+            fee_recipient,
+        });
+        Ok(())
+    }
+
+    /// Sets the tip a relayer takes from the protocol fee for executing a
+    /// `fill_order_delegated`, in basis points of the order amount. Capped
+    /// like the protocol fee, and at the fee of each fill. Admin only.
+    pub fn set_relayer_tip(ctx: Context<UpdateFeeConfig>, relayer_tip_bps: u16) -> Result<()> {
+        require!(relayer_tip_bps <= MAX_FEE_BPS, BridgeError::FeeTooHigh);
+
+        let fee_config = &mut ctx.accounts.fee_config;
+        let old_relayer_tip_bps = fee_config.relayer_tip_bps;
+        fee_config.relayer_tip_bps = relayer_tip_bps;
+
+        emit!(RelayerTipUpdated {
+            old_relayer_tip_bps,
+            relayer_tip_bps,
+        });
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // BRIDGE STATS — Protocol-wide volume counters
+    // ═══════════════════════════════════════════════════════════════════
+    /// Creates the singleton stats account with every counter at zero.
+    /// Must run before the first order of the release that introduces it.
+    pub fn initialize_stats(ctx: Context<InitializeStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.stats;
+        stats.total_escrowed = 0;
+        stats.total_filled = 0;
+        stats.total_cancelled = 0;
+        stats.makers_count = 0;
+        stats.bump = ctx.bumps.stats;
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // CREATE ORDER (Maker locks sGOR on Solana)
+    // ═══════════════════════════════════════════════════════════════════
+    /// Creates an sGOR escrow order on Solana.
+    /// Maker deposits sGOR into escrow, expecting gGOR on Gorbagana in return.
+    ///
+    /// `order_id` is chosen by the maker and only needs to be unique per
+    /// maker; it lets the same maker keep several orders of equal size open.
+    ///
+    /// `allowed_taker` pins the only account that may fill or lock the
+    /// order, for negotiated OTC swaps.
+    ///
+    /// `expected_ggor_amount` is the authoritative price relayers check the
+    /// Gorbagana leg against; it need not equal `amount`.
+    ///
+    /// `end_expected_ggor_amount` turns the order into a Dutch auction: the
+    /// price decays from `expected_ggor_amount` at creation to this amount
+    /// at expiration (see `Order::current_expected`).
+    ///
+    /// `expiry` is either a slot or a unix timestamp. Slot time drifts with
+    /// the cluster's slot rate, so makers who need a wall-clock deadline
+    /// should use the timestamp form.
+    ///
+    /// `client_order_id` is an opaque tag echoed in `OrderCreated`,
+    /// `OrderFilled`, and `OrderCancelled` so off-chain systems can match
+    /// events to their own orders. It is not checked for uniqueness.
+    ///
+    /// `hashlock` makes the order an HTLC: instead of a relayer-attested
+    /// `fill_order`, the escrow is released by `claim_htlc_order` to the
+    /// taker who reveals the sha256 preimage, and refunded only once the
+    /// order has expired. HTLC orders must set `allowed_taker`, since the
+    /// preimage is public once revealed on Gorbagana.
+    ///
+    /// `activation_slot` time-locks the order: fills and locks are rejected
+    /// before it, cancels are not. Omitted means fillable immediately. With
+    /// a slot expiry it must be before the expiration slot.
+    ///
+    /// `operator` optionally designates a low-privilege key that may cancel
+    /// the order or move its expiry. Refunds still go only to the maker.
+    ///
+    /// `payout_splits` divides the sGOR released on a fill (after the
+    /// protocol fee) between up to `MAX_PAYOUT_SPLITS` wallets, in basis
+    /// points summing to 10_000. `Pubkey::default()` as a recipient stands
+    /// for the filling taker. Empty means the taker receives everything.
+    /// Split orders are released by `fill_order` or `settle_order`.
+    ///
+    /// The escrow may hold the config's `sgor_mint` or any mint on the
+    /// allowlist (`add_mint`). The mint is fixed on the order and is part
+    /// of the escrow seeds; order ids are shared across mints.
+    pub fn create_order(ctx: Context<CreateOrder>, params: CreateOrderParams) -> Result<()> {
+        let CreateOrderParams {
+            order_id,
+            amount,
+            expected_ggor_amount,
+            expiry,
+            gorbagana_recipient,
+            allowed_taker,
+            end_expected_ggor_amount,
+            client_order_id,
+            hashlock,
+            activation_slot,
+            operator,
+            payout_splits,
+        } = params;
+
+        // ── Validation ───────────────────────────────────────────────
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        DenyEntry::require_not_listed(&ctx.accounts.maker_deny)?;
+        validate_amount_at_least(amount, config.min_order_amount)?;
+        require!(expected_ggor_amount > 0, BridgeError::InvalidAmount);
+        if let Some(end) = end_expected_ggor_amount {
+            require!(
+                end > 0 && end <= expected_ggor_amount,
+                BridgeError::InvalidAmount
+            );
         }
+        require!(
+            hashlock.is_none() || allowed_taker.is_some(),
+            BridgeError::HtlcRequiresTaker
+        );
+        let (payout_splits, payout_split_count) = PayoutSplit::pack(&payout_splits)?;
+        require!(
+            payout_split_count == 0 || hashlock.is_none(),
+            BridgeError::PayoutSplitsUnsupported
+        );
+        let clock = Clock::get()?;
+        expiry.validate(&clock, config)?;
+        let activation_slot = activation_slot.unwrap_or(0);
+        expiry.validate_activation(activation_slot)?;
+        let mint_key = ctx.accounts.sgor_mint.key();
+        MintAllow::require_allowed(&ctx.accounts.mint_allow, &mint_key, config)?;
 
         // ── Deposit sGOR into escrow ─────────────────────────────────
         let escrow_ta = &ctx.accounts.escrow_token_account;
         let maker_ta = &ctx.accounts.maker_token_account;
 
-        // Validate mint is sGOR
-        require!(maker_ta.mint == SGOR_MINT, BridgeError::InvalidMint);
-        require!(escrow_ta.mint == SGOR_MINT, BridgeError::InvalidMint);
+        // Validate both token accounts are in the order's mint
+        require!(maker_ta.mint == mint_key, BridgeError::InvalidMint);
+        require!(escrow_ta.mint == mint_key, BridgeError::InvalidMint);
 
-        let cpi_accounts = SplTransfer {
+        let cpi_accounts = TransferChecked {
             from: maker_ta.to_account_info(),
+            mint: ctx.accounts.sgor_mint.to_account_info(),
             to: escrow_ta.to_account_info(),
             authority: ctx.accounts.maker.to_account_info(),
         };
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
                 cpi_accounts,
             ),
             amount,
+            ctx.accounts.sgor_mint.decimals,
         )?;
 
-        emit!(OrderCreated {
-            order_key: ctx.accounts.order.key(),
+        // A Token-2022 transfer fee would leave the escrow short of `amount`.
+        // Record what actually arrived, and refuse the order if that is not
+        // the full amount rather than promise sGOR the escrow cannot pay.
+        ctx.accounts.escrow_token_account.reload()?;
+        let escrowed = ctx.accounts.escrow_token_account.amount;
+        require!(escrowed == amount, BridgeError::TransferFeeNotSupported);
+
+        // ── Populate order state ─────────────────────────────────────
+        {
+            let order = &mut ctx.accounts.order;
+            order.maker = ctx.accounts.maker.key();
+            order.order_id = order_id;
+            order.amount = escrowed;
+            order.expected_ggor_amount = expected_ggor_amount;
+            order.expiry = expiry;
+            order.created_slot = clock.slot;
+            order.created_timestamp = clock.unix_timestamp;
+            order.gorbagana_recipient = gorbagana_recipient;
+            order.status = OrderStatus::Open;
+            order.locked_by = Pubkey::default();
+            order.lock_expiry_slot = 0;
+            order.lock_bond = 0;
+            order.taker_gorbagana_address = Pubkey::default();
+            order.allowed_taker = allowed_taker;
+            order.end_expected_ggor_amount = end_expected_ggor_amount;
+            order.locked_expected_ggor_amount = 0;
+            order.bump = ctx.bumps.order;
+            order.version = ORDER_VERSION;
+            order.client_order_id = client_order_id;
+            order.hashlock = hashlock;
+            order.escrow_token_account = ctx.accounts.escrow_token_account.key();
+            order.escrow_bump = ctx.bumps.escrow_token_account;
+            order.activation_slot = activation_slot;
+            order.operator = operator;
+            order.decimals = Some(ctx.accounts.sgor_mint.decimals);
+            order.payout_splits = payout_splits;
+            order.payout_split_count = payout_split_count;
+            order.release_slot = 0;
+            order.pending_receipt = Pubkey::default();
+            order.mint = mint_key;
+            order.reserved = [0u8; ORDER_RESERVED_LEN];
+        }
+
+        // ── Register in the maker's open-order index ─────────────────
+        let order_key = ctx.accounts.order.key();
+        let maker_registry = &mut ctx.accounts.maker_registry;
+        let first_order = maker_registry.maker == Pubkey::default();
+        if first_order {
+            maker_registry.maker = ctx.accounts.maker.key();
+            maker_registry.bump = ctx.bumps.maker_registry;
+        }
+        maker_registry.record_create(clock.slot, config)?;
+        maker_registry.push(order_key)?;
+        ctx.accounts.stats.record_created(escrowed, first_order)?;
+
+        // Both accounts were created by this instruction, so their whole
+        // balance is rent the maker paid
+        let rent_locked_lamports = ctx.accounts.order.to_account_info().lamports()
+            + ctx.accounts.escrow_token_account.to_account_info().lamports();
+        let event = OrderCreated {
+            order_key,
+            order_id,
             maker: ctx.accounts.maker.key(),
-            amount,
+            amount: escrowed,
+            expected_ggor_amount,
+            end_expected_ggor_amount,
             gorbagana_recipient,
-            expiration_slot,
-        });
+            expiry,
+            is_private: allowed_taker.is_some(),
+            status: OrderStatus::Open,
+            client_order_id,
+            hashlock,
+            activation_slot,
+            operator,
+            created_slot: clock.slot,
+            decimals: ctx.accounts.sgor_mint.decimals,
+            payout_splits: payout_splits[..payout_split_count as usize].to_vec(),
+            rent_locked_lamports,
+            mint: mint_key,
+        };
+        emit!(event.clone()); // log event, kept for one release
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // BATCH CREATE — Open a ladder of public orders in one transaction
+    // ═══════════════════════════════════════════════════════════════════
+    /// Opens up to `MAX_BATCH_ORDERS` public, fixed-price orders, each
+    /// validated and escrowed exactly as `create_order` would.
+    ///
+    /// Anchor `init` cannot cover a variable number of accounts, so the
+    /// order and escrow PDAs are passed in `remaining_accounts` and created
+    /// here. Layout, per entry of `params` and in the same order:
+    ///
+    ///   `[order PDA (writable), escrow PDA (writable)]`
+    ///
+    /// with seeds as for `create_order`. Any invalid entry fails the whole
+    /// transaction, so either every order opens or none does. One
+    /// `OrderCreated` is emitted per order.
+    pub fn create_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateOrders<'info>>,
+        params: Vec<OrderParams>,
+    ) -> Result<()> {
+        require!(
+            !params.is_empty() && params.len() <= MAX_BATCH_ORDERS,
+            BridgeError::BatchTooLarge
+        );
+        require!(
+            ctx.remaining_accounts.len() == params.len() * 2,
+            BridgeError::InvalidBatchAccounts
+        );
+
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        DenyEntry::require_not_listed(&ctx.accounts.maker_deny)?;
+        let mint_key = ctx.accounts.sgor_mint.key();
+        MintAllow::require_allowed(&ctx.accounts.mint_allow, &mint_key, config)?;
+        require!(
+            ctx.accounts.maker_token_account.mint == mint_key,
+            BridgeError::InvalidMint
+        );
+        let clock = Clock::get()?;
+        let maker_key = ctx.accounts.maker.key();
+
+        let maker_registry = &mut ctx.accounts.maker_registry;
+        let mut first_order = maker_registry.maker == Pubkey::default();
+        if first_order {
+            maker_registry.maker = maker_key;
+            maker_registry.bump = ctx.bumps.maker_registry;
+        }
+
+        for (p, accounts) in params.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let (order_info, escrow_info) = (&accounts[0], &accounts[1]);
+
+            // ── Validation ───────────────────────────────────────────
+            validate_amount_at_least(p.amount, config.min_order_amount)?;
+            require!(p.expected_ggor_amount > 0, BridgeError::InvalidAmount);
+            p.expiry.validate(&clock, config)?;
+
+            let id_bytes = p.order_id.to_le_bytes();
+            let (order_key, order_bump) = Pubkey::find_program_address(
+                &[ORDER_SEED, maker_key.as_ref(), &id_bytes],
+                ctx.program_id,
+            );
+            let (escrow_key, escrow_bump) = Pubkey::find_program_address(
+                &[ESCROW_SEED, maker_key.as_ref(), &id_bytes, mint_key.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(order_info.key(), order_key, BridgeError::InvalidBatchAccounts);
+            require_keys_eq!(escrow_info.key(), escrow_key, BridgeError::InvalidBatchAccounts);
+
+            // ── Create the order and escrow accounts ─────────────────
+            init_pda_account(
+                ctx.accounts.maker.to_account_info(),
+                order_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+                Order::LEN,
+                ctx.program_id,
+                &[ORDER_SEED, maker_key.as_ref(), &id_bytes, &[order_bump]],
+            )?;
+            init_pda_account(
+                ctx.accounts.maker.to_account_info(),
+                escrow_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+                TOKEN_ACCOUNT_LEN,
+                &ctx.accounts.token_program.key(),
+                &[ESCROW_SEED, maker_key.as_ref(), &id_bytes, mint_key.as_ref(), &[escrow_bump]],
+            )?;
+            token_interface::initialize_account3(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeAccount3 {
+                    account: escrow_info.clone(),
+                    mint: ctx.accounts.sgor_mint.to_account_info(),
+                    authority: order_info.clone(),
+                },
+            ))?;
+
+            // ── Deposit sGOR into escrow ─────────────────────────────
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.maker_token_account.to_account_info(),
+                        mint: ctx.accounts.sgor_mint.to_account_info(),
+                        to: escrow_info.clone(),
+                        authority: ctx.accounts.maker.to_account_info(),
+                    },
+                ),
+                p.amount,
+                ctx.accounts.sgor_mint.decimals,
+            )?;
+            let escrowed = InterfaceAccount::<TokenAccount>::try_from(escrow_info)?.amount;
+            let rent_locked_lamports = order_info.lamports() + escrow_info.lamports();
+            require!(escrowed == p.amount, BridgeError::TransferFeeNotSupported);
+
+            // ── Populate order state ─────────────────────────────────
+            let order = Order {
+                maker: maker_key,
+                order_id: p.order_id,
+                amount: escrowed,
+                expected_ggor_amount: p.expected_ggor_amount,
+                gorbagana_recipient: p.gorbagana_recipient,
+                expiry: p.expiry,
+                created_slot: clock.slot,
+                created_timestamp: clock.unix_timestamp,
+                status: OrderStatus::Open,
+                locked_by: Pubkey::default(),
+                lock_expiry_slot: 0,
+                lock_bond: 0,
+                taker_gorbagana_address: Pubkey::default(),
+                allowed_taker: None,
+                end_expected_ggor_amount: None,
+                locked_expected_ggor_amount: 0,
+                bump: order_bump,
+                version: ORDER_VERSION,
+                client_order_id: p.client_order_id,
+                hashlock: None,
+                escrow_token_account: escrow_key,
+                escrow_bump,
+                activation_slot: 0,
+                operator: None,
+                decimals: Some(ctx.accounts.sgor_mint.decimals),
+                payout_splits: [PayoutSplit::default(); MAX_PAYOUT_SPLITS],
+                payout_split_count: 0,
+                release_slot: 0,
+                pending_receipt: Pubkey::default(),
+                mint: mint_key,
+                reserved: [0u8; ORDER_RESERVED_LEN],
+            };
+            order.try_serialize(&mut &mut order_info.try_borrow_mut_data()?[..])?;
+
+            ctx.accounts.maker_registry.record_create(clock.slot, config)?;
+            ctx.accounts.maker_registry.push(order_key)?;
+            ctx.accounts.stats.record_created(escrowed, first_order)?;
+            first_order = false;
+
+            let event = OrderCreated {
+                order_key,
+                order_id: p.order_id,
+                maker: maker_key,
+                amount: escrowed,
+                expected_ggor_amount: p.expected_ggor_amount,
+                end_expected_ggor_amount: None,
+                gorbagana_recipient: p.gorbagana_recipient,
+                expiry: p.expiry,
+                is_private: false,
+                status: OrderStatus::Open,
+                client_order_id: p.client_order_id,
+                hashlock: None,
+                activation_slot: 0,
+                operator: None,
+                created_slot: clock.slot,
+                decimals: ctx.accounts.sgor_mint.decimals,
+                payout_splits: Vec::new(),
+                rent_locked_lamports,
+                mint: mint_key,
+            };
+            emit!(event.clone()); // log event, kept for one release
+            emit_cpi!(event);
+        }
 
         Ok(())
     }
@@ -107,300 +961,4826 @@ pub mod solana_bridge {
     /// 1. Gorbagana maker locked gGOR there
     /// 2. Taker sends sGOR HERE to the Gorbagana maker
     /// 3. Taker then claims gGOR on Gorbagana
-    pub fn fill_order(ctx: Context<FillOrder>) -> Result<()> {
+    ///
+    /// A registered relayer must co-sign, attesting that the Gorbagana leg
+    /// landed in `gorbagana_tx_sig`. Without it the escrow would leave
+    /// with no evidence the counterparty paid. The signature is kept in a
+    /// `FillReceipt` PDA keyed by the signature itself, so one Gorbagana
+    /// payment can never settle two fills.
+    ///
+    /// `taker_gorbagana_address` records where the taker is paid on
+    /// Gorbagana should a reverse leg ever be needed.
+    ///
+    /// Passing `order_receipt` also writes an `OrderReceipt` looked up by
+    /// the order key, as durable proof for accounting; the taker pays its
+    /// rent and may reclaim it later with `close_receipt`.
+    ///
+    /// The protocol fee (`FeeConfig.fee_bps`) is carved out of the escrow;
+    /// the taker receives `amount - fee`.
+    ///
+    /// `OrderFilled.expected_ggor_amount` is the price at the fill slot.
+    ///
+    /// `expected_amount` and `expected_gorbagana_recipient` are what the
+    /// taker quoted and paid against. A maker who cancels and recreates
+    /// with the same `order_id` reuses the PDA, so without these the fill
+    /// could land on a different order than the one the taker paid for.
+    ///
+    /// `deadline_slot` bounds how late the transaction may land, so a fill
+    /// held up in forwarding cannot execute at a price the taker no longer
+    /// wants. Zero means no deadline.
+    pub fn fill_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FillOrder<'info>>,
+        gorbagana_tx_sig: [u8; TX_SIG_LEN],
+        taker_gorbagana_address: Pubkey,
+        expected_amount: u64,
+        expected_gorbagana_recipient: Pubkey,
+        deadline_slot: u64, // 0 = no deadline
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            deadline_slot == 0 || clock.slot <= deadline_slot,
+            BridgeError::FillDeadlineExceeded
+        );
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        RelayerEntry::record_attestation(&ctx.accounts.relayer_entry)?;
+        DenyEntry::require_not_listed(&ctx.accounts.taker_deny)?;
+        DenyEntry::require_not_listed(&ctx.accounts.maker_deny)?;
         let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        let escrow_balance = order.require_escrow_balance(&ctx.accounts.escrow_token_account)?;
+        config.require_not_self_fill(order, &ctx.accounts.taker.key())?;
 
         // ── Validation ───────────────────────────────────────────────
-        require!(!order.is_filled, BridgeError::OrderAlreadyFilled);
         require!(
-            Clock::get()?.slot <= order.expiration_slot,
-            BridgeError::OrderExpired
+            gorbagana_tx_sig != [0u8; TX_SIG_LEN],
+            BridgeError::InvalidTxSignature
+        );
+        require!(
+            taker_gorbagana_address != Pubkey::default(),
+            BridgeError::InvalidGorbaganaAddress
+        );
+        // Status, activation, expiry, and pricing are shared with `quote_fill`
+        let FillQuote { fill_amount: amount, fee, expected_ggor_amount, .. } =
+            order.quote_fill(order.amount, &clock, config, ctx.accounts.fee_config.fee_bps)?;
+        require!(
+            order.is_taker_allowed(&ctx.accounts.taker.key()),
+            BridgeError::TakerNotAllowed
+        );
+        require!(
+            amount == expected_amount
+                && order.gorbagana_recipient == expected_gorbagana_recipient,
+            BridgeError::OrderMismatch
         );
 
-        let amount = order.amount;
+        let order_id = order.order_id;
+        let client_order_id = order.client_order_id;
         let maker_key = order.maker;
         let bump = order.bump;
 
         // PDA signer seeds for escrow release
         let seeds: &[&[u8]] = &[
-            b"order",
+            ORDER_SEED,
             maker_key.as_ref(),
-            &amount.to_le_bytes(),
+            &order_id.to_le_bytes(),
             &[bump],
         ];
 
-        // Release sGOR from escrow to taker
+        // Release sGOR from escrow to taker, or across the payout splits
         let escrow_ta = &ctx.accounts.escrow_token_account;
         let taker_ta = &ctx.accounts.taker_token_account;
 
-        require!(taker_ta.mint == SGOR_MINT, BridgeError::InvalidMint);
+        require!(taker_ta.mint == escrow_ta.mint, BridgeError::InvalidMint);
 
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                SplTransfer {
-                    from: escrow_ta.to_account_info(),
-                    to: taker_ta.to_account_info(),
-                    authority: ctx.accounts.order.to_account_info(),
-                },
-                &[seeds],
-            ),
-            amount,
+        let payout = order.payout_legs(
+            &ctx.accounts.taker.key(),
+            amount - fee,
+            taker_ta.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: escrow_ta,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            Some((ctx.accounts.fee_token_account.to_account_info(), fee)),
+            &payout,
         )?;
 
         // Mark as filled
         let order = &mut ctx.accounts.order;
-        order.is_filled = true;
+        order.transition(OrderStatus::Filled)?;
+        let order_key = order.key();
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_filled(amount)?;
+        let gorbagana_recipient = order.gorbagana_recipient;
 
-        emit!(OrderFilled {
-            order_key: order.key(),
+        ctx.accounts.receipt.set_inner(FillReceipt {
+            order_key,
+            order_id,
             maker: maker_key,
             taker: ctx.accounts.taker.key(),
             amount,
+            relayer: ctx.accounts.relayer.key(),
+            gorbagana_tx_sig,
+            slot: clock.slot,
+            bump: ctx.bumps.receipt,
         });
 
-        Ok(())
-    }
+        if let Some(order_receipt) = ctx.accounts.order_receipt.as_mut() {
+            order_receipt.set_inner(OrderReceipt {
+                order_key,
+                order_id,
+                maker: maker_key,
+                taker: ctx.accounts.taker.key(),
+                amount,
+                gorbagana_recipient,
+                gorbagana_tx_sig,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+                bump: ctx.bumps.order_receipt.unwrap_or_default(),
+            });
+        }
 
+        let rent_returned_lamports = escrow_rent + ctx.accounts.order.to_account_info().lamports();
+        let event = OrderFilled {
+            order_key,
+            order_id,
+            maker: maker_key,
+            taker: ctx.accounts.taker.key(),
+            amount,
+            expected_ggor_amount,
+            fee,
+            relayer: ctx.accounts.relayer.key(),
+            gorbagana_tx_sig,
+            taker_gorbagana_address,
+            gorbagana_recipient,
+            status: OrderStatus::Filled,
+            client_order_id,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+            escrow_balance,
+            self_fill: ctx.accounts.taker.key() == maker_key,
+        };
+        emit!(event.clone()); // log event, kept for one release
+        emit_cpi!(event);
+        payout.emit(order_key, order_id);
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // QUOTE FILL — Read-only fill simulation
+    // ═══════════════════════════════════════════════════════════════════
+    /// Runs the same validation and pricing as `fill_order` for a fill of
+    /// `fill_amount` now and returns the `FillQuote` as return data (Anchor
+    /// calls `set_return_data`). Moves no funds and writes no state; call
+    /// it through `simulateTransaction` / `.view()`. Taker-specific checks
+    /// (deny list, private orders) are left to the fill itself.
+    pub fn quote_fill(ctx: Context<QuoteFill>, fill_amount: u64) -> Result<FillQuote> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        ctx.accounts.order.quote_fill(
+            fill_amount,
+            &Clock::get()?,
+            config,
+            ctx.accounts.fee_config.fee_bps,
+        )
+    }
+
+    /// Closes the taker's `OrderReceipt` and returns its rent.
+    pub fn close_receipt(_ctx: Context<CloseReceipt>) -> Result<()> {
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // DELEGATED FILL — Relayer fills for a taker who holds no SOL
+    // ═══════════════════════════════════════════════════════════════════
+    /// `fill_order` with the relayer as the only signer and fee payer. The
+    /// relayer attests the Gorbagana payment as in `fill_order`; the taker
+    /// takes part through two proofs made with their own key:
+    ///
+    /// - an SPL approval naming the relayer as delegate on the receiving
+    ///   token account, and
+    /// - an ed25519 signature over `DelegatedFillMessage { order,
+    ///   max_amount, nonce }`, verified by an ed25519 program instruction
+    ///   placed immediately before this one.
+    ///
+    /// `nonce` must equal the taker's `TakerNonce.next_nonce`, which then
+    /// advances, so each signed message fills at most once. The order's
+    /// current gGOR price must not exceed `max_amount`.
+    ///
+    /// The sGOR still goes only to the taker's token account, which gets
+    /// what a plain `fill_order` would pay. The relayer's tip,
+    /// `FeeConfig.relayer_tip_bps` of the order amount, comes out of the
+    /// protocol fee and is capped at it, so the signed message need not
+    /// cover it and a tip change cannot reach the taker.
+    pub fn fill_order_delegated(
+        ctx: Context<FillOrderDelegated>,
+        gorbagana_tx_sig: [u8; TX_SIG_LEN],
+        taker_gorbagana_address: Pubkey,
+        max_amount: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        RelayerEntry::record_attestation(&ctx.accounts.relayer_entry)?;
+        DenyEntry::require_not_listed(&ctx.accounts.taker_deny)?;
+        DenyEntry::require_not_listed(&ctx.accounts.maker_deny)?;
+        let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        let escrow_balance = order.require_escrow_balance(&ctx.accounts.escrow_token_account)?;
+        let taker_key = ctx.accounts.taker.key();
+        let relayer_key = ctx.accounts.relayer.key();
+        config.require_not_self_fill(order, &taker_key)?;
+
+        // ── Validation ───────────────────────────────────────────────
+        require!(
+            gorbagana_tx_sig != [0u8; TX_SIG_LEN],
+            BridgeError::InvalidTxSignature
+        );
+        require!(
+            taker_gorbagana_address != Pubkey::default(),
+            BridgeError::InvalidGorbaganaAddress
+        );
+        let clock = Clock::get()?;
+        let FillQuote { fill_amount: amount, fee, expected_ggor_amount, .. } =
+            order.quote_fill(order.amount, &clock, config, ctx.accounts.fee_config.fee_bps)?;
+        // The taker's signature covers only their own receiving account
+        require!(order.payout_split_count == 0, BridgeError::PayoutSplitsUnsupported);
+        require!(order.is_taker_allowed(&taker_key), BridgeError::TakerNotAllowed);
+        require!(expected_ggor_amount <= max_amount, BridgeError::OrderMismatch);
+
+        // ── Taker authorization ──────────────────────────────────────
+        require!(
+            ctx.accounts.taker_token_account.delegate == COption::Some(relayer_key),
+            BridgeError::DelegateNotApproved
+        );
+        let message = DelegatedFillMessage {
+            order: order.key(),
+            max_amount,
+            nonce,
+        };
+        require_ed25519_signature(&ctx.accounts.instructions, &taker_key, &message.to_bytes())?;
+
+        let taker_nonce = &mut ctx.accounts.taker_nonce;
+        if taker_nonce.taker == Pubkey::default() {
+            taker_nonce.taker = taker_key;
+            taker_nonce.bump = ctx.bumps.taker_nonce;
+        }
+        require!(nonce == taker_nonce.next_nonce, BridgeError::InvalidNonce);
+        taker_nonce.next_nonce = nonce.checked_add(1).ok_or(BridgeError::Overflow)?;
+
+        // ── Release ──────────────────────────────────────────────────
+        let order = &ctx.accounts.order;
+        let order_id = order.order_id;
+        let client_order_id = order.client_order_id;
+        let maker_key = order.maker;
+        let bump = order.bump;
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        let tip = bps_of(amount, ctx.accounts.fee_config.relayer_tip_bps)?.min(fee);
+        if tip > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.escrow_token_account.to_account_info(),
+                        mint: ctx.accounts.sgor_mint.to_account_info(),
+                        to: ctx.accounts.relayer_token_account.to_account_info(),
+                        authority: ctx.accounts.order.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                tip,
+                ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+            )?;
+            ctx.accounts.escrow_token_account.reload()?;
+        }
+
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: &ctx.accounts.escrow_token_account,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            Some((ctx.accounts.fee_token_account.to_account_info(), fee - tip)),
+            &Payout::single(ctx.accounts.taker_token_account.to_account_info()),
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Filled)?;
+        let order_key = order.key();
+        let gorbagana_recipient = order.gorbagana_recipient;
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_filled(amount)?;
+
+        ctx.accounts.receipt.set_inner(FillReceipt {
+            order_key,
+            order_id,
+            maker: maker_key,
+            taker: taker_key,
+            amount,
+            relayer: relayer_key,
+            gorbagana_tx_sig,
+            slot: clock.slot,
+            bump: ctx.bumps.receipt,
+        });
+
+        let rent_returned_lamports = escrow_rent + ctx.accounts.order.to_account_info().lamports();
+        let event = OrderFilled {
+            order_key,
+            order_id,
+            maker: maker_key,
+            taker: taker_key,
+            amount,
+            expected_ggor_amount,
+            fee,
+            relayer: relayer_key,
+            gorbagana_tx_sig,
+            taker_gorbagana_address,
+            gorbagana_recipient,
+            status: OrderStatus::Filled,
+            client_order_id,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+            escrow_balance,
+            self_fill: taker_key == maker_key,
+        };
+        emit!(event.clone()); // log event, kept for one release
+        emit_cpi!(event);
+        emit!(DelegatedFillExecuted {
+            order_key,
+            taker: taker_key,
+            relayer: relayer_key,
+            nonce,
+            tip,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // HTLC CLAIM — Taker releases a hash-locked escrow with the preimage
+    // ═══════════════════════════════════════════════════════════════════
+    /// Releases an HTLC order's escrow to its `allowed_taker` on a preimage
+    /// whose sha256 is the order's `hashlock`. No relayer is involved: the
+    /// maker reveals the preimage when claiming the taker's hash-locked
+    /// gGOR on Gorbagana, and the taker reuses it here, so either both legs
+    /// settle or neither does.
+    ///
+    /// Lands up to and including the expiry; from the next slot (or
+    /// second) on, only the maker's refund does, so a claim and a refund
+    /// can never both succeed. The taker's Gorbagana escrow must expire
+    /// well before this order, leaving time to claim here after the maker
+    /// claims there.
+    ///
+    /// Allowed while paused: once the preimage is public, blocking the
+    /// claim would let the maker refund after taking the gGOR. The protocol
+    /// fee is taken as in `fill_order`. `HtlcClaimed` carries the preimage.
+    pub fn claim_htlc_order(
+        ctx: Context<ClaimHtlcOrder>,
+        preimage: [u8; HASHLOCK_LEN],
+    ) -> Result<()> {
+        DenyEntry::require_not_listed(&ctx.accounts.taker_deny)?;
+        DenyEntry::require_not_listed(&ctx.accounts.maker_deny)?;
+        let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        order.require_escrow_balance(&ctx.accounts.escrow_token_account)?;
+        let taker_key = ctx.accounts.taker.key();
+
+        // ── Validation ───────────────────────────────────────────────
+        let hashlock = order.hashlock.ok_or(BridgeError::NotHashLocked)?;
+        require!(order.status == OrderStatus::Open, BridgeError::OrderAlreadyFilled);
+        require!(
+            !order.expiry.has_passed(&Clock::get()?),
+            BridgeError::OrderExpired
+        );
+        require!(order.is_taker_allowed(&taker_key), BridgeError::TakerNotAllowed);
+        verify_preimage(&hashlock, &preimage)?;
+
+        // ── Release ──────────────────────────────────────────────────
+        let amount = order.amount;
+        let order_id = order.order_id;
+        let client_order_id = order.client_order_id;
+        let maker_key = order.maker;
+        let bump = order.bump;
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        let fee = fee_for(amount, ctx.accounts.fee_config.fee_bps)?;
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: &ctx.accounts.escrow_token_account,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            Some((ctx.accounts.fee_token_account.to_account_info(), fee)),
+            &Payout::single(ctx.accounts.taker_token_account.to_account_info()),
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Filled)?;
+        let order_key = order.key();
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_filled(amount)?;
+
+        let rent_returned_lamports = escrow_rent + ctx.accounts.order.to_account_info().lamports();
+        let event = HtlcClaimed {
+            order_key,
+            order_id,
+            maker: maker_key,
+            taker: taker_key,
+            amount,
+            fee,
+            preimage,
+            status: OrderStatus::Filled,
+            client_order_id,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+        };
+        emit!(event.clone()); // log event, kept for one release
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // TWO-PHASE FILL — lock_order → settle_order (or expire_lock)
+    // ═══════════════════════════════════════════════════════════════════
+    /// Reserves an open order for the signing taker for `lock_slots`, so
+    /// the taker can pay on Gorbagana knowing the escrow cannot be cancelled
+    /// or taken by someone else meanwhile. The taker posts
+    /// `BridgeConfig.lock_bond_lamports` of native SOL, held on the order
+    /// PDA and recorded in `Order.lock_bond`: refunded in full on settle,
+    /// split between the maker and the keeper by `expire_lock` if the lock
+    /// lapses. `taker_gorbagana_address` is stored and reported again at
+    /// settle.
+    ///
+    /// The price is fixed at lock: `OrderLocked.expected_ggor_amount` is
+    /// what the taker owes on Gorbagana, and settle reports it again for
+    /// the attestor.
+    pub fn lock_order(
+        ctx: Context<LockOrder>,
+        lock_slots: u64,
+        taker_gorbagana_address: Pubkey,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        DenyEntry::require_not_listed(&ctx.accounts.taker_deny)?;
+        DenyEntry::require_not_listed(&ctx.accounts.maker_deny)?;
+        let order = &ctx.accounts.order;
+        require!(order.hashlock.is_none(), BridgeError::HtlcClaimRequired);
+        require!(order.status == OrderStatus::Open, BridgeError::InvalidOrderState);
+        require!(
+            order.is_taker_allowed(&ctx.accounts.taker.key()),
+            BridgeError::TakerNotAllowed
+        );
+        config.require_not_self_fill(order, &ctx.accounts.taker.key())?;
+        require!(
+            taker_gorbagana_address != Pubkey::default(),
+            BridgeError::InvalidGorbaganaAddress
+        );
+        require!(
+            lock_slots > 0 && lock_slots <= config.max_lock_slots,
+            BridgeError::InvalidLockDuration
+        );
+
+        let clock = Clock::get()?;
+        require!(order.is_active(&clock), BridgeError::OrderNotActive);
+        order.require_fill_delay(&clock, config.min_fill_delay_slots)?;
+        require!(!order.expiry.has_passed(&clock), BridgeError::OrderExpired);
+        let lock_expiry_slot = clock
+            .slot
+            .checked_add(lock_slots)
+            .ok_or(BridgeError::Overflow)?;
+
+        let bond = config.lock_bond_lamports;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.taker.to_account_info(),
+                    to: ctx.accounts.order.to_account_info(),
+                },
+            ),
+            bond,
+        )?;
+
+        let taker_key = ctx.accounts.taker.key();
+        let order = &mut ctx.accounts.order;
+        let expected_ggor_amount = order.current_expected(&clock);
+        order.transition(OrderStatus::Locked)?;
+        order.locked_by = taker_key;
+        order.lock_expiry_slot = lock_expiry_slot;
+        order.lock_bond = bond;
+        order.taker_gorbagana_address = taker_gorbagana_address;
+        order.locked_expected_ggor_amount = expected_ggor_amount;
+
+        emit!(OrderLocked {
+            order_key: order.key(),
+            order_id: order.order_id,
+            taker: taker_key,
+            taker_gorbagana_address,
+            lock_expiry_slot,
+            bond,
+            expected_ggor_amount,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    /// Releases the escrow to the taker holding the lock, on a registered
+    /// relayer's attestation of the Gorbagana payment. Must land before the
+    /// lock lapses. The bond goes back to the taker; the order closes.
+    /// The protocol fee is taken exactly as in `fill_order`.
+    /// `OrderFilled.expected_ggor_amount` is the price fixed at lock.
+    ///
+    /// With `BridgeConfig.challenge_slots` set, the attestation only moves
+    /// the order to `PendingRelease` and records the receipt; the release
+    /// happens in `finalize_settlement` once the window has passed.
+    pub fn settle_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleOrder<'info>>,
+        gorbagana_tx_sig: [u8; TX_SIG_LEN],
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        RelayerEntry::record_attestation(&ctx.accounts.relayer_entry)?;
+        let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        let escrow_balance = order.require_escrow_balance(&ctx.accounts.escrow_token_account)?;
+        require!(
+            gorbagana_tx_sig != [0u8; TX_SIG_LEN],
+            BridgeError::InvalidTxSignature
+        );
+        require!(order.status == OrderStatus::Locked, BridgeError::InvalidOrderState);
+        let slot = Clock::get()?.slot;
+        require!(slot <= order.lock_expiry_slot, BridgeError::LockExpired);
+
+        let amount = order.amount;
+        let order_id = order.order_id;
+        let client_order_id = order.client_order_id;
+        let maker_key = order.maker;
+        let bond = order.lock_bond;
+        let taker_gorbagana_address = order.taker_gorbagana_address;
+        let bump = order.bump;
+        let order_key = order.key();
+        let taker_key = ctx.accounts.taker.key();
+
+        ctx.accounts.receipt.set_inner(FillReceipt {
+            order_key,
+            order_id,
+            maker: maker_key,
+            taker: taker_key,
+            amount,
+            relayer: ctx.accounts.relayer.key(),
+            gorbagana_tx_sig,
+            slot,
+            bump: ctx.bumps.receipt,
+        });
+
+        // ── Challenge window: hold the escrow until finalize ─────────
+        if config.challenge_slots > 0 {
+            let release_slot = slot
+                .checked_add(config.challenge_slots)
+                .ok_or(BridgeError::Overflow)?;
+            let order = &mut ctx.accounts.order;
+            order.transition(OrderStatus::PendingRelease)?;
+            order.release_slot = release_slot;
+            order.pending_receipt = ctx.accounts.receipt.key();
+
+            emit!(SettlementPending {
+                order_key,
+                order_id,
+                maker: maker_key,
+                taker: taker_key,
+                relayer: ctx.accounts.relayer.key(),
+                gorbagana_tx_sig,
+                release_slot,
+                status: order.status,
+            });
+            return Ok(());
+        }
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        let fee = fee_for(amount, ctx.accounts.fee_config.fee_bps)?;
+        let payout = order.payout_legs(
+            &ctx.accounts.taker.key(),
+            amount - fee,
+            ctx.accounts.taker_token_account.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: &ctx.accounts.escrow_token_account,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            Some((ctx.accounts.fee_token_account.to_account_info(), fee)),
+            &payout,
+        )?;
+
+        // Refund the bond before the close sweeps the PDA's lamports
+        let order_info = ctx.accounts.order.to_account_info();
+        let taker_info = ctx.accounts.taker.to_account_info();
+        **order_info.try_borrow_mut_lamports()? -= bond;
+        **taker_info.try_borrow_mut_lamports()? += bond;
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Filled)?;
+        let gorbagana_recipient = order.gorbagana_recipient;
+        let expected_ggor_amount = order.locked_expected_ggor_amount;
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_filled(amount)?;
+
+        let rent_returned_lamports = escrow_rent + order_info.lamports();
+        let event = OrderFilled {
+            order_key,
+            order_id,
+            maker: maker_key,
+            taker: taker_key,
+            amount,
+            expected_ggor_amount,
+            fee,
+            relayer: ctx.accounts.relayer.key(),
+            gorbagana_tx_sig,
+            taker_gorbagana_address,
+            gorbagana_recipient,
+            status: OrderStatus::Filled,
+            client_order_id,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+            escrow_balance,
+            self_fill: taker_key == maker_key,
+        };
+        emit!(event.clone()); // log event, kept for one release
+        emit_cpi!(event);
+        payout.emit(order_key, order_id);
+
+        ctx.accounts.order.close(ctx.accounts.maker.to_account_info())
+    }
+
+    /// Reopens an order whose lock lapsed without settlement. Anyone may
+    /// call it. The bond is slashed: `keeper_share_bps` of it goes to the
+    /// caller for the cleanup, the rest to the maker for the lost time.
+    /// The bond is zeroed on the order, so it can only be claimed once.
+    pub fn expire_lock(ctx: Context<ExpireLock>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let order = &ctx.accounts.order;
+        require!(order.status == OrderStatus::Locked, BridgeError::InvalidOrderState);
+        require!(
+            Clock::get()?.slot > order.lock_expiry_slot,
+            BridgeError::LockNotExpired
+        );
+
+        let bond = order.lock_bond;
+        let taker = order.locked_by;
+        let keeper_share = bps_of(bond, config.keeper_share_bps)?;
+        let maker_share = bond - keeper_share;
+
+        let order_info = ctx.accounts.order.to_account_info();
+        let maker_info = ctx.accounts.maker.to_account_info();
+        let caller_info = ctx.accounts.caller.to_account_info();
+        **order_info.try_borrow_mut_lamports()? -= bond;
+        **maker_info.try_borrow_mut_lamports()? += maker_share;
+        **caller_info.try_borrow_mut_lamports()? += keeper_share;
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Open)?;
+        order.clear_lock();
+
+        emit!(LockExpired {
+            order_key: order.key(),
+            order_id: order.order_id,
+            taker,
+            bond_slashed: bond,
+            maker_share,
+            caller: caller_info.key(),
+            keeper_share,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // CHALLENGE PERIOD — Delayed release of attested settlements
+    // ═══════════════════════════════════════════════════════════════════
+    /// Releases a `PendingRelease` order once its challenge window has
+    /// passed, exactly as an immediate `settle_order` would have: escrow to
+    /// the taker less the protocol fee (current `fee_bps`), bond back to
+    /// the taker, order closed to the maker. Anyone may call it.
+    pub fn finalize_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeSettlement<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused()?;
+        let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        let escrow_balance = order.require_escrow_balance(&ctx.accounts.escrow_token_account)?;
+        require!(
+            order.status == OrderStatus::PendingRelease,
+            BridgeError::InvalidOrderState
+        );
+        require!(
+            Clock::get()?.slot >= order.release_slot,
+            BridgeError::ChallengeWindowOpen
+        );
+
+        let amount = order.amount;
+        let order_id = order.order_id;
+        let client_order_id = order.client_order_id;
+        let maker_key = order.maker;
+        let bond = order.lock_bond;
+        let taker_gorbagana_address = order.taker_gorbagana_address;
+        let bump = order.bump;
+        let order_key = order.key();
+        let taker_key = ctx.accounts.taker.key();
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        let fee = fee_for(amount, ctx.accounts.fee_config.fee_bps)?;
+        let payout = order.payout_legs(
+            &taker_key,
+            amount - fee,
+            ctx.accounts.taker_token_account.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: &ctx.accounts.escrow_token_account,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            Some((ctx.accounts.fee_token_account.to_account_info(), fee)),
+            &payout,
+        )?;
+
+        // Refund the bond before `close = maker` sweeps the PDA's lamports
+        let order_info = ctx.accounts.order.to_account_info();
+        let taker_info = ctx.accounts.taker.to_account_info();
+        **order_info.try_borrow_mut_lamports()? -= bond;
+        **taker_info.try_borrow_mut_lamports()? += bond;
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Filled)?;
+        let gorbagana_recipient = order.gorbagana_recipient;
+        let expected_ggor_amount = order.locked_expected_ggor_amount;
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_filled(amount)?;
+
+        let rent_returned_lamports = escrow_rent + order_info.lamports();
+        let event = OrderFilled {
+            order_key,
+            order_id,
+            maker: maker_key,
+            taker: taker_key,
+            amount,
+            expected_ggor_amount,
+            fee,
+            relayer: ctx.accounts.receipt.relayer,
+            gorbagana_tx_sig: ctx.accounts.receipt.gorbagana_tx_sig,
+            taker_gorbagana_address,
+            gorbagana_recipient,
+            status: OrderStatus::Filled,
+            client_order_id,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+            escrow_balance,
+            self_fill: taker_key == maker_key,
+        };
+        emit!(event.clone()); // log event, kept for one release
+        emit_cpi!(event);
+        payout.emit(order_key, order_id);
+
+        Ok(())
+    }
+
+    /// Freezes a `PendingRelease` order before its window ends, for the
+    /// admin to settle with `resolve_dispute`. Callable by the maker, or
+    /// by an active relayer other than the one that attested. Allowed
+    /// while paused.
+    pub fn dispute_settlement(ctx: Context<DisputeSettlement>) -> Result<()> {
+        let order = &ctx.accounts.order;
+        let disputer = ctx.accounts.disputer.key();
+        require!(
+            order.status == OrderStatus::PendingRelease,
+            BridgeError::InvalidOrderState
+        );
+        require!(
+            Clock::get()?.slot < order.release_slot,
+            BridgeError::ChallengeWindowClosed
+        );
+        let second_relayer = ctx
+            .accounts
+            .relayer_entry
+            .as_ref()
+            .is_some_and(|entry| entry.active && disputer != ctx.accounts.receipt.relayer);
+        require!(
+            disputer == order.maker || second_relayer,
+            BridgeError::Unauthorized
+        );
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Disputed)?;
+
+        emit!(SettlementDisputed {
+            order_key: order.key(),
+            order_id: order.order_id,
+            disputer,
+            relayer: ctx.accounts.receipt.relayer,
+            release_slot: order.release_slot,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a dispute. `release = true` upholds the attestation: the
+    /// order returns to `PendingRelease` with the window already over, so
+    /// `finalize_settlement` can pay the taker at once. `release = false`
+    /// rejects it: the taker's bond goes to the maker and the order
+    /// reopens, lock cleared. The receipt stays, so the disputed Gorbagana
+    /// signature cannot be attested again. Admin only.
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, release: bool) -> Result<()> {
+        let order = &ctx.accounts.order;
+        require!(order.status == OrderStatus::Disputed, BridgeError::InvalidOrderState);
+        let bond = order.lock_bond;
+        let slot = Clock::get()?.slot;
+
+        let bond_slashed = if release {
+            let order = &mut ctx.accounts.order;
+            order.transition(OrderStatus::PendingRelease)?;
+            order.release_slot = slot;
+            0
+        } else {
+            let order_info = ctx.accounts.order.to_account_info();
+            let maker_info = ctx.accounts.maker.to_account_info();
+            **order_info.try_borrow_mut_lamports()? -= bond;
+            **maker_info.try_borrow_mut_lamports()? += bond;
+
+            let order = &mut ctx.accounts.order;
+            order.transition(OrderStatus::Open)?;
+            order.clear_lock();
+            bond
+        };
+
+        let order = &ctx.accounts.order;
+        emit!(DisputeResolved {
+            order_key: order.key(),
+            order_id: order.order_id,
+            admin: ctx.accounts.admin.key(),
+            released: release,
+            bond_slashed,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // CANCEL ORDER — Maker reclaims escrowed sGOR
     // ═══════════════════════════════════════════════════════════════════
-    // CANCEL ORDER — Maker reclaims escrowed sGOR
-    // ═══════════════════════════════════════════════════════════════════
+    /// Signed by the maker or the order's operator. Either way the escrow
+    /// is refunded to a maker-owned token account and the rent to the maker.
     pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        let config = &ctx.accounts.config;
         let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        order.require_cancellable(&Clock::get()?, config.cancel_delay_slots)?;
+
+        let amount = order.amount;
+        let order_id = order.order_id;
+        let client_order_id = order.client_order_id;
+        let maker_key = order.maker;
+        let bump = order.bump;
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        // Return sGOR from escrow to maker
+        let escrow_ta = &ctx.accounts.escrow_token_account;
+        let maker_ta = &ctx.accounts.maker_token_account;
+
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: escrow_ta,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            None,
+            &Payout::single(maker_ta.to_account_info()),
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.cancel()?;
+        let order_key = order.key();
+        let status = order.status;
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_cancelled(amount)?;
+
+        let rent_returned_lamports = escrow_rent + ctx.accounts.order.to_account_info().lamports();
+        let event = OrderCancelled {
+            order_key,
+            order_id,
+            maker: maker_key,
+            amount,
+            status,
+            client_order_id,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+        };
+        emit!(event.clone()); // log event, kept for one release
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // BATCH CANCEL — Pull a whole ladder in one transaction
+    // ═══════════════════════════════════════════════════════════════════
+    /// Cancels up to `MAX_BATCH_ORDERS` of the signer's orders under the
+    /// same rules as `cancel_order`, refunding each escrow and closing both
+    /// accounts. Accounts follow in `remaining_accounts`, one triple per
+    /// order:
+    ///
+    ///   `[order PDA, escrow token account, maker sGOR token account]`
+    ///
+    /// all writable. Every triple is checked as `CancelOrder` would check
+    /// it; a single invalid one aborts the whole transaction. One
+    /// `OrderCancelled` is emitted per order.
+    pub fn cancel_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelOrders<'info>>,
+    ) -> Result<()> {
+        let triples = ctx.remaining_accounts.len() / CANCEL_GROUP_LEN;
+        require!(
+            ctx.remaining_accounts.len().is_multiple_of(CANCEL_GROUP_LEN),
+            BridgeError::InvalidBatchAccounts
+        );
+        require!(
+            triples > 0 && triples <= MAX_BATCH_ORDERS,
+            BridgeError::BatchTooLarge
+        );
+
+        let config = &ctx.accounts.config;
+        let clock = Clock::get()?;
+        let maker_key = ctx.accounts.maker.key();
+        let mint_key = ctx.accounts.sgor_mint.key();
+
+        for accounts in ctx.remaining_accounts.chunks(CANCEL_GROUP_LEN) {
+            let mut order = Account::<Order>::try_from(&accounts[0])?;
+            let escrow = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+            let maker_ta = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+
+            // ── Validation ───────────────────────────────────────────
+            require!(
+                order.version == ORDER_VERSION,
+                BridgeError::UnsupportedOrderVersion
+            );
+            require_keys_eq!(order.maker, maker_key, BridgeError::Unauthorized);
+            let order_id = order.order_id;
+            let client_order_id = order.client_order_id;
+            let bump = order.bump;
+            let seeds: &[&[u8]] = &[
+                ORDER_SEED,
+                maker_key.as_ref(),
+                &order_id.to_le_bytes(),
+                &[bump],
+            ];
+            let expected = Pubkey::create_program_address(seeds, ctx.program_id)
+                .map_err(|_| BridgeError::InvalidBatchAccounts)?;
+            require_keys_eq!(expected, order.key(), BridgeError::InvalidBatchAccounts);
+            order.require_escrow(&escrow.key())?;
+            require_keys_eq!(order.mint, mint_key, BridgeError::InvalidMint);
+            require_keys_eq!(maker_ta.owner, maker_key, BridgeError::InvalidRefundAccount);
+            require_keys_eq!(maker_ta.mint, mint_key, BridgeError::InvalidMint);
+            order.require_cancellable(&clock, config.cancel_delay_slots)?;
+            order.cancel()?;
+
+            // ── Refund and close ─────────────────────────────────────
+            let amount = order.amount;
+            let order_key = order.key();
+            let escrow_rent = release_and_close_escrow(
+                EscrowRelease {
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    escrow: &escrow,
+                    mint: &ctx.accounts.sgor_mint,
+                    decimals: order.transfer_decimals(&ctx.accounts.sgor_mint),
+                    maker: ctx.accounts.maker.to_account_info(),
+                    order: order.to_account_info(),
+                    seeds,
+                },
+                None,
+                &Payout::single(maker_ta.to_account_info()),
+            )?;
+            let rent_returned_lamports = escrow_rent + order.to_account_info().lamports();
+            order.close(ctx.accounts.maker.to_account_info())?;
+
+            ctx.accounts.maker_registry.remove(&order_key);
+            ctx.accounts.stats.record_cancelled(amount)?;
+
+            let event = OrderCancelled {
+                order_key,
+                order_id,
+                maker: maker_key,
+                amount,
+                status: order.status,
+                client_order_id,
+                rent_returned_lamports,
+                mint: mint_key,
+            };
+            emit!(event.clone()); // log event, kept for one release
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // UPDATE EXPIRATION — Maker rolls an open order forward or back
+    // ═══════════════════════════════════════════════════════════════════
+    /// Moves an open order's expiration without touching the escrow. The
+    /// new deadline may be earlier or later than the current one, and may
+    /// switch between slot and timestamp form, but must be in the future
+    /// and within the configured maximum lifetime. HTLC orders keep the
+    /// expiry they were created with. Signed by the maker or the order's
+    /// operator.
+    pub fn update_expiration(ctx: Context<UpdateExpiration>, new_expiry: Expiry) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let order = &mut ctx.accounts.order;
+
+        // The expiry is the HTLC timeout the taker's Gorbagana leg was sized
+        // against; moving it could open a refund before the taker can claim
+        require!(order.hashlock.is_none(), BridgeError::HtlcTermsFixed);
+        require!(order.status != OrderStatus::Locked, BridgeError::OrderLocked);
+        require!(order.status == OrderStatus::Open, BridgeError::OrderNotOpen);
+        new_expiry.validate(&Clock::get()?, config)?;
+        new_expiry.validate_activation(order.activation_slot)?;
+
+        let old_expiry = order.expiry;
+        order.expiry = new_expiry;
+
+        emit!(OrderExpirationUpdated {
+            order_key: order.key(),
+            order_id: order.order_id,
+            maker: order.maker,
+            old_expiry,
+            new_expiry,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // UPDATE RECIPIENT — Maker changes where gGOR is paid on Gorbagana
+    // ═══════════════════════════════════════════════════════════════════
+    /// Points an open order at a new Gorbagana payout address, so a maker
+    /// who rotated wallets need not cancel and re-escrow. Relayers pick up
+    /// the change from `RecipientUpdated`.
+    pub fn update_recipient(ctx: Context<UpdateRecipient>, new_recipient: Pubkey) -> Result<()> {
+        let order = &mut ctx.accounts.order;
+
+        require!(order.status != OrderStatus::Locked, BridgeError::OrderLocked);
+        require!(order.status == OrderStatus::Open, BridgeError::OrderNotOpen);
+        require!(
+            new_recipient != Pubkey::default(),
+            BridgeError::InvalidGorbaganaAddress
+        );
+
+        let old_recipient = order.gorbagana_recipient;
+        order.gorbagana_recipient = new_recipient;
+
+        emit!(RecipientUpdated {
+            order_key: order.key(),
+            order_id: order.order_id,
+            maker: order.maker,
+            old_recipient,
+            new_recipient,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // REPLACE ORDER — Atomic cancel-and-replace for re-quoting
+    // ═══════════════════════════════════════════════════════════════════
+    /// Re-quotes an open order in place: new size, expiry, and Gorbagana
+    /// recipient. The order PDA and escrow are never closed, so takers keep
+    /// watching one address; only the escrow delta moves, topped up from or
+    /// refunded to the maker's token account. The price, auction, and taker
+    /// restrictions are unchanged. Not available for HTLC orders, whose
+    /// amount and timeout the counterparty's leg was sized against.
+    pub fn replace_order(
+        ctx: Context<ReplaceOrder>,
+        new_amount: u64,
+        new_expiry: Expiry,
+        new_gorbagana_recipient: Pubkey,
+    ) -> Result<()> {
+        // ── Validation ───────────────────────────────────────────────
+        let config = &ctx.accounts.config;
+        require!(!config.paused, BridgeError::BridgePaused);
+        DenyEntry::require_not_listed(&ctx.accounts.maker_deny)?;
+        validate_amount_at_least(new_amount, config.min_order_amount)?;
+        new_expiry.validate(&Clock::get()?, config)?;
+        require!(
+            new_gorbagana_recipient != Pubkey::default(),
+            BridgeError::InvalidGorbaganaAddress
+        );
+
+        let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        require!(order.hashlock.is_none(), BridgeError::HtlcTermsFixed);
+        require!(order.status != OrderStatus::Locked, BridgeError::OrderLocked);
+        require!(order.status == OrderStatus::Open, BridgeError::OrderNotOpen);
+        new_expiry.validate_activation(order.activation_slot)?;
+
+        let old_amount = order.amount;
+        let old_expiry = order.expiry;
+        let old_gorbagana_recipient = order.gorbagana_recipient;
+        let order_id = order.order_id;
+        let maker_key = order.maker;
+        let bump = order.bump;
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        // ── Settle escrow delta ──────────────────────────────────────
+        let decimals = order.transfer_decimals(&ctx.accounts.sgor_mint);
+        if new_amount > old_amount {
+            let top_up = new_amount - old_amount;
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.maker_token_account.to_account_info(),
+                        mint: ctx.accounts.sgor_mint.to_account_info(),
+                        to: ctx.accounts.escrow_token_account.to_account_info(),
+                        authority: ctx.accounts.maker.to_account_info(),
+                    },
+                ),
+                top_up,
+                decimals,
+            )?;
+            ctx.accounts.stats.record_created(top_up, false)?;
+        } else if new_amount < old_amount {
+            let refund = old_amount - new_amount;
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.escrow_token_account.to_account_info(),
+                        mint: ctx.accounts.sgor_mint.to_account_info(),
+                        to: ctx.accounts.maker_token_account.to_account_info(),
+                        authority: ctx.accounts.order.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                refund,
+                decimals,
+            )?;
+            ctx.accounts.stats.record_cancelled(refund)?;
+        }
+
+        // As in `create_order`, a transfer fee would leave the escrow short
+        ctx.accounts.escrow_token_account.reload()?;
+        require!(
+            ctx.accounts.escrow_token_account.amount >= new_amount,
+            BridgeError::TransferFeeNotSupported
+        );
+
+        // ── Update order in place ────────────────────────────────────
+        let order = &mut ctx.accounts.order;
+        order.amount = new_amount;
+        order.expiry = new_expiry;
+        order.gorbagana_recipient = new_gorbagana_recipient;
+
+        emit!(OrderReplaced {
+            order_key: order.key(),
+            order_id,
+            maker: maker_key,
+            old_amount,
+            new_amount,
+            old_expiry,
+            new_expiry,
+            old_gorbagana_recipient,
+            new_gorbagana_recipient,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // UPDATE OPERATOR — Maker sets or clears the order's operator key
+    // ═══════════════════════════════════════════════════════════════════
+    pub fn update_operator(
+        ctx: Context<UpdateOperator>,
+        new_operator: Option<Pubkey>,
+    ) -> Result<()> {
+        let order = &mut ctx.accounts.order;
+        require!(order.status != OrderStatus::Locked, BridgeError::OrderLocked);
+        require!(order.status == OrderStatus::Open, BridgeError::OrderNotOpen);
+
+        let old_operator = order.operator;
+        order.operator = new_operator;
+        order.zero_tail(&order.to_account_info())?;
+
+        emit!(OperatorUpdated {
+            order_key: order.key(),
+            order_id: order.order_id,
+            maker: order.maker,
+            old_operator,
+            new_operator,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // SWEEP EXCESS — Maker recovers SOL sent to the order PDA by mistake
+    // ═══════════════════════════════════════════════════════════════════
+    /// Moves every lamport on the order PDA above its rent-exempt minimum
+    /// and any held lock bond to the maker. Without it, stray transfers to
+    /// the PDA would go to whoever the order's eventual close pays. A no-op
+    /// when there is nothing to sweep. Maker only.
+    pub fn sweep_excess(ctx: Context<SweepOrderLamports>) -> Result<()> {
+        let order_info = ctx.accounts.order.to_account_info();
+        let maker_info = ctx.accounts.maker.to_account_info();
+        let held = Rent::get()?
+            .minimum_balance(order_info.data_len())
+            .checked_add(ctx.accounts.order.lock_bond)
+            .ok_or(BridgeError::Overflow)?;
+        let amount = order_info.lamports().saturating_sub(held);
+        if amount == 0 {
+            return Ok(());
+        }
+
+        **order_info.try_borrow_mut_lamports()? -= amount;
+        **maker_info.try_borrow_mut_lamports()? += amount;
+
+        emit!(SweepExcess {
+            order_key: order_info.key(),
+            order_id: ctx.accounts.order.order_id,
+            maker: maker_info.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // CLOSE EXPIRED ORDER — Permissionless sweep back to the maker
+    // ═══════════════════════════════════════════════════════════════════
+    /// Returns an expired order's sGOR to the maker and closes it. Anyone
+    /// may call; the caller earns `BridgeConfig.expiry_bounty_lamports` out
+    /// of the order rent (all of the rent if it is smaller) and the maker
+    /// gets the rest. `OrderExpiredClosed.bounty` is what was actually paid.
+    /// Allowed while paused.
+    pub fn close_expired_order(ctx: Context<CloseExpiredOrder>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+
+        require!(order.status != OrderStatus::Locked, BridgeError::OrderLocked);
+        require!(order.status == OrderStatus::Open, BridgeError::OrderNotOpen);
+        require!(
+            order.expiry.has_passed(&Clock::get()?),
+            BridgeError::OrderNotExpired
+        );
+
+        let amount = order.amount;
+        let order_id = order.order_id;
+        let maker_key = order.maker;
+        let bump = order.bump;
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: &ctx.accounts.escrow_token_account,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            None,
+            &Payout::single(ctx.accounts.maker_token_account.to_account_info()),
+        )?;
+
+        // Pay the keeper before `close = maker` sweeps the PDA's lamports
+        let order_info = ctx.accounts.order.to_account_info();
+        let caller_info = ctx.accounts.caller.to_account_info();
+        let bounty = config.expiry_bounty_lamports.min(order_info.lamports());
+        **order_info.try_borrow_mut_lamports()? -= bounty;
+        **caller_info.try_borrow_mut_lamports()? += bounty;
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Expired)?;
+        let order_key = order.key();
+        let status = order.status;
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_cancelled(amount)?;
+
+        let rent_returned_lamports = escrow_rent + ctx.accounts.order.to_account_info().lamports();
+        emit!(OrderExpiredClosed {
+            order_key,
+            order_id,
+            maker: maker_key,
+            amount,
+            caller: ctx.accounts.caller.key(),
+            bounty,
+            status,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // QUARANTINE RECOVERY — Admin sweep of long-dead orders
+    // ═══════════════════════════════════════════════════════════════════
+    /// Moves the escrow of an order that has sat expired for the whole
+    /// quarantine period (`set_quarantine_period`) into the recovery vault
+    /// for its mint, `[b"recovery_vault", mint]`, and closes the order. For
+    /// orders whose maker can no longer sign. Admin only, Open orders only;
+    /// rent still goes to the maker. Allowed while paused.
+    ///
+    /// The vault's authority is the config PDA, and nothing in this
+    /// program moves tokens out of it: returning recovered funds is a
+    /// separate, reviewed step. `QuarantineRecovered` carries the full
+    /// order so every recovery can be audited from logs alone.
+    pub fn quarantine_recover(ctx: Context<QuarantineRecover>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let order = &ctx.accounts.order;
+        order.require_escrow(&ctx.accounts.escrow_token_account.key())?;
+        require!(order.status == OrderStatus::Open, BridgeError::InvalidOrderState);
+        // Never shorter than the longest order lifetime, even if the limits
+        // were raised after the period was set
+        let quarantine_slots = config.quarantine_slots.max(config.max_expiry_slots);
+        let quarantine_seconds = config.quarantine_seconds.max(config.max_expiry_seconds);
+        let clock = Clock::get()?;
+        require!(
+            order.expiry.has_passed_by(&clock, quarantine_slots, quarantine_seconds),
+            BridgeError::QuarantineNotElapsed
+        );
+
+        let amount = order.amount;
+        let order_id = order.order_id;
+        let maker_key = order.maker;
+        let bump = order.bump;
+        let recovered_amount = ctx.accounts.escrow_token_account.amount;
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &order_id.to_le_bytes(),
+            &[bump],
+        ];
+
+        release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: &ctx.accounts.escrow_token_account,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.order.transfer_decimals(&ctx.accounts.sgor_mint),
+                maker: ctx.accounts.maker.to_account_info(),
+                order: ctx.accounts.order.to_account_info(),
+                seeds,
+            },
+            None,
+            &Payout::single(ctx.accounts.recovery_vault.to_account_info()),
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.transition(OrderStatus::Expired)?;
+        let order_key = order.key();
+        ctx.accounts.maker_registry.remove(&order_key);
+        ctx.accounts.stats.record_cancelled(amount)?;
+
+        let order = &ctx.accounts.order;
+        emit!(QuarantineRecovered {
+            order_key,
+            order_id,
+            maker: maker_key,
+            admin: ctx.accounts.admin.key(),
+            mint: order.mint,
+            amount,
+            recovered_amount,
+            recovery_vault: ctx.accounts.recovery_vault.key(),
+            gorbagana_recipient: order.gorbagana_recipient,
+            expected_ggor_amount: order.expected_ggor_amount,
+            expiry: order.expiry,
+            created_slot: order.created_slot,
+            client_order_id: order.client_order_id,
+            recovered_slot: clock.slot,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // MAKER REGISTRY — Per-maker index of open orders
+    // ═══════════════════════════════════════════════════════════════════
+    /// Packs the maker's live entries to the front of the registry and drops
+    /// the tombstones left by filled, cancelled, and expired orders, so
+    /// readers scan only live keys and new orders append in creation order
+    /// again. `create_order` reuses tombstones on its own once the array is
+    /// full; only `MAX_OPEN_ORDERS` live orders hit `TooManyOpenOrders`.
+    /// Maker only.
+    pub fn compact_registry(ctx: Context<CompactRegistry>) -> Result<()> {
+        let maker_registry = &mut ctx.accounts.maker_registry;
+        let removed = maker_registry.compact();
+
+        emit!(RegistryCompacted {
+            maker: maker_registry.maker,
+            open_orders: maker_registry.len,
+            removed,
+        });
+
+        Ok(())
+    }
+
+    /// Returns a summary of each order passed in `remaining_accounts` as
+    /// return data (Anchor calls `set_return_data`), for light clients
+    /// without an indexer; pass the registry's listed orders. Writes no
+    /// state; call it through `simulateTransaction` / `.view()`. Every
+    /// order must be a current-version order of the registry's maker.
+    ///
+    /// Return data is capped at 1024 bytes, so only the first
+    /// `MAX_LISTED_ORDERS` accounts are summarized. Any beyond that are
+    /// not read and `truncated` is set; pass them in a further call.
+    pub fn get_maker_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetMakerOrders<'info>>,
+    ) -> Result<MakerOrders> {
+        let maker = ctx.accounts.maker_registry.maker;
+        let mut orders = Vec::with_capacity(ctx.remaining_accounts.len().min(MAX_LISTED_ORDERS));
+        for info in ctx.remaining_accounts.iter().take(MAX_LISTED_ORDERS) {
+            let order = Account::<Order>::try_from(info)?;
+            require!(
+                order.version == ORDER_VERSION,
+                BridgeError::UnsupportedOrderVersion
+            );
+            require_keys_eq!(order.maker, maker, BridgeError::Unauthorized);
+            orders.push(OrderSummary {
+                order_key: info.key(),
+                amount: order.amount,
+                remaining_amount: if order.status == OrderStatus::Open {
+                    order.amount
+                } else {
+                    0
+                },
+                expiry: order.expiry,
+                status: order.status,
+            });
+        }
+
+        Ok(MakerOrders {
+            orders,
+            truncated: ctx.remaining_accounts.len() > MAX_LISTED_ORDERS,
+        })
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // MIGRATE ORDER — Upgrade v0 through v4 order accounts in place
+    // ═══════════════════════════════════════════════════════════════════
+    /// Grows a v0 order (the 253-byte layout without `version`), a v1
+    /// order (318 bytes, no stored escrow), a v2 order (392 bytes, no
+    /// payout splits), a v3 order (572 bytes, no mint), or a v4 order
+    /// (604 bytes, 24 reserved bytes) to `Order::LEN` (644 bytes) with
+    /// `realloc`, and stamps it `ORDER_VERSION`. Each version only appended
+    /// fields, so `Order::decode_versioned` reads the old fields unchanged
+    /// and defaults the rest; the order is then rewritten whole and zeroed
+    /// past its encoding.
+    /// Byte offsets are not stable across versions: a `None` `Option`
+    /// serializes as a single byte, shifting every field after it, so
+    /// readers must decode the account rather than index into it.
+    /// v2 and later orders store their escrow key and bump; for older ones
+    /// they are derived from the order's seeds, which then lacked the mint.
+    /// The mint is read from that escrow. The maker pays the rent
+    /// difference. Maker only; the order keeps its status, lock, and escrow.
+    pub fn migrate_order(ctx: Context<MigrateOrder>) -> Result<()> {
+        let order_info = ctx.accounts.order.to_account_info();
+        let old_len = order_info.data_len();
+        let mut order = {
+            let data = order_info.try_borrow_data()?;
+            require!(
+                [Order::V0_LEN, Order::V1_LEN, Order::V2_LEN, Order::V3_LEN, Order::V4_LEN]
+                    .contains(&old_len)
+                    && data[..8] == Order::DISCRIMINATOR,
+                BridgeError::UnsupportedOrderVersion
+            );
+            Order::decode_versioned(&data)?
+        };
+        require_keys_eq!(order.maker, ctx.accounts.maker.key(), BridgeError::Unauthorized);
+
+        let rent = Rent::get()?;
+        let delta = rent
+            .minimum_balance(Order::LEN)
+            .saturating_sub(rent.minimum_balance(old_len));
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.maker.to_account_info(),
+                    to: order_info.clone(),
+                },
+            ),
+            delta,
+        )?;
+        order_info.realloc(Order::LEN, true)?;
+
+        let (escrow_key, escrow_bump) = if old_len >= Order::V2_LEN {
+            (order.escrow_token_account, order.escrow_bump)
+        } else {
+            Pubkey::find_program_address(
+                &[ESCROW_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+                ctx.program_id,
+            )
+        };
+        require_keys_eq!(
+            ctx.accounts.escrow_token_account.key(),
+            escrow_key,
+            BridgeError::InvalidEscrowAccount
+        );
+        order.version = ORDER_VERSION;
+        order.escrow_token_account = escrow_key;
+        order.escrow_bump = escrow_bump;
+        order.mint = ctx.accounts.escrow_token_account.mint;
+        order.try_serialize(&mut &mut order_info.try_borrow_mut_data()?[..])?;
+        order.zero_tail(&order_info)?;
+
+        emit!(OrderMigrated {
+            order_key: order_info.key(),
+            order_id: order.order_id,
+            maker: order.maker,
+            version: order.version,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // CANCEL LEGACY ORDER — Refund orders created before order_id
+    // ═══════════════════════════════════════════════════════════════════
+    /// Orders created before `order_id` was added use the old 90-byte
+    /// layout and `[order, maker, amount]` seeds, so `Account<Order>` cannot
+    /// load them. This decodes the old layout by hand, refunds the escrow to
+    /// the maker, and closes the order. Maker only.
+    pub fn cancel_legacy_order(ctx: Context<CancelLegacyOrder>) -> Result<()> {
+        let order_info = ctx.accounts.order.to_account_info();
+
+        let legacy = {
+            let data = order_info.try_borrow_data()?;
+            require!(
+                data.len() == LegacyOrder::LEN && data[..8] == Order::DISCRIMINATOR,
+                BridgeError::NotLegacyOrder
+            );
+            LegacyOrder::deserialize(&mut &data[8..])?
+        };
+
+        require_keys_eq!(legacy.maker, ctx.accounts.maker.key(), BridgeError::Unauthorized);
+        require!(!legacy.is_filled, BridgeError::OrderAlreadyFilled);
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            legacy.maker.as_ref(),
+            &legacy.amount.to_le_bytes(),
+            &[legacy.bump],
+        ];
+        let expected = Pubkey::create_program_address(seeds, ctx.program_id)
+            .map_err(|_| BridgeError::NotLegacyOrder)?;
+        require_keys_eq!(expected, order_info.key(), BridgeError::NotLegacyOrder);
+
+        let escrow_rent = release_and_close_escrow(
+            EscrowRelease {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                escrow: &ctx.accounts.escrow_token_account,
+                mint: &ctx.accounts.sgor_mint,
+                decimals: ctx.accounts.sgor_mint.decimals,
+                maker: ctx.accounts.maker.to_account_info(),
+                order: order_info.clone(),
+                seeds,
+            },
+            None,
+            &Payout::single(ctx.accounts.maker_token_account.to_account_info()),
+        )?;
+
+        // `Account<Order>` cannot load a legacy order, so there is no
+        // `close` constraint; close it by hand instead
+        let order_lamports = order_info.lamports();
+        let rent_returned_lamports = escrow_rent + order_lamports;
+        let maker_info = ctx.accounts.maker.to_account_info();
+        **maker_info.try_borrow_mut_lamports()? = maker_info
+            .lamports()
+            .checked_add(order_lamports)
+            .ok_or(BridgeError::Overflow)?;
+        **order_info.try_borrow_mut_lamports()? = 0;
+        order_info.assign(&system_program::ID);
+        order_info.realloc(0, false)?;
+
+        emit!(LegacyOrderCancelled {
+            order_key: order_info.key(),
+            maker: legacy.maker,
+            amount: legacy.amount,
+            status: OrderStatus::Cancelled,
+            rent_returned_lamports,
+            mint: ctx.accounts.sgor_mint.key(),
+        });
+
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// ESCROW RELEASE
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Protocol fee on `amount` at `fee_bps`, rounded down.
+fn fee_for(amount: u64, fee_bps: u16) -> Result<u64> {
+    bps_of(amount, fee_bps)
+}
+
+/// `bps` basis points of `amount`, rounded down. Never exceeds `amount`
+/// for `bps <= BPS_DENOMINATOR`.
+fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(BridgeError::Overflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(share).map_err(|_| BridgeError::Overflow.into())
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// SIGNATURE VERIFICATION
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Requires the instruction just before the current one to be an ed25519
+/// program instruction checking one signature by `signer` over exactly
+/// `message`, with all three held in its own data. The runtime has already
+/// rejected the transaction if that signature was invalid; this confirms
+/// it was the signature we need.
+fn require_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)? as usize;
+    require!(current > 0, BridgeError::InvalidTakerSignature);
+    let ix = load_instruction_at_checked(current - 1, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        BridgeError::InvalidTakerSignature
+    );
+
+    // Layout: count (u8), padding (u8), then one offsets record of seven
+    // u16s: signature offset and ix index, pubkey offset and ix index,
+    // message offset, size, and ix index.
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, BridgeError::InvalidTakerSignature);
+    let read = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let (signature_ix, pubkey_offset, pubkey_ix) = (read(4), read(6), read(8));
+    let (message_offset, message_size, message_ix) = (read(10), read(12), read(14));
+    let this_ix = u16::MAX as usize;
+    require!(
+        signature_ix == this_ix && pubkey_ix == this_ix && message_ix == this_ix,
+        BridgeError::InvalidTakerSignature
+    );
+
+    let pubkey = data.get(pubkey_offset..pubkey_offset + 32);
+    let signed = data.get(message_offset..message_offset + message_size);
+    require!(
+        pubkey == Some(signer.as_ref()) && signed == Some(message),
+        BridgeError::InvalidTakerSignature
+    );
+    Ok(())
+}
+
+/// The escrow being released and what closing it needs: the order PDA
+/// signs with `seeds`, and the escrow's rent goes back to `maker`, who
+/// paid it at create.
+struct EscrowRelease<'a, 'info> {
+    token_program: AccountInfo<'info>,
+    escrow: &'a InterfaceAccount<'info, TokenAccount>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    decimals: u8,
+    maker: AccountInfo<'info>,
+    order: AccountInfo<'info>,
+    seeds: &'a [&'a [u8]],
+}
+
+/// Moves the escrow's entire balance out as `payout` says, then closes the
+/// escrow token account with its rent going to the maker. The full balance
+/// is sent, not just the order amount, so stray deposits cannot leave a
+/// non-zero balance that blocks the close.
+///
+/// When `fee` is given, that many tokens go to the fee account first, then
+/// each of `payout.others` is paid, and `payout.to` receives the rest. Zero
+/// amounts skip their transfer entirely.
+///
+/// Returns the lamports the close refunded to `maker`, measured on the
+/// maker's balance around the close.
+fn release_and_close_escrow<'info>(
+    release: EscrowRelease<'_, 'info>,
+    fee: Option<(AccountInfo<'info>, u64)>,
+    payout: &Payout<'info>,
+) -> Result<u64> {
+    let EscrowRelease {
+        token_program,
+        escrow,
+        mint,
+        decimals,
+        maker,
+        order,
+        seeds,
+    } = release;
+    let mut remaining = escrow.amount;
+
+    let payments = fee.into_iter().chain(payout.others.iter().cloned());
+    for (account, amount) in payments.filter(|(_, amount)| *amount > 0) {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: escrow.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: account,
+                    authority: order.clone(),
+                },
+                &[seeds],
+            ),
+            amount,
+            decimals,
+        )?;
+        remaining = remaining.checked_sub(amount).ok_or(BridgeError::Overflow)?;
+    }
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TransferChecked {
+                from: escrow.to_account_info(),
+                mint: mint.to_account_info(),
+                to: payout.to.clone(),
+                authority: order.clone(),
+            },
+            &[seeds],
+        ),
+        remaining,
+        decimals,
+    )?;
+
+    let maker_before = maker.lamports();
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: maker.clone(),
+            authority: order,
+        },
+        &[seeds],
+    ))?;
+    Ok(maker.lamports() - maker_before)
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// MANUAL ACCOUNT INIT
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Creates the PDA `account` with `space` bytes owned by `owner`, funded
+/// rent-exempt by `payer`. Mirrors Anchor's `init`: an address someone has
+/// already sent lamports to is topped up, allocated, and assigned instead
+/// of failing `create_account`.
+fn init_pda_account<'info>(
+    payer: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                system_program::CreateAccount {
+                    from: payer,
+                    to: account,
+                },
+                &[seeds],
+            ),
+            required,
+            space as u64,
+            owner,
+        );
+    }
+
+    require_keys_eq!(
+        *account.owner,
+        system_program.key(),
+        BridgeError::InvalidBatchAccounts
+    );
+    if current < required {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer,
+                    to: account.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program,
+            system_program::Assign {
+                account_to_assign: account,
+            },
+            &[seeds],
+        ),
+        owner,
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// ACCOUNT STRUCTS
+// ═══════════════════════════════════════════════════════════════════════
+
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct AddRelayer<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        init,
+        seeds = [b"relayer", relayer.as_ref()],
+        bump,
+        payer = admin,
+        space = RelayerEntry::LEN,
+    )]
+    pub relayer_entry: Account<'info, RelayerEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct RemoveRelayer<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"relayer", relayer.as_ref()],
+        bump = relayer_entry.bump,
+    )]
+    pub relayer_entry: Account<'info, RelayerEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct SetRelayerPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer", relayer.as_ref()],
+        bump = relayer_entry.bump,
+    )]
+    pub relayer_entry: Account<'info, RelayerEntry>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [b"config"],
+        bump,
+        payer = admin,
+        space = BridgeConfig::LEN,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    /// This program; locates its program data account
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub program: Program<'info, SolanaBridge>,
+
+    /// Only the upgrade authority may initialize, so the admin cannot be
+    /// claimed by whoever calls first after deployment
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct DenyAddress<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        init,
+        seeds = [b"deny", address.as_ref()],
+        bump,
+        payer = admin,
+        space = DenyEntry::LEN,
+    )]
+    pub deny_entry: Account<'info, DenyEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct AllowAddress<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"deny", address.as_ref()],
+        bump = deny_entry.bump,
+    )]
+    pub deny_entry: Account<'info, DenyEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct AddMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        init,
+        seeds = [b"mint_allow", mint.as_ref()],
+        bump,
+        payer = admin,
+        space = MintAllow::LEN,
+    )]
+    pub mint_allow: Account<'info, MintAllow>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RemoveMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"mint_allow", mint.as_ref()],
+        bump = mint_allow.bump,
+    )]
+    pub mint_allow: Account<'info, MintAllow>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [b"fee_config"],
+        bump,
+        payer = admin,
+        space = FeeConfig::LEN,
+    )]
+    pub fee_config: Account<'info, FeeConfig>,
+
+    /// This program; locates its program data account
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub program: Program<'info, SolanaBridge>,
+
+    /// Only the upgrade authority may initialize, so the admin cannot be
+    /// claimed by whoever calls first after deployment
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_config"],
+        bump = fee_config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub fee_config: Account<'info, FeeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [b"stats"],
+        bump,
+        payer = payer,
+        space = BridgeStats::LEN,
+    )]
+    pub stats: Account<'info, BridgeStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(params: CreateOrderParams)]
+pub struct CreateOrder<'info> {
+    /// Wallet or program PDA signing through `invoke_signed`. It pays the
+    /// order's rent, so a PDA maker must be a funded system account.
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// Order PDA — deterministic from maker + order_id
+    #[account(
+        init,
+        seeds = [ORDER_SEED, maker.key().as_ref(), &params.order_id.to_le_bytes()],
+        bump,
+        payer = maker,
+        space = Order::LEN,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Maker's open-order index, created with the maker's first order
+    #[account(
+        init_if_needed,
+        seeds = [b"maker_registry", maker.key().as_ref()],
+        bump,
+        payer = maker,
+        space = MakerRegistry::LEN,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow token account for sGOR
+    /// Initialized with the order PDA as authority
+    #[account(
+        init,
+        token::mint = sgor_mint,
+        token::authority = order,
+        token::token_program = token_program,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), &params.order_id.to_le_bytes(), sgor_mint.key().as_ref()],
+        bump,
+        payer = maker,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Maker's token account (source of deposit); mint checked in the
+    /// handler
+    #[account(mut)]
+    pub maker_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow mint; checked against the config and allowlist in the
+    /// handler
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Allowlist entry for the mint; only its existence is read
+    #[account(seeds = [b"mint_allow", sgor_mint.key().as_ref()], bump)]
+    pub mint_allow: UncheckedAccount<'info>,
+
+    /// Bridge config, read for order limits
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Denylist entry for the maker; only its existence is read
+    #[account(seeds = [b"deny", maker.key().as_ref()], bump)]
+    pub maker_deny: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Accounts shared by every order in a `create_orders` batch. The order
+/// and escrow PDAs follow in `remaining_accounts`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateOrders<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// Maker's open-order index, created with the maker's first order
+    #[account(
+        init_if_needed,
+        seeds = [b"maker_registry", maker.key().as_ref()],
+        bump,
+        payer = maker,
+        space = MakerRegistry::LEN,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Maker's token account (source of every deposit); mint checked in
+    /// the handler
+    #[account(mut)]
+    pub maker_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow mint; checked against the config and allowlist in the
+    /// handler
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Allowlist entry for the mint; only its existence is read
+    #[account(seeds = [b"mint_allow", sgor_mint.key().as_ref()], bump)]
+    pub mint_allow: UncheckedAccount<'info>,
+
+    /// Bridge config, read for order limits
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Denylist entry for the maker; only its existence is read
+    #[account(seeds = [b"deny", maker.key().as_ref()], bump)]
+    pub maker_deny: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(gorbagana_tx_sig: [u8; TX_SIG_LEN])]
+pub struct FillOrder<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// Registered attestor confirming the Gorbagana payment
+    pub relayer: Signer<'info>,
+
+    /// CHECK: Relayer's entry PDA; must exist and be active (see
+    /// `RelayerEntry::record_attestation`)
+    #[account(mut, seeds = [b"relayer", relayer.key().as_ref()], bump)]
+    pub relayer_entry: UncheckedAccount<'info>,
+
+    /// Bridge config, read for the pause flag
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Denylist entry for the taker; only its existence is read
+    #[account(seeds = [b"deny", taker.key().as_ref()], bump)]
+    pub taker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist entry for the maker; only its existence is read
+    #[account(seeds = [b"deny", order.maker.as_ref()], bump)]
+    pub maker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Maker receives only the order and escrow rent on Solana (gets
+    /// gGOR on Gorbagana). Validated via order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account (holds maker's escrowed sGOR)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Taker's sGOR token account (receives escrowed sGOR)
+    #[account(
+        mut,
+        constraint = taker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = taker_token_account.owner == taker.key() @ BridgeError::InvalidTakerTokenAccount,
+    )]
+    pub taker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"fee_config"], bump = fee_config.bump)]
+    pub fee_config: Account<'info, FeeConfig>,
+
+    /// Fee recipient's sGOR token account (receives the protocol fee)
+    #[account(
+        mut,
+        constraint = fee_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = fee_token_account.owner == fee_config.fee_recipient @ BridgeError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fill receipt keyed by the Gorbagana signature; `init` rejects reuse
+    #[account(
+        init,
+        seeds = [b"receipt", &gorbagana_tx_sig[..32], &gorbagana_tx_sig[32..]],
+        bump,
+        payer = taker,
+        space = FillReceipt::LEN,
+    )]
+    pub receipt: Box<Account<'info, FillReceipt>>,
+
+    /// Optional accounting receipt keyed by the order; `init` makes it
+    /// write-once
+    #[account(
+        init,
+        seeds = [b"receipt", order.key().as_ref()],
+        bump,
+        payer = taker,
+        space = OrderReceipt::LEN,
+    )]
+    pub order_receipt: Option<Box<Account<'info, OrderReceipt>>>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(gorbagana_tx_sig: [u8; TX_SIG_LEN])]
+pub struct FillOrderDelegated<'info> {
+    /// Registered attestor executing the fill; pays all fees and rent
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: Relayer's entry PDA; must exist and be active (see
+    /// `RelayerEntry::record_attestation`)
+    #[account(mut, seeds = [b"relayer", relayer.key().as_ref()], bump)]
+    pub relayer_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Taker receiving the sGOR. Not a signer; proven by the
+    /// ed25519 signature and the delegate approval in the handler.
+    pub taker: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"taker_nonce", taker.key().as_ref()],
+        bump,
+        payer = relayer,
+        space = TakerNonce::LEN,
+    )]
+    pub taker_nonce: Box<Account<'info, TakerNonce>>,
+
+    /// Bridge config, read for the pause flag
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Denylist entry for the taker; only its existence is read
+    #[account(seeds = [b"deny", taker.key().as_ref()], bump)]
+    pub taker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist entry for the maker; only its existence is read
+    #[account(seeds = [b"deny", order.maker.as_ref()], bump)]
+    pub maker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the order and escrow rent. Validated via
+    /// order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account (holds maker's escrowed sGOR)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Taker's sGOR token account (receives escrowed sGOR); must name the
+    /// relayer as delegate
+    #[account(
+        mut,
+        constraint = taker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = taker_token_account.owner == taker.key() @ BridgeError::InvalidTakerTokenAccount,
+    )]
+    pub taker_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Relayer's sGOR token account (receives the tip)
+    #[account(
+        mut,
+        constraint = relayer_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = relayer_token_account.owner == relayer.key() @ BridgeError::InvalidFeeRecipient,
+    )]
+    pub relayer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(seeds = [b"fee_config"], bump = fee_config.bump)]
+    pub fee_config: Box<Account<'info, FeeConfig>>,
+
+    /// Fee recipient's sGOR token account (receives the protocol fee)
+    #[account(
+        mut,
+        constraint = fee_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = fee_token_account.owner == fee_config.fee_recipient @ BridgeError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Fill receipt keyed by the Gorbagana signature; `init` rejects reuse
+    #[account(
+        init,
+        seeds = [b"receipt", &gorbagana_tx_sig[..32], &gorbagana_tx_sig[32..]],
+        bump,
+        payer = relayer,
+        space = FillReceipt::LEN,
+    )]
+    pub receipt: Box<Account<'info, FillReceipt>>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Instructions sysvar, read for the taker's ed25519 signature
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimHtlcOrder<'info> {
+    /// The order's `allowed_taker`
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// CHECK: Denylist entry for the taker; only its existence is read
+    #[account(seeds = [b"deny", taker.key().as_ref()], bump)]
+    pub taker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist entry for the maker; only its existence is read
+    #[account(seeds = [b"deny", order.maker.as_ref()], bump)]
+    pub maker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the order and escrow rent. Validated via
+    /// order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account (holds maker's escrowed sGOR)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Taker's sGOR token account (receives escrowed sGOR)
+    #[account(
+        mut,
+        constraint = taker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = taker_token_account.owner == taker.key() @ BridgeError::InvalidTakerTokenAccount,
+    )]
+    pub taker_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(seeds = [b"fee_config"], bump = fee_config.bump)]
+    pub fee_config: Box<Account<'info, FeeConfig>>,
+
+    /// Fee recipient's sGOR token account (receives the protocol fee)
+    #[account(
+        mut,
+        constraint = fee_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = fee_token_account.owner == fee_config.fee_recipient @ BridgeError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteFill<'info> {
+    #[account(
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Bridge config, read for the pause flag
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    #[account(seeds = [b"fee_config"], bump = fee_config.bump)]
+    pub fee_config: Account<'info, FeeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CloseReceipt<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = taker,
+        has_one = taker @ BridgeError::Unauthorized,
+        seeds = [b"receipt", order_receipt.order_key.as_ref()],
+        bump = order_receipt.bump,
+    )]
+    pub order_receipt: Account<'info, OrderReceipt>,
+}
+
+#[derive(Accounts)]
+pub struct LockOrder<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Bridge config, read for the pause flag
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Denylist entry for the taker; only its existence is read
+    #[account(seeds = [b"deny", taker.key().as_ref()], bump)]
+    pub taker_deny: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist entry for the maker; only its existence is read
+    #[account(seeds = [b"deny", order.maker.as_ref()], bump)]
+    pub maker_deny: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(gorbagana_tx_sig: [u8; TX_SIG_LEN])]
+pub struct SettleOrder<'info> {
+    /// Registered attestor confirming the Gorbagana payment; pays for the
+    /// fill receipt
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: Relayer's entry PDA; must exist and be active (see
+    /// `RelayerEntry::record_attestation`)
+    #[account(mut, seeds = [b"relayer", relayer.key().as_ref()], bump)]
+    pub relayer_entry: UncheckedAccount<'info>,
+
+    /// Bridge config, read for the pause flag
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Lock holder; receives the bond refund. Must match order.locked_by.
+    #[account(
+        mut,
+        constraint = taker.key() == order.locked_by @ BridgeError::Unauthorized
+    )]
+    pub taker: AccountInfo<'info>,
+
+    /// CHECK: Rent destination. Validated via order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    /// Closed by the handler on an immediate release; left open while a
+    /// challenge window runs
+    #[account(
+        mut,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account (holds maker's escrowed sGOR)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lock holder's sGOR token account (receives escrowed sGOR)
+    #[account(
+        mut,
+        constraint = taker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = taker_token_account.owner == order.locked_by @ BridgeError::Unauthorized,
+    )]
+    pub taker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"fee_config"], bump = fee_config.bump)]
+    pub fee_config: Account<'info, FeeConfig>,
+
+    /// Fee recipient's sGOR token account (receives the protocol fee)
+    #[account(
+        mut,
+        constraint = fee_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = fee_token_account.owner == fee_config.fee_recipient @ BridgeError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fill receipt keyed by the Gorbagana signature; `init` rejects reuse
+    #[account(
+        init,
+        seeds = [b"receipt", &gorbagana_tx_sig[..32], &gorbagana_tx_sig[32..]],
+        bump,
+        payer = relayer,
+        space = FillReceipt::LEN,
+    )]
+    pub receipt: Box<Account<'info, FillReceipt>>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireLock<'info> {
+    /// Keeper expiring the lock; receives the keeper share of the bond
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// CHECK: Receives the maker share of the bond. Validated via order.maker
+    /// constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Bridge config, read for the keeper share
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeSettlement<'info> {
+    /// Bridge config, read for the pause flag
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Lock holder; receives the bond refund. Must match order.locked_by.
+    #[account(
+        mut,
+        constraint = taker.key() == order.locked_by @ BridgeError::Unauthorized
+    )]
+    pub taker: AccountInfo<'info>,
+
+    /// CHECK: Rent destination. Validated via order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Receipt recorded by the pending `settle_order`
+    #[account(constraint = receipt.key() == order.pending_receipt @ BridgeError::InvalidOrderState)]
+    pub receipt: Box<Account<'info, FillReceipt>>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account (holds maker's escrowed sGOR)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lock holder's sGOR token account (receives escrowed sGOR)
+    #[account(
+        mut,
+        constraint = taker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = taker_token_account.owner == order.locked_by @ BridgeError::Unauthorized,
+    )]
+    pub taker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"fee_config"], bump = fee_config.bump)]
+    pub fee_config: Account<'info, FeeConfig>,
+
+    /// Fee recipient's sGOR token account (receives the protocol fee)
+    #[account(
+        mut,
+        constraint = fee_token_account.mint == order.mint @ BridgeError::InvalidMint,
+        constraint = fee_token_account.owner == fee_config.fee_recipient @ BridgeError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeSettlement<'info> {
+    /// The order's maker, or a second relayer
+    pub disputer: Signer<'info>,
+
+    /// Disputer's relayer entry; omitted when the maker disputes
+    #[account(seeds = [b"relayer", disputer.key().as_ref()], bump = relayer_entry.bump)]
+    pub relayer_entry: Option<Account<'info, RelayerEntry>>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Receipt recorded by the pending `settle_order`
+    #[account(constraint = receipt.key() == order.pending_receipt @ BridgeError::InvalidOrderState)]
+    pub receipt: Account<'info, FillReceipt>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    /// CHECK: Receives the bond when the attestation is rejected.
+    /// Validated via order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    /// Maker or the order's designated operator
+    #[account(
+        constraint = order.is_maker_or_operator(&authority.key()) @ BridgeError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Refund and rent destination. Always the order's maker
+    /// (enforced by has_one on order), whoever signs.
+    #[account(mut)]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account
+    #[account(mut)]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Maker's sGOR token account (receives refund). Must be owned by the
+    /// maker so an operator cannot redirect funds.
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == order.maker @ BridgeError::InvalidRefundAccount,
+        constraint = maker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Bridge config, read for the cancel delay
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts shared by every order in a `cancel_orders` batch. The
+/// per-order triples follow in `remaining_accounts`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelOrders<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", maker.key().as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Bridge config, read for the cancel delay
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// sGOR mint every escrow in the batch must hold, needed by
+    /// `transfer_checked`
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateExpiration<'info> {
+    /// Maker or the order's designated operator
+    #[account(
+        constraint = order.is_maker_or_operator(&authority.key()) @ BridgeError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Bridge config, read for the expiry limit
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecipient<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+}
+
+#[derive(Accounts)]
+pub struct ReplaceOrder<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account; checked against the order in the handler
+    #[account(mut)]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Maker's sGOR token account, source of top-ups and destination of
+    /// refunds
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == maker.key() @ BridgeError::InvalidRefundAccount,
+        constraint = maker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+    )]
+    pub maker_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Bridge config, read for the pause flag and order limits
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Denylist entry for the maker; only its existence is read
+    #[account(seeds = [b"deny", maker.key().as_ref()], bump)]
+    pub maker_deny: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOperator<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+}
+
+#[derive(Accounts)]
+pub struct SweepOrderLamports<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredOrder<'info> {
+    /// Keeper closing the order; receives the bounty
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// Bridge config, read for the bounty
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Receives escrow and order rent. Validated via order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account (holds maker's escrowed sGOR)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Maker's sGOR token account (receives the refund)
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == order.maker @ BridgeError::InvalidRefundAccount,
+        constraint = maker_token_account.mint == order.mint @ BridgeError::InvalidMint,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct QuarantineRecover<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    /// CHECK: Receives escrow and order rent. Validated via order.maker constraint.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Maker's open-order index
+    #[account(
+        mut,
+        seeds = [b"maker_registry", order.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Box<Account<'info, BridgeStats>>,
+
+    /// Escrow sGOR token account (holds maker's escrowed sGOR)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Per-mint vault for recovered escrow, created on first use. Owned by
+    /// the config PDA.
+    #[account(
+        init_if_needed,
+        token::mint = sgor_mint,
+        token::authority = config,
+        token::token_program = token_program,
+        seeds = [b"recovery_vault", sgor_mint.key().as_ref()],
+        bump,
+        payer = admin,
+    )]
+    pub recovery_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == order.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CompactRegistry<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [b"maker_registry", maker_registry.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+}
+
+/// The orders to summarize follow in `remaining_accounts`.
+#[derive(Accounts)]
+pub struct GetMakerOrders<'info> {
+    #[account(
+        seeds = [b"maker_registry", maker_registry.maker.as_ref()],
+        bump = maker_registry.bump,
+    )]
+    pub maker_registry: Box<Account<'info, MakerRegistry>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLegacyOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: Old-layout order; discriminator, length, maker, and PDA seeds
+    /// are verified in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub order: UncheckedAccount<'info>,
+
+    /// Escrow sGOR token account controlled by the legacy order
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Maker's sGOR token account (receives refund)
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == maker.key() @ BridgeError::InvalidRefundAccount,
+        constraint = maker_token_account.mint == escrow_token_account.mint @ BridgeError::InvalidMint,
+    )]
+    pub maker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow's sGOR mint, needed by `transfer_checked`
+    #[account(constraint = sgor_mint.key() == escrow_token_account.mint @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: v0 to v4 order that `Account<Order>` cannot load yet;
+    /// discriminator, length, and maker are verified in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub order: UncheckedAccount<'info>,
+
+    /// The order's escrow, read for its mint; checked against the order's
+    /// seeds in the handler
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// STATE
+// ═══════════════════════════════════════════════════════════════════════
+
+#[account]
+pub struct Order {
+    pub maker: Pubkey,                 // 32 - Solana maker address
+    pub order_id: u64,                 // 8  - Maker-chosen id used in PDA seeds
+    pub amount: u64,                   // 8  - sGOR amount in base units (see `decimals`)
+    pub expected_ggor_amount: u64,     // 8  - gGOR owed to the maker on Gorbagana
+    pub gorbagana_recipient: Pubkey,   // 32 - Maker's Gorbagana address (for gGOR)
+    pub expiry: Expiry,                // 9  - Slot or unix-timestamp deadline
+    pub created_slot: u64,             // 8  - Start of the cancel delay
+    pub created_timestamp: i64,        // 8  - Start of a timestamp-mode auction
+    pub status: OrderStatus,           // 1
+    pub locked_by: Pubkey,             // 32 - Taker holding the lock (default if none)
+    pub lock_expiry_slot: u64,         // 8
+    pub lock_bond: u64,                // 8  - SOL bond held on the PDA for the lock (lamports, not sGOR)
+    pub taker_gorbagana_address: Pubkey, // 32 - Lock holder's Gorbagana payout address
+    pub allowed_taker: Option<Pubkey>, // 33 - Sole permitted taker (None = public)
+    pub end_expected_ggor_amount: Option<u64>, // 9 - Dutch auction floor (None = fixed price)
+    pub locked_expected_ggor_amount: u64, // 8 - Price fixed by lock_order (0 if unlocked)
+    pub bump: u8,                      // 1
+    pub version: u8,                   // 1  - Layout version (ORDER_VERSION)
+    pub client_order_id: u64,          // 8  - Maker's correlation tag (0 on migrated orders)
+    pub hashlock: Option<[u8; HASHLOCK_LEN]>, // 33 - HTLC sha256 hashlock (None = attested fills)
+    pub escrow_token_account: Pubkey,  // 32 - This order's escrow, fixed at creation
+    pub escrow_bump: u8,               // 1
+    pub activation_slot: u64,          // 8  - Earliest fill/lock slot (0 = immediately)
+    pub operator: Option<Pubkey>,      // 33 - May cancel and roll the expiry; never receives funds
+    pub decimals: Option<u8>,          // 2  - Escrow mint decimals at creation (None on older orders)
+    pub payout_splits: [PayoutSplit; MAX_PAYOUT_SPLITS], // 136 - Release shares; first `payout_split_count` used
+    pub payout_split_count: u8,        // 1  - 0 = whole release to the taker
+    pub release_slot: u64,             // 8  - End of the challenge window (PendingRelease only)
+    pub pending_receipt: Pubkey,       // 32 - Receipt of the pending settlement (default if none)
+    pub mint: Pubkey,                  // 32 - Escrow mint, fixed at creation
+    pub reserved: [u8; ORDER_RESERVED_LEN], // 64 - Zeroed; new fields are carved from here
+}
+
+impl Order {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // maker
+        + 8   // order_id
+        + 8   // amount
+        + 8   // expected_ggor_amount
+        + 32  // gorbagana_recipient
+        + 1 + 8  // expiry
+        + 8   // created_slot
+        + 8   // created_timestamp
+        + 1   // status
+        + 32  // locked_by
+        + 8   // lock_expiry_slot
+        + 8   // lock_bond
+        + 32  // taker_gorbagana_address
+        + 1 + 32 // allowed_taker
+        + 1 + 8  // end_expected_ggor_amount
+        + 8   // locked_expected_ggor_amount
+        + 1   // bump
+        + 1   // version
+        + 8   // client_order_id
+        + 1 + HASHLOCK_LEN // hashlock
+        + 32  // escrow_token_account
+        + 1   // escrow_bump
+        + 8   // activation_slot
+        + 1 + 32 // operator
+        + 1 + 1  // decimals
+        + PayoutSplit::LEN * MAX_PAYOUT_SPLITS // payout_splits
+        + 1   // payout_split_count
+        + 8   // release_slot
+        + 32  // pending_receipt
+        + 32  // mint
+        + ORDER_RESERVED_LEN; // reserved = 644 bytes total
+
+    /// Size of a v0 order, which ends at `bump`.
+    pub const V0_LEN: usize = 253;
+
+    /// Size of a v1 order, which ends at a 23-byte `reserved` after
+    /// `hashlock`.
+    pub const V1_LEN: usize = 318;
+
+    /// Size of a v2 order, which ends at a 21-byte `reserved` after
+    /// `decimals`.
+    pub const V2_LEN: usize = 392;
+
+    /// Size of a v3 order, which ends at a 24-byte `reserved` after
+    /// `pending_receipt`.
+    pub const V3_LEN: usize = 572;
+
+    /// Size of a v4 order, which ends at a 24-byte `reserved` after
+    /// `mint`.
+    pub const V4_LEN: usize = 604;
+
+    /// Decodes an order stored in any layout from v0 to v4, as identified
+    /// by `data.len()`. Fields are read one version at a time, stopping at
+    /// the last field of the stored layout; everything later takes its
+    /// default. Decoding the whole account as the current layout instead
+    /// would read the old layout's trailing bytes, which an `Option`
+    /// cleared before `zero_tail` existed may have left as stale data.
+    pub fn decode_versioned(data: &[u8]) -> Result<Self> {
+        let buf = &mut &data[8..];
+        let mut order = Order {
+            maker: AnchorDeserialize::deserialize(buf)?,
+            order_id: AnchorDeserialize::deserialize(buf)?,
+            amount: AnchorDeserialize::deserialize(buf)?,
+            expected_ggor_amount: AnchorDeserialize::deserialize(buf)?,
+            gorbagana_recipient: AnchorDeserialize::deserialize(buf)?,
+            expiry: AnchorDeserialize::deserialize(buf)?,
+            created_slot: AnchorDeserialize::deserialize(buf)?,
+            created_timestamp: AnchorDeserialize::deserialize(buf)?,
+            status: AnchorDeserialize::deserialize(buf)?,
+            locked_by: AnchorDeserialize::deserialize(buf)?,
+            lock_expiry_slot: AnchorDeserialize::deserialize(buf)?,
+            lock_bond: AnchorDeserialize::deserialize(buf)?,
+            taker_gorbagana_address: AnchorDeserialize::deserialize(buf)?,
+            allowed_taker: AnchorDeserialize::deserialize(buf)?,
+            end_expected_ggor_amount: AnchorDeserialize::deserialize(buf)?,
+            locked_expected_ggor_amount: AnchorDeserialize::deserialize(buf)?,
+            bump: AnchorDeserialize::deserialize(buf)?,
+            version: 0,
+            client_order_id: 0,
+            hashlock: None,
+            escrow_token_account: Pubkey::default(),
+            escrow_bump: 0,
+            activation_slot: 0,
+            operator: None,
+            decimals: None,
+            payout_splits: [PayoutSplit::default(); MAX_PAYOUT_SPLITS],
+            payout_split_count: 0,
+            release_slot: 0,
+            pending_receipt: Pubkey::default(),
+            mint: Pubkey::default(),
+            reserved: [0u8; ORDER_RESERVED_LEN],
+        };
+        if data.len() >= Self::V1_LEN {
+            order.version = AnchorDeserialize::deserialize(buf)?;
+            order.client_order_id = AnchorDeserialize::deserialize(buf)?;
+            order.hashlock = AnchorDeserialize::deserialize(buf)?;
+        }
+        if data.len() >= Self::V2_LEN {
+            order.escrow_token_account = AnchorDeserialize::deserialize(buf)?;
+            order.escrow_bump = AnchorDeserialize::deserialize(buf)?;
+            order.activation_slot = AnchorDeserialize::deserialize(buf)?;
+            order.operator = AnchorDeserialize::deserialize(buf)?;
+            order.decimals = AnchorDeserialize::deserialize(buf)?;
+        }
+        if data.len() >= Self::V3_LEN {
+            order.payout_splits = AnchorDeserialize::deserialize(buf)?;
+            order.payout_split_count = AnchorDeserialize::deserialize(buf)?;
+            order.release_slot = AnchorDeserialize::deserialize(buf)?;
+            order.pending_receipt = AnchorDeserialize::deserialize(buf)?;
+        }
+        if data.len() >= Self::V4_LEN {
+            order.mint = AnchorDeserialize::deserialize(buf)?;
+        }
+        Ok(order)
+    }
+
+    /// Zeroes `info`'s data past this order's encoding. Borsh writes a
+    /// `None` as a single byte, so clearing an `Option` shortens the
+    /// encoding, and Anchor's write on exit leaves the bytes past the new
+    /// end as they were. Call after any change that can shrink an order.
+    pub fn zero_tail(&self, info: &AccountInfo) -> Result<()> {
+        let end = 8 + self.try_to_vec()?.len();
+        info.try_borrow_mut_data()?[end..].fill(0);
+        Ok(())
+    }
+
+    /// Requires `escrow` to be the escrow created with this order. Owner
+    /// checks alone would accept any token account the order PDA controls.
+    pub fn require_escrow(&self, escrow: &Pubkey) -> Result<()> {
+        require_keys_eq!(*escrow, self.escrow_token_account, BridgeError::InvalidEscrowAccount);
+        Ok(())
+    }
+
+    /// Requires the escrow to still hold the order's amount before a
+    /// release and returns its actual balance. Orders are filled whole, so
+    /// nothing of `amount` has been released yet. A drained escrow (an
+    /// exotic token extension, a bad migration) fails here with
+    /// `EscrowBalanceMismatch` rather than partway through the transfers;
+    /// a surplus is released along with the rest.
+    pub fn require_escrow_balance(&self, escrow: &TokenAccount) -> Result<u64> {
+        require!(escrow.amount >= self.amount, BridgeError::EscrowBalanceMismatch);
+        Ok(escrow.amount)
+    }
+
+    /// True once the order's activation slot has been reached.
+    pub fn is_active(&self, clock: &Clock) -> bool {
+        clock.slot >= self.activation_slot
+    }
+
+    /// The order's payout splits in use (empty for single-recipient orders).
+    pub fn payout_splits(&self) -> &[PayoutSplit] {
+        &self.payout_splits[..self.payout_split_count as usize]
+    }
+
+    /// Splits `output`, the sGOR a fill releases after the protocol fee,
+    /// across the order's payout splits. `accounts` holds one token account
+    /// per split, in order, each owned by its recipient (the taker for
+    /// `Pubkey::default()`) and of the order's mint. Every leg but the first
+    /// gets its share rounded down; the first gets the rest. Orders without
+    /// splits pay the whole release to `taker_account`.
+    pub fn payout_legs<'info>(
+        &self,
+        taker: &Pubkey,
+        output: u64,
+        taker_account: AccountInfo<'info>,
+        accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Payout<'info>> {
+        let splits = self.payout_splits();
+        if splits.is_empty() {
+            return Ok(Payout::single(taker_account));
+        }
+        require!(
+            accounts.len() == splits.len(),
+            BridgeError::InvalidPayoutAccounts
+        );
+
+        let mut legs = Vec::with_capacity(splits.len());
+        for (split, info) in splits.iter().zip(accounts) {
+            let recipient = if split.recipient == Pubkey::default() {
+                *taker
+            } else {
+                split.recipient
+            };
+            let token_account = InterfaceAccount::<TokenAccount>::try_from(info)?;
+            require_keys_eq!(token_account.owner, recipient, BridgeError::InvalidPayoutAccounts);
+            require_keys_eq!(token_account.mint, self.mint, BridgeError::InvalidMint);
+            legs.push(PayoutLeg {
+                recipient,
+                token_account: info.key(),
+                bps: split.bps,
+                amount: bps_of(output, split.bps)?,
+            });
+        }
+        let others: u64 = legs[1..].iter().map(|leg| leg.amount).sum();
+        legs[0].amount = output - others;
+
+        Ok(Payout {
+            to: accounts[0].clone(),
+            others: accounts[1..]
+                .iter()
+                .cloned()
+                .zip(legs[1..].iter().map(|leg| leg.amount))
+                .collect(),
+            legs,
+        })
+    }
+
+    /// Decimals for `transfer_checked` on this order's escrow: the ones
+    /// recorded at creation, so the token program rejects any transfer if
+    /// `mint` disagrees. Orders created before decimals were recorded fall
+    /// back to `mint`'s.
+    pub fn transfer_decimals(&self, mint: &Mint) -> u8 {
+        self.decimals.unwrap_or(mint.decimals)
+    }
+
+    /// Requires `min_fill_delay_slots` to have passed since the order was
+    /// created, so it cannot be created and taken in one slot.
+    pub fn require_fill_delay(&self, clock: &Clock, min_fill_delay_slots: u64) -> Result<()> {
+        require!(
+            clock.slot >= self.created_slot.saturating_add(min_fill_delay_slots),
+            BridgeError::FillTooEarly
+        );
+        Ok(())
+    }
+
+    /// True if `key` may cancel this order or move its expiry (the maker
+    /// or its operator).
+    pub fn is_maker_or_operator(&self, key: &Pubkey) -> bool {
+        *key == self.maker || self.operator == Some(*key)
+    }
+
+    /// Public orders accept anyone; private orders only `allowed_taker`.
+    pub fn is_taker_allowed(&self, taker: &Pubkey) -> bool {
+        self.allowed_taker.is_none_or(|allowed| allowed == *taker)
+    }
+
+    /// Moves the order to `next`, failing with `InvalidOrderState` if the
+    /// transition table does not allow it. Every status change goes
+    /// through here.
+    pub fn transition(&mut self, next: OrderStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next),
+            BridgeError::InvalidOrderState
+        );
+        self.status = next;
+        Ok(())
+    }
+
+    /// Drops the lock and any pending settlement. The bond must already
+    /// have been paid out of the PDA.
+    pub fn clear_lock(&mut self) {
+        self.locked_by = Pubkey::default();
+        self.lock_expiry_slot = 0;
+        self.lock_bond = 0;
+        self.taker_gorbagana_address = Pubkey::default();
+        self.locked_expected_ggor_amount = 0;
+        self.release_slot = 0;
+        self.pending_receipt = Pubkey::default();
+    }
+
+    /// Marks an order that passed `require_cancellable` as cancelled. A
+    /// lapsed lock is first released back to Open, since a Locked order
+    /// cannot be cancelled directly.
+    pub fn cancel(&mut self) -> Result<()> {
+        if self.status == OrderStatus::Locked {
+            self.transition(OrderStatus::Open)?;
+        }
+        self.transition(OrderStatus::Cancelled)
+    }
+
+    /// Cancel rules shared by `cancel_order` and `cancel_orders`. A live
+    /// lock means the taker may already have paid on Gorbagana; a lapsed
+    /// lock does not block, and its bond goes to the maker with the order's
+    /// rent on close. An HTLC order is refundable only after its expiry,
+    /// once `claim_htlc_order` can no longer land.
+    pub fn require_cancellable(&self, clock: &Clock, cancel_delay_slots: u64) -> Result<()> {
+        let slot = clock.slot;
+        require!(
+            self.hashlock.is_none() || self.expiry.has_passed(clock),
+            BridgeError::HtlcNotExpired
+        );
+        require!(
+            self.status != OrderStatus::Locked || slot > self.lock_expiry_slot,
+            BridgeError::OrderLocked
+        );
+        require!(
+            self.status == OrderStatus::Open || self.status == OrderStatus::Locked,
+            BridgeError::OrderAlreadyFilled
+        );
+        require!(
+            slot >= self.created_slot.saturating_add(cancel_delay_slots),
+            BridgeError::CancelTooEarly
+        );
+        Ok(())
+    }
+
+    /// gGOR owed to the maker now. Fixed-price orders always return
+    /// `expected_ggor_amount`; Dutch auctions decay from it to
+    /// `end_expected_ggor_amount` between creation and expiry, measured in
+    /// slots or seconds to match the expiry.
+    pub fn current_expected(&self, clock: &Clock) -> u64 {
+        let Some(end) = self.end_expected_ggor_amount else {
+            return self.expected_ggor_amount;
+        };
+        let (start, finish, now) = match self.expiry {
+            Expiry::Slot { slot } => (self.created_slot, slot, clock.slot),
+            Expiry::UnixTimestamp { timestamp } => (
+                self.created_timestamp.max(0) as u64,
+                timestamp.max(0) as u64,
+                clock.unix_timestamp.max(0) as u64,
+            ),
+        };
+        dutch_expected_at(self.expected_ggor_amount, end, start, finish, now)
+    }
+
+    /// Validation and pricing for an attested fill of `fill_amount` now.
+    /// The one pricing path behind `fill_order`, `fill_order_delegated`,
+    /// and `quote_fill`, so quotes and fills cannot diverge.
+    ///
+    /// Orders fill in full, so `fill_amount` must equal `amount`.
+    pub fn quote_fill(
+        &self,
+        fill_amount: u64,
+        clock: &Clock,
+        config: &BridgeConfig,
+        fee_bps: u16,
+    ) -> Result<FillQuote> {
+        require!(self.hashlock.is_none(), BridgeError::HtlcClaimRequired);
+        require!(self.status != OrderStatus::Locked, BridgeError::OrderLocked);
+        require!(self.status == OrderStatus::Open, BridgeError::OrderAlreadyFilled);
+        require!(self.is_active(clock), BridgeError::OrderNotActive);
+        self.require_fill_delay(clock, config.min_fill_delay_slots)?;
+        require!(!self.expiry.has_passed(clock), BridgeError::OrderExpired);
+        require!(
+            fill_amount == self.amount,
+            BridgeError::PartialFillNotSupported
+        );
+
+        let fee = fee_for(fill_amount, fee_bps)?;
+        Ok(FillQuote {
+            fill_amount,
+            fee,
+            output_amount: fill_amount - fee,
+            expected_ggor_amount: self.current_expected(clock),
+        })
+    }
+}
+
+/// Result of `Order::quote_fill`, returned by the `quote_fill` instruction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FillQuote {
+    /// sGOR released from escrow
+    pub fill_amount: u64,
+    /// Protocol fee taken from the released sGOR
+    pub fee: u64,
+    /// sGOR the taker receives (`fill_amount - fee`)
+    pub output_amount: u64,
+    /// gGOR owed to the maker on Gorbagana at the current auction price
+    pub expected_ggor_amount: u64,
+}
+
+/// One order in `MakerOrders`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct OrderSummary {
+    pub order_key: Pubkey,
+    /// Escrowed size in base units of the order's mint
+    pub amount: u64,
+    /// Size a new taker can still take: `amount` while Open, else 0
+    pub remaining_amount: u64,
+    pub expiry: Expiry,
+    pub status: OrderStatus,
+}
+
+impl OrderSummary {
+    pub const LEN: usize = 32 + 8 + 8 + (1 + 8) + 1;
+}
+
+/// Result of the `get_maker_orders` instruction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct MakerOrders {
+    pub orders: Vec<OrderSummary>,
+    /// More accounts were passed than fit in the return data
+    pub truncated: bool,
+}
+
+impl MakerOrders {
+    /// Vec length prefix plus `truncated`.
+    pub const HEADER_LEN: usize = 4 + 1;
+}
+
+/// Order lifecycle on the Solana side.
+///   Open   → Locked (lock_order) | Filled (fill_order, claim_htlc_order)
+///            | Cancelled (cancel) | Expired (close_expired_order)
+///   Locked → Filled (settle_order) | Open (expire_lock, or a cancel of a
+///            lapsed lock, which reopens before cancelling)
+///            | PendingRelease (settle_order with a challenge window)
+///   PendingRelease → Filled (finalize_settlement)
+///            | Disputed (dispute_settlement)
+///   Disputed → PendingRelease (resolve_dispute upholds)
+///            | Open (resolve_dispute rejects)
+/// Filled, Cancelled, and Expired are terminal. The account closes in the
+/// same instruction that reaches them, so they are seen only in events.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Open,
+    Locked,
+    Filled,
+    Cancelled,
+    Expired,
+    PendingRelease,
+    Disputed,
+}
+
+impl OrderStatus {
+    /// The legal-transition table above.
+    pub fn can_transition_to(self, next: OrderStatus) -> bool {
+        use OrderStatus::*;
+        matches!(
+            (self, next),
+            (Open, Locked)
+                | (Open, Filled)
+                | (Open, Cancelled)
+                | (Open, Expired)
+                | (Locked, Filled)
+                | (Locked, Open)
+                | (Locked, PendingRelease)
+                | (PendingRelease, Filled)
+                | (PendingRelease, Disputed)
+                | (Disputed, PendingRelease)
+                | (Disputed, Open)
+        )
+    }
+}
+
+/// A maker-defined share of an order's escrow release.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayoutSplit {
+    /// Wallet that owns the receiving token account; `Pubkey::default()`
+    /// for the filling taker
+    pub recipient: Pubkey,
+    /// Share of the release in basis points
+    pub bps: u16,
+}
+
+impl PayoutSplit {
+    pub const LEN: usize = 32 + 2;
+
+    /// Validates `splits` (at most `MAX_PAYOUT_SPLITS` non-zero shares
+    /// summing to `BPS_DENOMINATOR`, or none) and packs them into the
+    /// fixed-size layout stored on `Order`.
+    pub fn pack(splits: &[PayoutSplit]) -> Result<([PayoutSplit; MAX_PAYOUT_SPLITS], u8)> {
+        let mut packed = [PayoutSplit::default(); MAX_PAYOUT_SPLITS];
+        if splits.is_empty() {
+            return Ok((packed, 0));
+        }
+        require!(
+            splits.len() <= MAX_PAYOUT_SPLITS
+                && splits.iter().all(|split| split.bps > 0)
+                && splits.iter().map(|split| split.bps as u64).sum::<u64>() == BPS_DENOMINATOR,
+            BridgeError::InvalidPayoutSplits
+        );
+        packed[..splits.len()].copy_from_slice(splits);
+        Ok((packed, splits.len() as u8))
+    }
+}
+
+/// How a release is paid out: `to` receives the rest of the escrow in
+/// `release_and_close_escrow`, `others` are paid their amounts first. For
+/// an order with payout splits, `to` is the first leg's account.
+pub struct Payout<'info> {
+    pub to: AccountInfo<'info>,
+    pub others: Vec<(AccountInfo<'info>, u64)>,
+    pub legs: Vec<PayoutLeg>,
+}
+
+impl<'info> Payout<'info> {
+    /// The whole release to `to`, with no split legs.
+    pub fn single(to: AccountInfo<'info>) -> Self {
+        Payout {
+            to,
+            others: Vec::new(),
+            legs: Vec::new(),
+        }
+    }
+
+    /// Emits `PayoutSplitPaid` for a split release; no-op otherwise.
+    pub fn emit(&self, order_key: Pubkey, order_id: u64) {
+        if !self.legs.is_empty() {
+            emit!(PayoutSplitPaid {
+                order_key,
+                order_id,
+                legs: self.legs.clone(),
+            });
+        }
+    }
+}
+
+/// One recipient's part of a split release, reported in `PayoutSplitPaid`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct PayoutLeg {
+    pub recipient: Pubkey,
+    pub token_account: Pubkey,
+    pub bps: u16,
+    pub amount: u64,
+}
+
+/// Mint, order limits, lock/cancel timing, and lock bond, as set by
+/// `initialize_config` and `update_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub sgor_mint: Pubkey,
+    pub min_order_amount: u64,
+    pub max_expiry_slots: u64,
+    pub max_expiry_seconds: u64,
+    pub cancel_delay_slots: u64,
+    pub max_lock_slots: u64,
+    pub lock_bond_lamports: u64,
+    pub keeper_share_bps: u16,
+}
+
+impl ConfigParams {
+    /// Rejects zero limits, a zero bond, and a keeper share above 100%.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_order_amount > 0
+                && self.max_expiry_slots > 0
+                && self.max_expiry_seconds > 0
+                && self.max_lock_slots > 0,
+            BridgeError::InvalidConfig
+        );
+        require!(
+            self.lock_bond_lamports > 0 && self.keeper_share_bps as u64 <= BPS_DENOMINATOR,
+            BridgeError::InvalidConfig
+        );
+        Ok(())
+    }
+}
+
+/// Arguments of `create_order`; see there for what each one does.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateOrderParams {
+    pub order_id: u64,
+    pub amount: u64,
+    pub expected_ggor_amount: u64, // gGOR the maker wants on Gorbagana
+    pub expiry: Expiry, // slot or unix-timestamp deadline
+    pub gorbagana_recipient: Pubkey, // Maker's Gorbagana address to receive gGOR
+    pub allowed_taker: Option<Pubkey>, // Some = private order for this taker only
+    pub end_expected_ggor_amount: Option<u64>, // Some = Dutch auction down to this price
+    pub client_order_id: u64, // Off-chain correlation tag, echoed in events
+    pub hashlock: Option<[u8; HASHLOCK_LEN]>, // Some = HTLC, claimed by preimage
+    pub activation_slot: Option<u64>, // Some = not fillable before this slot
+    pub operator: Option<Pubkey>, // Some = may cancel and roll the expiry
+    pub payout_splits: Vec<PayoutSplit>, // Empty = whole release to the taker
+}
+
+/// One order of a `create_orders` batch. Batched orders are public and
+/// fixed-price; use `create_order` for private or Dutch-auction orders.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OrderParams {
+    pub order_id: u64,
+    pub amount: u64,
+    pub expected_ggor_amount: u64,
+    pub expiry: Expiry,
+    pub gorbagana_recipient: Pubkey,
+    pub client_order_id: u64,
+}
+
+/// When an order stops accepting fills. Slot deadlines drift with the
+/// cluster's slot rate; timestamp deadlines follow the validators' clock.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    Slot { slot: u64 },
+    UnixTimestamp { timestamp: i64 },
+}
+
+impl Expiry {
+    /// True once the deadline is behind `clock`; the deadline itself is
+    /// still fillable.
+    pub fn has_passed(&self, clock: &Clock) -> bool {
+        match *self {
+            Expiry::Slot { slot } => clock.slot > slot,
+            Expiry::UnixTimestamp { timestamp } => clock.unix_timestamp > timestamp,
+        }
+    }
+
+    /// True once the deadline is more than `slots` (slot deadlines) or
+    /// `seconds` (timestamp deadlines) behind `clock`.
+    pub fn has_passed_by(&self, clock: &Clock, slots: u64, seconds: u64) -> bool {
+        match *self {
+            Expiry::Slot { slot } => clock.slot > slot.saturating_add(slots),
+            Expiry::UnixTimestamp { timestamp } => {
+                clock.unix_timestamp > timestamp.saturating_add(seconds.min(i64::MAX as u64) as i64)
+            }
+        }
+    }
+
+    /// Requires the deadline to be in the future and within the config's
+    /// maximum lifetime for its form.
+    pub fn validate(&self, clock: &Clock, config: &BridgeConfig) -> Result<()> {
+        match *self {
+            Expiry::Slot { slot } => {
+                validate_expiration_within(clock.slot, slot, config.max_expiry_slots)
+            }
+            Expiry::UnixTimestamp { timestamp } => validate_expiration_timestamp_within(
+                clock.unix_timestamp,
+                timestamp,
+                config.max_expiry_seconds,
+            ),
+        }
+    }
+
+    /// Requires `activation_slot` to fall before a slot deadline. Timestamp
+    /// deadlines cannot be compared against a slot and are not checked.
+    pub fn validate_activation(&self, activation_slot: u64) -> Result<()> {
+        if let Expiry::Slot { slot } = *self {
+            require!(activation_slot < slot, BridgeError::InvalidActivationSlot);
+        }
+        Ok(())
+    }
+}
+
+/// Order layout before `order_id` was added (seeds used `amount`).
+/// Only read by `cancel_legacy_order`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyOrder {
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub gorbagana_recipient: Pubkey,
+    pub expiration_slot: u64,
+    pub is_filled: bool,
+    pub bump: u8,
+}
+
+impl LegacyOrder {
+    pub const LEN: usize = 90;
+}
+
+/// Permanent record linking a Solana fill to its Gorbagana payment.
+/// Seeds: `[b"receipt", sig[..32], sig[32..]]`.
+#[account]
+pub struct FillReceipt {
+    pub order_key: Pubkey,                     // 32
+    pub order_id: u64,                         // 8
+    pub maker: Pubkey,                         // 32
+    pub taker: Pubkey,                         // 32
+    pub amount: u64,                           // 8
+    pub relayer: Pubkey,                       // 32
+    pub gorbagana_tx_sig: [u8; TX_SIG_LEN],    // 64
+    pub slot: u64,                             // 8  - Solana slot of the fill
+    pub bump: u8,                              // 1
+}
+
+impl FillReceipt {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // order_key
+        + 8   // order_id
+        + 32  // maker
+        + 32  // taker
+        + 8   // amount
+        + 32  // relayer
+        + TX_SIG_LEN // gorbagana_tx_sig
+        + 8   // slot
+        + 1;  // bump
+}
+
+/// Accounting record of a fill, looked up by order. Unlike `FillReceipt`,
+/// which guards against signature replay and must never close, this one is
+/// optional and its rent can be reclaimed by the taker.
+#[account]
+pub struct OrderReceipt {
+    pub order_key: Pubkey,                     // 32
+    pub order_id: u64,                         // 8
+    pub maker: Pubkey,                         // 32
+    pub taker: Pubkey,                         // 32
+    pub amount: u64,                           // 8
+    pub gorbagana_recipient: Pubkey,           // 32
+    pub gorbagana_tx_sig: [u8; TX_SIG_LEN],    // 64
+    pub slot: u64,                             // 8
+    pub timestamp: i64,                        // 8
+    pub bump: u8,                              // 1
+}
+
+impl OrderReceipt {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // order_key
+        + 8   // order_id
+        + 32  // maker
+        + 32  // taker
+        + 8   // amount
+        + 32  // gorbagana_recipient
+        + TX_SIG_LEN // gorbagana_tx_sig
+        + 8   // slot
+        + 8   // timestamp
+        + 1;  // bump
+}
+
+/// One authorized attestor. Seeds: `[b"relayer", relayer]`. Removing a
+/// relayer closes the entry; pausing only clears `active`.
+#[account]
+pub struct RelayerEntry {
+    pub relayer: Pubkey,    // 32
+    pub active: bool,       // 1
+    pub attestations: u64,  // 8  - Fills and settlements co-signed
+    pub bump: u8,           // 1
+}
+
+impl RelayerEntry {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // relayer
+        + 1   // active
+        + 8   // attestations
+        + 1;  // bump
+
+    /// Fails with `UnauthorizedRelayer` unless the entry at `info` exists
+    /// and is active, then counts one attestation. Callers pin `info` to
+    /// the signer's `[b"relayer", relayer]` PDA.
+    pub fn record_attestation(info: &AccountInfo) -> Result<()> {
+        require!(!info.data_is_empty(), BridgeError::UnauthorizedRelayer);
+        let mut entry = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(entry.active, BridgeError::UnauthorizedRelayer);
+        entry.attestations = entry
+            .attestations
+            .checked_add(1)
+            .ok_or(BridgeError::Overflow)?;
+        entry.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+}
+
+#[account]
+pub struct BridgeConfig {
+    pub admin: Pubkey,            // 32
+    pub sgor_mint: Pubkey,        // 32
+    pub min_order_amount: u64,    // 8
+    pub max_expiry_slots: u64,    // 8
+    pub max_expiry_seconds: u64,  // 8
+    pub cancel_delay_slots: u64,  // 8
+    pub max_lock_slots: u64,      // 8
+    pub lock_bond_lamports: u64,  // 8
+    pub keeper_share_bps: u16,    // 2
+    pub paused: bool,             // 1
+    pub bump: u8,                 // 1
+    pub expiry_bounty_lamports: u64, // 8
+    pub min_fill_delay_slots: u64, // 8
+    pub allow_self_fill: bool,    // 1
+    pub max_creates_per_window: u32, // 4 - 0 = no per-maker create limit
+    pub window_slots: u64,        // 8
+    pub challenge_slots: u64,     // 8 - 0 = settlements release immediately
+    pub quarantine_slots: u64,    // 8
+    pub quarantine_seconds: u64,  // 8
+}
+
+impl BridgeConfig {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // admin
+        + 32  // sgor_mint
+        + 8   // min_order_amount
+        + 8   // max_expiry_slots
+        + 8   // max_expiry_seconds
+        + 8   // cancel_delay_slots
+        + 8   // max_lock_slots
+        + 8   // lock_bond_lamports
+        + 2   // keeper_share_bps
+        + 1   // paused
+        + 1   // bump
+        + 8   // expiry_bounty_lamports
+        + 8   // min_fill_delay_slots
+        + 1   // allow_self_fill
+        + 4   // max_creates_per_window
+        + 8   // window_slots
+        + 8   // challenge_slots
+        + 8   // quarantine_slots
+        + 8;  // quarantine_seconds
+
+    /// Fails with `SelfFillNotAllowed` when `taker` is the order's maker,
+    /// unless the admin has allowed self-fills.
+    pub fn require_not_self_fill(&self, order: &Order, taker: &Pubkey) -> Result<()> {
+        require!(
+            self.allow_self_fill || *taker != order.maker,
+            BridgeError::SelfFillNotAllowed
+        );
+        Ok(())
+    }
+
+    /// Fails with `BridgePaused` while the admin has the bridge paused.
+    pub fn require_not_paused(&self) -> Result<()> {
+        require!(!self.paused, BridgeError::BridgePaused);
+        Ok(())
+    }
+}
+
+/// Index of a maker's open orders, readable in one account fetch.
+/// Seeds: `[b"maker_registry", maker]`.
+///
+/// `orders[..len]` holds order keys, oldest first until tombstones are
+/// reused. Closing an order overwrites its entry with `Pubkey::default()`
+/// instead of shifting the array, so `len` only shrinks on
+/// `compact_registry`. Legacy orders are never listed.
+///
+/// The registry also carries the maker's order-creation window for
+/// `BridgeConfig.max_creates_per_window`.
+#[account]
+pub struct MakerRegistry {
+    pub maker: Pubkey,                      // 32
+    pub orders: [Pubkey; MAX_OPEN_ORDERS],  // 32 * MAX_OPEN_ORDERS
+    pub len: u8,                            // 1  - Slots used, tombstones included
+    pub bump: u8,                           // 1
+    pub window_start_slot: u64,             // 8  - Slot the current create window opened
+    pub creates_in_window: u32,             // 4  - Orders created since then
+}
+
+impl MakerRegistry {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // maker
+        + 32 * MAX_OPEN_ORDERS // orders
+        + 1   // len
+        + 1   // bump
+        + 8   // window_start_slot
+        + 4;  // creates_in_window
+
+    /// Counts one order creation at `slot` against the maker's window,
+    /// failing with `RateLimited` once the window is full. A window covers
+    /// `[window_start_slot, window_start_slot + window_slots)`; the first
+    /// create at or past its end opens a new one.
+    pub fn record_create(&mut self, slot: u64, config: &BridgeConfig) -> Result<()> {
+        if slot >= self.window_start_slot.saturating_add(config.window_slots) {
+            self.window_start_slot = slot;
+            self.creates_in_window = 0;
+        }
+        require!(
+            config.max_creates_per_window == 0
+                || self.creates_in_window < config.max_creates_per_window,
+            BridgeError::RateLimited
+        );
+        self.creates_in_window = self.creates_in_window.saturating_add(1);
+        Ok(())
+    }
+
+    /// Appends `order` after the last used slot, or reuses a tombstone
+    /// once every slot has been used.
+    pub fn push(&mut self, order: Pubkey) -> Result<()> {
+        let len = self.len as usize;
+        if len < MAX_OPEN_ORDERS {
+            self.orders[len] = order;
+            self.len += 1;
+            return Ok(());
+        }
+        let slot = self
+            .orders
+            .iter_mut()
+            .find(|o| **o == Pubkey::default())
+            .ok_or(BridgeError::TooManyOpenOrders)?;
+        *slot = order;
+        Ok(())
+    }
+
+    /// Tombstones `order`'s entry. Orders created before the registry
+    /// existed are not listed, so a missing entry is not an error.
+    pub fn remove(&mut self, order: &Pubkey) {
+        let len = self.len as usize;
+        if let Some(entry) = self.orders[..len].iter_mut().find(|o| **o == *order) {
+            *entry = Pubkey::default();
+        }
+    }
+
+    /// Moves live entries to the front, keeping their order, and returns
+    /// how many tombstones were dropped.
+    pub fn compact(&mut self) -> u8 {
+        let mut live = 0;
+        for i in 0..self.len as usize {
+            if self.orders[i] != Pubkey::default() {
+                self.orders[live] = self.orders[i];
+                live += 1;
+            }
+        }
+        for entry in &mut self.orders[live..] {
+            *entry = Pubkey::default();
+        }
+        let removed = self.len - live as u8;
+        self.len = live as u8;
+        removed
+    }
+}
+
+/// Protocol-wide totals in sGOR base units, readable in one account fetch.
+/// Seeds: `[b"stats"]`.
+///
+/// `total_filled` counts `fill_order` and `settle_order` at the gross order
+/// amount (fee included); `total_cancelled` counts `cancel_order` and
+/// `close_expired_order`. Legacy orders are not counted.
+///
+/// Every create, fill, and cancel writes this one account, so those
+/// transactions take a write lock on it and the runtime executes them one
+/// after another rather than in parallel within a block. That caps bridge
+/// throughput at what a single hot account can sustain, and under load a
+/// transaction may need a higher priority fee to land. Sharding the
+/// counters would lift the cap but make the totals a sum over shards; at
+/// current volume one exact account is the better trade. Revisit if
+/// orders start failing on account contention.
+#[account]
+pub struct BridgeStats {
+    pub total_escrowed: u128,   // 16 - Sum of order amounts at create
+    pub total_filled: u128,     // 16 - Sum of order amounts released to takers
+    pub total_cancelled: u128,  // 16 - Sum of order amounts refunded to makers
+    pub makers_count: u64,      // 8  - Makers with at least one order
+    pub bump: u8,               // 1
+}
+
+impl BridgeStats {
+    pub const LEN: usize = 8  // discriminator
+        + 16  // total_escrowed
+        + 16  // total_filled
+        + 16  // total_cancelled
+        + 8   // makers_count
+        + 1;  // bump
+
+    /// Counts a new order, and its maker if this is the maker's first.
+    pub fn record_created(&mut self, amount: u64, first_order: bool) -> Result<()> {
+        self.total_escrowed = self
+            .total_escrowed
+            .checked_add(amount as u128)
+            .ok_or(BridgeError::Overflow)?;
+        if first_order {
+            self.makers_count = self.makers_count.checked_add(1).ok_or(BridgeError::Overflow)?;
+        }
+        Ok(())
+    }
+
+    pub fn record_filled(&mut self, amount: u64) -> Result<()> {
+        self.total_filled = self
+            .total_filled
+            .checked_add(amount as u128)
+            .ok_or(BridgeError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_cancelled(&mut self, amount: u64) -> Result<()> {
+        self.total_cancelled = self
+            .total_cancelled
+            .checked_add(amount as u128)
+            .ok_or(BridgeError::Overflow)?;
+        Ok(())
+    }
+}
+
+/// Marks `address` as denied; its existence is the whole signal.
+/// Seeds: `[b"deny", address]`.
+#[account]
+pub struct DenyEntry {
+    pub address: Pubkey, // 32
+    pub bump: u8,        // 1
+}
+
+impl DenyEntry {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // address
+        + 1;  // bump
+
+    /// Fails with `AddressDenied` if the entry at `info` exists. Callers
+    /// pin `info` to the `[b"deny", address]` PDA, which only this program
+    /// can fill with data.
+    pub fn require_not_listed(info: &AccountInfo) -> Result<()> {
+        require!(info.data_is_empty(), BridgeError::AddressDenied);
+        Ok(())
+    }
+}
+
+/// Allowlist entry at `[b"mint_allow", mint]`: orders may escrow `mint`.
+#[account]
+pub struct MintAllow {
+    pub mint: Pubkey, // 32
+    pub bump: u8,     // 1
+}
+
+impl MintAllow {
+    pub const LEN: usize = 8 + 32 + 1;
 
-        require!(!order.is_filled, BridgeError::OrderAlreadyFilled);
+    /// Fails with `MintNotAllowed` unless `mint` is the config's
+    /// `sgor_mint` or has an entry at `info`. Callers pin `info` to the
+    /// `[b"mint_allow", mint]` PDA, which only this program can fill with
+    /// data.
+    pub fn require_allowed(info: &AccountInfo, mint: &Pubkey, config: &BridgeConfig) -> Result<()> {
         require!(
-            ctx.accounts.maker.key() == order.maker,
-            BridgeError::Unauthorized
+            *mint == config.sgor_mint || !info.data_is_empty(),
+            BridgeError::MintNotAllowed
         );
+        Ok(())
+    }
+}
 
-        let amount = order.amount;
-        let maker_key = order.maker;
-        let bump = order.bump;
+#[account]
+pub struct FeeConfig {
+    pub admin: Pubkey,          // 32
+    pub fee_bps: u16,           // 2
+    pub fee_recipient: Pubkey,  // 32 - owner of the sGOR fee token account
+    pub relayer_tip_bps: u16,   // 2  - relayer's cut of the fee on delegated fills
+    pub bump: u8,               // 1
+}
 
-        let seeds: &[&[u8]] = &[
-            b"order",
-            maker_key.as_ref(),
-            &amount.to_le_bytes(),
-            &[bump],
-        ];
+impl FeeConfig {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // admin
+        + 2   // fee_bps
+        + 32  // fee_recipient
+        + 2   // relayer_tip_bps
+        + 1;  // bump
+}
 
-        // Return sGOR from escrow to maker
-        let escrow_ta = &ctx.accounts.escrow_token_account;
-        let maker_ta = &ctx.accounts.maker_token_account;
+/// Replay guard for a taker's delegated-fill authorizations.
+/// Seeds: `[b"taker_nonce", taker]`.
+#[account]
+pub struct TakerNonce {
+    pub taker: Pubkey,      // 32
+    pub next_nonce: u64,    // 8  - Nonce the next signed message must carry
+    pub bump: u8,           // 1
+}
 
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                SplTransfer {
-                    from: escrow_ta.to_account_info(),
-                    to: maker_ta.to_account_info(),
-                    authority: ctx.accounts.order.to_account_info(),
-                },
-                &[seeds],
-            ),
-            amount,
-        )?;
+impl TakerNonce {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // taker
+        + 8   // next_nonce
+        + 1;  // bump
+}
 
-        emit!(OrderCancelled {
-            order_key: order.key(),
-            maker: maker_key,
-            amount,
-        });
+/// What a taker signs to let a relayer fill `order` for them. Signed as
+/// `order (32) || max_amount (u64 LE) || nonce (u64 LE)`.
+pub struct DelegatedFillMessage {
+    pub order: Pubkey,
+    /// Most gGOR the taker agreed to pay for the order
+    pub max_amount: u64,
+    pub nonce: u64,
+}
 
-        Ok(())
+impl DelegatedFillMessage {
+    pub const LEN: usize = 32 + 8 + 8;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[..32].copy_from_slice(self.order.as_ref());
+        bytes[32..40].copy_from_slice(&self.max_amount.to_le_bytes());
+        bytes[40..].copy_from_slice(&self.nonce.to_le_bytes());
+        bytes
     }
 }
 
 // ═══════════════════════════════════════════════════════════════════════
-// ACCOUNT STRUCTS
+// EVENTS
 // ═══════════════════════════════════════════════════════════════════════
 
-#[derive(Accounts)]
-#[instruction(amount: u64)]
-pub struct CreateOrder<'info> {
-    #[account(mut)]
-    pub maker: Signer<'info>,
-
-    /// Order PDA — deterministic from maker + amount
-    #[account(
-        init,
-        seeds = [b"order", maker.key().as_ref(), &amount.to_le_bytes()],
-        bump,
-        payer = maker,
-        space = Order::LEN,
-    )]
-    pub order: Box<Account<'info, Order>>,
+#[event]
+#[derive(Clone)]
+pub struct OrderCreated {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub expected_ggor_amount: u64,
+    pub end_expected_ggor_amount: Option<u64>,
+    pub gorbagana_recipient: Pubkey,
+    pub expiry: Expiry,
+    pub is_private: bool,
+    pub status: OrderStatus,
+    pub client_order_id: u64,
+    pub hashlock: Option<[u8; HASHLOCK_LEN]>,
+    pub activation_slot: u64,
+    pub operator: Option<Pubkey>,
+    pub created_slot: u64,
+    pub decimals: u8,
+    pub payout_splits: Vec<PayoutSplit>,
+    /// Lamports the maker paid in rent for the order PDA and escrow
+    pub rent_locked_lamports: u64,
+    pub mint: Pubkey,
+}
 
-    /// Escrow token account for sGOR
-    /// Initialized with the order PDA as authority
-    #[account(
-        init,
-        token::mint = sgor_mint,
-        token::authority = order,
-        seeds = [b"escrow", maker.key().as_ref(), &amount.to_le_bytes()],
-        bump,
-        payer = maker,
-    )]
-    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+#[event]
+#[derive(Clone)]
+pub struct OrderFilled {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub amount: u64,
+    pub expected_ggor_amount: u64,
+    pub fee: u64,
+    pub relayer: Pubkey,
+    pub gorbagana_tx_sig: [u8; TX_SIG_LEN],
+    pub taker_gorbagana_address: Pubkey,
+    pub gorbagana_recipient: Pubkey,
+    pub status: OrderStatus,
+    pub client_order_id: u64,
+    /// Taker was the maker (only possible with `allow_self_fill`)
+    pub self_fill: bool,
+    /// Rent lamports refunded to the maker by closing the order and escrow
+    pub rent_returned_lamports: u64,
+    pub mint: Pubkey,
+    /// Escrow balance just before the release; above `amount` if
+    /// something topped the escrow up
+    pub escrow_balance: u64,
+}
 
-    /// Maker's sGOR token account (source of deposit)
-    #[account(
-        mut,
-        constraint = maker_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint
-    )]
-    pub maker_token_account: Box<Account<'info, TokenAccount>>,
+#[event]
+#[derive(Clone)]
+pub struct OrderCancelled {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub status: OrderStatus,
+    pub client_order_id: u64,
+    /// Rent lamports refunded to the maker by closing the order and escrow
+    pub rent_returned_lamports: u64,
+    pub mint: Pubkey,
+}
 
-    /// sGOR mint account
-    #[account(constraint = sgor_mint.key() == SGOR_MINT @ BridgeError::InvalidMint)]
-    pub sgor_mint: Box<Account<'info, Mint>>,
+/// Emitted by `cancel_legacy_order`. Legacy orders have no `order_id` or
+/// `client_order_id`, so they get their own event rather than
+/// `OrderCancelled` with placeholder zeros.
+#[event]
+pub struct LegacyOrderCancelled {
+    pub order_key: Pubkey,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub status: OrderStatus,
+    /// Rent lamports refunded to the maker by closing the order and escrow
+    pub rent_returned_lamports: u64,
+    pub mint: Pubkey,
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+#[event]
+pub struct RelayerAdded {
+    pub relayer: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct FillOrder<'info> {
-    #[account(mut)]
-    pub taker: Signer<'info>,
+#[event]
+pub struct RelayerRemoved {
+    pub relayer: Pubkey,
+    pub attestations: u64,
+}
 
-    /// CHECK: Maker receives nothing on Solana (gets gGOR on Gorbagana).
-    /// Validated via order.maker constraint.
-    #[account(
-        mut,
-        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
-    )]
-    pub maker: AccountInfo<'info>,
+#[event]
+pub struct RelayerPauseSet {
+    pub relayer: Pubkey,
+    pub paused: bool,
+}
 
-    #[account(
-        mut,
-        close = maker,
-        seeds = [b"order", order.maker.as_ref(), &order.amount.to_le_bytes()],
-        bump = order.bump,
-    )]
-    pub order: Account<'info, Order>,
+#[event]
+pub struct OrderLocked {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub taker: Pubkey,
+    pub taker_gorbagana_address: Pubkey,
+    pub lock_expiry_slot: u64,
+    pub bond: u64,
+    pub expected_ggor_amount: u64,
+    pub status: OrderStatus,
+}
 
-    /// Escrow sGOR token account (holds maker's escrowed sGOR)
-    #[account(
-        mut,
-        constraint = escrow_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint
-    )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+#[event]
+pub struct LockExpired {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub taker: Pubkey,
+    pub bond_slashed: u64,
+    pub maker_share: u64,
+    pub caller: Pubkey,
+    pub keeper_share: u64,
+    pub status: OrderStatus,
+}
 
-    /// Taker's sGOR token account (receives escrowed sGOR)
-    #[account(
-        mut,
-        constraint = taker_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint
-    )]
-    pub taker_token_account: Account<'info, TokenAccount>,
+#[event]
+pub struct FeeConfigInitialized {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub fee_recipient: Pubkey,
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct FeeConfigUpdated {
+    pub old_fee_bps: u16,
+    pub fee_bps: u16,
+    pub fee_recipient: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct CancelOrder<'info> {
-    #[account(mut)]
-    pub maker: Signer<'info>,
+#[event]
+pub struct RelayerTipUpdated {
+    pub old_relayer_tip_bps: u16,
+    pub relayer_tip_bps: u16,
+}
 
-    #[account(
-        mut,
-        close = maker,
-        has_one = maker @ BridgeError::Unauthorized,
-        seeds = [b"order", order.maker.as_ref(), &order.amount.to_le_bytes()],
-        bump = order.bump,
-    )]
-    pub order: Account<'info, Order>,
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub sgor_mint: Pubkey,
+    pub min_order_amount: u64,
+    pub max_expiry_slots: u64,
+    pub max_expiry_seconds: u64,
+    pub cancel_delay_slots: u64,
+    pub max_lock_slots: u64,
+    pub lock_bond_lamports: u64,
+    pub keeper_share_bps: u16,
+}
 
-    /// Escrow sGOR token account
-    #[account(mut)]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+#[event]
+pub struct ConfigUpdated {
+    pub sgor_mint: Pubkey,
+    pub min_order_amount: u64,
+    pub max_expiry_slots: u64,
+    pub max_expiry_seconds: u64,
+    pub cancel_delay_slots: u64,
+    pub max_lock_slots: u64,
+    pub lock_bond_lamports: u64,
+    pub keeper_share_bps: u16,
+}
 
-    /// Maker's sGOR token account (receives refund)
-    #[account(mut)]
-    pub maker_token_account: Account<'info, TokenAccount>,
+#[event]
+pub struct Paused {
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct Unpaused {
+    pub admin: Pubkey,
+    pub timestamp: i64,
 }
 
-// ═══════════════════════════════════════════════════════════════════════
-// STATE
-// ═══════════════════════════════════════════════════════════════════════
+#[event]
+pub struct OrderExpiredClosed {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub caller: Pubkey,
+    pub bounty: u64,
+    pub status: OrderStatus,
+    /// Rent lamports refunded to the maker, after the keeper's bounty
+    pub rent_returned_lamports: u64,
+    pub mint: Pubkey,
+}
 
-#[account]
-pub struct Order {
-    pub maker: Pubkey,                 // 32 - Solana maker address
-    pub amount: u64,                   // 8  - sGOR amount (6 decimals)
-    pub gorbagana_recipient: Pubkey,   // 32 - Maker's Gorbagana address (for gGOR)
-    pub expiration_slot: u64,          // 8
-    pub is_filled: bool,               // 1
-    pub bump: u8,                      // 1
+#[event]
+pub struct OrderExpirationUpdated {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub old_expiry: Expiry,
+    pub new_expiry: Expiry,
+    pub status: OrderStatus,
 }
 
-impl Order {
-    pub const LEN: usize = 8  // discriminator
-        + 32  // maker
-        + 8   // amount
-        + 32  // gorbagana_recipient
-        + 8   // expiration_slot
-        + 1   // is_filled
-        + 1;  // bump = 90 bytes total
+#[event]
+pub struct RecipientUpdated {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub old_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+    pub status: OrderStatus,
 }
 
-// ═══════════════════════════════════════════════════════════════════════
-// ERRORS
-// ═══════════════════════════════════════════════════════════════════════
+#[event]
+pub struct RegistryCompacted {
+    pub maker: Pubkey,
+    pub open_orders: u8,
+    pub removed: u8,
+}
 
-#[error_code]
-pub enum BridgeError {
-    #[msg("Amount must be >= minimum order size.")]
-    InvalidAmount,
+#[event]
+pub struct AddressDenylisted {
+    pub address: Pubkey,
+    pub admin: Pubkey,
+}
 
-    #[msg("Invalid token mint. Must be sGOR.")]
-    InvalidMint,
+#[event]
+pub struct AddressAllowed {
+    pub address: Pubkey,
+    pub admin: Pubkey,
+}
 
-    #[msg("Order has expired.")]
-    OrderExpired,
+#[event]
+pub struct OrderMigrated {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub version: u8,
+    pub status: OrderStatus,
+}
 
-    #[msg("Order has already been filled.")]
-    OrderAlreadyFilled,
+#[event]
+pub struct DelegatedFillExecuted {
+    pub order_key: Pubkey,
+    pub taker: Pubkey,
+    pub relayer: Pubkey,
+    pub nonce: u64,
+    pub tip: u64,
+}
 
-    #[msg("Unauthorized — only the maker can perform this action.")]
-    Unauthorized,
+#[event]
+#[derive(Clone)]
+pub struct HtlcClaimed {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub preimage: [u8; HASHLOCK_LEN],
+    pub status: OrderStatus,
+    pub client_order_id: u64,
+    /// Rent lamports refunded to the maker by closing the order and escrow
+    pub rent_returned_lamports: u64,
+    pub mint: Pubkey,
+}
 
-    #[msg("Expiration slot is in the past.")]
-    ExpirationInPast,
+#[event]
+pub struct SweepExcess {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub amount: u64,
+}
 
-    #[msg("Expiration too far in the future (max ~24 hours).")]
-    ExpirationTooFar,
+#[event]
+pub struct ExpiryBountyUpdated {
+    pub old_expiry_bounty_lamports: u64,
+    pub expiry_bounty_lamports: u64,
 }
 
-// ═══════════════════════════════════════════════════════════════════════
-// EVENTS
-// ═══════════════════════════════════════════════════════════════════════
+#[event]
+pub struct OperatorUpdated {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub old_operator: Option<Pubkey>,
+    pub new_operator: Option<Pubkey>,
+    pub status: OrderStatus,
+}
 
 #[event]
-pub struct OrderCreated {
+pub struct OrderReplaced {
     pub order_key: Pubkey,
+    pub order_id: u64,
     pub maker: Pubkey,
-    pub amount: u64,
-    pub gorbagana_recipient: Pubkey,
-    pub expiration_slot: u64,
+    pub old_amount: u64,
+    pub new_amount: u64,
+    pub old_expiry: Expiry,
+    pub new_expiry: Expiry,
+    pub old_gorbagana_recipient: Pubkey,
+    pub new_gorbagana_recipient: Pubkey,
+    pub status: OrderStatus,
 }
 
 #[event]
-pub struct OrderFilled {
+pub struct FillPolicyUpdated {
+    pub old_min_fill_delay_slots: u64,
+    pub min_fill_delay_slots: u64,
+    pub old_allow_self_fill: bool,
+    pub allow_self_fill: bool,
+}
+
+#[event]
+pub struct CreateRateLimitUpdated {
+    pub old_max_creates_per_window: u32,
+    pub max_creates_per_window: u32,
+    pub old_window_slots: u64,
+    pub window_slots: u64,
+}
+
+#[event]
+pub struct PayoutSplitPaid {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub legs: Vec<PayoutLeg>,
+}
+
+#[event]
+pub struct ChallengePeriodUpdated {
+    pub old_challenge_slots: u64,
+    pub challenge_slots: u64,
+}
+
+#[event]
+pub struct SettlementPending {
     pub order_key: Pubkey,
+    pub order_id: u64,
     pub maker: Pubkey,
     pub taker: Pubkey,
-    pub amount: u64,
+    pub relayer: Pubkey,
+    pub gorbagana_tx_sig: [u8; TX_SIG_LEN],
+    pub release_slot: u64,
+    pub status: OrderStatus,
 }
 
 #[event]
-pub struct OrderCancelled {
+pub struct SettlementDisputed {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub disputer: Pubkey,
+    pub relayer: Pubkey,
+    pub release_slot: u64,
+    pub status: OrderStatus,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub order_key: Pubkey,
+    pub order_id: u64,
+    pub admin: Pubkey,
+    pub released: bool,
+    pub bond_slashed: u64,
+    pub status: OrderStatus,
+}
+
+#[event]
+pub struct MintAdded {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct MintRemoved {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct QuarantinePeriodUpdated {
+    pub old_quarantine_slots: u64,
+    pub quarantine_slots: u64,
+    pub old_quarantine_seconds: u64,
+    pub quarantine_seconds: u64,
+}
+
+#[event]
+pub struct QuarantineRecovered {
     pub order_key: Pubkey,
+    pub order_id: u64,
     pub maker: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    /// Order amount
     pub amount: u64,
+    /// Whole escrow balance moved to the vault
+    pub recovered_amount: u64,
+    pub recovery_vault: Pubkey,
+    pub gorbagana_recipient: Pubkey,
+    pub expected_ggor_amount: u64,
+    pub expiry: Expiry,
+    pub created_slot: u64,
+    pub client_order_id: u64,
+    pub recovered_slot: u64,
+    pub status: OrderStatus,
 }
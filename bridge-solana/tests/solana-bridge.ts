@@ -0,0 +1,5802 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  ComputeBudgetProgram,
+  Ed25519Program,
+  Keypair,
+  SystemProgram,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  approve,
+  revoke,
+  burn,
+  createAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  createInitializeMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  ExtensionType,
+  getMintLen,
+  mintTo,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { createHash, randomBytes } from "crypto";
+import { SolanaBridge } from "../target/types/solana_bridge";
+import { OrderMirror } from "../target/types/order_mirror";
+
+// ═══════════════════════════════════════════════════════════════════════
+// Helper: derive PDAs
+// ═══════════════════════════════════════════════════════════════════════
+function deriveOrderPDA(
+  programId: PublicKey,
+  maker: PublicKey,
+  orderId: anchor.BN
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("order"),
+      maker.toBuffer(),
+      orderId.toArrayLike(Buffer, "le", 8),
+    ],
+    programId
+  );
+}
+
+function deriveEscrowPDA(
+  programId: PublicKey,
+  maker: PublicKey,
+  orderId: anchor.BN,
+  mint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("escrow"),
+      maker.toBuffer(),
+      orderId.toArrayLike(Buffer, "le", 8),
+      mint.toBuffer(),
+    ],
+    programId
+  );
+}
+
+function deriveRelayerPDA(
+  programId: PublicKey,
+  relayer: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("relayer"), relayer.toBuffer()],
+    programId
+  );
+}
+
+function deriveConfigPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("config")], programId);
+}
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+function deriveProgramDataPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE
+  );
+}
+
+function deriveFeeConfigPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("fee_config")], programId);
+}
+
+function deriveMakerRegistryPDA(
+  programId: PublicKey,
+  maker: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("maker_registry"), maker.toBuffer()],
+    programId
+  );
+}
+
+function deriveStatsPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("stats")], programId);
+}
+
+function deriveDenyPDA(
+  programId: PublicKey,
+  address: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("deny"), address.toBuffer()],
+    programId
+  );
+}
+
+function deriveMintAllowPDA(
+  programId: PublicKey,
+  mint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_allow"), mint.toBuffer()],
+    programId
+  );
+}
+
+function deriveTakerNoncePDA(
+  programId: PublicKey,
+  taker: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("taker_nonce"), taker.toBuffer()],
+    programId
+  );
+}
+
+function deriveOrderReceiptPDA(
+  programId: PublicKey,
+  order: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt"), order.toBuffer()],
+    programId
+  );
+}
+
+function deriveReceiptPDA(
+  programId: PublicKey,
+  txSig: number[]
+): [PublicKey, number] {
+  const sig = Buffer.from(txSig);
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt"), sig.subarray(0, 32), sig.subarray(32)],
+    programId
+  );
+}
+
+/// `Expiry::Slot` argument
+function slotExpiry(slot: number) {
+  return { slot: { slot: new anchor.BN(slot) } };
+}
+
+/// `Expiry::UnixTimestamp` argument
+function timestampExpiry(timestamp: number) {
+  return { unixTimestamp: { timestamp: new anchor.BN(timestamp) } };
+}
+
+/// Deadline slot of a slot-expiry order
+function expirySlot(order: { expiry: any }): number {
+  return order.expiry.slot.slot.toNumber();
+}
+
+/// Stand-in for a Gorbagana transaction signature; unique per call
+function randomTxSig(): number[] {
+  return Array.from({ length: 64 }, () => 1 + Math.floor(Math.random() * 255));
+}
+
+/// HTLC secret and its sha256 hashlock
+function randomPreimage(): number[] {
+  return Array.from(randomBytes(32));
+}
+
+function hashlockOf(preimage: number[]): number[] {
+  return Array.from(createHash("sha256").update(Buffer.from(preimage)).digest());
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Test Suite
+// ═══════════════════════════════════════════════════════════════════════
+describe("solana_bridge", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SolanaBridge as Program<SolanaBridge>;
+
+  // Actors
+  let maker: Keypair;
+  let taker: Keypair;
+  let relayer: Keypair;
+  let outsider: Keypair;
+
+  // sGOR SPL mint (simulated in tests)
+  let sgorMint: PublicKey;
+  let mintAuthority: Keypair;
+
+  // Token accounts
+  let makerSgorATA: PublicKey;
+  let takerSgorATA: PublicKey;
+
+  // Bridge config (admin = provider wallet), pointed at the test mint
+  let configPDA: PublicKey;
+  let programDataPDA: PublicKey;
+  let outsiderInitError: string | undefined;
+  let outsiderFeeInitError: string | undefined;
+
+  // Maker's open-order index
+  let makerRegistryPDA: PublicKey;
+
+  // Protocol-wide counters
+  let statsPDA: PublicKey;
+
+  // Fee config (admin = provider wallet) and the treasury receiving fees
+  let feeConfigPDA: PublicKey;
+  let treasury: Keypair;
+  let treasurySgorATA: PublicKey;
+
+  // Taker's payout address on Gorbagana
+  const takerGorbaganaAddress = Keypair.generate().publicKey;
+
+  const ORDER_AMOUNT = new anchor.BN(10_000_000); // 10 sGOR (6 decimals)
+  const EXPIRY_OFFSET = 500; // slots in the future
+  const MIN_ORDER_AMOUNT = new anchor.BN(100_000);
+  const MAX_EXPIRY_SLOTS = new anchor.BN(216_000);
+  const MAX_EXPIRY_SECONDS = new anchor.BN(86_400);
+  const MAX_LOCK_SLOTS = new anchor.BN(1_500);
+  const BOND = new anchor.BN(0.1 * LAMPORTS_PER_SOL); // lock bond from config
+  const KEEPER_SHARE_BPS = 1_000;
+
+  // ─── Setup ───────────────────────────────────────────────────────
+  before(async () => {
+    maker = Keypair.generate();
+    taker = Keypair.generate();
+    relayer = Keypair.generate();
+    outsider = Keypair.generate();
+    mintAuthority = Keypair.generate();
+    treasury = Keypair.generate();
+
+    for (const kp of [maker, taker, outsider, mintAuthority]) {
+      const sig = await provider.connection.requestAirdrop(
+        kp.publicKey,
+        10 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+    }
+
+    sgorMint = await createMint(
+      provider.connection,
+      mintAuthority,
+      mintAuthority.publicKey,
+      mintAuthority.publicKey,
+      6 // sGOR decimals on Solana
+    );
+
+    makerSgorATA = await createAssociatedTokenAccount(
+      provider.connection,
+      maker,
+      sgorMint,
+      maker.publicKey
+    );
+    takerSgorATA = await createAssociatedTokenAccount(
+      provider.connection,
+      taker,
+      sgorMint,
+      taker.publicKey
+    );
+
+    treasurySgorATA = await createAssociatedTokenAccount(
+      provider.connection,
+      mintAuthority,
+      sgorMint,
+      treasury.publicKey
+    );
+
+    await mintTo(
+      provider.connection,
+      mintAuthority,
+      sgorMint,
+      makerSgorATA,
+      mintAuthority,
+      1_000_000_000 // 1000 sGOR
+    );
+
+    [makerRegistryPDA] = deriveMakerRegistryPDA(program.programId, maker.publicKey);
+
+    [statsPDA] = deriveStatsPDA(program.programId);
+    if ((await provider.connection.getAccountInfo(statsPDA)) === null) {
+      await program.methods
+        .initializeStats()
+        .accounts({
+          payer: provider.wallet.publicKey,
+          stats: statsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    [configPDA] = deriveConfigPDA(program.programId);
+    [programDataPDA] = deriveProgramDataPDA(program.programId);
+    if ((await provider.connection.getAccountInfo(configPDA)) === null) {
+      const initializeConfig = (admin: Keypair | null) => {
+        const builder = program.methods
+          // No cancel delay by default so suites can cancel right after create
+          .initializeConfig({
+            sgorMint,
+            minOrderAmount: MIN_ORDER_AMOUNT,
+            maxExpirySlots: MAX_EXPIRY_SLOTS,
+            maxExpirySeconds: MAX_EXPIRY_SECONDS,
+            cancelDelaySlots: new anchor.BN(0),
+            maxLockSlots: MAX_LOCK_SLOTS,
+            lockBondLamports: BOND,
+            keeperShareBps: KEEPER_SHARE_BPS,
+          })
+          .accounts({
+            admin: admin ? admin.publicKey : provider.wallet.publicKey,
+            config: configPDA,
+            program: program.programId,
+            programData: programDataPDA,
+            systemProgram: SystemProgram.programId,
+          });
+        return admin ? builder.signers([admin]).rpc() : builder.rpc();
+      };
+
+      // The config can only be claimed once, so the rejection is
+      // recorded here and asserted in the Bridge Config suite
+      outsiderInitError = "";
+      try {
+        await initializeConfig(outsider);
+      } catch (e: any) {
+        outsiderInitError = e.message;
+      }
+      await initializeConfig(null);
+    } else {
+      await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+    }
+
+    // Relayers are managed by the config admin
+    await addRelayer(relayer.publicKey);
+
+    // Fees start at zero so other suites see exact amounts
+    [feeConfigPDA] = deriveFeeConfigPDA(program.programId);
+    if ((await provider.connection.getAccountInfo(feeConfigPDA)) === null) {
+      const initializeFeeConfig = (admin: Keypair | null) => {
+        const builder = program.methods
+          .initializeFeeConfig(0, treasury.publicKey)
+          .accounts({
+            admin: admin ? admin.publicKey : provider.wallet.publicKey,
+            feeConfig: feeConfigPDA,
+            program: program.programId,
+            programData: programDataPDA,
+            systemProgram: SystemProgram.programId,
+          });
+        return admin ? builder.signers([admin]).rpc() : builder.rpc();
+      };
+
+      outsiderFeeInitError = "";
+      try {
+        await initializeFeeConfig(outsider);
+      } catch (e: any) {
+        outsiderFeeInitError = e.message;
+      }
+      await initializeFeeConfig(null);
+    } else {
+      await setFee(0);
+    }
+  });
+
+  // ─── Utility ─────────────────────────────────────────────────────
+  async function getCurrentSlot(): Promise<number> {
+    return provider.connection.getSlot();
+  }
+
+  async function getLamports(pubkey: PublicKey): Promise<number> {
+    return provider.connection.getBalance(pubkey);
+  }
+
+  async function waitForSlot(target: number) {
+    while ((await getCurrentSlot()) < target) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+  }
+
+  function setConfig(
+    mint: PublicKey,
+    minOrderAmount: anchor.BN,
+    cancelDelaySlots = 0,
+    lockBond = BOND,
+    keeperShareBps = KEEPER_SHARE_BPS
+  ) {
+    return program.methods
+      .updateConfig({
+        sgorMint: mint,
+        minOrderAmount,
+        maxExpirySlots: MAX_EXPIRY_SLOTS,
+        maxExpirySeconds: MAX_EXPIRY_SECONDS,
+        cancelDelaySlots: new anchor.BN(cancelDelaySlots),
+        maxLockSlots: MAX_LOCK_SLOTS,
+        lockBondLamports: lockBond,
+        keeperShareBps,
+      })
+      .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+      .rpc();
+  }
+
+  function addRelayer(key: PublicKey, admin: Keypair | null = null) {
+    const builder = program.methods.addRelayer(key).accounts({
+      admin: admin ? admin.publicKey : provider.wallet.publicKey,
+      config: configPDA,
+      relayerEntry: deriveRelayerPDA(program.programId, key)[0],
+      systemProgram: SystemProgram.programId,
+    });
+    return admin ? builder.signers([admin]).rpc() : builder.rpc();
+  }
+
+  function setFee(feeBps: number) {
+    return program.methods
+      .updateFeeConfig(feeBps, treasury.publicKey)
+      .accounts({ admin: provider.wallet.publicKey, feeConfig: feeConfigPDA })
+      .rpc();
+  }
+
+  async function createOrder(
+    orderId: anchor.BN,
+    amount: anchor.BN,
+    expiryOffset = EXPIRY_OFFSET,
+    allowedTaker: PublicKey | null = null,
+    expectedGgorAmount: anchor.BN = amount,
+    endExpectedGgorAmount: anchor.BN | null = null,
+    clientOrderId: anchor.BN = new anchor.BN(0),
+    hashlock: number[] | null = null,
+    activationOffset: number | null = null,
+    operator: PublicKey | null = null,
+    payoutSplits: { recipient: PublicKey; bps: number }[] = []
+  ) {
+    const currentSlot = await getCurrentSlot();
+    const [order] = deriveOrderPDA(program.programId, maker.publicKey, orderId);
+    const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, orderId, sgorMint);
+
+    await program.methods
+      .createOrder({
+        orderId,
+        amount,
+        expectedGgorAmount,
+        expiry: slotExpiry(currentSlot + expiryOffset),
+        gorbaganaRecipient: Keypair.generate().publicKey, // maker's Gorbagana address
+        allowedTaker,
+        endExpectedGgorAmount,
+        clientOrderId,
+        hashlock,
+        activationSlot: activationOffset === null ? null : new anchor.BN(currentSlot + activationOffset),
+        operator,
+        payoutSplits,
+      })
+      .accounts({
+        maker: maker.publicKey,
+        order,
+        makerRegistry: makerRegistryPDA,
+        makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+        stats: statsPDA,
+        escrowTokenAccount: escrow,
+        makerTokenAccount: makerSgorATA,
+        sgorMint: sgorMint,
+        mintAllow: deriveMintAllowPDA(program.programId, sgorMint)[0],
+        config: configPDA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([maker])
+      .rpc();
+
+    return { order, escrow };
+  }
+
+  // The amount and recipient a taker would have quoted for `order` now.
+  // A missing order quotes zeros so the fill fails on the account instead.
+  async function quoteOf(order: PublicKey): Promise<[anchor.BN, PublicKey]> {
+    const account = await program.account.order.fetchNullable(order);
+    return account
+      ? [account.amount, account.gorbaganaRecipient]
+      : [new anchor.BN(0), PublicKey.default];
+  }
+
+  async function fillOrder(
+    order: PublicKey,
+    escrow: PublicKey,
+    attestor: Keypair,
+    txSig: number[],
+    takerGorbagana: PublicKey = takerGorbaganaAddress,
+    orderReceipt: PublicKey | null = null,
+    quote: [anchor.BN, PublicKey] | null = null,
+    payoutAccounts: PublicKey[] = [],
+    deadlineSlot: anchor.BN = new anchor.BN(0)
+  ) {
+    const [expectedAmount, expectedRecipient] = quote ?? (await quoteOf(order));
+    return program.methods
+      .fillOrder(txSig, takerGorbagana, expectedAmount, expectedRecipient, deadlineSlot)
+      .accounts({
+        taker: taker.publicKey,
+        relayer: attestor.publicKey,
+        relayerEntry: deriveRelayerPDA(program.programId, attestor.publicKey)[0],
+        config: configPDA,
+        maker: maker.publicKey,
+        order,
+        makerRegistry: makerRegistryPDA,
+        takerDeny: deriveDenyPDA(program.programId, taker.publicKey)[0],
+        makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+        stats: statsPDA,
+        escrowTokenAccount: escrow,
+        takerTokenAccount: takerSgorATA,
+        feeConfig: feeConfigPDA,
+        feeTokenAccount: treasurySgorATA,
+        receipt: deriveReceiptPDA(program.programId, txSig)[0],
+        orderReceipt,
+        sgorMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        payoutAccounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+      )
+      .signers([taker, attestor])
+      .rpc();
+  }
+
+  function cancelOrder(order: PublicKey, escrow: PublicKey) {
+    return program.methods
+      .cancelOrder()
+      .accounts({
+        authority: maker.publicKey,
+        maker: maker.publicKey,
+        order,
+        makerRegistry: makerRegistryPDA,
+        stats: statsPDA,
+        escrowTokenAccount: escrow,
+        makerTokenAccount: makerSgorATA,
+        config: configPDA,
+        sgorMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([maker])
+      .rpc();
+  }
+
+  function lockOrder(order: PublicKey, who: Keypair, lockSlots: number) {
+    return program.methods
+      .lockOrder(new anchor.BN(lockSlots), takerGorbaganaAddress)
+      .accounts({
+        taker: who.publicKey,
+        order,
+        config: configPDA,
+        takerDeny: deriveDenyPDA(program.programId, who.publicKey)[0],
+        makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([who])
+      .rpc();
+  }
+
+  function expireLock(order: PublicKey, caller: Keypair = outsider) {
+    return program.methods
+      .expireLock()
+      .accounts({
+        caller: caller.publicKey,
+        maker: maker.publicKey,
+        order,
+        config: configPDA,
+      })
+      .signers([caller])
+      .rpc();
+  }
+
+  function settleOrder(order: PublicKey, escrow: PublicKey, txSig = randomTxSig()) {
+    return program.methods
+      .settleOrder(txSig)
+      .accounts({
+        relayer: relayer.publicKey,
+        relayerEntry: deriveRelayerPDA(program.programId, relayer.publicKey)[0],
+        config: configPDA,
+        taker: taker.publicKey,
+        maker: maker.publicKey,
+        order,
+        makerRegistry: makerRegistryPDA,
+        stats: statsPDA,
+        escrowTokenAccount: escrow,
+        takerTokenAccount: takerSgorATA,
+        feeConfig: feeConfigPDA,
+        feeTokenAccount: treasurySgorATA,
+        receipt: deriveReceiptPDA(program.programId, txSig)[0],
+        sgorMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([relayer])
+      .rpc();
+  }
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER IDS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order IDs", () => {
+    it("allows two open orders of the same amount from one maker", async () => {
+      const first = await createOrder(new anchor.BN(1), ORDER_AMOUNT);
+      const second = await createOrder(new anchor.BN(2), ORDER_AMOUNT);
+
+      assert.notEqual(first.order.toBase58(), second.order.toBase58());
+      assert.notEqual(first.escrow.toBase58(), second.escrow.toBase58());
+
+      const a = await program.account.order.fetch(first.order);
+      const b = await program.account.order.fetch(second.order);
+      assert.equal(a.orderId.toNumber(), 1);
+      assert.equal(b.orderId.toNumber(), 2);
+      assert.equal(a.amount.toString(), b.amount.toString());
+
+      const escrowBalance = (await getAccount(provider.connection, second.escrow))
+        .amount;
+      assert.equal(escrowBalance.toString(), ORDER_AMOUNT.toString());
+
+      await cancelOrder(first.order, first.escrow);
+      await cancelOrder(second.order, second.escrow);
+    });
+
+    it("rejects reusing an order id that is still open", async () => {
+      const id = new anchor.BN(3);
+      const { order, escrow } = await createOrder(id, ORDER_AMOUNT);
+      try {
+        await createOrder(id, ORDER_AMOUNT.muln(2));
+        assert.fail("Should reject a duplicate order id");
+      } catch (e: any) {
+        assert.include(e.message, "already in use");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // RELAYER ATTESTATION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Relayer Attestation", () => {
+    const TX_SIG = randomTxSig();
+
+    it("rejects a fill co-signed by an unregistered key", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(10), ORDER_AMOUNT);
+      try {
+        await fillOrder(order, escrow, outsider, randomTxSig());
+        assert.fail("Should reject an unregistered relayer");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedRelayer");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("fills with a registered relayer and records the attestation", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(11), ORDER_AMOUNT);
+
+      let event: any = null;
+      const listener = program.addEventListener("orderFilled", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await fillOrder(order, escrow, relayer, TX_SIG);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      assert.isNotNull(event);
+      assert.equal(event.relayer.toBase58(), relayer.publicKey.toBase58());
+      assert.deepEqual(Array.from(event.gorbaganaTxSig), TX_SIG);
+
+      const takerBalance = (await getAccount(provider.connection, takerSgorATA))
+        .amount;
+      assert.equal(takerBalance.toString(), ORDER_AMOUNT.toString());
+    });
+
+    function removeRelayer(key: PublicKey) {
+      return program.methods
+        .removeRelayer(key)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPDA,
+          relayerEntry: deriveRelayerPDA(program.programId, key)[0],
+        })
+        .rpc();
+    }
+
+    function setRelayerPaused(key: PublicKey, paused: boolean) {
+      return program.methods
+        .setRelayerPaused(key, paused)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPDA,
+          relayerEntry: deriveRelayerPDA(program.programId, key)[0],
+        })
+        .rpc();
+    }
+
+    it("only lets the admin manage relayers", async () => {
+      try {
+        await addRelayer(outsider.publicKey, outsider);
+        assert.fail("Should reject a non-admin");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+
+    it("keeps a non-admin from removing a registered relayer", async () => {
+      // The config admin is the upgrade authority that initialized it
+      const config = await program.account.bridgeConfig.fetch(configPDA);
+      assert.equal(config.admin.toBase58(), provider.wallet.publicKey.toBase58());
+
+      try {
+        await program.methods
+          .removeRelayer(relayer.publicKey)
+          .accounts({
+            admin: outsider.publicKey,
+            config: configPDA,
+            relayerEntry: deriveRelayerPDA(program.programId, relayer.publicKey)[0],
+          })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Should reject a non-admin");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+
+    it("counts attestations on the relayer entry", async () => {
+      const [entryPDA] = deriveRelayerPDA(program.programId, relayer.publicKey);
+      const before = await program.account.relayerEntry.fetch(entryPDA);
+      const { order, escrow } = await createOrder(new anchor.BN(13), ORDER_AMOUNT);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+
+      const after = await program.account.relayerEntry.fetch(entryPDA);
+      assert.isTrue(after.active);
+      assert.equal(after.attestations.sub(before.attestations).toNumber(), 1);
+    });
+
+    it("rejects a paused relayer until it is resumed", async () => {
+      const temp = Keypair.generate();
+      await addRelayer(temp.publicKey);
+      await setRelayerPaused(temp.publicKey, true);
+
+      const { order, escrow } = await createOrder(new anchor.BN(14), ORDER_AMOUNT);
+      try {
+        await fillOrder(order, escrow, temp, randomTxSig());
+        assert.fail("Should reject a paused relayer");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedRelayer");
+      }
+
+      await setRelayerPaused(temp.publicKey, false);
+      await fillOrder(order, escrow, temp, randomTxSig());
+      await removeRelayer(temp.publicKey);
+    });
+
+    it("stops accepting a relayer once removed", async () => {
+      const temp = Keypair.generate();
+      await addRelayer(temp.publicKey);
+
+      const events: any[] = [];
+      const listener = program.addEventListener("relayerRemoved", (e) => {
+        if (e.relayer.equals(temp.publicKey)) events.push(e);
+      });
+      try {
+        await removeRelayer(temp.publicKey);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+      assert.lengthOf(events, 1);
+      assert.isNull(
+        await provider.connection.getAccountInfo(
+          deriveRelayerPDA(program.programId, temp.publicKey)[0]
+        )
+      );
+
+      const { order, escrow } = await createOrder(new anchor.BN(12), ORDER_AMOUNT);
+      try {
+        await fillOrder(order, escrow, temp, randomTxSig());
+        assert.fail("Should reject a removed relayer");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedRelayer");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // TWO-PHASE FILL
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Two-Phase Fill", () => {
+    it("locks, blocks cancel and competing locks, then settles", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(20), ORDER_AMOUNT);
+
+      await lockOrder(order, taker, 200);
+      const locked = await program.account.order.fetch(order);
+      assert.deepEqual(locked.status, { locked: {} });
+      assert.equal(locked.lockedBy.toBase58(), taker.publicKey.toBase58());
+      assert.equal(locked.lockBond.toString(), BOND.toString());
+
+      try {
+        await cancelOrder(order, escrow);
+        assert.fail("Maker should not cancel a locked order");
+      } catch (e: any) {
+        assert.include(e.message, "OrderLocked");
+      }
+
+      try {
+        await lockOrder(order, outsider, 200);
+        assert.fail("A second taker should not lock");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidOrderState");
+      }
+
+      const takerSgorBefore = (await getAccount(provider.connection, takerSgorATA))
+        .amount;
+      const takerLamportsBefore = await getLamports(taker.publicKey);
+
+      await settleOrder(order, escrow);
+
+      const takerSgorAfter = (await getAccount(provider.connection, takerSgorATA))
+        .amount;
+      assert.equal(
+        (takerSgorAfter - takerSgorBefore).toString(),
+        ORDER_AMOUNT.toString()
+      );
+      assert.equal(
+        (await getLamports(taker.publicKey)) - takerLamportsBefore,
+        BOND.toNumber(),
+        "Bond should be refunded on settle"
+      );
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("rejects settle after the lock lapses and slashes the bond on expiry", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(21), ORDER_AMOUNT);
+      await lockOrder(order, taker, 2);
+      const { lockExpirySlot } = await program.account.order.fetch(order);
+      await waitForSlot(lockExpirySlot.toNumber() + 1);
+
+      try {
+        await settleOrder(order, escrow);
+        assert.fail("Should reject settling a lapsed lock");
+      } catch (e: any) {
+        assert.include(e.message, "LockExpired");
+      }
+
+      const makerLamportsBefore = await getLamports(maker.publicKey);
+      await expireLock(order);
+
+      const keeperShare = (BOND.toNumber() * KEEPER_SHARE_BPS) / 10_000;
+      assert.equal(
+        (await getLamports(maker.publicKey)) - makerLamportsBefore,
+        BOND.toNumber() - keeperShare,
+        "Bond should be slashed to the maker, less the keeper share"
+      );
+      const reopened = await program.account.order.fetch(order);
+      assert.deepEqual(reopened.status, { open: {} });
+
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects expiring a lock that has not lapsed", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(22), ORDER_AMOUNT);
+      await lockOrder(order, taker, 200);
+      try {
+        await expireLock(order);
+        assert.fail("Should reject an early expire");
+      } catch (e: any) {
+        assert.include(e.message, "LockNotExpired");
+      }
+      await settleOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // FILL RECEIPTS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Fill Receipts", () => {
+    it("rejects an all-zero Gorbagana signature", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(30), ORDER_AMOUNT);
+      try {
+        await fillOrder(order, escrow, relayer, new Array(64).fill(0));
+        assert.fail("Should reject a zero signature");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidTxSignature");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("stores a receipt linking the fill to the Gorbagana payment", async () => {
+      const txSig = randomTxSig();
+      const { order, escrow } = await createOrder(new anchor.BN(31), ORDER_AMOUNT);
+      await fillOrder(order, escrow, relayer, txSig);
+
+      const [receiptPDA] = deriveReceiptPDA(program.programId, txSig);
+      const receipt = await program.account.fillReceipt.fetch(receiptPDA);
+      assert.equal(receipt.orderKey.toBase58(), order.toBase58());
+      assert.equal(receipt.orderId.toNumber(), 31);
+      assert.equal(receipt.taker.toBase58(), taker.publicKey.toBase58());
+      assert.equal(receipt.relayer.toBase58(), relayer.publicKey.toBase58());
+      assert.equal(receipt.amount.toString(), ORDER_AMOUNT.toString());
+      assert.deepEqual(Array.from(receipt.gorbaganaTxSig), txSig);
+    });
+
+    it("rejects reusing a Gorbagana signature for a second fill", async () => {
+      const txSig = randomTxSig();
+      const first = await createOrder(new anchor.BN(32), ORDER_AMOUNT);
+      const second = await createOrder(new anchor.BN(33), ORDER_AMOUNT);
+      await fillOrder(first.order, first.escrow, relayer, txSig);
+      try {
+        await fillOrder(second.order, second.escrow, relayer, txSig);
+        assert.fail("Should reject a replayed signature");
+      } catch (e: any) {
+        assert.include(e.message, "already in use");
+      }
+      await cancelOrder(second.order, second.escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // TAKER GORBAGANA ADDRESS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Taker Gorbagana Address", () => {
+    it("rejects the default pubkey", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(40), ORDER_AMOUNT);
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig(), PublicKey.default);
+        assert.fail("Should reject the default pubkey");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidGorbaganaAddress");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("emits the taker's Gorbagana address in OrderFilled", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(41), ORDER_AMOUNT);
+      let event: any = null;
+      const listener = program.addEventListener("orderFilled", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+      assert.equal(
+        event.takerGorbaganaAddress.toBase58(),
+        takerGorbaganaAddress.toBase58()
+      );
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ESCROW CLOSE
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Escrow Close", () => {
+    it("closes the escrow on cancel and refunds its rent to the maker", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(50), ORDER_AMOUNT);
+      const escrowRent = await getLamports(escrow);
+      const orderRent = await getLamports(order);
+      const makerBefore = await getLamports(maker.publicKey);
+
+      await cancelOrder(order, escrow);
+
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+      assert.isNull(await provider.connection.getAccountInfo(order));
+      const makerAfter = await getLamports(maker.publicKey);
+      // Maker pays the tx fee, so allow a small margin below full refund
+      assert.isAbove(makerAfter - makerBefore, escrowRent + orderRent - 10_000);
+    });
+
+    it("allows recreating an identical order after cancel", async () => {
+      const id = new anchor.BN(51);
+      const first = await createOrder(id, ORDER_AMOUNT);
+      await cancelOrder(first.order, first.escrow);
+
+      const second = await createOrder(id, ORDER_AMOUNT);
+      assert.equal(second.escrow.toBase58(), first.escrow.toBase58());
+      const escrowBalance = (await getAccount(provider.connection, second.escrow))
+        .amount;
+      assert.equal(escrowBalance.toString(), ORDER_AMOUNT.toString());
+
+      await cancelOrder(second.order, second.escrow);
+    });
+
+    it("closes the escrow on fill", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(52), ORDER_AMOUNT);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("sweeps stray deposits so they cannot block the close", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(53), ORDER_AMOUNT);
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        sgorMint,
+        escrow,
+        mintAuthority,
+        1
+      );
+      const takerBefore = (await getAccount(provider.connection, takerSgorATA))
+        .amount;
+
+      await fillOrder(order, escrow, relayer, randomTxSig());
+
+      const takerAfter = (await getAccount(provider.connection, takerSgorATA))
+        .amount;
+      assert.equal(
+        (takerAfter - takerBefore).toString(),
+        ORDER_AMOUNT.addn(1).toString()
+      );
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // PROTOCOL FEE
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Protocol Fee", () => {
+    async function balance(ata: PublicKey): Promise<bigint> {
+      return (await getAccount(provider.connection, ata)).amount;
+    }
+
+    it("only lets the upgrade authority initialize the fee config", function () {
+      if (outsiderFeeInitError === undefined) this.skip();
+      assert.include(outsiderFeeInitError, "UnauthorizedAdmin");
+    });
+
+    it("leaves fills untouched when the fee is zero", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(60), ORDER_AMOUNT);
+      const takerBefore = await balance(takerSgorATA);
+      const treasuryBefore = await balance(treasurySgorATA);
+
+      await fillOrder(order, escrow, relayer, randomTxSig());
+
+      assert.equal(
+        (await balance(takerSgorATA)) - takerBefore,
+        BigInt(ORDER_AMOUNT.toString())
+      );
+      assert.equal(await balance(treasurySgorATA), treasuryBefore);
+    });
+
+    it("carves the fee out of the escrow and emits it", async () => {
+      await setFee(100); // 1%
+      try {
+        const { order, escrow } = await createOrder(new anchor.BN(61), ORDER_AMOUNT);
+        const expectedFee = ORDER_AMOUNT.muln(100).divn(10_000);
+        const takerBefore = await balance(takerSgorATA);
+        const treasuryBefore = await balance(treasurySgorATA);
+
+        let event: any = null;
+        const listener = program.addEventListener("orderFilled", (e) => {
+          if (e.orderKey.equals(order)) event = e;
+        });
+        try {
+          await fillOrder(order, escrow, relayer, randomTxSig());
+          await new Promise((r) => setTimeout(r, 1000));
+        } finally {
+          await program.removeEventListener(listener);
+        }
+
+        assert.equal(
+          (await balance(takerSgorATA)) - takerBefore,
+          BigInt(ORDER_AMOUNT.sub(expectedFee).toString())
+        );
+        assert.equal(
+          (await balance(treasurySgorATA)) - treasuryBefore,
+          BigInt(expectedFee.toString())
+        );
+        assert.equal(event.fee.toString(), expectedFee.toString());
+      } finally {
+        await setFee(0);
+      }
+    });
+
+    it("rejects a fee above the cap", async () => {
+      try {
+        await setFee(301);
+        assert.fail("Should reject fee_bps above 300");
+      } catch (e: any) {
+        assert.include(e.message, "FeeTooHigh");
+      }
+    });
+
+    it("rejects updates from a non-admin", async () => {
+      try {
+        await program.methods
+          .updateFeeConfig(50, outsider.publicKey)
+          .accounts({ admin: outsider.publicKey, feeConfig: feeConfigPDA })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Should reject a non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+
+    it("rejects a fee account not owned by the fee recipient", async () => {
+      const txSig = randomTxSig();
+      const { order, escrow } = await createOrder(new anchor.BN(62), ORDER_AMOUNT);
+      try {
+        await program.methods
+          .fillOrder(txSig, takerGorbaganaAddress, ...(await quoteOf(order)), new anchor.BN(0))
+          .accounts({
+            taker: taker.publicKey,
+            relayer: relayer.publicKey,
+            relayerEntry: deriveRelayerPDA(program.programId, relayer.publicKey)[0],
+            config: configPDA,
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            takerDeny: deriveDenyPDA(program.programId, taker.publicKey)[0],
+            makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            takerTokenAccount: takerSgorATA,
+            feeConfig: feeConfigPDA,
+            feeTokenAccount: takerSgorATA,
+            receipt: deriveReceiptPDA(program.programId, txSig)[0],
+            orderReceipt: null,
+            sgorMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker, relayer])
+          .rpc();
+        assert.fail("Should reject a foreign fee account");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidFeeRecipient");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // BRIDGE CONFIG
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Bridge Config", () => {
+    it("only lets the upgrade authority initialize the config", function () {
+      if (outsiderInitError === undefined) this.skip();
+      assert.include(outsiderInitError, "UnauthorizedAdmin");
+    });
+
+    it("stores the test mint and limits", async () => {
+      const config = await program.account.bridgeConfig.fetch(configPDA);
+      assert.equal(config.sgorMint.toBase58(), sgorMint.toBase58());
+      assert.equal(
+        config.minOrderAmount.toString(),
+        MIN_ORDER_AMOUNT.toString()
+      );
+      assert.equal(
+        config.maxExpirySlots.toString(),
+        MAX_EXPIRY_SLOTS.toString()
+      );
+    });
+
+    it("enforces a raised minimum on new orders", async () => {
+      await setConfig(sgorMint, ORDER_AMOUNT.addn(1));
+      try {
+        await createOrder(new anchor.BN(70), ORDER_AMOUNT);
+        assert.fail("Should reject an order below the configured minimum");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidAmount");
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+
+    it("rejects orders in a mint other than the configured one", async () => {
+      await setConfig(Keypair.generate().publicKey, MIN_ORDER_AMOUNT);
+      try {
+        await createOrder(new anchor.BN(71), ORDER_AMOUNT);
+        assert.fail("Should reject the old mint");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidMint");
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+
+    it("lets open orders settle after a mint change", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(72), ORDER_AMOUNT);
+      await setConfig(Keypair.generate().publicKey, MIN_ORDER_AMOUNT);
+      try {
+        await cancelOrder(order, escrow);
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("rejects zero limits", async () => {
+      try {
+        await setConfig(sgorMint, new anchor.BN(0));
+        assert.fail("Should reject a zero minimum");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidConfig");
+      }
+    });
+
+    it("rejects updates from a non-admin", async () => {
+      try {
+        await program.methods
+          .updateConfig({
+            sgorMint,
+            minOrderAmount: new anchor.BN(1),
+            maxExpirySlots: MAX_EXPIRY_SLOTS,
+            maxExpirySeconds: MAX_EXPIRY_SECONDS,
+            cancelDelaySlots: new anchor.BN(0),
+            maxLockSlots: MAX_LOCK_SLOTS,
+            lockBondLamports: BOND,
+            keeperShareBps: KEEPER_SHARE_BPS,
+          })
+          .accounts({ admin: outsider.publicKey, config: configPDA })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Should reject a non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // PAUSE
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Pause", () => {
+    function pause() {
+      return program.methods
+        .pause()
+        .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+    }
+
+    function unpause() {
+      return program.methods
+        .unpause()
+        .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+    }
+
+    async function expectPaused(fn: () => Promise<unknown>) {
+      try {
+        await fn();
+        assert.fail("Should be rejected while paused");
+      } catch (e: any) {
+        assert.include(e.message, "BridgePaused");
+      }
+    }
+
+    it("blocks create, fill, lock, and settle while paused", async () => {
+      const open = await createOrder(new anchor.BN(80), ORDER_AMOUNT);
+      const locked = await createOrder(new anchor.BN(81), ORDER_AMOUNT);
+      await lockOrder(locked.order, taker, 100);
+
+      await pause();
+      try {
+        await expectPaused(() => createOrder(new anchor.BN(82), ORDER_AMOUNT));
+        await expectPaused(() =>
+          fillOrder(open.order, open.escrow, relayer, randomTxSig())
+        );
+        await expectPaused(() => lockOrder(open.order, taker, 100));
+        await expectPaused(() => settleOrder(locked.order, locked.escrow));
+      } finally {
+        await unpause();
+      }
+
+      // Everything works again once unpaused
+      await fillOrder(open.order, open.escrow, relayer, randomTxSig());
+      await settleOrder(locked.order, locked.escrow);
+      const again = await createOrder(new anchor.BN(82), ORDER_AMOUNT);
+      await cancelOrder(again.order, again.escrow);
+    });
+
+    it("keeps cancel and lock expiry open while paused", async () => {
+      const open = await createOrder(new anchor.BN(83), ORDER_AMOUNT);
+      const locked = await createOrder(new anchor.BN(84), ORDER_AMOUNT);
+      await lockOrder(locked.order, taker, 1);
+
+      await pause();
+      try {
+        await cancelOrder(open.order, open.escrow);
+        assert.isNull(await provider.connection.getAccountInfo(open.order));
+
+        const { lockExpirySlot } = await program.account.order.fetch(locked.order);
+        await waitForSlot(lockExpirySlot.toNumber() + 1);
+        await expireLock(locked.order);
+        await cancelOrder(locked.order, locked.escrow);
+      } finally {
+        await unpause();
+      }
+    });
+
+    it("emits Paused and Unpaused with the admin key", async () => {
+      const events: any[] = [];
+      const onPause = program.addEventListener("paused", (e) => events.push(e));
+      const onUnpause = program.addEventListener("unpaused", (e) => events.push(e));
+      try {
+        await pause();
+        await unpause();
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(onPause);
+        await program.removeEventListener(onUnpause);
+      }
+      assert.equal(events.length, 2);
+      for (const e of events) {
+        assert.equal(e.admin.toBase58(), provider.wallet.publicKey.toBase58());
+        assert.isAbove(e.timestamp.toNumber(), 0);
+      }
+    });
+
+    it("rejects pause from a non-admin", async () => {
+      try {
+        await program.methods
+          .pause()
+          .accounts({ admin: outsider.publicKey, config: configPDA })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Should reject a non-admin pause");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // CLOSE EXPIRED ORDER
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Close Expired Order", () => {
+    const EXPIRY_BOUNTY_LAMPORTS = 100_000;
+
+    function closeExpiredOrder(
+      order: PublicKey,
+      escrow: PublicKey,
+      makerTokenAccount = makerSgorATA
+    ) {
+      return program.methods
+        .closeExpiredOrder()
+        .accounts({
+          caller: outsider.publicKey,
+          config: configPDA,
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([outsider])
+        .rpc();
+    }
+
+    it("rejects closing before expiry", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(90), ORDER_AMOUNT);
+      try {
+        await closeExpiredOrder(order, escrow);
+        assert.fail("Should reject an unexpired order");
+      } catch (e: any) {
+        assert.include(e.message, "OrderNotExpired");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("refunds the maker and pays the caller a bounty", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(91), ORDER_AMOUNT, 5);
+      await waitForSlot(expirySlot(await program.account.order.fetch(order)) + 1);
+
+      const makerTokensBefore = (await getAccount(provider.connection, makerSgorATA))
+        .amount;
+      const callerBefore = await getLamports(outsider.publicKey);
+
+      let event: any = null;
+      const listener = program.addEventListener("orderExpiredClosed", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await closeExpiredOrder(order, escrow);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const makerTokensAfter = (await getAccount(provider.connection, makerSgorATA))
+        .amount;
+      assert.equal(
+        (makerTokensAfter - makerTokensBefore).toString(),
+        ORDER_AMOUNT.toString()
+      );
+      // Provider wallet pays the tx fee, so the caller nets the full bounty
+      assert.equal(
+        (await getLamports(outsider.publicKey)) - callerBefore,
+        EXPIRY_BOUNTY_LAMPORTS
+      );
+      assert.isNull(await provider.connection.getAccountInfo(order));
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+      assert.equal(event.caller.toBase58(), outsider.publicKey.toBase58());
+      assert.equal(event.bounty.toNumber(), EXPIRY_BOUNTY_LAMPORTS);
+    });
+
+    it("rejects a refund account not owned by the maker", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(92), ORDER_AMOUNT, 5);
+      await waitForSlot(expirySlot(await program.account.order.fetch(order)) + 1);
+      try {
+        await closeExpiredOrder(order, escrow, takerSgorATA);
+        assert.fail("Should reject a foreign refund account");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+      await closeExpiredOrder(order, escrow);
+    });
+
+    it("rejects a filled order", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(93), ORDER_AMOUNT, 5);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+      const slot = await getCurrentSlot();
+      await waitForSlot(slot + 6);
+      try {
+        await closeExpiredOrder(order, escrow);
+        assert.fail("Should reject a filled order");
+      } catch (e: any) {
+        assert.include(e.message, "AccountNotInitialized");
+      }
+    });
+
+    function setExpiryBounty(lamports: number, admin: Keypair | null = null) {
+      const builder = program.methods
+        .setExpiryBounty(new anchor.BN(lamports))
+        .accounts({
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+        });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    it("pays the admin-configured bounty", async () => {
+      const BOUNTY = 250_000;
+      await setExpiryBounty(BOUNTY);
+      try {
+        const config = await program.account.bridgeConfig.fetch(configPDA);
+        assert.equal(config.expiryBountyLamports.toNumber(), BOUNTY);
+
+        const { order, escrow } = await createOrder(new anchor.BN(396), ORDER_AMOUNT, 5);
+        await waitForSlot(expirySlot(await program.account.order.fetch(order)) + 1);
+        const rent = await getLamports(order);
+        const callerBefore = await getLamports(outsider.publicKey);
+        const makerBefore = await getLamports(maker.publicKey);
+
+        await closeExpiredOrder(order, escrow);
+
+        assert.equal((await getLamports(outsider.publicKey)) - callerBefore, BOUNTY);
+        // Maker also gets the escrow's rent back
+        assert.isAtLeast((await getLamports(maker.publicKey)) - makerBefore, rent - BOUNTY);
+      } finally {
+        await setExpiryBounty(EXPIRY_BOUNTY_LAMPORTS);
+      }
+    });
+
+    it("rejects a bounty above the cap", async () => {
+      try {
+        await setExpiryBounty(1_000_001);
+        assert.fail("Should reject a bounty above the cap");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidConfig");
+      }
+    });
+
+    it("rejects bounty updates from a non-admin", async () => {
+      try {
+        await setExpiryBounty(0, outsider);
+        assert.fail("Should reject a non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // UPDATE EXPIRATION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Update Expiration", () => {
+    function updateExpiration(order: PublicKey, slot: number, signer = maker) {
+      return program.methods
+        .updateExpiration(slotExpiry(slot))
+        .accounts({ authority: signer.publicKey, order, config: configPDA })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("extends and shortens an open order", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(100), ORDER_AMOUNT);
+      const before = await program.account.order.fetch(order);
+
+      let event: any = null;
+      const listener = program.addEventListener("orderExpirationUpdated", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        const later = expirySlot(before) + 1_000;
+        await updateExpiration(order, later);
+        await new Promise((r) => setTimeout(r, 1000));
+        assert.equal(expirySlot(await program.account.order.fetch(order)), later);
+        assert.equal(expirySlot({ expiry: event.oldExpiry }), expirySlot(before));
+        assert.equal(expirySlot({ expiry: event.newExpiry }), later);
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const sooner = (await getCurrentSlot()) + 50;
+      await updateExpiration(order, sooner);
+      assert.equal(expirySlot(await program.account.order.fetch(order)), sooner);
+
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a slot in the past or beyond the max lifetime", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(101), ORDER_AMOUNT);
+      const slot = await getCurrentSlot();
+      try {
+        await updateExpiration(order, slot - 1);
+        assert.fail("Should reject a past slot");
+      } catch (e: any) {
+        assert.include(e.message, "ExpirationInPast");
+      }
+      try {
+        await updateExpiration(order, slot + MAX_EXPIRY_SLOTS.toNumber() + 100);
+        assert.fail("Should reject a slot beyond the max lifetime");
+      } catch (e: any) {
+        assert.include(e.message, "ExpirationTooFar");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a non-maker and a locked order", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(102), ORDER_AMOUNT);
+      const slot = await getCurrentSlot();
+      try {
+        await updateExpiration(order, slot + 100, outsider);
+        assert.fail("Should reject a non-maker");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+
+      await lockOrder(order, taker, 100);
+      try {
+        await updateExpiration(order, slot + 100);
+        assert.fail("Should reject a locked order");
+      } catch (e: any) {
+        assert.include(e.message, "OrderLocked");
+      }
+      await settleOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // PRIVATE ORDERS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Private Orders", () => {
+    it("flags private orders in OrderCreated", async () => {
+      const events: any[] = [];
+      const listener = program.addEventListener("orderCreated", (e) => events.push(e));
+      let pub: { order: PublicKey; escrow: PublicKey };
+      let priv: { order: PublicKey; escrow: PublicKey };
+      try {
+        pub = await createOrder(new anchor.BN(110), ORDER_AMOUNT);
+        priv = await createOrder(
+          new anchor.BN(111),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          taker.publicKey
+        );
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const byKey = (k: PublicKey) => events.find((e) => e.orderKey.equals(k));
+      assert.isFalse(byKey(pub.order).isPrivate);
+      assert.isTrue(byKey(priv.order).isPrivate);
+
+      const stored = await program.account.order.fetch(priv.order);
+      assert.equal(stored.allowedTaker.toBase58(), taker.publicKey.toBase58());
+
+      await cancelOrder(pub.order, pub.escrow);
+      await cancelOrder(priv.order, priv.escrow);
+    });
+
+    it("lets only the allowed taker fill", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(112),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        outsider.publicKey
+      );
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        assert.fail("Should reject a taker other than the allowed one");
+      } catch (e: any) {
+        assert.include(e.message, "TakerNotAllowed");
+      }
+      try {
+        await lockOrder(order, taker, 100);
+        assert.fail("Should reject a lock by a taker other than the allowed one");
+      } catch (e: any) {
+        assert.include(e.message, "TakerNotAllowed");
+      }
+      await cancelOrder(order, escrow);
+
+      const mine = await createOrder(
+        new anchor.BN(113),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        taker.publicKey
+      );
+      await fillOrder(mine.order, mine.escrow, relayer, randomTxSig());
+      assert.isNull(await provider.connection.getAccountInfo(mine.order));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // UPDATE RECIPIENT
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Update Recipient", () => {
+    function updateRecipient(order: PublicKey, recipient: PublicKey, signer = maker) {
+      return program.methods
+        .updateRecipient(recipient)
+        .accounts({ maker: signer.publicKey, order })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("reports the new recipient on fill", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(120), ORDER_AMOUNT);
+      const oldRecipient = (await program.account.order.fetch(order))
+        .gorbaganaRecipient;
+      const newRecipient = Keypair.generate().publicKey;
+
+      let updated: any = null;
+      let filled: any = null;
+      const onUpdate = program.addEventListener("recipientUpdated", (e) => {
+        if (e.orderKey.equals(order)) updated = e;
+      });
+      const onFill = program.addEventListener("orderFilled", (e) => {
+        if (e.orderKey.equals(order)) filled = e;
+      });
+      try {
+        await updateRecipient(order, newRecipient);
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(onUpdate);
+        await program.removeEventListener(onFill);
+      }
+
+      assert.equal(updated.oldRecipient.toBase58(), oldRecipient.toBase58());
+      assert.equal(updated.newRecipient.toBase58(), newRecipient.toBase58());
+      assert.equal(filled.gorbaganaRecipient.toBase58(), newRecipient.toBase58());
+    });
+
+    it("rejects the default pubkey, a non-maker, and a locked order", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(121), ORDER_AMOUNT);
+      try {
+        await updateRecipient(order, PublicKey.default);
+        assert.fail("Should reject the default pubkey");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidGorbaganaAddress");
+      }
+      try {
+        await updateRecipient(order, Keypair.generate().publicKey, outsider);
+        assert.fail("Should reject a non-maker");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+
+      await lockOrder(order, taker, 100);
+      try {
+        await updateRecipient(order, Keypair.generate().publicKey);
+        assert.fail("Should reject a locked order");
+      } catch (e: any) {
+        assert.include(e.message, "OrderLocked");
+      }
+      await settleOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // EXPECTED gGOR AMOUNT
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Expected gGOR Amount", () => {
+    it("stores a non-par price and reports it on create and fill", async () => {
+      const expected = ORDER_AMOUNT.muln(3).divn(2);
+      const created: any[] = [];
+      const filled: any[] = [];
+      const onCreate = program.addEventListener("orderCreated", (e) => created.push(e));
+      const onFill = program.addEventListener("orderFilled", (e) => filled.push(e));
+      let order: PublicKey;
+      try {
+        const o = await createOrder(
+          new anchor.BN(130),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          expected
+        );
+        order = o.order;
+        const stored = await program.account.order.fetch(order);
+        assert.equal(stored.expectedGgorAmount.toString(), expected.toString());
+        assert.equal(stored.amount.toString(), ORDER_AMOUNT.toString());
+
+        await fillOrder(o.order, o.escrow, relayer, randomTxSig());
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(onCreate);
+        await program.removeEventListener(onFill);
+      }
+
+      const c = created.find((e) => e.orderKey.equals(order));
+      const f = filled.find((e) => e.orderKey.equals(order));
+      assert.equal(c.expectedGgorAmount.toString(), expected.toString());
+      assert.equal(f.expectedGgorAmount.toString(), expected.toString());
+    });
+
+    it("rejects a zero expected amount", async () => {
+      try {
+        await createOrder(
+          new anchor.BN(131),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          new anchor.BN(0)
+        );
+        assert.fail("Should reject a zero expected gGOR amount");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidAmount");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // CANCEL PROTECTION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Cancel Protection", () => {
+    it("rejects cancel within the configured delay", async () => {
+      const DELAY = 10;
+      await setConfig(sgorMint, MIN_ORDER_AMOUNT, DELAY);
+      try {
+        const { order, escrow } = await createOrder(new anchor.BN(140), ORDER_AMOUNT);
+        try {
+          await cancelOrder(order, escrow);
+          assert.fail("Should reject an early cancel");
+        } catch (e: any) {
+          assert.include(e.message, "CancelTooEarly");
+        }
+
+        const { createdSlot } = await program.account.order.fetch(order);
+        await waitForSlot(createdSlot.toNumber() + DELAY);
+        await cancelOrder(order, escrow);
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+
+    it("rejects a cancel landing in the slot after a lock", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(141), ORDER_AMOUNT);
+      const [lock, cancel] = await Promise.allSettled([
+        lockOrder(order, taker, 100),
+        cancelOrder(order, escrow),
+      ]);
+
+      if (lock.status === "fulfilled") {
+        // Lock landed first: the cancel must have been refused
+        assert.equal(cancel.status, "rejected");
+        assert.include((cancel as PromiseRejectedResult).reason.message, "OrderLocked");
+        await settleOrder(order, escrow);
+      } else {
+        // Cancel landed first: nothing left to lock
+        assert.equal(cancel.status, "fulfilled");
+        assert.isNull(await provider.connection.getAccountInfo(order));
+      }
+    });
+
+    it("rejects cancel while a lock is live", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(142), ORDER_AMOUNT);
+      await lockOrder(order, taker, 100);
+      const slot = await getCurrentSlot();
+      await waitForSlot(slot + 1);
+      try {
+        await cancelOrder(order, escrow);
+        assert.fail("Should reject cancel during a live lock");
+      } catch (e: any) {
+        assert.include(e.message, "OrderLocked");
+      }
+      await settleOrder(order, escrow);
+    });
+
+    it("allows cancel once the lock has lapsed, slashing the bond", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(143), ORDER_AMOUNT);
+      await lockOrder(order, taker, 1);
+      const { lockExpirySlot } = await program.account.order.fetch(order);
+      await waitForSlot(lockExpirySlot.toNumber() + 1);
+
+      const orderLamports = await getLamports(order);
+      const makerBefore = await getLamports(maker.publicKey);
+      await cancelOrder(order, escrow);
+
+      // Maker recovers the order PDA (rent + bond) less the tx fee
+      assert.isAbove(
+        (await getLamports(maker.publicKey)) - makerBefore,
+        orderLamports - 10_000
+      );
+      assert.isAbove(orderLamports, BOND.toNumber());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER RECEIPTS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Receipts", () => {
+    function closeReceipt(orderReceipt: PublicKey, signer = taker) {
+      return program.methods
+        .closeReceipt()
+        .accounts({ taker: signer.publicKey, orderReceipt })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("records the fill by order key when requested", async () => {
+      const txSig = randomTxSig();
+      const { order, escrow } = await createOrder(new anchor.BN(150), ORDER_AMOUNT);
+      const { gorbaganaRecipient } = await program.account.order.fetch(order);
+      const [orderReceipt] = deriveOrderReceiptPDA(program.programId, order);
+
+      await fillOrder(order, escrow, relayer, txSig, takerGorbaganaAddress, orderReceipt);
+
+      const receipt = await program.account.orderReceipt.fetch(orderReceipt);
+      assert.equal(receipt.orderKey.toBase58(), order.toBase58());
+      assert.equal(receipt.maker.toBase58(), maker.publicKey.toBase58());
+      assert.equal(receipt.taker.toBase58(), taker.publicKey.toBase58());
+      assert.equal(receipt.amount.toString(), ORDER_AMOUNT.toString());
+      assert.equal(
+        receipt.gorbaganaRecipient.toBase58(),
+        gorbaganaRecipient.toBase58()
+      );
+      assert.deepEqual(Array.from(receipt.gorbaganaTxSig), txSig);
+      assert.isAbove(receipt.timestamp.toNumber(), 0);
+    });
+
+    it("is write-once", async () => {
+      const id = new anchor.BN(151);
+      const first = await createOrder(id, ORDER_AMOUNT);
+      const [orderReceipt] = deriveOrderReceiptPDA(program.programId, first.order);
+      await fillOrder(
+        first.order,
+        first.escrow,
+        relayer,
+        randomTxSig(),
+        takerGorbaganaAddress,
+        orderReceipt
+      );
+
+      // Same order id recreates the same order key
+      const second = await createOrder(id, ORDER_AMOUNT);
+      try {
+        await fillOrder(
+          second.order,
+          second.escrow,
+          relayer,
+          randomTxSig(),
+          takerGorbaganaAddress,
+          orderReceipt
+        );
+        assert.fail("Should not overwrite an existing receipt");
+      } catch (e: any) {
+        assert.include(e.message, "already in use");
+      }
+      await cancelOrder(second.order, second.escrow);
+    });
+
+    it("lets only the taker close it and reclaim rent", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(152), ORDER_AMOUNT);
+      const [orderReceipt] = deriveOrderReceiptPDA(program.programId, order);
+      await fillOrder(
+        order,
+        escrow,
+        relayer,
+        randomTxSig(),
+        takerGorbaganaAddress,
+        orderReceipt
+      );
+
+      try {
+        await closeReceipt(orderReceipt, outsider);
+        assert.fail("Should reject a non-taker");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+
+      const rent = await getLamports(orderReceipt);
+      const takerBefore = await getLamports(taker.publicKey);
+      await closeReceipt(orderReceipt);
+      assert.isNull(await provider.connection.getAccountInfo(orderReceipt));
+      assert.isAbove(
+        (await getLamports(taker.publicKey)) - takerBefore,
+        rent - 10_000
+      );
+    });
+
+    it("is skipped when not requested", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(153), ORDER_AMOUNT);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+      const [orderReceipt] = deriveOrderReceiptPDA(program.programId, order);
+      assert.isNull(await provider.connection.getAccountInfo(orderReceipt));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // CPI EVENTS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("CPI Events", () => {
+    // Decodes the events carried by self-CPI inner instructions of `sig`
+    async function cpiEvents(sig: string): Promise<any[]> {
+      await provider.connection.confirmTransaction(sig, "confirmed");
+      const tx = await provider.connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const keys = tx.transaction.message.getAccountKeys();
+      const events: any[] = [];
+      for (const inner of tx.meta.innerInstructions ?? []) {
+        for (const ix of inner.instructions) {
+          if (!keys.get(ix.programIdIndex).equals(program.programId)) continue;
+          const data = anchor.utils.bytes.bs58.decode(ix.data);
+          // Skip the 8-byte event-CPI instruction tag
+          const event = program.coder.events.decode(
+            anchor.utils.bytes.base64.encode(data.subarray(8))
+          );
+          if (event) events.push(event);
+        }
+      }
+      return events;
+    }
+
+    it("emits OrderCancelled through self-CPI", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(160), ORDER_AMOUNT);
+      const sig = await cancelOrder(order, escrow);
+
+      const events = await cpiEvents(sig);
+      const cancelled = events.find((e) => e.name === "orderCancelled");
+      assert.isDefined(cancelled);
+      assert.equal(cancelled.data.orderKey.toBase58(), order.toBase58());
+      assert.equal(cancelled.data.amount.toString(), ORDER_AMOUNT.toString());
+    });
+
+    it("emits OrderFilled through self-CPI", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(161), ORDER_AMOUNT);
+      const sig = await fillOrder(order, escrow, relayer, randomTxSig());
+
+      const events = await cpiEvents(sig);
+      const filled = events.find((e) => e.name === "orderFilled");
+      assert.isDefined(filled);
+      assert.equal(filled.data.orderKey.toBase58(), order.toBase58());
+      assert.equal(filled.data.taker.toBase58(), taker.publicKey.toBase58());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // TOKEN-2022
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Token-2022", () => {
+    // Token-2022 mint, optionally with a transfer fee; returns the mint and
+    // a funded maker token account
+    async function createToken2022Mint(feeBps: number | null) {
+      const mint = Keypair.generate();
+      const extensions = feeBps === null ? [] : [ExtensionType.TransferFeeConfig];
+      const mintLen = getMintLen(extensions);
+      const lamports =
+        await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+      const tx = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: mintAuthority.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        })
+      );
+      if (feeBps !== null) {
+        tx.add(
+          createInitializeTransferFeeConfigInstruction(
+            mint.publicKey,
+            mintAuthority.publicKey,
+            mintAuthority.publicKey,
+            feeBps,
+            BigInt(1_000_000_000),
+            TOKEN_2022_PROGRAM_ID
+          )
+        );
+      }
+      tx.add(
+        createInitializeMintInstruction(
+          mint.publicKey,
+          6,
+          mintAuthority.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await sendAndConfirmTransaction(provider.connection, tx, [mintAuthority, mint]);
+
+      const makerAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        maker,
+        mint.publicKey,
+        maker.publicKey,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        mint.publicKey,
+        makerAccount,
+        mintAuthority,
+        1_000_000_000,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      return { mint: mint.publicKey, makerAccount };
+    }
+
+    async function createToken2022Order(
+      orderId: anchor.BN,
+      mint: PublicKey,
+      makerAccount: PublicKey
+    ) {
+      const slot = await getCurrentSlot();
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, orderId);
+      const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, orderId, mint);
+      await program.methods
+        .createOrder({
+          orderId,
+          amount: ORDER_AMOUNT,
+          expectedGgorAmount: ORDER_AMOUNT,
+          expiry: slotExpiry(slot + EXPIRY_OFFSET),
+          gorbaganaRecipient: Keypair.generate().publicKey,
+          allowedTaker: null,
+          endExpectedGgorAmount: null,
+          clientOrderId: new anchor.BN(0),
+          hashlock: null,
+          activationSlot: null,
+          operator: null,
+          payoutSplits: [],
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: makerAccount,
+          sgorMint: mint,
+          mintAllow: deriveMintAllowPDA(program.programId, mint)[0],
+          config: configPDA,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+      return { order, escrow };
+    }
+
+    it("escrows and refunds a Token-2022 mint without fees", async () => {
+      const { mint, makerAccount } = await createToken2022Mint(null);
+      await setConfig(mint, MIN_ORDER_AMOUNT);
+      try {
+        const { order, escrow } = await createToken2022Order(
+          new anchor.BN(170),
+          mint,
+          makerAccount
+        );
+        const escrowed = await getAccount(
+          provider.connection,
+          escrow,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
+        assert.equal(escrowed.amount.toString(), ORDER_AMOUNT.toString());
+
+        await program.methods
+          .cancelOrder()
+          .accounts({
+            authority: maker.publicKey,
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerAccount,
+            config: configPDA,
+            sgorMint: mint,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([maker])
+          .rpc();
+        assert.isNull(await provider.connection.getAccountInfo(escrow));
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+
+    it("rejects a mint with a 1% transfer fee", async () => {
+      const { mint, makerAccount } = await createToken2022Mint(100);
+      await setConfig(mint, MIN_ORDER_AMOUNT);
+      try {
+        await createToken2022Order(new anchor.BN(171), mint, makerAccount);
+        assert.fail("Should reject a fee-bearing mint");
+      } catch (e: any) {
+        assert.include(e.message, "TransferFeeNotSupported");
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // TAKER TOKEN ACCOUNT
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Taker Token Account", () => {
+    it("rejects a token account the taker does not own", async () => {
+      const outsiderSgorATA = await createAssociatedTokenAccount(
+        provider.connection,
+        outsider,
+        sgorMint,
+        outsider.publicKey
+      );
+      const txSig = randomTxSig();
+      const { order, escrow } = await createOrder(new anchor.BN(180), ORDER_AMOUNT);
+      try {
+        await program.methods
+          .fillOrder(txSig, takerGorbaganaAddress, ...(await quoteOf(order)), new anchor.BN(0))
+          .accounts({
+            taker: taker.publicKey,
+            relayer: relayer.publicKey,
+            relayerEntry: deriveRelayerPDA(program.programId, relayer.publicKey)[0],
+            config: configPDA,
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            takerDeny: deriveDenyPDA(program.programId, taker.publicKey)[0],
+            makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            takerTokenAccount: outsiderSgorATA,
+            feeConfig: feeConfigPDA,
+            feeTokenAccount: treasurySgorATA,
+            receipt: deriveReceiptPDA(program.programId, txSig)[0],
+            orderReceipt: null,
+            sgorMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker, relayer])
+          .rpc();
+        assert.fail("Should reject a foreign taker token account");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidTakerTokenAccount");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // MAKER REFUND ACCOUNT
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Maker Refund Account", () => {
+    it("rejects a refund account the maker does not own", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(190), ORDER_AMOUNT);
+      try {
+        await program.methods
+          .cancelOrder()
+          .accounts({
+            authority: maker.publicKey,
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: takerSgorATA,
+            config: configPDA,
+            sgorMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should reject a foreign refund account");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // MAKER REGISTRY
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Maker Registry", () => {
+    const MAX_OPEN_ORDERS = 32;
+    const DEFAULT_KEY = PublicKey.default.toBase58();
+
+    async function liveOrders(): Promise<string[]> {
+      const registry = await program.account.makerRegistry.fetch(makerRegistryPDA);
+      return registry.orders
+        .slice(0, registry.len)
+        .map((o: PublicKey) => o.toBase58())
+        .filter((o: string) => o !== DEFAULT_KEY);
+    }
+
+    function compactRegistry(signer: Keypair = maker) {
+      return program.methods
+        .compactRegistry()
+        .accounts({ maker: signer.publicKey, makerRegistry: makerRegistryPDA })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("lists an order on create and tombstones it on cancel", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(200), ORDER_AMOUNT);
+      assert.include(await liveOrders(), order.toBase58());
+
+      const before = await program.account.makerRegistry.fetch(makerRegistryPDA);
+      await cancelOrder(order, escrow);
+      const after = await program.account.makerRegistry.fetch(makerRegistryPDA);
+
+      assert.equal(after.len, before.len);
+      assert.notInclude(await liveOrders(), order.toBase58());
+    });
+
+    it("removes an order on fill", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(201), ORDER_AMOUNT);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+      assert.notInclude(await liveOrders(), order.toBase58());
+    });
+
+    it("compacts tombstones and keeps live orders in creation order", async () => {
+      const first = await createOrder(new anchor.BN(202), ORDER_AMOUNT);
+      const second = await createOrder(new anchor.BN(203), ORDER_AMOUNT);
+      await cancelOrder(first.order, first.escrow);
+
+      const live = await liveOrders();
+      await compactRegistry();
+      const registry = await program.account.makerRegistry.fetch(makerRegistryPDA);
+
+      assert.equal(registry.len, live.length);
+      assert.deepEqual(
+        registry.orders.slice(0, registry.len).map((o: PublicKey) => o.toBase58()),
+        live
+      );
+      assert.include(live, second.order.toBase58());
+
+      await cancelOrder(second.order, second.escrow);
+    });
+
+    it("rejects compaction by anyone but the maker", async () => {
+      try {
+        await compactRegistry(outsider);
+        assert.fail("Should reject a non-maker");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+    });
+
+    it("rejects an order beyond the open-order limit", async () => {
+      const opened: { order: PublicKey; escrow: PublicKey }[] = [];
+      const room = MAX_OPEN_ORDERS - (await liveOrders()).length;
+      for (let i = 0; i < room; i++) {
+        opened.push(await createOrder(new anchor.BN(210 + i), ORDER_AMOUNT));
+      }
+      try {
+        await createOrder(new anchor.BN(209), ORDER_AMOUNT);
+        assert.fail("Should reject a full registry");
+      } catch (e: any) {
+        assert.include(e.message, "TooManyOpenOrders");
+      }
+      for (const { order, escrow } of opened) {
+        await cancelOrder(order, escrow);
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // BRIDGE STATS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Bridge Stats", () => {
+    function fetchStats() {
+      return program.account.bridgeStats.fetch(statsPDA);
+    }
+
+    it("adds the order amount to total_escrowed on create", async () => {
+      const before = await fetchStats();
+      const { order, escrow } = await createOrder(new anchor.BN(250), ORDER_AMOUNT);
+      const after = await fetchStats();
+
+      assert.equal(
+        after.totalEscrowed.sub(before.totalEscrowed).toString(),
+        ORDER_AMOUNT.toString()
+      );
+      // Same maker as earlier suites: not a new maker
+      assert.equal(after.makersCount.toString(), before.makersCount.toString());
+
+      await cancelOrder(order, escrow);
+    });
+
+    it("adds the order amount to total_filled on fill", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(251), ORDER_AMOUNT);
+      const before = await fetchStats();
+      await fillOrder(order, escrow, relayer, randomTxSig());
+      const after = await fetchStats();
+
+      assert.equal(
+        after.totalFilled.sub(before.totalFilled).toString(),
+        ORDER_AMOUNT.toString()
+      );
+      assert.equal(after.totalCancelled.toString(), before.totalCancelled.toString());
+    });
+
+    it("adds the order amount to total_cancelled on cancel", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(252), ORDER_AMOUNT);
+      const before = await fetchStats();
+      await cancelOrder(order, escrow);
+      const after = await fetchStats();
+
+      assert.equal(
+        after.totalCancelled.sub(before.totalCancelled).toString(),
+        ORDER_AMOUNT.toString()
+      );
+      assert.equal(after.totalFilled.toString(), before.totalFilled.toString());
+    });
+
+    it("counts a maker once, on their first order", async () => {
+      const newMaker = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        newMaker.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+      const newMakerATA = await createAssociatedTokenAccount(
+        provider.connection,
+        newMaker,
+        sgorMint,
+        newMaker.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        sgorMint,
+        newMakerATA,
+        mintAuthority,
+        ORDER_AMOUNT.muln(2).toNumber()
+      );
+
+      const create = async (orderId: anchor.BN) => {
+        const slot = await getCurrentSlot();
+        const [order] = deriveOrderPDA(program.programId, newMaker.publicKey, orderId);
+        const [escrow] = deriveEscrowPDA(program.programId, newMaker.publicKey, orderId, sgorMint);
+        await program.methods
+          .createOrder({
+            orderId,
+            amount: ORDER_AMOUNT,
+            expectedGgorAmount: ORDER_AMOUNT,
+            expiry: slotExpiry(slot + EXPIRY_OFFSET),
+            gorbaganaRecipient: Keypair.generate().publicKey,
+            allowedTaker: null,
+            endExpectedGgorAmount: null,
+            clientOrderId: new anchor.BN(0),
+            hashlock: null,
+            activationSlot: null,
+            operator: null,
+            payoutSplits: [],
+          })
+          .accounts({
+            maker: newMaker.publicKey,
+            order,
+            makerRegistry: deriveMakerRegistryPDA(program.programId, newMaker.publicKey)[0],
+            makerDeny: deriveDenyPDA(program.programId, newMaker.publicKey)[0],
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: newMakerATA,
+            sgorMint,
+            mintAllow: deriveMintAllowPDA(program.programId, sgorMint)[0],
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([newMaker])
+          .rpc();
+      };
+
+      const before = await fetchStats();
+      await create(new anchor.BN(1));
+      const afterFirst = await fetchStats();
+      await create(new anchor.BN(2));
+      const afterSecond = await fetchStats();
+
+      assert.equal(afterFirst.makersCount.toNumber(), before.makersCount.toNumber() + 1);
+      assert.equal(afterSecond.makersCount.toNumber(), afterFirst.makersCount.toNumber());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // BONDED FILLS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Bonded Fills", () => {
+    const keeperShare = (BOND.toNumber() * KEEPER_SHARE_BPS) / 10_000;
+
+    it("takes the configured bond on lock and refunds it in full on settle", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(260), ORDER_AMOUNT);
+      const orderLamportsBefore = await getLamports(order);
+
+      await lockOrder(order, taker, 100);
+      const locked = await program.account.order.fetch(order);
+      assert.equal(locked.lockBond.toString(), BOND.toString());
+      assert.equal(locked.amount.toString(), ORDER_AMOUNT.toString());
+      assert.equal(
+        (await getLamports(order)) - orderLamportsBefore,
+        BOND.toNumber()
+      );
+
+      const takerBefore = await getLamports(taker.publicKey);
+      await settleOrder(order, escrow);
+      assert.equal((await getLamports(taker.publicKey)) - takerBefore, BOND.toNumber());
+    });
+
+    it("follows a config change to the bond", async () => {
+      const bigger = BOND.muln(2);
+      await setConfig(sgorMint, MIN_ORDER_AMOUNT, 0, bigger);
+      try {
+        const { order, escrow } = await createOrder(new anchor.BN(261), ORDER_AMOUNT);
+        await lockOrder(order, taker, 100);
+        const locked = await program.account.order.fetch(order);
+        assert.equal(locked.lockBond.toString(), bigger.toString());
+        await settleOrder(order, escrow);
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+
+    it("splits a lapsed bond between maker and keeper", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(262), ORDER_AMOUNT);
+      await lockOrder(order, taker, 1);
+      const { lockExpirySlot } = await program.account.order.fetch(order);
+      await waitForSlot(lockExpirySlot.toNumber() + 1);
+
+      const makerBefore = await getLamports(maker.publicKey);
+      const keeperBefore = await getLamports(outsider.publicKey);
+      await expireLock(order);
+
+      assert.equal(
+        (await getLamports(maker.publicKey)) - makerBefore,
+        BOND.toNumber() - keeperShare
+      );
+      assert.equal((await getLamports(outsider.publicKey)) - keeperBefore, keeperShare);
+
+      const reopened = await program.account.order.fetch(order);
+      assert.equal(reopened.lockBond.toNumber(), 0);
+      assert.equal(reopened.amount.toString(), ORDER_AMOUNT.toString());
+
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects claiming a lapsed bond twice", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(263), ORDER_AMOUNT);
+      await lockOrder(order, taker, 1);
+      const { lockExpirySlot } = await program.account.order.fetch(order);
+      await waitForSlot(lockExpirySlot.toNumber() + 1);
+
+      await expireLock(order);
+      try {
+        await expireLock(order);
+        assert.fail("Should reject a second expire");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidOrderState");
+      }
+      try {
+        await settleOrder(order, escrow);
+        assert.fail("Should reject settling after the bond was slashed");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidOrderState");
+      }
+
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a zero bond or a keeper share above 100%", async () => {
+      for (const [bond, bps] of [
+        [new anchor.BN(0), KEEPER_SHARE_BPS],
+        [BOND, 10_001],
+      ] as [anchor.BN, number][]) {
+        try {
+          await setConfig(sgorMint, MIN_ORDER_AMOUNT, 0, bond, bps);
+          assert.fail("Should reject an invalid bond config");
+        } catch (e: any) {
+          assert.include(e.message, "InvalidConfig");
+        }
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // DUTCH AUCTION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Dutch Auction", () => {
+    const START = new anchor.BN(20_000_000);
+    const END = new anchor.BN(10_000_000);
+
+    function priceAt(order: any, slot: number): number {
+      const created = order.createdSlot.toNumber();
+      const expiry = expirySlot(order);
+      if (slot >= expiry) return END.toNumber();
+      if (slot <= created) return START.toNumber();
+      const spread = START.sub(END).toNumber();
+      return START.toNumber() - Math.floor((spread * (slot - created)) / (expiry - created));
+    }
+
+    it("stores the auction floor on the order", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(270),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        START,
+        END
+      );
+      const stored = await program.account.order.fetch(order);
+      assert.equal(stored.expectedGgorAmount.toString(), START.toString());
+      assert.equal(stored.endExpectedGgorAmount.toString(), END.toString());
+      await cancelOrder(order, escrow);
+    });
+
+    it("fixes the decayed price at lock and reports it again at settle", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(271),
+        ORDER_AMOUNT,
+        20,
+        null,
+        START,
+        END
+      );
+      const created = await program.account.order.fetch(order);
+      await waitForSlot(created.createdSlot.toNumber() + 5);
+
+      const events: any[] = [];
+      const onLock = program.addEventListener("orderLocked", (e) => events.push(e));
+      const onFill = program.addEventListener("orderFilled", (e) => events.push(e));
+      try {
+        await lockOrder(order, taker, 100);
+        const locked = await program.account.order.fetch(order);
+        const atLock = locked.lockedExpectedGgorAmount.toNumber();
+
+        // Strictly inside the auction: below the start, above the floor
+        assert.isBelow(atLock, START.toNumber());
+        assert.isAbove(atLock, END.toNumber());
+
+        await waitForSlot(expirySlot(created) + 1);
+        await settleOrder(order, escrow);
+        await new Promise((r) => setTimeout(r, 1000));
+
+        const lockEvent = events.find((e) => e.lockExpirySlot !== undefined);
+        const fillEvent = events.find((e) => e.fee !== undefined);
+        assert.equal(lockEvent.expectedGgorAmount.toNumber(), atLock);
+        assert.equal(
+          fillEvent.expectedGgorAmount.toNumber(),
+          atLock,
+          "Settle should report the price fixed at lock, not the floor"
+        );
+      } finally {
+        await program.removeEventListener(onLock);
+        await program.removeEventListener(onFill);
+      }
+    });
+
+    it("reports the interpolated price on a one-step fill", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(272),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        START,
+        END
+      );
+      const stored = await program.account.order.fetch(order);
+
+      const events: any[] = [];
+      const listener = program.addEventListener("orderFilled", (e) => events.push(e));
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        await new Promise((r) => setTimeout(r, 1000));
+
+        const filledSlot = await getCurrentSlot();
+        const price = events[0].expectedGgorAmount.toNumber();
+        assert.isAtMost(price, priceAt(stored, stored.createdSlot.toNumber()));
+        assert.isAtLeast(price, priceAt(stored, filledSlot));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+    });
+
+    it("rejects a floor above the start price or at zero", async () => {
+      for (const [id, end] of [
+        [273, START.addn(1)],
+        [274, new anchor.BN(0)],
+      ] as [number, anchor.BN][]) {
+        try {
+          await createOrder(new anchor.BN(id), ORDER_AMOUNT, EXPIRY_OFFSET, null, START, end);
+          assert.fail("Should reject an invalid auction floor");
+        } catch (e: any) {
+          assert.include(e.message, "InvalidAmount");
+        }
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // TIMESTAMP EXPIRY
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Timestamp Expiry", () => {
+    async function chainTime(): Promise<number> {
+      return (await provider.connection.getBlockTime(await getCurrentSlot())) as number;
+    }
+
+    async function createTimestampOrder(orderId: anchor.BN, timestamp: number) {
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, orderId);
+      const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, orderId, sgorMint);
+      await program.methods
+        .createOrder({
+          orderId,
+          amount: ORDER_AMOUNT,
+          expectedGgorAmount: ORDER_AMOUNT,
+          expiry: timestampExpiry(timestamp),
+          gorbaganaRecipient: Keypair.generate().publicKey,
+          allowedTaker: null,
+          endExpectedGgorAmount: null,
+          clientOrderId: new anchor.BN(0),
+          hashlock: null,
+          activationSlot: null,
+          operator: null,
+          payoutSplits: [],
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: makerSgorATA,
+          sgorMint,
+          mintAllow: deriveMintAllowPDA(program.programId, sgorMint)[0],
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+      return { order, escrow };
+    }
+
+    it("stores a timestamp deadline and fills before it", async () => {
+      const deadline = (await chainTime()) + 600;
+      const { order, escrow } = await createTimestampOrder(new anchor.BN(280), deadline);
+
+      const stored = await program.account.order.fetch(order);
+      assert.equal(stored.expiry.unixTimestamp.timestamp.toNumber(), deadline);
+
+      await fillOrder(order, escrow, relayer, randomTxSig());
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("rejects a deadline in the past or beyond the configured horizon", async () => {
+      const now = await chainTime();
+      for (const [id, deadline, error] of [
+        [281, now - 1, "ExpirationInPast"],
+        [282, now + MAX_EXPIRY_SECONDS.toNumber() + 600, "ExpirationTooFar"],
+      ] as [number, number, string][]) {
+        try {
+          await createTimestampOrder(new anchor.BN(id), deadline);
+          assert.fail("Should reject the deadline");
+        } catch (e: any) {
+          assert.include(e.message, error);
+        }
+      }
+    });
+
+    it("stops fills and allows the sweep once the deadline passes", async () => {
+      const deadline = (await chainTime()) + 2;
+      const { order, escrow } = await createTimestampOrder(new anchor.BN(283), deadline);
+      while ((await chainTime()) <= deadline) {
+        await new Promise((r) => setTimeout(r, 500));
+      }
+
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        assert.fail("Should reject a fill past the deadline");
+      } catch (e: any) {
+        assert.include(e.message, "OrderExpired");
+      }
+
+      await program.methods
+        .closeExpiredOrder()
+        .accounts({
+          caller: outsider.publicKey,
+          config: configPDA,
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: makerSgorATA,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([outsider])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // BATCH CREATE
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Batch Create", () => {
+    const MAX_BATCH_ORDERS = 10;
+
+    async function ladder(firstId: number, count: number) {
+      const slot = await getCurrentSlot();
+      return Array.from({ length: count }, (_, i) => ({
+        orderId: new anchor.BN(firstId + i),
+        amount: ORDER_AMOUNT.muln(i + 1),
+        expectedGgorAmount: ORDER_AMOUNT.muln(i + 1),
+        expiry: slotExpiry(slot + EXPIRY_OFFSET),
+        gorbaganaRecipient: Keypair.generate().publicKey,
+        clientOrderId: new anchor.BN(0),
+      }));
+    }
+
+    function batchAccounts(params: { orderId: anchor.BN }[]) {
+      return params.flatMap(({ orderId }) => [
+        deriveOrderPDA(program.programId, maker.publicKey, orderId)[0],
+        deriveEscrowPDA(program.programId, maker.publicKey, orderId, sgorMint)[0],
+      ]);
+    }
+
+    function createOrders(params: any[], accounts: PublicKey[] = batchAccounts(params)) {
+      return program.methods
+        .createOrders(params)
+        .accounts({
+          maker: maker.publicKey,
+          makerRegistry: makerRegistryPDA,
+          makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+          stats: statsPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint,
+          mintAllow: deriveMintAllowPDA(program.programId, sgorMint)[0],
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          accounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 600_000 })])
+        .signers([maker])
+        .rpc();
+    }
+
+    it("opens a ladder of orders with one event each", async () => {
+      const params = await ladder(300, 3);
+      const events: any[] = [];
+      const listener = program.addEventListener("orderCreated", (e) => events.push(e));
+      try {
+        await createOrders(params);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      for (const p of params) {
+        const [order] = deriveOrderPDA(program.programId, maker.publicKey, p.orderId);
+        const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, p.orderId, sgorMint);
+        const stored = await program.account.order.fetch(order);
+        assert.equal(stored.amount.toString(), p.amount.toString());
+        assert.deepEqual(stored.status, { open: {} });
+        assert.equal(
+          (await getAccount(provider.connection, escrow)).amount.toString(),
+          p.amount.toString()
+        );
+        assert.isTrue(events.some((e) => e.orderKey.equals(order)));
+        await cancelOrder(order, escrow);
+      }
+    });
+
+    it("rejects an empty batch and one above the cap", async () => {
+      // No remaining accounts: the size check comes first, and 22 PDAs would
+      // not fit in one transaction anyway
+      for (const params of [[], await ladder(310, MAX_BATCH_ORDERS + 1)]) {
+        try {
+          await createOrders(params, []);
+          assert.fail("Should reject the batch size");
+        } catch (e: any) {
+          assert.include(e.message, "BatchTooLarge");
+        }
+      }
+    });
+
+    it("opens nothing when one entry is invalid", async () => {
+      const params = await ladder(330, 3);
+      const accounts = batchAccounts(params);
+      // Swap the last order's escrow for the first one's
+      accounts[5] = accounts[1];
+      try {
+        await createOrders(params, accounts);
+        assert.fail("Should reject mismatched accounts");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidBatchAccounts");
+      }
+      for (const pubkey of batchAccounts(params)) {
+        assert.isNull(await provider.connection.getAccountInfo(pubkey));
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // BATCH CANCEL
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Batch Cancel", () => {
+    function cancelOrders(triples: PublicKey[][]) {
+      return program.methods
+        .cancelOrders()
+        .accounts({
+          maker: maker.publicKey,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          config: configPDA,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          triples
+            .flat()
+            .map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .signers([maker])
+        .rpc();
+    }
+
+    it("cancels a ladder with one event per order", async () => {
+      const orders = [];
+      for (const id of [350, 351, 352]) {
+        orders.push(await createOrder(new anchor.BN(id), ORDER_AMOUNT));
+      }
+      const makerBefore = (await getAccount(provider.connection, makerSgorATA)).amount;
+
+      const events: any[] = [];
+      const listener = program.addEventListener("orderCancelled", (e) => events.push(e));
+      try {
+        await cancelOrders(orders.map(({ order, escrow }) => [order, escrow, makerSgorATA]));
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const makerAfter = (await getAccount(provider.connection, makerSgorATA)).amount;
+      assert.equal(
+        (makerAfter - makerBefore).toString(),
+        ORDER_AMOUNT.muln(3).toString()
+      );
+      for (const { order, escrow } of orders) {
+        assert.isNull(await provider.connection.getAccountInfo(order));
+        assert.isNull(await provider.connection.getAccountInfo(escrow));
+        assert.isTrue(events.some((e) => e.orderKey.equals(order)));
+      }
+    });
+
+    it("aborts the batch on one foreign refund account", async () => {
+      const first = await createOrder(new anchor.BN(353), ORDER_AMOUNT);
+      const second = await createOrder(new anchor.BN(354), ORDER_AMOUNT);
+      try {
+        await cancelOrders([
+          [first.order, first.escrow, makerSgorATA],
+          [second.order, second.escrow, takerSgorATA],
+        ]);
+        assert.fail("Should reject a foreign refund account");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+      assert.isNotNull(await provider.connection.getAccountInfo(first.order));
+      await cancelOrders([
+        [first.order, first.escrow, makerSgorATA],
+        [second.order, second.escrow, makerSgorATA],
+      ]);
+    });
+
+    it("refuses a batch containing a live lock", async () => {
+      const open = await createOrder(new anchor.BN(355), ORDER_AMOUNT);
+      const locked = await createOrder(new anchor.BN(356), ORDER_AMOUNT);
+      await lockOrder(locked.order, taker, 100);
+      try {
+        await cancelOrders([
+          [open.order, open.escrow, makerSgorATA],
+          [locked.order, locked.escrow, makerSgorATA],
+        ]);
+        assert.fail("Should reject a locked order");
+      } catch (e: any) {
+        assert.include(e.message, "OrderLocked");
+      }
+      await cancelOrder(open.order, open.escrow);
+      await settleOrder(locked.order, locked.escrow);
+    });
+
+    it("rejects a partial triple", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(357), ORDER_AMOUNT);
+      try {
+        await cancelOrders([[order, escrow]]);
+        assert.fail("Should reject a partial triple");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidBatchAccounts");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // DENYLIST
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Denylist", () => {
+    function deny(address: PublicKey, admin: Keypair | null = null) {
+      const builder = program.methods.denyAddress(address).accounts({
+        admin: admin ? admin.publicKey : provider.wallet.publicKey,
+        config: configPDA,
+        denyEntry: deriveDenyPDA(program.programId, address)[0],
+        systemProgram: SystemProgram.programId,
+      });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    function allow(address: PublicKey) {
+      return program.methods
+        .allowAddress(address)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPDA,
+          denyEntry: deriveDenyPDA(program.programId, address)[0],
+        })
+        .rpc();
+    }
+
+    async function expectDenied(fn: () => Promise<unknown>) {
+      try {
+        await fn();
+        assert.fail("Should reject a denylisted address");
+      } catch (e: any) {
+        assert.include(e.message, "AddressDenied");
+      }
+    }
+
+    it("blocks a listed maker from creating orders", async () => {
+      await deny(maker.publicKey);
+      try {
+        await expectDenied(() => createOrder(new anchor.BN(360), ORDER_AMOUNT));
+      } finally {
+        await allow(maker.publicKey);
+      }
+      const { order, escrow } = await createOrder(new anchor.BN(360), ORDER_AMOUNT);
+      await cancelOrder(order, escrow);
+    });
+
+    it("blocks fills and locks by a listed taker", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(361), ORDER_AMOUNT);
+      await deny(taker.publicKey);
+      try {
+        await expectDenied(() => fillOrder(order, escrow, relayer, randomTxSig()));
+        await expectDenied(() => lockOrder(order, taker, 100));
+      } finally {
+        await allow(taker.publicKey);
+      }
+      await fillOrder(order, escrow, relayer, randomTxSig());
+    });
+
+    it("blocks fills and locks against a listed maker's orders", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(362), ORDER_AMOUNT);
+      await deny(maker.publicKey);
+      try {
+        await expectDenied(() => fillOrder(order, escrow, relayer, randomTxSig()));
+        await expectDenied(() => lockOrder(order, taker, 100));
+      } finally {
+        await allow(maker.publicKey);
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("keeps cancel and settle open to listed addresses", async () => {
+      const open = await createOrder(new anchor.BN(363), ORDER_AMOUNT);
+      const locked = await createOrder(new anchor.BN(364), ORDER_AMOUNT);
+      await lockOrder(locked.order, taker, 100);
+
+      await deny(maker.publicKey);
+      await deny(taker.publicKey);
+      try {
+        await cancelOrder(open.order, open.escrow);
+        assert.isNull(await provider.connection.getAccountInfo(open.order));
+        await settleOrder(locked.order, locked.escrow);
+        assert.isNull(await provider.connection.getAccountInfo(locked.order));
+      } finally {
+        await allow(maker.publicKey);
+        await allow(taker.publicKey);
+      }
+    });
+
+    it("emits AddressDenylisted and AddressAllowed", async () => {
+      const address = Keypair.generate().publicKey;
+      const events: string[] = [];
+      const onDeny = program.addEventListener("addressDenylisted", (e) => {
+        if (e.address.equals(address)) events.push("denied");
+      });
+      const onAllow = program.addEventListener("addressAllowed", (e) => {
+        if (e.address.equals(address)) events.push("allowed");
+      });
+      try {
+        await deny(address);
+        const entry = await program.account.denyEntry.fetch(
+          deriveDenyPDA(program.programId, address)[0]
+        );
+        assert.isTrue(entry.address.equals(address));
+        await allow(address);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(onDeny);
+        await program.removeEventListener(onAllow);
+      }
+      assert.deepEqual(events, ["denied", "allowed"]);
+      assert.isNull(
+        await provider.connection.getAccountInfo(
+          deriveDenyPDA(program.programId, address)[0]
+        )
+      );
+    });
+
+    it("rejects a non-admin", async () => {
+      try {
+        await deny(Keypair.generate().publicKey, outsider);
+        assert.fail("Should reject a non-admin");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER STATUS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Status", () => {
+    async function collect(name: string, orderKey: PublicKey, fn: () => Promise<unknown>) {
+      const events: any[] = [];
+      const listener = program.addEventListener(name as any, (e: any) => {
+        if (e.orderKey.equals(orderKey)) events.push(e);
+      });
+      try {
+        await fn();
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+      return events;
+    }
+
+    it("reports Open on creation and Locked on lock", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(365), ORDER_AMOUNT);
+      assert.deepEqual((await program.account.order.fetch(order)).status, { open: {} });
+
+      const events = await collect("orderLocked", order, () => lockOrder(order, taker, 100));
+      assert.deepEqual(events[0].status, { locked: {} });
+      assert.deepEqual((await program.account.order.fetch(order)).status, { locked: {} });
+      await settleOrder(order, escrow);
+    });
+
+    it("reports Filled on fill", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(366), ORDER_AMOUNT);
+      const events = await collect("orderFilled", order, () =>
+        fillOrder(order, escrow, relayer, randomTxSig())
+      );
+      assert.deepEqual(events[0].status, { filled: {} });
+    });
+
+    it("reopens a lapsed lock and reports Cancelled", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(367), ORDER_AMOUNT);
+      await lockOrder(order, taker, 1);
+      const { lockExpirySlot } = await program.account.order.fetch(order);
+      await waitForSlot(lockExpirySlot.toNumber() + 1);
+
+      const events = await collect("orderCancelled", order, () => cancelOrder(order, escrow));
+      assert.deepEqual(events[0].status, { cancelled: {} });
+    });
+
+    it("reports Open again after a lock expires", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(368), ORDER_AMOUNT);
+      await lockOrder(order, taker, 1);
+      const { lockExpirySlot } = await program.account.order.fetch(order);
+      await waitForSlot(lockExpirySlot.toNumber() + 1);
+
+      const events = await collect("lockExpired", order, () => expireLock(order));
+      assert.deepEqual(events[0].status, { open: {} });
+      assert.deepEqual((await program.account.order.fetch(order)).status, { open: {} });
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER VERSION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Version", () => {
+    const ORDER_VERSION = 5;
+
+    it("stamps new orders with the current version and zeroed reserve", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(369), ORDER_AMOUNT);
+      const account = await program.account.order.fetch(order);
+      assert.equal(account.version, ORDER_VERSION);
+      assert.isTrue(account.reserved.every((b: number) => b === 0));
+      await cancelOrder(order, escrow);
+    });
+
+    it("refuses to migrate an order that is already current", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(370), ORDER_AMOUNT);
+      try {
+        await program.methods
+          .migrateOrder()
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: escrow,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should reject a current-version order");
+      } catch (e: any) {
+        assert.include(e.message, "UnsupportedOrderVersion");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // CLIENT ORDER ID
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Client Order Id", () => {
+    const CLIENT_ORDER_ID = new anchor.BN("18446744073709551615");
+
+    async function collect(names: string[], fn: () => Promise<unknown>) {
+      const events: any[] = [];
+      const listeners = names.map((name) =>
+        program.addEventListener(name as any, (e: any) => events.push(e))
+      );
+      try {
+        await fn();
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        for (const l of listeners) await program.removeEventListener(l);
+      }
+      return events;
+    }
+
+    it("persists the id and echoes it on create and fill", async () => {
+      let created: { order: PublicKey; escrow: PublicKey };
+      const events = await collect(["orderCreated", "orderFilled"], async () => {
+        created = await createOrder(
+          new anchor.BN(371),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          ORDER_AMOUNT,
+          null,
+          CLIENT_ORDER_ID
+        );
+        const account = await program.account.order.fetch(created.order);
+        assert.equal(account.clientOrderId.toString(), CLIENT_ORDER_ID.toString());
+        await fillOrder(created.order, created.escrow, relayer, randomTxSig());
+      });
+      const mine = events.filter((e) => e.orderKey.equals(created.order));
+      assert.lengthOf(mine, 2);
+      for (const e of mine) {
+        assert.equal(e.clientOrderId.toString(), CLIENT_ORDER_ID.toString());
+      }
+    });
+
+    it("echoes the id on cancel and allows duplicates", async () => {
+      const first = await createOrder(
+        new anchor.BN(372), ORDER_AMOUNT, EXPIRY_OFFSET, null, ORDER_AMOUNT, null, new anchor.BN(7)
+      );
+      const second = await createOrder(
+        new anchor.BN(373), ORDER_AMOUNT, EXPIRY_OFFSET, null, ORDER_AMOUNT, null, new anchor.BN(7)
+      );
+      const events = await collect(["orderCancelled"], async () => {
+        await cancelOrder(first.order, first.escrow);
+        await cancelOrder(second.order, second.escrow);
+      });
+      const mine = events.filter(
+        (e) => e.orderKey.equals(first.order) || e.orderKey.equals(second.order)
+      );
+      assert.lengthOf(mine, 2);
+      for (const e of mine) assert.equal(e.clientOrderId.toNumber(), 7);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // FILL QUOTE PROTECTION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Fill Quote Protection", () => {
+    it("rejects a fill after the order was cancelled and recreated", async () => {
+      const orderId = new anchor.BN(374);
+      const first = await createOrder(orderId, ORDER_AMOUNT);
+      const quote = await quoteOf(first.order);
+
+      // Maker swaps the order out under the same PDA before the fill lands
+      await cancelOrder(first.order, first.escrow);
+      const second = await createOrder(orderId, ORDER_AMOUNT);
+      assert.isTrue(second.order.equals(first.order));
+
+      try {
+        await fillOrder(second.order, second.escrow, relayer, randomTxSig(), takerGorbaganaAddress, null, quote);
+        assert.fail("Should reject a recreated order");
+      } catch (e: any) {
+        assert.include(e.message, "OrderMismatch");
+      }
+      await cancelOrder(second.order, second.escrow);
+    });
+
+    it("rejects a quote for a different amount", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(375), ORDER_AMOUNT);
+      const [, recipient] = await quoteOf(order);
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig(), takerGorbaganaAddress, null, [
+          ORDER_AMOUNT.muln(2),
+          recipient,
+        ]);
+        assert.fail("Should reject a mismatched amount");
+      } catch (e: any) {
+        assert.include(e.message, "OrderMismatch");
+      }
+      await fillOrder(order, escrow, relayer, randomTxSig());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // DELEGATED FILL
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Delegated Fill", () => {
+    const FEE_BPS = 200; // 2%
+    const TIP_BPS = 100; // 1%, out of the fee
+    let relayerSgorATA: PublicKey;
+
+    function takerNoncePDA() {
+      return deriveTakerNoncePDA(program.programId, taker.publicKey)[0];
+    }
+
+    function setRelayerTip(bps: number) {
+      return program.methods
+        .setRelayerTip(bps)
+        .accounts({ admin: provider.wallet.publicKey, feeConfig: feeConfigPDA })
+        .rpc();
+    }
+
+    async function nextNonce(): Promise<anchor.BN> {
+      const account = await program.account.takerNonce.fetchNullable(takerNoncePDA());
+      return account ? account.nextNonce : new anchor.BN(0);
+    }
+
+    // order (32) || max_amount (u64 LE) || nonce (u64 LE)
+    function message(order: PublicKey, maxAmount: anchor.BN, nonce: anchor.BN) {
+      return Buffer.concat([
+        order.toBuffer(),
+        maxAmount.toArrayLike(Buffer, "le", 8),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ]);
+    }
+
+    async function fillDelegated(
+      order: PublicKey,
+      escrow: PublicKey,
+      opts: {
+        signer?: Keypair;
+        maxAmount?: anchor.BN;
+        nonce?: anchor.BN;
+        txSig?: number[];
+        withSignature?: boolean;
+      } = {}
+    ) {
+      const signer = opts.signer ?? taker;
+      const maxAmount = opts.maxAmount ?? ORDER_AMOUNT;
+      const nonce = opts.nonce ?? (await nextNonce());
+      const txSig = opts.txSig ?? randomTxSig();
+
+      const preInstructions = (opts.withSignature ?? true)
+        ? [
+            Ed25519Program.createInstructionWithPrivateKey({
+              privateKey: signer.secretKey,
+              message: message(order, maxAmount, nonce),
+            }),
+          ]
+        : [];
+
+      // Only the relayer signs; the taker never does
+      return program.methods
+        .fillOrderDelegated(txSig, takerGorbaganaAddress, maxAmount, nonce)
+        .accounts({
+          relayer: relayer.publicKey,
+          relayerEntry: deriveRelayerPDA(program.programId, relayer.publicKey)[0],
+          taker: taker.publicKey,
+          takerNonce: takerNoncePDA(),
+          config: configPDA,
+          takerDeny: deriveDenyPDA(program.programId, taker.publicKey)[0],
+          makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          takerTokenAccount: takerSgorATA,
+          relayerTokenAccount: relayerSgorATA,
+          feeConfig: feeConfigPDA,
+          feeTokenAccount: treasurySgorATA,
+          receipt: deriveReceiptPDA(program.programId, txSig)[0],
+          sgorMint,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .preInstructions(preInstructions)
+        .signers([relayer])
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(
+        relayer.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+      relayerSgorATA = await createAssociatedTokenAccount(
+        provider.connection,
+        relayer,
+        sgorMint,
+        relayer.publicKey
+      );
+      await approve(provider.connection, taker, takerSgorATA, relayer.publicKey, taker, 1);
+      await setFee(FEE_BPS);
+      await setRelayerTip(TIP_BPS);
+    });
+
+    after(async () => {
+      await setRelayerTip(0);
+      await setFee(0);
+      await revoke(provider.connection, taker, takerSgorATA, taker);
+    });
+
+    it("fills for the taker, pays the relayer tip out of the fee, and advances the nonce", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(376), ORDER_AMOUNT);
+      const nonce = await nextNonce();
+      const balance = async (ata: PublicKey) =>
+        (await getAccount(provider.connection, ata)).amount;
+      const takerBefore = await balance(takerSgorATA);
+      const relayerBefore = await balance(relayerSgorATA);
+      const treasuryBefore = await balance(treasurySgorATA);
+
+      await fillDelegated(order, escrow, { nonce });
+
+      const fee = ORDER_AMOUNT.muln(FEE_BPS).divn(10_000);
+      const tip = ORDER_AMOUNT.muln(TIP_BPS).divn(10_000);
+      // The taker gets what a plain fill pays; the tip only splits the fee
+      assert.equal(
+        ((await balance(takerSgorATA)) - takerBefore).toString(),
+        ORDER_AMOUNT.sub(fee).toString()
+      );
+      assert.equal(((await balance(relayerSgorATA)) - relayerBefore).toString(), tip.toString());
+      assert.equal(
+        ((await balance(treasurySgorATA)) - treasuryBefore).toString(),
+        fee.sub(tip).toString()
+      );
+      assert.equal((await nextNonce()).toString(), nonce.addn(1).toString());
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("caps the tip at the protocol fee", async () => {
+      await setFee(0);
+      try {
+        const { order, escrow } = await createOrder(new anchor.BN(453), ORDER_AMOUNT);
+        const takerBefore = (await getAccount(provider.connection, takerSgorATA)).amount;
+        const relayerBefore = (await getAccount(provider.connection, relayerSgorATA)).amount;
+        await fillDelegated(order, escrow);
+        const takerAfter = (await getAccount(provider.connection, takerSgorATA)).amount;
+        const relayerAfter = (await getAccount(provider.connection, relayerSgorATA)).amount;
+        assert.equal((takerAfter - takerBefore).toString(), ORDER_AMOUNT.toString());
+        assert.equal(relayerAfter, relayerBefore);
+      } finally {
+        await setFee(FEE_BPS);
+      }
+    });
+
+    it("rejects a replayed nonce", async () => {
+      const first = await createOrder(new anchor.BN(377), ORDER_AMOUNT);
+      const second = await createOrder(new anchor.BN(378), ORDER_AMOUNT);
+      const nonce = await nextNonce();
+      await fillDelegated(first.order, first.escrow, { nonce });
+      try {
+        await fillDelegated(second.order, second.escrow, { nonce });
+        assert.fail("Should reject a reused nonce");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidNonce");
+      }
+      await cancelOrder(second.order, second.escrow);
+    });
+
+    it("rejects a missing or foreign signature", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(379), ORDER_AMOUNT);
+      for (const opts of [{ withSignature: false }, { signer: outsider }]) {
+        try {
+          await fillDelegated(order, escrow, opts);
+          assert.fail("Should reject without the taker's signature");
+        } catch (e: any) {
+          assert.include(e.message, "InvalidTakerSignature");
+        }
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a price above the signed maximum", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(380), ORDER_AMOUNT);
+      try {
+        await fillDelegated(order, escrow, { maxAmount: ORDER_AMOUNT.subn(1) });
+        assert.fail("Should reject a price above max_amount");
+      } catch (e: any) {
+        assert.include(e.message, "OrderMismatch");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("requires the relayer as delegate on the taker account", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(381), ORDER_AMOUNT);
+      await revoke(provider.connection, taker, takerSgorATA, taker);
+      try {
+        await fillDelegated(order, escrow);
+        assert.fail("Should reject without a delegate approval");
+      } catch (e: any) {
+        assert.include(e.message, "DelegateNotApproved");
+      } finally {
+        await approve(provider.connection, taker, takerSgorATA, relayer.publicKey, taker, 1);
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // HTLC
+  // ═══════════════════════════════════════════════════════════════════
+  describe("HTLC", () => {
+    function createHtlc(orderId: number, hashlock: number[], expiryOffset = EXPIRY_OFFSET) {
+      return createOrder(
+        new anchor.BN(orderId),
+        ORDER_AMOUNT,
+        expiryOffset,
+        taker.publicKey,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        hashlock
+      );
+    }
+
+    function claimHtlc(order: PublicKey, escrow: PublicKey, preimage: number[]) {
+      return program.methods
+        .claimHtlcOrder(preimage)
+        .accounts({
+          taker: taker.publicKey,
+          takerDeny: deriveDenyPDA(program.programId, taker.publicKey)[0],
+          makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          takerTokenAccount: takerSgorATA,
+          feeConfig: feeConfigPDA,
+          feeTokenAccount: treasurySgorATA,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([taker])
+        .rpc();
+    }
+
+    it("releases the escrow to the taker on the preimage and reports it", async () => {
+      const preimage = randomPreimage();
+      const { order, escrow } = await createHtlc(382, hashlockOf(preimage));
+      const before = (await getAccount(provider.connection, takerSgorATA)).amount;
+
+      let event: any = null;
+      const listener = program.addEventListener("htlcClaimed", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await claimHtlc(order, escrow, preimage);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const after = (await getAccount(provider.connection, takerSgorATA)).amount;
+      assert.equal((after - before).toString(), ORDER_AMOUNT.toString());
+      assert.isNull(await provider.connection.getAccountInfo(order));
+      assert.deepEqual(Array.from(event.preimage), preimage);
+    });
+
+    it("rejects a wrong preimage", async () => {
+      const preimage = randomPreimage();
+      const { order, escrow } = await createHtlc(383, hashlockOf(preimage));
+      try {
+        await claimHtlc(order, escrow, randomPreimage());
+        assert.fail("Should reject a preimage that does not match");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidPreimage");
+      }
+      // Still claimable with the right one
+      await claimHtlc(order, escrow, preimage);
+    });
+
+    it("requires a named taker", async () => {
+      try {
+        await createOrder(
+          new anchor.BN(384),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          ORDER_AMOUNT,
+          null,
+          new anchor.BN(0),
+          hashlockOf(randomPreimage())
+        );
+        assert.fail("Should reject a public HTLC order");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcRequiresTaker");
+      }
+    });
+
+    it("keeps hash-locked orders off the attested fill path", async () => {
+      const preimage = randomPreimage();
+      const { order, escrow } = await createHtlc(385, hashlockOf(preimage));
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        assert.fail("Should require the preimage");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcClaimRequired");
+      }
+      try {
+        await lockOrder(order, taker, 10);
+        assert.fail("Should not lock a hash-locked order");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcClaimRequired");
+      }
+      await claimHtlc(order, escrow, preimage);
+    });
+
+    it("rejects a claim on an order without a hashlock", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(386), ORDER_AMOUNT);
+      try {
+        await claimHtlc(order, escrow, randomPreimage());
+        assert.fail("Should reject a plain order");
+      } catch (e: any) {
+        assert.include(e.message, "NotHashLocked");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("holds the refund and the expiry until the timeout", async () => {
+      const preimage = randomPreimage();
+      const { order, escrow } = await createHtlc(387, hashlockOf(preimage));
+      try {
+        await cancelOrder(order, escrow);
+        assert.fail("Should not refund before expiry");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcNotExpired");
+      }
+      try {
+        await program.methods
+          .updateExpiration(slotExpiry((await getCurrentSlot()) + 5))
+          .accounts({ authority: maker.publicKey, order, config: configPDA })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should not move an HTLC expiry");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcTermsFixed");
+      }
+      await claimHtlc(order, escrow, preimage);
+    });
+
+    it("refunds the maker after the timeout and rejects a late claim", async () => {
+      const preimage = randomPreimage();
+      const { order, escrow } = await createHtlc(388, hashlockOf(preimage), 5);
+      await waitForSlot(expirySlot(await program.account.order.fetch(order)) + 1);
+
+      try {
+        await claimHtlc(order, escrow, preimage);
+        assert.fail("Should reject a claim after expiry");
+      } catch (e: any) {
+        assert.include(e.message, "OrderExpired");
+      }
+
+      const before = (await getAccount(provider.connection, makerSgorATA)).amount;
+      await cancelOrder(order, escrow);
+      const after = (await getAccount(provider.connection, makerSgorATA)).amount;
+      assert.equal((after - before).toString(), ORDER_AMOUNT.toString());
+    });
+
+    it("lets a claim land while the bridge is paused", async () => {
+      const preimage = randomPreimage();
+      const { order, escrow } = await createHtlc(389, hashlockOf(preimage));
+      await program.methods
+        .pause()
+        .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+      try {
+        await claimHtlc(order, escrow, preimage);
+      } finally {
+        await program.methods
+          .unpause()
+          .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+          .rpc();
+      }
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ESCROW LINKAGE
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Escrow Linkage", () => {
+    // A token account the order PDA owns but that is not its escrow
+    function decoyEscrow(order: PublicKey) {
+      return createAccount(
+        provider.connection,
+        maker,
+        sgorMint,
+        order,
+        Keypair.generate()
+      );
+    }
+
+    it("stores the escrow address and bump on the order", async () => {
+      const orderId = new anchor.BN(390);
+      const { order, escrow } = await createOrder(orderId, ORDER_AMOUNT);
+      const [, bump] = deriveEscrowPDA(program.programId, maker.publicKey, orderId, sgorMint);
+      const account = await program.account.order.fetch(order);
+      assert.isTrue(account.escrowTokenAccount.equals(escrow));
+      assert.equal(account.escrowBump, bump);
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a fill against a substitute escrow", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(391), ORDER_AMOUNT);
+      const decoy = await decoyEscrow(order);
+      try {
+        await fillOrder(order, decoy, relayer, randomTxSig());
+        assert.fail("Should reject an escrow the order did not create");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidEscrowAccount");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a cancel against a substitute escrow", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(392), ORDER_AMOUNT);
+      const decoy = await decoyEscrow(order);
+      try {
+        await cancelOrder(order, decoy);
+        assert.fail("Should reject an escrow the order did not create");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidEscrowAccount");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // SWEEP EXCESS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Sweep Excess", () => {
+    const STRAY = 5_000_000;
+
+    function fund(order: PublicKey, lamports: number) {
+      return provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: order,
+            lamports,
+          })
+        )
+      );
+    }
+
+    function sweep(order: PublicKey, signer: Keypair = maker) {
+      return program.methods
+        .sweepExcess()
+        .accounts({ maker: signer.publicKey, order })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("returns lamports sent to the order PDA to the maker", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(393), ORDER_AMOUNT);
+      const rentOnly = await getLamports(order);
+      await fund(order, STRAY);
+
+      let event: any = null;
+      const listener = program.addEventListener("sweepExcess", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      const makerBefore = await getLamports(maker.publicKey);
+      try {
+        await sweep(order);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      // The provider wallet pays the transaction fee
+      assert.equal(await getLamports(maker.publicKey), makerBefore + STRAY);
+      assert.equal(await getLamports(order), rentOnly);
+      assert.equal(event.amount.toNumber(), STRAY);
+      await cancelOrder(order, escrow);
+    });
+
+    it("leaves a held lock bond on the PDA", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(394), ORDER_AMOUNT);
+      const rentOnly = await getLamports(order);
+      await lockOrder(order, taker, 2);
+      await fund(order, STRAY);
+
+      await sweep(order);
+
+      assert.equal(await getLamports(order), rentOnly + BOND.toNumber());
+      const { lockExpirySlot } = await program.account.order.fetch(order);
+      await waitForSlot(lockExpirySlot.toNumber() + 1);
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects anyone but the maker", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(395), ORDER_AMOUNT);
+      await fund(order, STRAY);
+      try {
+        await sweep(order, outsider);
+        assert.fail("Should reject a non-maker sweep");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ACTIVATION SLOT
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Activation Slot", () => {
+    const ACTIVATION_OFFSET = 20;
+
+    function activationOf(order: PublicKey) {
+      return program.account.order
+        .fetch(order)
+        .then((o) => o.activationSlot.toNumber());
+    }
+
+    it("stores the activation slot and emits it", async () => {
+      let event: any = null;
+      const listener = program.addEventListener("orderCreated", (e) => {
+        if (e.orderId.eq(new anchor.BN(397))) event = e;
+      });
+      let order: PublicKey, escrow: PublicKey;
+      try {
+        ({ order, escrow } = await createOrder(
+          new anchor.BN(397),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          ORDER_AMOUNT,
+          null,
+          new anchor.BN(0),
+          null,
+          ACTIVATION_OFFSET
+        ));
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const activationSlot = await activationOf(order);
+      assert.isAbove(activationSlot, 0);
+      assert.equal(event.activationSlot.toNumber(), activationSlot);
+      await cancelOrder(order, escrow);
+    });
+
+    it("defaults to fillable immediately", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(398), ORDER_AMOUNT);
+      assert.equal(await activationOf(order), 0);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+    });
+
+    it("rejects fills and locks before activation, then fills from it on", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(399),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        null,
+        ACTIVATION_OFFSET
+      );
+      const activationSlot = await activationOf(order);
+
+      // activation_slot - 1 and earlier
+      assert.isBelow(await getCurrentSlot(), activationSlot);
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        assert.fail("Should reject a fill before activation");
+      } catch (e: any) {
+        assert.include(e.message, "OrderNotActive");
+      }
+      try {
+        await lockOrder(order, taker, 5);
+        assert.fail("Should reject a lock before activation");
+      } catch (e: any) {
+        assert.include(e.message, "OrderNotActive");
+      }
+
+      // activation_slot onwards: the fill lands at or after it
+      await waitForSlot(activationSlot);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("lets the maker cancel before activation", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(400),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        null,
+        ACTIVATION_OFFSET
+      );
+      assert.isBelow(await getCurrentSlot(), await activationOf(order));
+      await cancelOrder(order, escrow);
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("rejects an activation slot at or after the expiration slot", async () => {
+      try {
+        await createOrder(
+          new anchor.BN(401),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          ORDER_AMOUNT,
+          null,
+          new anchor.BN(0),
+          null,
+          EXPIRY_OFFSET
+        );
+        assert.fail("Should reject activation at expiry");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidActivationSlot");
+      }
+    });
+
+    it("rejects moving the expiry to or before the activation slot", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(402),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        null,
+        ACTIVATION_OFFSET
+      );
+      try {
+        await program.methods
+          .updateExpiration(slotExpiry(await activationOf(order)))
+          .accounts({ authority: maker.publicKey, order, config: configPDA })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should reject an expiry at the activation slot");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidActivationSlot");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // QUOTE FILL
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Quote Fill", () => {
+    function quoteFill(order: PublicKey, fillAmount: anchor.BN) {
+      return program.methods
+        .quoteFill(fillAmount)
+        .accounts({ order, config: configPDA, feeConfig: feeConfigPDA })
+        .view();
+    }
+
+    it("returns the quote without touching the order", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(403), ORDER_AMOUNT);
+      const before = await provider.connection.getAccountInfo(order);
+
+      const quote = await quoteFill(order, ORDER_AMOUNT);
+      assert.equal(quote.fillAmount.toString(), ORDER_AMOUNT.toString());
+      assert.equal(quote.fee.toString(), "0");
+      assert.equal(quote.outputAmount.toString(), ORDER_AMOUNT.toString());
+      assert.equal(quote.expectedGgorAmount.toString(), ORDER_AMOUNT.toString());
+
+      const after = await provider.connection.getAccountInfo(order);
+      assert.isTrue(after!.data.equals(before!.data));
+      assert.equal(after!.lamports, before!.lamports);
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a partial fill amount", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(404), ORDER_AMOUNT);
+      try {
+        await quoteFill(order, ORDER_AMOUNT.subn(1));
+        assert.fail("Should reject a partial fill");
+      } catch (e: any) {
+        assert.include(e.message, "PartialFillNotSupported");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects orders the fill path would reject", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(405),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        null,
+        20
+      );
+      try {
+        await quoteFill(order, ORDER_AMOUNT);
+        assert.fail("Should reject an order that is not active yet");
+      } catch (e: any) {
+        assert.include(e.message, "OrderNotActive");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("matches what fill_order actually moves", async () => {
+      await setFee(100); // 1%
+      try {
+        const { order, escrow } = await createOrder(
+          new anchor.BN(406),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          ORDER_AMOUNT,
+          ORDER_AMOUNT.divn(2)
+        );
+        const quote = await quoteFill(order, ORDER_AMOUNT);
+        assert.equal(quote.fee.toString(), ORDER_AMOUNT.divn(100).toString());
+        assert.equal(
+          quote.outputAmount.toString(),
+          ORDER_AMOUNT.sub(quote.fee).toString()
+        );
+        // Dutch auction: the quoted price has started to decay
+        assert.isTrue(quote.expectedGgorAmount.lte(ORDER_AMOUNT));
+        assert.isTrue(quote.expectedGgorAmount.gte(ORDER_AMOUNT.divn(2)));
+
+        const takerBefore = (await getAccount(provider.connection, takerSgorATA)).amount;
+        const treasuryBefore = (await getAccount(provider.connection, treasurySgorATA))
+          .amount;
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        const takerAfter = (await getAccount(provider.connection, takerSgorATA)).amount;
+        const treasuryAfter = (await getAccount(provider.connection, treasurySgorATA))
+          .amount;
+
+        assert.equal((takerAfter - takerBefore).toString(), quote.outputAmount.toString());
+        assert.equal((treasuryAfter - treasuryBefore).toString(), quote.fee.toString());
+      } finally {
+        await setFee(0);
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER OPERATOR
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Operator", () => {
+    // The taker doubles as operator: it already holds an sGOR account of
+    // its own to try redirecting a refund into
+    const operator = () => taker;
+
+    function createOperatedOrder(orderId: number) {
+      return createOrder(
+        new anchor.BN(orderId),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        null,
+        null,
+        operator().publicKey
+      );
+    }
+
+    function cancelAs(
+      signer: Keypair,
+      order: PublicKey,
+      escrow: PublicKey,
+      makerTokenAccount = makerSgorATA
+    ) {
+      return program.methods
+        .cancelOrder()
+        .accounts({
+          authority: signer.publicKey,
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount,
+          config: configPDA,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    function updateOperator(order: PublicKey, newOperator: PublicKey | null, signer = maker) {
+      return program.methods
+        .updateOperator(newOperator)
+        .accounts({ maker: signer.publicKey, order })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("lets the operator cancel, refunding the maker", async () => {
+      const { order, escrow } = await createOperatedOrder(407);
+      const stored = await program.account.order.fetch(order);
+      assert.equal(stored.operator!.toBase58(), operator().publicKey.toBase58());
+
+      const makerBefore = (await getAccount(provider.connection, makerSgorATA)).amount;
+      await cancelAs(operator(), order, escrow);
+      const makerAfter = (await getAccount(provider.connection, makerSgorATA)).amount;
+
+      assert.equal((makerAfter - makerBefore).toString(), ORDER_AMOUNT.toString());
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("rejects an operator refunding into a non-maker account", async () => {
+      const { order, escrow } = await createOperatedOrder(408);
+      try {
+        await cancelAs(operator(), order, escrow, takerSgorATA);
+        assert.fail("Should reject the operator's own refund account");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("lets the operator roll the expiration", async () => {
+      const { order, escrow } = await createOperatedOrder(409);
+      const later = expirySlot(await program.account.order.fetch(order)) + 100;
+      await program.methods
+        .updateExpiration(slotExpiry(later))
+        .accounts({ authority: operator().publicKey, order, config: configPDA })
+        .signers([operator()])
+        .rpc();
+      assert.equal(expirySlot(await program.account.order.fetch(order)), later);
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects cancels from anyone else", async () => {
+      const { order, escrow } = await createOperatedOrder(410);
+      try {
+        await cancelAs(outsider, order, escrow);
+        assert.fail("Should reject a stranger");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("lets only the maker change the operator", async () => {
+      const { order, escrow } = await createOperatedOrder(411);
+      try {
+        await updateOperator(order, outsider.publicKey, operator());
+        assert.fail("Should reject an operator promoting another key");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+
+      let event: any = null;
+      const listener = program.addEventListener("operatorUpdated", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await updateOperator(order, null);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+      assert.equal(event.oldOperator.toBase58(), operator().publicKey.toBase58());
+      assert.isNull(event.newOperator);
+      assert.deepEqual(event.status, { open: {} });
+
+      // The cleared operator can no longer cancel
+      try {
+        await cancelAs(operator(), order, escrow);
+        assert.fail("Should reject a cleared operator");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // REPLACE ORDER
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Replace Order", () => {
+    function replaceOrder(
+      order: PublicKey,
+      escrow: PublicKey,
+      newAmount: anchor.BN,
+      newExpirySlot: number,
+      newRecipient: PublicKey,
+      signer = maker
+    ) {
+      return program.methods
+        .replaceOrder(newAmount, slotExpiry(newExpirySlot), newRecipient)
+        .accounts({
+          maker: signer.publicKey,
+          order,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: makerSgorATA,
+          sgorMint,
+          config: configPDA,
+          makerDeny: deriveDenyPDA(program.programId, signer.publicKey)[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    async function balanceOf(account: PublicKey) {
+      return (await getAccount(provider.connection, account)).amount;
+    }
+
+    it("tops up the escrow and updates the order in place", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(412), ORDER_AMOUNT);
+      const newAmount = ORDER_AMOUNT.muln(2);
+      const newExpiry = (await getCurrentSlot()) + EXPIRY_OFFSET * 2;
+      const newRecipient = Keypair.generate().publicKey;
+      const makerBefore = await balanceOf(makerSgorATA);
+
+      let event: any = null;
+      const listener = program.addEventListener("orderReplaced", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await replaceOrder(order, escrow, newAmount, newExpiry, newRecipient);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const stored = await program.account.order.fetch(order);
+      assert.equal(stored.amount.toString(), newAmount.toString());
+      assert.equal(expirySlot(stored), newExpiry);
+      assert.equal(stored.gorbaganaRecipient.toBase58(), newRecipient.toBase58());
+      assert.equal((await balanceOf(escrow)).toString(), newAmount.toString());
+      assert.equal(
+        (makerBefore - (await balanceOf(makerSgorATA))).toString(),
+        ORDER_AMOUNT.toString()
+      );
+      assert.equal(event.oldAmount.toString(), ORDER_AMOUNT.toString());
+      assert.equal(event.newAmount.toString(), newAmount.toString());
+      assert.equal(event.newGorbaganaRecipient.toBase58(), newRecipient.toBase58());
+
+      // The same PDA fills at the new size
+      const takerBefore = await balanceOf(takerSgorATA);
+      await fillOrder(order, escrow, relayer, randomTxSig());
+      assert.equal(
+        ((await balanceOf(takerSgorATA)) - takerBefore).toString(),
+        newAmount.toString()
+      );
+    });
+
+    it("refunds the difference when shrinking", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(413), ORDER_AMOUNT.muln(2));
+      const expiry = expirySlot(await program.account.order.fetch(order));
+      const makerBefore = await balanceOf(makerSgorATA);
+
+      await replaceOrder(order, escrow, ORDER_AMOUNT, expiry, Keypair.generate().publicKey);
+
+      assert.equal((await balanceOf(escrow)).toString(), ORDER_AMOUNT.toString());
+      assert.equal(
+        ((await balanceOf(makerSgorATA)) - makerBefore).toString(),
+        ORDER_AMOUNT.toString()
+      );
+      assert.isNotNull(await provider.connection.getAccountInfo(order));
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects anyone but the maker", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(414), ORDER_AMOUNT);
+      const expiry = expirySlot(await program.account.order.fetch(order));
+      try {
+        await replaceOrder(
+          order,
+          escrow,
+          ORDER_AMOUNT,
+          expiry,
+          Keypair.generate().publicKey,
+          outsider
+        );
+        assert.fail("Should reject a non-maker replace");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects hash-locked orders", async () => {
+      const preimage = randomPreimage();
+      const { order, escrow } = await createOrder(
+        new anchor.BN(415),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        taker.publicKey,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        hashlockOf(preimage)
+      );
+      const expiry = expirySlot(await program.account.order.fetch(order));
+      try {
+        await replaceOrder(order, escrow, ORDER_AMOUNT, expiry, Keypair.generate().publicKey);
+        assert.fail("Should reject an HTLC replace");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcTermsFixed");
+      }
+      await program.methods
+        .claimHtlcOrder(preimage)
+        .accounts({
+          taker: taker.publicKey,
+          takerDeny: deriveDenyPDA(program.programId, taker.publicKey)[0],
+          makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          takerTokenAccount: takerSgorATA,
+          feeConfig: feeConfigPDA,
+          feeTokenAccount: treasurySgorATA,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([taker])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // FILL POLICY
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Fill Policy", () => {
+    function setFillPolicy(minFillDelaySlots: number, allowSelfFill: boolean, admin: Keypair | null = null) {
+      const builder = program.methods
+        .setFillPolicy(new anchor.BN(minFillDelaySlots), allowSelfFill)
+        .accounts({
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+        });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    it("emits the creation slot", async () => {
+      let event: any = null;
+      const listener = program.addEventListener("orderCreated", (e) => {
+        if (e.orderId.eq(new anchor.BN(416))) event = e;
+      });
+      let order: PublicKey, escrow: PublicKey;
+      try {
+        ({ order, escrow } = await createOrder(new anchor.BN(416), ORDER_AMOUNT));
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+      const stored = await program.account.order.fetch(order);
+      assert.equal(event.createdSlot.toString(), stored.createdSlot.toString());
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects fills and locks before the minimum delay", async () => {
+      const DELAY = 20;
+      await setFillPolicy(DELAY, false);
+      try {
+        const { order, escrow } = await createOrder(new anchor.BN(417), ORDER_AMOUNT);
+        try {
+          await fillOrder(order, escrow, relayer, randomTxSig());
+          assert.fail("Should reject a fill before the delay");
+        } catch (e: any) {
+          assert.include(e.message, "FillTooEarly");
+        }
+        try {
+          await lockOrder(order, taker, 5);
+          assert.fail("Should reject a lock before the delay");
+        } catch (e: any) {
+          assert.include(e.message, "FillTooEarly");
+        }
+
+        const { createdSlot } = await program.account.order.fetch(order);
+        await waitForSlot(createdSlot.toNumber() + DELAY);
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        assert.isNull(await provider.connection.getAccountInfo(order));
+      } finally {
+        await setFillPolicy(1, false);
+      }
+    });
+
+    it("rejects a maker taking their own order", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(418), ORDER_AMOUNT);
+      try {
+        await lockOrder(order, maker, 5);
+        assert.fail("Should reject a self-fill");
+      } catch (e: any) {
+        assert.include(e.message, "SelfFillNotAllowed");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("allows self-fills when the admin enables them", async () => {
+      await setFillPolicy(1, true);
+      try {
+        const { order, escrow } = await createOrder(new anchor.BN(419), ORDER_AMOUNT);
+        await lockOrder(order, maker, 2);
+        const { lockedBy, lockExpirySlot } = await program.account.order.fetch(order);
+        assert.equal(lockedBy.toBase58(), maker.publicKey.toBase58());
+        await waitForSlot(lockExpirySlot.toNumber() + 1);
+        await cancelOrder(order, escrow);
+      } finally {
+        await setFillPolicy(1, false);
+      }
+    });
+
+    it("flags self-fills in OrderFilled", async () => {
+      const events: any[] = [];
+      const listener = program.addEventListener("orderFilled", (e) => events.push(e));
+      await setFillPolicy(1, true);
+      try {
+        const plain = await createOrder(new anchor.BN(428), ORDER_AMOUNT);
+        await fillOrder(plain.order, plain.escrow, relayer, randomTxSig());
+
+        const own = await createOrder(new anchor.BN(429), ORDER_AMOUNT);
+        await lockOrder(own.order, maker, 50);
+        const txSig = randomTxSig();
+        await program.methods
+          .settleOrder(txSig)
+          .accounts({
+            relayer: relayer.publicKey,
+            relayerEntry: deriveRelayerPDA(program.programId, relayer.publicKey)[0],
+            config: configPDA,
+            taker: maker.publicKey,
+            maker: maker.publicKey,
+            order: own.order,
+            makerRegistry: makerRegistryPDA,
+            stats: statsPDA,
+            escrowTokenAccount: own.escrow,
+            takerTokenAccount: makerSgorATA,
+            feeConfig: feeConfigPDA,
+            feeTokenAccount: treasurySgorATA,
+            receipt: deriveReceiptPDA(program.programId, txSig)[0],
+            sgorMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([relayer])
+          .rpc();
+        await new Promise((r) => setTimeout(r, 1000));
+
+        const byOrder = (key: PublicKey) => events.find((e) => e.orderKey.equals(key));
+        assert.isFalse(byOrder(plain.order).selfFill);
+        assert.isTrue(byOrder(own.order).selfFill);
+      } finally {
+        await program.removeEventListener(listener);
+        await setFillPolicy(1, false);
+      }
+    });
+
+    it("rejects policy updates from a non-admin", async () => {
+      try {
+        await setFillPolicy(0, true, outsider);
+        assert.fail("Should reject a non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER DECIMALS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Decimals", () => {
+    it("records the sGOR mint's decimals", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(420), ORDER_AMOUNT);
+      assert.equal((await program.account.order.fetch(order)).decimals, 6);
+      await cancelOrder(order, escrow);
+    });
+
+    it("records, reports, and settles a mint with unexpected decimals", async () => {
+      // Gorbagana-style 9 decimals: the same ORDER_AMOUNT is 1000x smaller
+      const mint = await createMint(
+        provider.connection,
+        mintAuthority,
+        mintAuthority.publicKey,
+        null,
+        9
+      );
+      const makerAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        maker,
+        mint,
+        maker.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        mint,
+        makerAccount,
+        mintAuthority,
+        ORDER_AMOUNT.toNumber()
+      );
+
+      const orderId = new anchor.BN(421);
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, orderId);
+      const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, orderId, mint);
+
+      await setConfig(mint, MIN_ORDER_AMOUNT);
+      let event: any = null;
+      const listener = program.addEventListener("orderCreated", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await program.methods
+          .createOrder({
+            orderId,
+            amount: ORDER_AMOUNT,
+            expectedGgorAmount: ORDER_AMOUNT,
+            expiry: slotExpiry((await getCurrentSlot()) + EXPIRY_OFFSET),
+            gorbaganaRecipient: Keypair.generate().publicKey,
+            allowedTaker: null,
+            endExpectedGgorAmount: null,
+            clientOrderId: new anchor.BN(0),
+            hashlock: null,
+            activationSlot: null,
+            operator: null,
+            payoutSplits: [],
+          })
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerAccount,
+            sgorMint: mint,
+            mintAllow: deriveMintAllowPDA(program.programId, mint)[0],
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc();
+        await new Promise((r) => setTimeout(r, 1000));
+
+        assert.equal((await program.account.order.fetch(order)).decimals, 9);
+        assert.equal(event.decimals, 9);
+
+        await program.methods
+          .cancelOrder()
+          .accounts({
+            authority: maker.publicKey,
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerAccount,
+            config: configPDA,
+            sgorMint: mint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([maker])
+          .rpc();
+        assert.equal(
+          (await getAccount(provider.connection, makerAccount)).amount.toString(),
+          ORDER_AMOUNT.toString()
+        );
+      } finally {
+        await program.removeEventListener(listener);
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // PAYOUT SPLITS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Payout Splits", () => {
+    const affiliate = Keypair.generate();
+    let affiliateSgorATA: PublicKey;
+
+    async function balanceOf(account: PublicKey) {
+      return (await getAccount(provider.connection, account)).amount;
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(
+        affiliate.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+      affiliateSgorATA = await createAssociatedTokenAccount(
+        provider.connection,
+        affiliate,
+        sgorMint,
+        affiliate.publicKey
+      );
+    });
+
+    it("splits the release, rounding the remainder to the first recipient", async () => {
+      // 1/3 each way does not divide evenly; the taker leg takes the dust
+      const amount = ORDER_AMOUNT.addn(1);
+      const splits = [
+        { recipient: PublicKey.default, bps: 6_667 },
+        { recipient: affiliate.publicKey, bps: 3_333 },
+      ];
+      const { order, escrow } = await createOrder(
+        new anchor.BN(422),
+        amount,
+        EXPIRY_OFFSET,
+        null,
+        amount,
+        null,
+        new anchor.BN(0),
+        null,
+        null,
+        null,
+        splits
+      );
+      const stored = await program.account.order.fetch(order);
+      assert.equal(stored.payoutSplitCount, 2);
+
+      const takerBefore = await balanceOf(takerSgorATA);
+      const affiliateBefore = await balanceOf(affiliateSgorATA);
+      let event: any = null;
+      const listener = program.addEventListener("payoutSplitPaid", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig(), undefined, null, null, [
+          takerSgorATA,
+          affiliateSgorATA,
+        ]);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const affiliateShare = amount.muln(3_333).divn(10_000);
+      const takerShare = amount.sub(affiliateShare);
+      assert.equal(
+        ((await balanceOf(affiliateSgorATA)) - affiliateBefore).toString(),
+        affiliateShare.toString()
+      );
+      assert.equal(
+        ((await balanceOf(takerSgorATA)) - takerBefore).toString(),
+        takerShare.toString()
+      );
+      assert.equal(event.legs.length, 2);
+      assert.equal(event.legs[0].recipient.toBase58(), taker.publicKey.toBase58());
+      assert.equal(event.legs[0].amount.toString(), takerShare.toString());
+      assert.equal(event.legs[1].tokenAccount.toBase58(), affiliateSgorATA.toBase58());
+      assert.equal(event.legs[1].amount.toString(), affiliateShare.toString());
+    });
+
+    it("rejects splits that do not sum to 10000 bps", async () => {
+      try {
+        await createOrder(
+          new anchor.BN(423),
+          ORDER_AMOUNT,
+          EXPIRY_OFFSET,
+          null,
+          ORDER_AMOUNT,
+          null,
+          new anchor.BN(0),
+          null,
+          null,
+          null,
+          [
+            { recipient: PublicKey.default, bps: 5_000 },
+            { recipient: affiliate.publicKey, bps: 4_000 },
+          ]
+        );
+        assert.fail("Should reject an incomplete split");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidPayoutSplits");
+      }
+    });
+
+    it("rejects payout accounts that do not match the splits", async () => {
+      const { order, escrow } = await createOrder(
+        new anchor.BN(424),
+        ORDER_AMOUNT,
+        EXPIRY_OFFSET,
+        null,
+        ORDER_AMOUNT,
+        null,
+        new anchor.BN(0),
+        null,
+        null,
+        null,
+        [
+          { recipient: PublicKey.default, bps: 5_000 },
+          { recipient: affiliate.publicKey, bps: 5_000 },
+        ]
+      );
+      for (const accounts of [[takerSgorATA], [takerSgorATA, makerSgorATA]]) {
+        try {
+          await fillOrder(order, escrow, relayer, randomTxSig(), undefined, null, null, accounts);
+          assert.fail("Should reject mismatched payout accounts");
+        } catch (e: any) {
+          assert.include(e.message, "InvalidPayoutAccounts");
+        }
+      }
+      await cancelOrder(order, escrow);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // CREATE RATE LIMIT
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Create Rate Limit", () => {
+    const DEFAULT_WINDOW_SLOTS = 150;
+
+    function setCreateRateLimit(maxCreates: number, windowSlots: number, admin: Keypair | null = null) {
+      const builder = program.methods
+        .setCreateRateLimit(maxCreates, new anchor.BN(windowSlots))
+        .accounts({
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+        });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    it("rejects creates past the cap and resets at the window boundary", async () => {
+      const WINDOW = 40;
+      await setCreateRateLimit(2, WINDOW);
+      const opened: { order: PublicKey; escrow: PublicKey }[] = [];
+      try {
+        opened.push(await createOrder(new anchor.BN(425), ORDER_AMOUNT));
+        const { windowStartSlot } = await program.account.makerRegistry.fetch(makerRegistryPDA);
+        const start = windowStartSlot.toNumber();
+        opened.push(await createOrder(new anchor.BN(426), ORDER_AMOUNT));
+        try {
+          await createOrder(new anchor.BN(427), ORDER_AMOUNT);
+          assert.fail("Should reject a third create in the window");
+        } catch (e: any) {
+          assert.include(e.message, "RateLimited");
+        }
+
+        // The first slot of the next window accepts creates again
+        await waitForSlot(start + WINDOW);
+        opened.push(await createOrder(new anchor.BN(427), ORDER_AMOUNT));
+        const registry = await program.account.makerRegistry.fetch(makerRegistryPDA);
+        assert.isAtLeast(registry.windowStartSlot.toNumber(), start + WINDOW);
+        assert.equal(registry.createsInWindow, 1);
+      } finally {
+        await setCreateRateLimit(0, DEFAULT_WINDOW_SLOTS);
+        for (const { order, escrow } of opened) await cancelOrder(order, escrow);
+      }
+    });
+
+    it("rejects a zero-length window", async () => {
+      try {
+        await setCreateRateLimit(1, 0);
+        assert.fail("Should reject a zero window");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidConfig");
+      }
+    });
+
+    it("rejects rate limit updates from a non-admin", async () => {
+      try {
+        await setCreateRateLimit(1, DEFAULT_WINDOW_SLOTS, outsider);
+        assert.fail("Should reject a non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // RENT ACCOUNTING
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Rent Accounting", () => {
+    const ORDER_LEN = 644; // Order::LEN
+    const TOKEN_ACCOUNT_LEN = 165;
+
+    async function expectedRent() {
+      return (
+        (await provider.connection.getMinimumBalanceForRentExemption(ORDER_LEN)) +
+        (await provider.connection.getMinimumBalanceForRentExemption(TOKEN_ACCOUNT_LEN))
+      );
+    }
+
+    async function collect(names: string[], orderKey: () => PublicKey, fn: () => Promise<unknown>) {
+      const events: Record<string, any> = {};
+      const listeners = names.map((name) =>
+        program.addEventListener(name as any, (e: any) => {
+          if (e.orderKey.equals(orderKey())) events[name] = e;
+        })
+      );
+      try {
+        await fn();
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        for (const l of listeners) await program.removeEventListener(l);
+      }
+      return events;
+    }
+
+    it("reports the rent locked at create and returned on cancel", async () => {
+      const orderId = new anchor.BN(430);
+      const [orderKey] = deriveOrderPDA(program.programId, maker.publicKey, orderId);
+      let created!: { order: PublicKey; escrow: PublicKey };
+      const events = await collect(["orderCreated"], () => orderKey, async () => {
+        created = await createOrder(orderId, ORDER_AMOUNT);
+      });
+      const locked = events.orderCreated.rentLockedLamports.toNumber();
+      assert.equal(locked, await expectedRent());
+      assert.equal(
+        locked,
+        (await getLamports(created.order)) + (await getLamports(created.escrow))
+      );
+
+      const makerBefore = await getLamports(maker.publicKey);
+      const cancelled = await collect(["orderCancelled"], () => orderKey, () =>
+        cancelOrder(created.order, created.escrow)
+      );
+      assert.equal(cancelled.orderCancelled.rentReturnedLamports.toNumber(), locked);
+      // The provider wallet pays transaction fees, so the maker's gain is exact
+      assert.equal((await getLamports(maker.publicKey)) - makerBefore, locked);
+    });
+
+    it("reports the rent returned on fill", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(431), ORDER_AMOUNT);
+      const makerBefore = await getLamports(maker.publicKey);
+      const events = await collect(["orderFilled"], () => order, () =>
+        fillOrder(order, escrow, relayer, randomTxSig())
+      );
+      const returned = events.orderFilled.rentReturnedLamports.toNumber();
+      assert.equal(returned, await expectedRent());
+      assert.equal((await getLamports(maker.publicKey)) - makerBefore, returned);
+    });
+
+    it("reports the rent returned on expiry net of the keeper bounty", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(432), ORDER_AMOUNT, 5);
+      await waitForSlot(expirySlot(await program.account.order.fetch(order)) + 1);
+
+      const makerBefore = await getLamports(maker.publicKey);
+      const events = await collect(["orderExpiredClosed"], () => order, () =>
+        program.methods
+          .closeExpiredOrder()
+          .accounts({
+            caller: outsider.publicKey,
+            config: configPDA,
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerSgorATA,
+            sgorMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([outsider])
+          .rpc()
+      );
+      const { bounty, rentReturnedLamports } = events.orderExpiredClosed;
+      assert.equal(
+        rentReturnedLamports.toNumber(),
+        (await expectedRent()) - bounty.toNumber()
+      );
+      assert.equal(
+        (await getLamports(maker.publicKey)) - makerBefore,
+        rentReturnedLamports.toNumber()
+      );
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // CHALLENGE PERIOD
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Challenge Period", () => {
+    const CHALLENGE_SLOTS = 20;
+
+    function setChallengePeriod(challengeSlots: number, admin: Keypair | null = null) {
+      const builder = program.methods
+        .setChallengePeriod(new anchor.BN(challengeSlots))
+        .accounts({
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+        });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    function finalizeSettlement(order: PublicKey, escrow: PublicKey, receipt: PublicKey) {
+      return program.methods
+        .finalizeSettlement()
+        .accounts({
+          config: configPDA,
+          taker: taker.publicKey,
+          maker: maker.publicKey,
+          order,
+          receipt,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          takerTokenAccount: takerSgorATA,
+          feeConfig: feeConfigPDA,
+          feeTokenAccount: treasurySgorATA,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    }
+
+    function disputeSettlement(order: PublicKey, receipt: PublicKey, disputer: Keypair = maker) {
+      return program.methods
+        .disputeSettlement()
+        .accounts({ disputer: disputer.publicKey, relayerEntry: null, order, receipt })
+        .signers([disputer])
+        .rpc();
+    }
+
+    function resolveDispute(order: PublicKey, release: boolean, admin: Keypair | null = null) {
+      const builder = program.methods.resolveDispute(release).accounts({
+        admin: admin ? admin.publicKey : provider.wallet.publicKey,
+        config: configPDA,
+        maker: maker.publicKey,
+        order,
+      });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    // Creates, locks and settles an order inside the challenge window
+    async function pendingOrder(id: number) {
+      const { order, escrow } = await createOrder(new anchor.BN(id), ORDER_AMOUNT);
+      await lockOrder(order, taker, 200);
+      const txSig = randomTxSig();
+      await settleOrder(order, escrow, txSig);
+      const [receipt] = deriveReceiptPDA(program.programId, txSig);
+      return { order, escrow, receipt };
+    }
+
+    before(async () => {
+      await setChallengePeriod(CHALLENGE_SLOTS);
+    });
+
+    after(async () => {
+      await setChallengePeriod(0);
+    });
+
+    it("holds the escrow on settle and releases it after the window", async () => {
+      const { order, escrow, receipt } = await pendingOrder(433);
+      const pending = await program.account.order.fetch(order);
+      assert.deepEqual(pending.status, { pendingRelease: {} });
+      assert.equal(pending.pendingReceipt.toBase58(), receipt.toBase58());
+      assert.equal(
+        (await getAccount(provider.connection, escrow)).amount.toString(),
+        ORDER_AMOUNT.toString()
+      );
+
+      try {
+        await finalizeSettlement(order, escrow, receipt);
+        assert.fail("Should reject finalizing inside the window");
+      } catch (e: any) {
+        assert.include(e.message, "ChallengeWindowOpen");
+      }
+
+      await waitForSlot(pending.releaseSlot.toNumber());
+      const takerSgorBefore = (await getAccount(provider.connection, takerSgorATA)).amount;
+      const takerLamportsBefore = await getLamports(taker.publicKey);
+      await finalizeSettlement(order, escrow, receipt);
+
+      const takerSgorAfter = (await getAccount(provider.connection, takerSgorATA)).amount;
+      assert.equal((takerSgorAfter - takerSgorBefore).toString(), ORDER_AMOUNT.toString());
+      assert.equal((await getLamports(taker.publicKey)) - takerLamportsBefore, BOND.toNumber());
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("rejects maker edits while a settlement is pending", async () => {
+      const { order, escrow, receipt } = await pendingOrder(454);
+      try {
+        await program.methods
+          .updateOperator(outsider.publicKey)
+          .accounts({ maker: maker.publicKey, order })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should reject editing an order that is not open");
+      } catch (e: any) {
+        assert.include(e.message, "OrderNotOpen");
+      }
+
+      const { releaseSlot } = await program.account.order.fetch(order);
+      await waitForSlot(releaseSlot.toNumber());
+      await finalizeSettlement(order, escrow, receipt);
+    });
+
+    it("freezes a disputed settlement until the admin releases it", async () => {
+      const { order, escrow, receipt } = await pendingOrder(434);
+      await disputeSettlement(order, receipt);
+      assert.deepEqual((await program.account.order.fetch(order)).status, { disputed: {} });
+
+      const { releaseSlot } = await program.account.order.fetch(order);
+      await waitForSlot(releaseSlot.toNumber());
+      try {
+        await finalizeSettlement(order, escrow, receipt);
+        assert.fail("Should reject finalizing a disputed order");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidOrderState");
+      }
+
+      try {
+        await resolveDispute(order, true, outsider);
+        assert.fail("Should reject a non-admin resolve");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+
+      await resolveDispute(order, true);
+      assert.deepEqual((await program.account.order.fetch(order)).status, { pendingRelease: {} });
+      await finalizeSettlement(order, escrow, receipt);
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("reopens a rejected settlement and pays the bond to the maker", async () => {
+      const { order, escrow, receipt } = await pendingOrder(435);
+      await disputeSettlement(order, receipt);
+
+      const makerLamportsBefore = await getLamports(maker.publicKey);
+      await resolveDispute(order, false);
+      assert.equal((await getLamports(maker.publicKey)) - makerLamportsBefore, BOND.toNumber());
+
+      const reopened = await program.account.order.fetch(order);
+      assert.deepEqual(reopened.status, { open: {} });
+      assert.equal(reopened.lockedBy.toBase58(), PublicKey.default.toBase58());
+      assert.equal(reopened.pendingReceipt.toBase58(), PublicKey.default.toBase58());
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects disputes after the window and from outsiders", async () => {
+      const { order, escrow, receipt } = await pendingOrder(436);
+      try {
+        await disputeSettlement(order, receipt, outsider);
+        assert.fail("Should reject a dispute from an outsider");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+
+      const { releaseSlot } = await program.account.order.fetch(order);
+      await waitForSlot(releaseSlot.toNumber());
+      try {
+        await disputeSettlement(order, receipt);
+        assert.fail("Should reject a dispute after the window");
+      } catch (e: any) {
+        assert.include(e.message, "ChallengeWindowClosed");
+      }
+      await finalizeSettlement(order, escrow, receipt);
+    });
+
+    it("rejects a challenge period longer than the max expiry", async () => {
+      try {
+        await setChallengePeriod(MAX_EXPIRY_SLOTS.toNumber() + 1);
+        assert.fail("Should reject an oversized window");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidConfig");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // MINT ALLOWLIST
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Mint Allowlist", () => {
+    let stableMint: PublicKey;
+    let makerStableATA: PublicKey;
+
+    function addMint(mint: PublicKey, admin: Keypair | null = null) {
+      const builder = program.methods.addMint(mint).accounts({
+        admin: admin ? admin.publicKey : provider.wallet.publicKey,
+        config: configPDA,
+        mintAllow: deriveMintAllowPDA(program.programId, mint)[0],
+        systemProgram: SystemProgram.programId,
+      });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    function removeMint(mint: PublicKey) {
+      return program.methods
+        .removeMint(mint)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: configPDA,
+          mintAllow: deriveMintAllowPDA(program.programId, mint)[0],
+        })
+        .rpc();
+    }
+
+    async function createStableOrder(orderId: anchor.BN) {
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, orderId);
+      const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, orderId, stableMint);
+      await program.methods
+        .createOrder({
+          orderId,
+          amount: ORDER_AMOUNT,
+          expectedGgorAmount: ORDER_AMOUNT,
+          expiry: slotExpiry((await getCurrentSlot()) + EXPIRY_OFFSET),
+          gorbaganaRecipient: Keypair.generate().publicKey,
+          allowedTaker: null,
+          endExpectedGgorAmount: null,
+          clientOrderId: new anchor.BN(0),
+          hashlock: null,
+          activationSlot: null,
+          operator: null,
+          payoutSplits: [],
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: makerStableATA,
+          sgorMint: stableMint,
+          mintAllow: deriveMintAllowPDA(program.programId, stableMint)[0],
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+      return { order, escrow };
+    }
+
+    function cancelStableOrder(order: PublicKey, escrow: PublicKey, makerTokenAccount = makerStableATA) {
+      return program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order,
+          makerRegistry: makerRegistryPDA,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount,
+          config: configPDA,
+          sgorMint: stableMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    }
+
+    before(async () => {
+      stableMint = await createMint(
+        provider.connection,
+        mintAuthority,
+        mintAuthority.publicKey,
+        null,
+        6
+      );
+      makerStableATA = await createAssociatedTokenAccount(
+        provider.connection,
+        maker,
+        stableMint,
+        maker.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        stableMint,
+        makerStableATA,
+        mintAuthority,
+        ORDER_AMOUNT.muln(4).toNumber()
+      );
+    });
+
+    it("rejects orders in a mint that is not allowed", async () => {
+      try {
+        await createStableOrder(new anchor.BN(437));
+        assert.fail("Should reject an unlisted mint");
+      } catch (e: any) {
+        assert.include(e.message, "MintNotAllowed");
+      }
+    });
+
+    it("escrows a listed mint beside sGOR and validates against the order's mint", async () => {
+      await addMint(stableMint);
+      try {
+        const sgor = await createOrder(new anchor.BN(438), ORDER_AMOUNT);
+        let event: any = null;
+        const [stableKey] = deriveOrderPDA(program.programId, maker.publicKey, new anchor.BN(439));
+        const listener = program.addEventListener("orderCreated", (e) => {
+          if (e.orderKey.equals(stableKey)) event = e;
+        });
+        let stable!: { order: PublicKey; escrow: PublicKey };
+        try {
+          stable = await createStableOrder(new anchor.BN(439));
+          await new Promise((r) => setTimeout(r, 1000));
+        } finally {
+          await program.removeEventListener(listener);
+        }
+
+        assert.notEqual(sgor.escrow.toBase58(), stable.escrow.toBase58());
+        assert.isTrue((await program.account.order.fetch(sgor.order)).mint.equals(sgorMint));
+        assert.isTrue((await program.account.order.fetch(stable.order)).mint.equals(stableMint));
+        assert.isTrue(event.mint.equals(stableMint));
+
+        // An sGOR refund account does not match the stable order's mint
+        try {
+          await cancelStableOrder(stable.order, stable.escrow, makerSgorATA);
+          assert.fail("Should reject a refund account in another mint");
+        } catch (e: any) {
+          assert.include(e.message, "InvalidMint");
+        }
+
+        await cancelStableOrder(stable.order, stable.escrow);
+        await cancelOrder(sgor.order, sgor.escrow);
+      } finally {
+        await removeMint(stableMint);
+      }
+
+      try {
+        await createStableOrder(new anchor.BN(437));
+        assert.fail("Should reject a removed mint");
+      } catch (e: any) {
+        assert.include(e.message, "MintNotAllowed");
+      }
+    });
+
+    it("rejects allowlist updates from a non-admin", async () => {
+      try {
+        await addMint(stableMint, outsider);
+        assert.fail("Should reject a non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // MAKER ORDERS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Maker Orders", () => {
+    const MAX_LISTED_ORDERS = 17;
+
+    function getMakerOrders(orders: PublicKey[]) {
+      return program.methods
+        .getMakerOrders()
+        .accounts({ makerRegistry: makerRegistryPDA })
+        .remainingAccounts(orders.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+        .view();
+    }
+
+    async function listedOrders(): Promise<PublicKey[]> {
+      const registry = await program.account.makerRegistry.fetch(makerRegistryPDA);
+      return registry.orders
+        .slice(0, registry.len)
+        .filter((key: PublicKey) => !key.equals(PublicKey.default));
+    }
+
+    it("summarizes the maker's listed orders without touching them", async () => {
+      const open = await createOrder(new anchor.BN(440), ORDER_AMOUNT);
+      const locked = await createOrder(new anchor.BN(441), ORDER_AMOUNT.muln(2));
+      await lockOrder(locked.order, taker, 200);
+      const before = await provider.connection.getAccountInfo(open.order);
+
+      const listed = await listedOrders();
+      const { orders, truncated } = await getMakerOrders(listed);
+      assert.isFalse(truncated);
+      assert.equal(orders.length, listed.length);
+
+      const byKey = (key: PublicKey) => orders.find((o: any) => o.orderKey.equals(key));
+      const openSummary = byKey(open.order);
+      assert.equal(openSummary.amount.toString(), ORDER_AMOUNT.toString());
+      assert.equal(openSummary.remainingAmount.toString(), ORDER_AMOUNT.toString());
+      assert.deepEqual(openSummary.status, { open: {} });
+      const lockedSummary = byKey(locked.order);
+      assert.equal(lockedSummary.amount.toString(), ORDER_AMOUNT.muln(2).toString());
+      assert.equal(lockedSummary.remainingAmount.toString(), "0");
+      assert.deepEqual(lockedSummary.status, { locked: {} });
+
+      const after = await provider.connection.getAccountInfo(open.order);
+      assert.isTrue(after!.data.equals(before!.data));
+
+      await settleOrder(locked.order, locked.escrow);
+      await cancelOrder(open.order, open.escrow);
+    });
+
+    it("truncates at the return-data limit", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(442), ORDER_AMOUNT);
+      const { orders, truncated } = await getMakerOrders(
+        Array(MAX_LISTED_ORDERS + 1).fill(order)
+      );
+      assert.isTrue(truncated);
+      assert.equal(orders.length, MAX_LISTED_ORDERS);
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects an account that is not an order", async () => {
+      try {
+        await getMakerOrders([makerRegistryPDA]);
+        assert.fail("Should reject a non-order account");
+      } catch (e: any) {
+        assert.include(e.message, "AccountDiscriminatorMismatch");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ESCROW RECONCILIATION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Escrow Reconciliation", () => {
+    it("releases and reports a topped-up escrow balance", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(443), ORDER_AMOUNT);
+      await mintTo(provider.connection, mintAuthority, sgorMint, escrow, mintAuthority, 5);
+
+      let event: any = null;
+      const listener = program.addEventListener("orderFilled", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      const takerBefore = (await getAccount(provider.connection, takerSgorATA)).amount;
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig());
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      const expected = ORDER_AMOUNT.addn(5).toString();
+      assert.equal(event.escrowBalance.toString(), expected);
+      const takerAfter = (await getAccount(provider.connection, takerSgorATA)).amount;
+      assert.equal((takerAfter - takerBefore).toString(), expected);
+    });
+
+    it("refuses to fill an escrow drained below the order amount", async () => {
+      // Token-2022 mint whose permanent delegate can move escrowed tokens
+      const mint = Keypair.generate();
+      const mintLen = getMintLen([ExtensionType.PermanentDelegate]);
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: mintAuthority.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space: mintLen,
+            lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          createInitializePermanentDelegateInstruction(
+            mint.publicKey,
+            mintAuthority.publicKey,
+            TOKEN_2022_PROGRAM_ID
+          ),
+          createInitializeMintInstruction(
+            mint.publicKey,
+            6,
+            mintAuthority.publicKey,
+            null,
+            TOKEN_2022_PROGRAM_ID
+          )
+        ),
+        [mintAuthority, mint]
+      );
+      const ata = (owner: Keypair, payer: Keypair = owner) =>
+        createAssociatedTokenAccount(
+          provider.connection,
+          payer,
+          mint.publicKey,
+          owner.publicKey,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
+      const makerAccount = await ata(maker);
+      const takerAccount = await ata(taker);
+      const treasuryAccount = await ata(treasury, maker);
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        mint.publicKey,
+        makerAccount,
+        mintAuthority,
+        ORDER_AMOUNT.toNumber(),
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const orderId = new anchor.BN(444);
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, orderId);
+      const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, orderId, mint.publicKey);
+      await setConfig(mint.publicKey, MIN_ORDER_AMOUNT);
+      try {
+        await program.methods
+          .createOrder({
+            orderId,
+            amount: ORDER_AMOUNT,
+            expectedGgorAmount: ORDER_AMOUNT,
+            expiry: slotExpiry((await getCurrentSlot()) + EXPIRY_OFFSET),
+            gorbaganaRecipient: Keypair.generate().publicKey,
+            allowedTaker: null,
+            endExpectedGgorAmount: null,
+            clientOrderId: new anchor.BN(0),
+            hashlock: null,
+            activationSlot: null,
+            operator: null,
+            payoutSplits: [],
+          })
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerAccount,
+            sgorMint: mint.publicKey,
+            mintAllow: deriveMintAllowPDA(program.programId, mint.publicKey)[0],
+            config: configPDA,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc();
+
+        await burn(
+          provider.connection,
+          mintAuthority,
+          escrow,
+          mint.publicKey,
+          mintAuthority,
+          1,
+          [],
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
+
+        const txSig = randomTxSig();
+        const [expectedAmount, expectedRecipient] = await quoteOf(order);
+        try {
+          await program.methods
+            .fillOrder(txSig, takerGorbaganaAddress, expectedAmount, expectedRecipient, new anchor.BN(0))
+            .accounts({
+              taker: taker.publicKey,
+              relayer: relayer.publicKey,
+              relayerEntry: deriveRelayerPDA(program.programId, relayer.publicKey)[0],
+              config: configPDA,
+              maker: maker.publicKey,
+              order,
+              makerRegistry: makerRegistryPDA,
+              takerDeny: deriveDenyPDA(program.programId, taker.publicKey)[0],
+              makerDeny: deriveDenyPDA(program.programId, maker.publicKey)[0],
+              stats: statsPDA,
+              escrowTokenAccount: escrow,
+              takerTokenAccount: takerAccount,
+              feeConfig: feeConfigPDA,
+              feeTokenAccount: treasuryAccount,
+              receipt: deriveReceiptPDA(program.programId, txSig)[0],
+              orderReceipt: null,
+              sgorMint: mint.publicKey,
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([taker, relayer])
+            .rpc();
+          assert.fail("Should reject a drained escrow");
+        } catch (e: any) {
+          assert.include(e.message, "EscrowBalanceMismatch");
+        }
+
+        // The maker can still pull what is left
+        await program.methods
+          .cancelOrder()
+          .accounts({
+            authority: maker.publicKey,
+            maker: maker.publicKey,
+            order,
+            makerRegistry: makerRegistryPDA,
+            stats: statsPDA,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerAccount,
+            config: configPDA,
+            sgorMint: mint.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([maker])
+          .rpc();
+        const refunded = await getAccount(
+          provider.connection,
+          makerAccount,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        );
+        assert.equal(refunded.amount.toString(), ORDER_AMOUNT.subn(1).toString());
+      } finally {
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // QUARANTINE RECOVERY
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Quarantine Recovery", () => {
+    const DEFAULT_QUARANTINE_SLOTS = 19_440_000;
+    const DEFAULT_QUARANTINE_SECONDS = 7_776_000;
+
+    function vaultFor(mint: PublicKey) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("recovery_vault"), mint.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    function setQuarantinePeriod(slots: number, seconds: number) {
+      return program.methods
+        .setQuarantinePeriod(new anchor.BN(slots), new anchor.BN(seconds))
+        .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+    }
+
+    function quarantineRecover(order: PublicKey, escrow: PublicKey, admin: Keypair | null = null) {
+      const builder = program.methods.quarantineRecover().accounts({
+        admin: admin ? admin.publicKey : provider.wallet.publicKey,
+        config: configPDA,
+        maker: maker.publicKey,
+        order,
+        makerRegistry: makerRegistryPDA,
+        stats: statsPDA,
+        escrowTokenAccount: escrow,
+        recoveryVault: vaultFor(sgorMint),
+        sgorMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    it("cannot touch an order that has not expired", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(445), ORDER_AMOUNT);
+      try {
+        await quarantineRecover(order, escrow);
+        assert.fail("Should reject a live order");
+      } catch (e: any) {
+        assert.include(e.message, "QuarantineNotElapsed");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("cannot touch a recently expired order", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(446), ORDER_AMOUNT, 5);
+      await waitForSlot(expirySlot(await program.account.order.fetch(order)) + 1);
+      try {
+        await quarantineRecover(order, escrow);
+        assert.fail("Should reject an order still in quarantine");
+      } catch (e: any) {
+        assert.include(e.message, "QuarantineNotElapsed");
+      }
+      assert.isNotNull(await provider.connection.getAccountInfo(order));
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects recovery by a non-admin", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(447), ORDER_AMOUNT);
+      try {
+        await quarantineRecover(order, escrow, outsider);
+        assert.fail("Should reject a non-admin");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+      await cancelOrder(order, escrow);
+    });
+
+    it("rejects a quarantine shorter than the longest order lifetime", async () => {
+      try {
+        await setQuarantinePeriod(MAX_EXPIRY_SLOTS.toNumber() - 1, DEFAULT_QUARANTINE_SECONDS);
+        assert.fail("Should reject a short quarantine");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidConfig");
+      }
+    });
+
+    it("moves a long-expired escrow to the recovery vault", async () => {
+      const QUARANTINE = 20;
+      await program.methods
+        .updateConfig({
+          sgorMint,
+          minOrderAmount: MIN_ORDER_AMOUNT,
+          maxExpirySlots: new anchor.BN(QUARANTINE),
+          maxExpirySeconds: MAX_EXPIRY_SECONDS,
+          cancelDelaySlots: new anchor.BN(0),
+          maxLockSlots: MAX_LOCK_SLOTS,
+          lockBondLamports: BOND,
+          keeperShareBps: KEEPER_SHARE_BPS,
+        })
+        .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+      try {
+        await setQuarantinePeriod(QUARANTINE, MAX_EXPIRY_SECONDS.toNumber());
+        const { order, escrow } = await createOrder(new anchor.BN(448), ORDER_AMOUNT, 5);
+        const expiry = expirySlot(await program.account.order.fetch(order));
+        await waitForSlot(expiry + QUARANTINE + 1);
+
+        let event: any = null;
+        const listener = program.addEventListener("quarantineRecovered", (e) => {
+          if (e.orderKey.equals(order)) event = e;
+        });
+        try {
+          await quarantineRecover(order, escrow);
+          await new Promise((r) => setTimeout(r, 1000));
+        } finally {
+          await program.removeEventListener(listener);
+        }
+
+        const vault = await getAccount(provider.connection, vaultFor(sgorMint));
+        assert.isTrue(vault.owner.equals(configPDA));
+        assert.isAtLeast(Number(vault.amount), ORDER_AMOUNT.toNumber());
+        assert.isNull(await provider.connection.getAccountInfo(order));
+        assert.isNull(await provider.connection.getAccountInfo(escrow));
+        assert.equal(event.recoveredAmount.toString(), ORDER_AMOUNT.toString());
+        assert.isTrue(event.recoveryVault.equals(vaultFor(sgorMint)));
+        assert.isTrue(event.mint.equals(sgorMint));
+      } finally {
+        await setQuarantinePeriod(DEFAULT_QUARANTINE_SLOTS, DEFAULT_QUARANTINE_SECONDS);
+        await setConfig(sgorMint, MIN_ORDER_AMOUNT);
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // Cross-program makers: orders made through CPI by a program's PDA
+  // ═══════════════════════════════════════════════════════════════════
+  describe("CPI Maker", () => {
+    const mirror = anchor.workspace.OrderMirror as Program<OrderMirror>;
+    const [pdaMaker] = PublicKey.findProgramAddressSync(
+      [Buffer.from("maker")],
+      mirror.programId
+    );
+    const [eventAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("__event_authority")],
+      program.programId
+    );
+    const [pdaRegistry] = deriveMakerRegistryPDA(program.programId, pdaMaker);
+    let pdaSgorATA: PublicKey;
+
+    before(async () => {
+      // The PDA pays rent for its orders, so it needs lamports of its own
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: maker.publicKey,
+            toPubkey: pdaMaker,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        ),
+        [maker]
+      );
+      pdaSgorATA = (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          maker,
+          sgorMint,
+          pdaMaker,
+          true // PDA owner is off the curve
+        )
+      ).address;
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        sgorMint,
+        pdaSgorATA,
+        mintAuthority,
+        ORDER_AMOUNT.toNumber()
+      );
+    });
+
+    it("creates and cancels an order with a program PDA as maker", async () => {
+      const orderId = new anchor.BN(449);
+      const [order] = deriveOrderPDA(program.programId, pdaMaker, orderId);
+      const [escrow] = deriveEscrowPDA(program.programId, pdaMaker, orderId, sgorMint);
+
+      await mirror.methods
+        .createOrder(
+          orderId,
+          ORDER_AMOUNT,
+          ORDER_AMOUNT,
+          slotExpiry((await getCurrentSlot()) + EXPIRY_OFFSET),
+          Keypair.generate().publicKey
+        )
+        .accounts({
+          maker: pdaMaker,
+          order,
+          makerRegistry: pdaRegistry,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: pdaSgorATA,
+          sgorMint,
+          mintAllow: deriveMintAllowPDA(program.programId, sgorMint)[0],
+          config: configPDA,
+          makerDeny: deriveDenyPDA(program.programId, pdaMaker)[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          eventAuthority,
+          bridgeProgram: program.programId,
+        })
+        .rpc();
+
+      const created = await program.account.order.fetch(order);
+      assert.ok(created.maker.equals(pdaMaker));
+      assert.equal(created.amount.toString(), ORDER_AMOUNT.toString());
+      assert.equal((await getAccount(provider.connection, pdaSgorATA)).amount.toString(), "0");
+
+      await mirror.methods
+        .cancelOrder()
+        .accounts({
+          maker: pdaMaker,
+          order,
+          makerRegistry: pdaRegistry,
+          stats: statsPDA,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: pdaSgorATA,
+          config: configPDA,
+          sgorMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          eventAuthority,
+          bridgeProgram: program.programId,
+        })
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(order));
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+      assert.equal(
+        (await getAccount(provider.connection, pdaSgorATA)).amount.toString(),
+        ORDER_AMOUNT.toString()
+      );
+    });
+
+    it("rejects an order for the PDA without the program's signature", async () => {
+      const orderId = new anchor.BN(450);
+      try {
+        await program.methods
+          .createOrder({
+            orderId,
+            amount: ORDER_AMOUNT,
+            expectedGgorAmount: ORDER_AMOUNT,
+            expiry: slotExpiry((await getCurrentSlot()) + EXPIRY_OFFSET),
+            gorbaganaRecipient: Keypair.generate().publicKey,
+            allowedTaker: null,
+            endExpectedGgorAmount: null,
+            clientOrderId: new anchor.BN(0),
+            hashlock: null,
+            activationSlot: null,
+            operator: null,
+            payoutSplits: [],
+          })
+          .accounts({
+            maker: pdaMaker,
+            order: deriveOrderPDA(program.programId, pdaMaker, orderId)[0],
+            makerRegistry: pdaRegistry,
+            makerDeny: deriveDenyPDA(program.programId, pdaMaker)[0],
+            stats: statsPDA,
+            escrowTokenAccount: deriveEscrowPDA(program.programId, pdaMaker, orderId, sgorMint)[0],
+            makerTokenAccount: pdaSgorATA,
+            sgorMint,
+            mintAllow: deriveMintAllowPDA(program.programId, sgorMint)[0],
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .rpc();
+        assert.fail("Should require the PDA to sign");
+      } catch (e: any) {
+        assert.include(e.toString(), "Signature verification failed");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // Fill deadline: stale fill transactions are rejected
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Fill Deadline", () => {
+    it("rejects a fill that lands after its deadline slot", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(451), ORDER_AMOUNT);
+      const deadline = new anchor.BN(await getCurrentSlot());
+      await waitForSlot(deadline.toNumber() + 1);
+      try {
+        await fillOrder(order, escrow, relayer, randomTxSig(), undefined, null, null, [], deadline);
+        assert.fail("Should reject a stale fill");
+      } catch (e: any) {
+        assert.include(e.message, "FillDeadlineExceeded");
+      }
+      const untouched = await getAccount(provider.connection, escrow);
+      assert.equal(untouched.amount.toString(), ORDER_AMOUNT.toString());
+      await cancelOrder(order, escrow);
+    });
+
+    it("fills before the deadline slot", async () => {
+      const { order, escrow } = await createOrder(new anchor.BN(452), ORDER_AMOUNT);
+      const deadline = new anchor.BN((await getCurrentSlot()) + 100);
+      await fillOrder(order, escrow, relayer, randomTxSig(), undefined, null, null, [], deadline);
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+    });
+  });
+});
@@ -1,6 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as SplTransfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, TransferChecked};
+// Imported rather than written as a path in `InitializeConfig`: the IDL
+// build resolves `Program<'info, T>` by `T`'s last segment alone
+use crate::program::GorbaganaBridge;
+pub use bridge_common::{
+    hashlock_of, pack_memo, unpack_memo, validate_amount, validate_expiration, validate_memo,
+    verify_preimage, BridgeError, ESCROW_SEED, HASHLOCK_LEN, MAX_EXPIRY_SLOTS, MEMO_LEN,
+    MIN_ORDER_AMOUNT, ORDER_SEED,
+};
 
 declare_id!("FreEcfZtek5atZJCJ1ER8kGLXB1C17WKWXqsVcsn1kPq");
 
@@ -8,11 +17,19 @@ declare_id!("FreEcfZtek5atZJCJ1ER8kGLXB1C17WKWXqsVcsn1kPq");
 /// sGOR SPL Token mint on Gorbagana
 pub const SGOR_MINT: Pubkey = pubkey!("71Jvq4Epe2FCJ7JFSF7jLXdNk1Wy4Bhqd9iL6bEFELvg");
 
-/// Minimum order size in lamports / token base units
-pub const MIN_ORDER_AMOUNT: u64 = 100_000; // 0.0001 in 9-decimal tokens
+/// Default slots past `expiration_slot` during which fills still land (~2s)
+pub const DEFAULT_FILL_GRACE_SLOTS: u64 = 5;
 
-/// Maximum order lifetime in slots (~400ms/slot → ~24 hours)
-pub const MAX_EXPIRY_SLOTS: u64 = 216_000;
+/// Upper bound for the admin-configurable fill grace window (~1 minute)
+pub const MAX_FILL_GRACE_SLOTS: u64 = 150;
+
+/// Accounts per order in `fill_orders` remaining_accounts
+pub const FILL_GROUP_LEN: usize = 4;
+
+/// Current `Order` layout. Handlers refuse any other value; v0 accounts
+/// (the original layout, with no version byte) are upgraded with
+/// `migrate_order` or refunded as they are with `cancel_legacy_order`.
+pub const ORDER_VERSION: u8 = 1;
 
 // ── Direction Enum ───────────────────────────────────────────────────
 /// Direction 0 = Maker sells sGOR (SPL), wants gGOR (native) in return
@@ -22,43 +39,121 @@ pub const MAX_EXPIRY_SLOTS: u64 = 216_000;
 pub mod gorbagana_bridge {
     use super::*;
 
+    // ═══════════════════════════════════════════════════════════════════
+    // CONFIG — Admin-managed bridge parameters
+    // ═══════════════════════════════════════════════════════════════════
+    /// Creates the singleton config PDA. Signed by the program's upgrade
+    /// authority, which becomes the admin; the fill grace window starts at
+    /// `DEFAULT_FILL_GRACE_SLOTS`, and self-fills are rejected.
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.fill_grace_slots = DEFAULT_FILL_GRACE_SLOTS;
+        config.order_sequence = 0;
+        config.bump = ctx.bumps.config;
+        config.allow_self_fill = false;
+
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            fill_grace_slots: config.fill_grace_slots,
+            allow_self_fill: config.allow_self_fill,
+        });
+
+        Ok(())
+    }
+
+    /// Updates the fill grace window. Admin only.
+    pub fn update_config(ctx: Context<UpdateConfig>, fill_grace_slots: u64) -> Result<()> {
+        require!(
+            fill_grace_slots <= MAX_FILL_GRACE_SLOTS,
+            BridgeError::GracePeriodTooLong
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.fill_grace_slots = fill_grace_slots;
+
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            fill_grace_slots,
+            allow_self_fill: config.allow_self_fill,
+        });
+
+        Ok(())
+    }
+
+    /// Lets makers fill their own orders. Only meant for test deployments,
+    /// where it saves a second funded wallet. Admin only.
+    pub fn set_allow_self_fill(ctx: Context<UpdateConfig>, allow_self_fill: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.allow_self_fill = allow_self_fill;
+
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            fill_grace_slots: config.fill_grace_slots,
+            allow_self_fill,
+        });
+
+        Ok(())
+    }
+
     // ═══════════════════════════════════════════════════════════════════
     // CREATE ORDER
     // ═══════════════════════════════════════════════════════════════════
     /// Creates an escrow order. The maker deposits funds into the escrow:
     ///   - Direction 0 (sGOR→gGOR): maker deposits sGOR via SPL transfer
     ///   - Direction 1 (gGOR→sGOR): maker deposits gGOR via system transfer
-    pub fn create_order(
-        ctx: Context<CreateOrder>,
-        amount: u64,
-        direction: u8,
-        expiration_slot: u64,
-    ) -> Result<()> {
+    ///
+    /// `operator` optionally designates a low-privilege key that may cancel
+    /// the order; refunds always go to the maker.
+    ///
+    /// Each order takes the next value of the global `order_sequence`
+    /// counter so clients can sort orders created within the same slot.
+    ///
+    /// `memo` is an opaque off-chain reference (see `pack_memo`); only its
+    /// zero padding is validated. Omitted memos are stored as all zeros.
+    ///
+    /// `activation_slot` time-locks the order: fills are rejected before it,
+    /// cancels are not. Omitted means fillable immediately.
+    ///
+    /// `htlc` makes the order one leg of a cross-chain atomic swap: the
+    /// escrow is not sold through `fill_order` but released by
+    /// `claim_htlc_order` to `htlc.recipient` on the sha256 preimage of
+    /// `htlc.hashlock`, and refunded only after the fill deadline.
+    pub fn create_order(ctx: Context<CreateOrder>, args: CreateOrderArgs) -> Result<()> {
+        let CreateOrderArgs {
+            amount,
+            direction,
+            expiration_slot,
+            operator,
+            memo,
+            activation_slot,
+            htlc,
+        } = args;
+
         // ── Validation ───────────────────────────────────────────────
-        require!(amount >= MIN_ORDER_AMOUNT, BridgeError::InvalidAmount);
+        validate_amount(amount)?;
         require!(direction <= 1, BridgeError::InvalidDirection);
-
-        let clock = Clock::get()?;
+        validate_expiration(Clock::get()?.slot, expiration_slot)?;
+        let memo = memo.unwrap_or([0u8; MEMO_LEN]);
+        validate_memo(&memo)?;
+        let activation_slot = activation_slot.unwrap_or(0);
         require!(
-            expiration_slot > clock.slot,
-            BridgeError::ExpirationInPast
+            activation_slot < expiration_slot,
+            BridgeError::InvalidActivationSlot
         );
         require!(
-            expiration_slot <= clock.slot.checked_add(MAX_EXPIRY_SLOTS).unwrap(),
-            BridgeError::ExpirationTooFar
+            htlc.is_none_or(|h| h.recipient != Pubkey::default()),
+            BridgeError::HtlcRequiresTaker
         );
 
-        // ── Populate order state ─────────────────────────────────────
+        let maker_info = ctx.accounts.maker.to_account_info();
+        let order_info = ctx.accounts.order.to_account_info();
+        let maker_key = maker_info.key();
+        let order_key = order_info.key();
 
-        {
-            let order = &mut ctx.accounts.order;
-            order.maker = ctx.accounts.maker.key();
-            order.amount = amount;
-            order.direction = direction;
-            order.expiration_slot = expiration_slot;
-            order.is_filled = false;
-            order.bump = ctx.bumps.order;
-        }
+        // Recorded on the order so every later sGOR movement is checked
+        // against the mint's real decimals by the token program.
+        let decimals = ctx.accounts.sgor_mint.decimals;
 
         // ── Escrow deposit ───────────────────────────────────────────
         match direction {
@@ -73,18 +168,20 @@ pub mod gorbagana_bridge {
 
                 // Validate mint is sGOR
                 require!(maker_ta.mint == SGOR_MINT, BridgeError::InvalidMint);
+                check_sgor_transfer(maker_ta, escrow_ta, amount)?;
 
-                let cpi_accounts = SplTransfer {
-                    from: maker_ta.to_account_info(),
-                    to: escrow_ta.to_account_info(),
-                    authority: ctx.accounts.maker.to_account_info(),
-                };
-                token::transfer(
+                token::transfer_checked(
                     CpiContext::new(
                         ctx.accounts.token_program.to_account_info(),
-                        cpi_accounts,
+                        TransferChecked {
+                            from: maker_ta.to_account_info(),
+                            mint: ctx.accounts.sgor_mint.to_account_info(),
+                            to: escrow_ta.to_account_info(),
+                            authority: maker_info,
+                        },
                     ),
                     amount,
+                    decimals,
                 )?;
             }
             // Direction 1: Maker deposits gGOR (native gas) into escrow PDA
@@ -93,8 +190,8 @@ pub mod gorbagana_bridge {
                     CpiContext::new(
                         ctx.accounts.system_program.to_account_info(),
                         system_program::Transfer {
-                            from: ctx.accounts.maker.to_account_info(),
-                            to: ctx.accounts.order.to_account_info(),
+                            from: maker_info,
+                            to: order_info,
                         },
                     ),
                     amount,
@@ -103,12 +200,43 @@ pub mod gorbagana_bridge {
             _ => return Err(BridgeError::InvalidDirection.into()),
         }
 
+        // ── Assign global sequence number ────────────────────────────
+        let config = &mut ctx.accounts.config;
+        let sequence = config.order_sequence;
+        config.order_sequence = sequence
+            .checked_add(1)
+            .ok_or(BridgeError::Overflow)?;
+
+        // ── Populate order state (single write) ──────────────────────
+        let order = &mut ctx.accounts.order;
+        order.maker = maker_key;
+        order.amount = amount;
+        order.seed_amount = amount;
+        order.direction = direction;
+        order.decimals = decimals;
+        order.expiration_slot = expiration_slot;
+        order.activation_slot = activation_slot;
+        order.status = OrderStatus::Open;
+        order.operator = operator;
+        order.sequence = sequence;
+        order.memo = memo;
+        order.bump = ctx.bumps.order;
+        order.version = ORDER_VERSION;
+        order.htlc = htlc;
+
         emit!(OrderCreated {
-            order_key: ctx.accounts.order.key(),
-            maker: ctx.accounts.maker.key(),
+            order_key,
+            sequence,
+            maker: maker_key,
             amount,
             direction,
+            decimals,
             expiration_slot,
+            activation_slot,
+            operator,
+            memo,
+            status: OrderStatus::Open,
+            htlc,
         });
 
         Ok(())
@@ -127,68 +255,103 @@ pub mod gorbagana_bridge {
     /// Direction 1 (maker sold gGOR):
     ///   Taker sends sGOR (SPL) → Maker
     ///   Escrow releases gGOR (native) → Taker
+    ///
+    /// Makers cannot take their own orders unless the admin has set
+    /// `allow_self_fill`.
+    ///
+    /// Order fields are read once into locals and the status is written
+    /// back once at the end; this handler is composed inside larger
+    /// routing transactions, so keep it lean on compute.
     pub fn fill_order(ctx: Context<FillOrder>) -> Result<()> {
-        let order = &ctx.accounts.order;
-
-        // ── Validation ───────────────────────────────────────────────
-        require!(!order.is_filled, BridgeError::OrderAlreadyFilled);
-        require!(
-            Clock::get()?.slot <= order.expiration_slot,
-            BridgeError::OrderExpired
-        );
-
-        let amount = order.amount;
-        let direction = order.direction;
-        let maker_key = order.maker;
-        let bump = order.bump;
+        // ── Read order state once ────────────────────────────────────
+        let (maker_key, amount, seed_amount, direction, decimals, sequence, memo, bump) = {
+            let order = &ctx.accounts.order;
+            (
+                order.maker,
+                order.amount,
+                order.seed_amount,
+                order.direction,
+                order.decimals,
+                order.sequence,
+                order.memo,
+                order.bump,
+            )
+        };
+
+        // ── Validation + pricing (shared with `quote_fill`) ──────────
+        let FillQuote { taker_payment, output_amount, .. } = ctx.accounts.order.quote_fill(
+            amount,
+            Clock::get()?.slot,
+            ctx.accounts.config.fill_grace_slots,
+        )?;
+
+        let order_info = ctx.accounts.order.to_account_info();
+        let taker_info = ctx.accounts.taker.to_account_info();
+        let order_key = order_info.key();
+        let taker_key = taker_info.key();
+        let self_fill = taker_key == maker_key;
+        if !ctx.accounts.config.allow_self_fill {
+            require_keys_neq!(taker_key, maker_key, BridgeError::SelfFillNotAllowed);
+        }
 
         // PDA signer seeds for escrow releases
         let seeds: &[&[u8]] = &[
-            b"order",
+            ORDER_SEED,
             maker_key.as_ref(),
-            &amount.to_le_bytes(),
+            &seed_amount.to_le_bytes(),
             &[bump],
         ];
 
         match direction {
             // Direction 0: sGOR escrowed → release SPL to taker; taker pays native gGOR to maker
             0 => {
+                let escrow_ta = ctx.accounts.escrow_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingEscrowTokenAccount)?;
+                let taker_receive_ta = ctx.accounts.taker_receive_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingTakerReceiveTokenAccount)?;
+                check_sgor_transfer(escrow_ta, taker_receive_ta, output_amount)?;
+
+                // A fresh maker wallet (0 lamports) cannot receive a payment
+                // that leaves it below rent-exemption; fail with a clear error.
+                let maker_info = ctx.accounts.maker.to_account_info();
+                require!(
+                    maker_info.lamports().saturating_add(taker_payment)
+                        >= Rent::get()?.minimum_balance(0),
+                    BridgeError::MakerBelowRentExempt
+                );
+
                 // (a) Taker sends gGOR (native) to Maker
                 system_program::transfer(
                     CpiContext::new(
                         ctx.accounts.system_program.to_account_info(),
                         system_program::Transfer {
-                            from: ctx.accounts.taker.to_account_info(),
-                            to: ctx.accounts.maker.to_account_info(),
+                            from: taker_info,
+                            to: maker_info,
                         },
                     ),
-                    amount,
+                    taker_payment,
                 )?;
 
                 // (b) Escrow releases sGOR (SPL) to Taker
-                let escrow_ta = ctx.accounts.escrow_token_account
-                    .as_ref()
-                    .ok_or(BridgeError::MissingEscrowTokenAccount)?;
-                let taker_receive_ta = ctx.accounts.taker_receive_token_account
-                    .as_ref()
-                    .ok_or(BridgeError::MissingTakerReceiveTokenAccount)?;
-
-                token::transfer(
+                token::transfer_checked(
                     CpiContext::new_with_signer(
                         ctx.accounts.token_program.to_account_info(),
-                        SplTransfer {
+                        TransferChecked {
                             from: escrow_ta.to_account_info(),
+                            mint: ctx.accounts.sgor_mint.to_account_info(),
                             to: taker_receive_ta.to_account_info(),
-                            authority: ctx.accounts.order.to_account_info(),
+                            authority: order_info,
                         },
                         &[seeds],
                     ),
-                    amount,
+                    output_amount,
+                    decimals,
                 )?;
             }
             // Direction 1: gGOR escrowed (native in PDA) → release to taker; taker pays sGOR to maker
             1 => {
-                // (a) Taker sends sGOR (SPL) to Maker
                 let taker_ta = ctx.accounts.taker_token_account
                     .as_ref()
                     .ok_or(BridgeError::MissingTakerTokenAccount)?;
@@ -197,40 +360,248 @@ pub mod gorbagana_bridge {
                     .ok_or(BridgeError::MissingMakerReceiveTokenAccount)?;
 
                 require!(taker_ta.mint == SGOR_MINT, BridgeError::InvalidMint);
+                check_sgor_transfer(taker_ta, maker_receive_ta, taker_payment)?;
 
-                token::transfer(
+                // (a) Taker sends sGOR (SPL) to Maker
+                token::transfer_checked(
                     CpiContext::new(
                         ctx.accounts.token_program.to_account_info(),
-                        SplTransfer {
+                        TransferChecked {
                             from: taker_ta.to_account_info(),
+                            mint: ctx.accounts.sgor_mint.to_account_info(),
                             to: maker_receive_ta.to_account_info(),
-                            authority: ctx.accounts.taker.to_account_info(),
+                            authority: taker_info.clone(),
                         },
                     ),
-                    amount,
+                    taker_payment,
+                    decimals,
                 )?;
 
                 // (b) Release gGOR (native) from PDA to Taker
                 // We transfer lamports directly from the order PDA
-                let order_info = ctx.accounts.order.to_account_info();
-                let taker_info = ctx.accounts.taker.to_account_info();
-
-                **order_info.try_borrow_mut_lamports()? -= amount;
-                **taker_info.try_borrow_mut_lamports()? += amount;
+                **order_info.try_borrow_mut_lamports()? -= output_amount;
+                **taker_info.try_borrow_mut_lamports()? += output_amount;
             }
             _ => return Err(BridgeError::InvalidDirection.into()),
         }
 
-        // Mark as filled (account will be closed below via `close` constraint)
-        let order = &mut ctx.accounts.order;
-        order.is_filled = true;
+        // ── Write back once (account is closed via `close` constraint) ─
+        ctx.accounts.order.status = OrderStatus::Filled;
 
         emit!(OrderFilled {
-            order_key: order.key(),
+            order_key,
+            sequence,
             maker: maker_key,
-            taker: ctx.accounts.taker.key(),
+            taker: taker_key,
             amount,
             direction,
+            memo,
+            status: OrderStatus::Filled,
+            self_fill,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // QUOTE FILL — Read-only fill simulation
+    // ═══════════════════════════════════════════════════════════════════
+    /// Runs the same validation and pricing as `fill_order` for a fill of
+    /// `fill_amount` at the current slot and returns the `FillQuote` as
+    /// return data (Anchor calls `set_return_data`). Moves no funds and
+    /// writes no state; call it through `simulateTransaction` / `.view()`.
+    pub fn quote_fill(ctx: Context<QuoteFill>, fill_amount: u64) -> Result<FillQuote> {
+        ctx.accounts.order.quote_fill(
+            fill_amount,
+            Clock::get()?.slot,
+            ctx.accounts.config.fill_grace_slots,
+        )
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // FILL ORDERS — Sweep several orders in one instruction
+    // ═══════════════════════════════════════════════════════════════════
+    /// Fills multiple orders in the given sequence until the next order
+    /// would push the running total above `max_total`. Each order is filled
+    /// in full and passes the same validation as `fill_order`; a single
+    /// invalid group aborts the whole transaction.
+    ///
+    /// `remaining_accounts` holds one group of `FILL_GROUP_LEN` (4) accounts
+    /// per order, all writable:
+    ///   [0] order PDA
+    ///   [1] maker wallet (must equal order.maker, system-owned)
+    ///   [2] direction 0: escrow sGOR token account
+    ///       direction 1: taker's sGOR token account to send FROM
+    ///   [3] direction 0: taker's sGOR token account to receive INTO
+    ///       direction 1: maker's sGOR token account to receive INTO
+    ///       (owned by order.maker)
+    ///
+    /// Emits one `OrderFilled` per order, then a summary `OrdersFilled`.
+    pub fn fill_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FillOrders<'info>>,
+        max_total: u64,
+    ) -> Result<()> {
+        let groups = ctx.remaining_accounts;
+        require!(
+            !groups.is_empty() && groups.len().is_multiple_of(FILL_GROUP_LEN),
+            BridgeError::InvalidRemainingAccounts
+        );
+
+        let slot = Clock::get()?.slot;
+        let grace_slots = ctx.accounts.config.fill_grace_slots;
+        let allow_self_fill = ctx.accounts.config.allow_self_fill;
+        let taker_info = ctx.accounts.taker.to_account_info();
+        let taker_key = taker_info.key();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let system_program_info = ctx.accounts.system_program.to_account_info();
+        let sgor_mint = ctx.accounts.sgor_mint.to_account_info();
+        let min_wallet_balance = Rent::get()?.minimum_balance(0);
+
+        let mut total_amount: u64 = 0;
+        let mut orders_filled: u32 = 0;
+
+        for group in groups.chunks_exact(FILL_GROUP_LEN) {
+            let order_info = &group[0];
+            let maker_info = &group[1];
+            let leg_from = &group[2];
+            let leg_to = &group[3];
+
+            let order = Account::<Order>::try_from(order_info)?;
+            require!(
+                order.version == ORDER_VERSION,
+                BridgeError::UnsupportedOrderVersion
+            );
+
+            // Stop before exceeding the taker's limit (orders fill in full)
+            let next_total = total_amount
+                .checked_add(order.amount)
+                .ok_or(BridgeError::Overflow)?;
+            if next_total > max_total {
+                break;
+            }
+
+            // ── Validation (mirrors FillOrder + fill_order) ──────────
+            let FillQuote { taker_payment, output_amount, .. } =
+                order.quote_fill(order.amount, slot, grace_slots)?;
+            require_keys_eq!(maker_info.key(), order.maker, BridgeError::Unauthorized);
+            if !allow_self_fill {
+                require_keys_neq!(taker_key, order.maker, BridgeError::SelfFillNotAllowed);
+            }
+            require!(
+                maker_info.owner == &system_program::ID,
+                BridgeError::MakerNotSystemAccount
+            );
+
+            let amount = order.amount;
+            let direction = order.direction;
+            let decimals = order.decimals;
+            let maker_key = order.maker;
+            let seed_amount = order.seed_amount;
+            let sequence = order.sequence;
+            let memo = order.memo;
+            let seeds: &[&[u8]] = &[
+                ORDER_SEED,
+                maker_key.as_ref(),
+                &seed_amount.to_le_bytes(),
+                &[order.bump],
+            ];
+
+            let from_ta = Account::<TokenAccount>::try_from(leg_from)?;
+            let to_ta = Account::<TokenAccount>::try_from(leg_to)?;
+            // Direction 0 releases the output from escrow; direction 1
+            // moves the taker's payment
+            let spl_amount = if direction == 1 {
+                require!(from_ta.mint == SGOR_MINT, BridgeError::InvalidMint);
+                // The payment must land with the maker, not wherever the
+                // taker points it
+                require_keys_eq!(to_ta.owner, order.maker, BridgeError::InvalidRefundAccount);
+                taker_payment
+            } else {
+                output_amount
+            };
+            check_sgor_transfer(&from_ta, &to_ta, spl_amount)?;
+
+            match direction {
+                0 => {
+                    require!(
+                        maker_info.lamports().saturating_add(taker_payment) >= min_wallet_balance,
+                        BridgeError::MakerBelowRentExempt
+                    );
+
+                    system_program::transfer(
+                        CpiContext::new(
+                            system_program_info.clone(),
+                            system_program::Transfer {
+                                from: taker_info.clone(),
+                                to: maker_info.clone(),
+                            },
+                        ),
+                        taker_payment,
+                    )?;
+
+                    token::transfer_checked(
+                        CpiContext::new_with_signer(
+                            token_program.clone(),
+                            TransferChecked {
+                                from: leg_from.clone(),
+                                mint: sgor_mint.clone(),
+                                to: leg_to.clone(),
+                                authority: order_info.clone(),
+                            },
+                            &[seeds],
+                        ),
+                        output_amount,
+                        decimals,
+                    )?;
+                }
+                1 => {
+                    token::transfer_checked(
+                        CpiContext::new(
+                            token_program.clone(),
+                            TransferChecked {
+                                from: leg_from.clone(),
+                                mint: sgor_mint.clone(),
+                                to: leg_to.clone(),
+                                authority: taker_info.clone(),
+                            },
+                        ),
+                        taker_payment,
+                        decimals,
+                    )?;
+
+                    **order_info.try_borrow_mut_lamports()? -= output_amount;
+                    **taker_info.try_borrow_mut_lamports()? += output_amount;
+                }
+                _ => return Err(BridgeError::InvalidDirection.into()),
+            }
+
+            // Close the order with remaining rent to the maker; a duplicate
+            // group for the same order then fails the owner check above.
+            order.close(maker_info.clone())?;
+
+            emit!(OrderFilled {
+                order_key: order_info.key(),
+                sequence,
+                maker: maker_key,
+                taker: taker_key,
+                amount,
+                direction,
+                memo,
+                status: OrderStatus::Filled,
+                self_fill: taker_key == maker_key,
+            });
+
+            total_amount = next_total;
+            orders_filled += 1;
+        }
+
+        require!(orders_filled > 0, BridgeError::FillLimitTooLow);
+
+        emit!(OrdersFilled {
+            taker: taker_key,
+            orders_filled,
+            total_amount,
+            max_total,
         });
 
         Ok(())
@@ -239,24 +610,43 @@ pub mod gorbagana_bridge {
     // ═══════════════════════════════════════════════════════════════════
     // CANCEL ORDER — Maker reclaims escrowed funds
     // ═══════════════════════════════════════════════════════════════════
+    /// Signed by the maker or the order's operator. Either way the escrow
+    /// is refunded to the maker's own accounts.
+    ///
+    /// HTLC orders are the timeout refund: rejected until the order has
+    /// expired, then through the grace window like any other order, so
+    /// they open exactly when `claim_htlc_order` closes.
     pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
         let order = &ctx.accounts.order;
+        let slot = Clock::get()?.slot;
 
-        require!(!order.is_filled, BridgeError::OrderAlreadyFilled);
+        // Signer, maker, escrow, and refund-account checks live on
+        // `CancelOrder`; only lifecycle and timing are checked here.
+        require!(
+            order.status.can_transition_to(OrderStatus::Cancelled),
+            BridgeError::InvalidOrderState
+        );
         require!(
-            ctx.accounts.maker.key() == order.maker,
-            BridgeError::Unauthorized
+            order.htlc.is_none() || slot > order.expiration_slot,
+            BridgeError::HtlcNotExpired
+        );
+        require!(
+            !order.in_grace_window(slot, ctx.accounts.config.fill_grace_slots),
+            BridgeError::OrderInGracePeriod
         );
 
         let amount = order.amount;
+        let seed_amount = order.seed_amount;
         let direction = order.direction;
+        let decimals = order.decimals;
         let maker_key = order.maker;
+        let sequence = order.sequence;
         let bump = order.bump;
 
         let seeds: &[&[u8]] = &[
-            b"order",
+            ORDER_SEED,
             maker_key.as_ref(),
-            &amount.to_le_bytes(),
+            &seed_amount.to_le_bytes(),
             &[bump],
         ];
 
@@ -269,18 +659,21 @@ pub mod gorbagana_bridge {
                 let maker_ta = ctx.accounts.maker_token_account
                     .as_ref()
                     .ok_or(BridgeError::MissingMakerTokenAccount)?;
+                check_sgor_transfer(escrow_ta, maker_ta, amount)?;
 
-                token::transfer(
+                token::transfer_checked(
                     CpiContext::new_with_signer(
                         ctx.accounts.token_program.to_account_info(),
-                        SplTransfer {
+                        TransferChecked {
                             from: escrow_ta.to_account_info(),
+                            mint: ctx.accounts.sgor_mint.to_account_info(),
                             to: maker_ta.to_account_info(),
                             authority: ctx.accounts.order.to_account_info(),
                         },
                         &[seeds],
                     ),
                     amount,
+                    decimals,
                 )?;
             }
             // Direction 1: Return gGOR (native) from PDA to maker
@@ -294,124 +687,903 @@ pub mod gorbagana_bridge {
             _ => return Err(BridgeError::InvalidDirection.into()),
         }
 
+        // Mark as cancelled (account will be closed via `close` constraint)
+        let order = &mut ctx.accounts.order;
+        order.status = OrderStatus::Cancelled;
+
         emit!(OrderCancelled {
             order_key: order.key(),
+            sequence,
             maker: maker_key,
             amount,
             direction,
+            status: order.status,
         });
 
         Ok(())
     }
-}
 
-// ═══════════════════════════════════════════════════════════════════════
-// ACCOUNT STRUCTS
-// ═══════════════════════════════════════════════════════════════════════
+    // ═══════════════════════════════════════════════════════════════════
+    // HTLC CLAIM — Recipient releases a hash-locked escrow with the preimage
+    // ═══════════════════════════════════════════════════════════════════
+    /// Releases an HTLC order's escrow to `htlc.recipient` on a preimage
+    /// whose sha256 is `htlc.hashlock`. The recipient pays nothing here;
+    /// their side of the swap is the matching hash-locked order on Solana,
+    /// which the maker claims with the preimage this reveals.
+    ///
+    /// Lands up to `fill_deadline`; `cancel_order` only from the slot
+    /// after, so a claim and a refund can never both succeed.
+    ///
+    ///   - Direction 0: escrowed sGOR → `recipient_token_account`
+    ///   - Direction 1: escrowed gGOR (PDA lamports) → recipient
+    pub fn claim_htlc_order(
+        ctx: Context<ClaimHtlcOrder>,
+        preimage: [u8; HASHLOCK_LEN],
+    ) -> Result<()> {
+        let order = &ctx.accounts.order;
 
-#[derive(Accounts)]
-#[instruction(amount: u64, direction: u8)]
-pub struct CreateOrder<'info> {
-    #[account(mut)]
-    pub maker: Signer<'info>,
+        // ── Validation ───────────────────────────────────────────────
+        let htlc = order.htlc.ok_or(BridgeError::NotHashLocked)?;
+        require!(
+            order.status.can_transition_to(OrderStatus::Filled),
+            BridgeError::InvalidOrderState
+        );
+        require!(
+            Clock::get()?.slot <= order.fill_deadline(ctx.accounts.config.fill_grace_slots),
+            BridgeError::OrderExpired
+        );
+        require_keys_eq!(
+            ctx.accounts.recipient.key(),
+            htlc.recipient,
+            BridgeError::TakerNotAllowed
+        );
+        verify_preimage(&htlc.hashlock, &preimage)?;
 
-    /// Order PDA — deterministic from maker + amount
-    #[account(
-        init,
-        seeds = [b"order", maker.key().as_ref(), &amount.to_le_bytes()],
-        bump,
-        payer = maker,
-        space = Order::LEN,
-    )]
-    pub order: Box<Account<'info, Order>>,
+        let amount = order.amount;
+        let seed_amount = order.seed_amount;
+        let direction = order.direction;
+        let decimals = order.decimals;
+        let maker_key = order.maker;
+        let sequence = order.sequence;
+        let bump = order.bump;
 
-    /// Escrow token account for sGOR (only needed for direction 0)
-    /// Initialized with the order PDA as authority
-    #[account(
-        init_if_needed,
-        token::mint = sgor_mint,
-        token::authority = order,
-        seeds = [b"escrow", maker.key().as_ref(), &amount.to_le_bytes()],
-        bump,
-        payer = maker,
-    )]
-    pub escrow_token_account: Option<Box<Account<'info, TokenAccount>>>,
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &seed_amount.to_le_bytes(),
+            &[bump],
+        ];
 
-    /// Maker's sGOR token account (only needed for direction 0)
-    #[account(mut)]
-    pub maker_token_account: Option<Box<Account<'info, TokenAccount>>>,
+        // ── Release ──────────────────────────────────────────────────
+        match direction {
+            0 => {
+                let escrow_ta = ctx.accounts.escrow_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingEscrowTokenAccount)?;
+                let recipient_ta = ctx.accounts.recipient_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingTakerReceiveTokenAccount)?;
+                check_sgor_transfer(escrow_ta, recipient_ta, amount)?;
 
-    /// sGOR mint account (needed for escrow_token_account init)
-    pub sgor_mint: Option<Box<Account<'info, Mint>>>,
+                token::transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: escrow_ta.to_account_info(),
+                            mint: ctx.accounts.sgor_mint.to_account_info(),
+                            to: recipient_ta.to_account_info(),
+                            authority: ctx.accounts.order.to_account_info(),
+                        },
+                        &[seeds],
+                    ),
+                    amount,
+                    decimals,
+                )?;
+            }
+            1 => {
+                let order_info = ctx.accounts.order.to_account_info();
+                let recipient_info = ctx.accounts.recipient.to_account_info();
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+                **order_info.try_borrow_mut_lamports()? -= amount;
+                **recipient_info.try_borrow_mut_lamports()? += amount;
+            }
+            _ => return Err(BridgeError::InvalidDirection.into()),
+        }
 
-#[derive(Accounts)]
-pub struct FillOrder<'info> {
-    #[account(mut)]
-    pub taker: Signer<'info>,
+        let order = &mut ctx.accounts.order;
+        order.status = OrderStatus::Filled;
 
-    /// CHECK: Maker receives funds. Validated via order.maker constraint.
-    #[account(
-        mut,
-        constraint = maker.key() == order.maker @ BridgeError::Unauthorized
-    )]
-    pub maker: AccountInfo<'info>,
+        emit!(HtlcClaimed {
+            order_key: order.key(),
+            sequence,
+            maker: maker_key,
+            recipient: htlc.recipient,
+            amount,
+            direction,
+            preimage,
+            status: order.status,
+        });
 
-    #[account(
-        mut,
-        close = maker,
-        seeds = [b"order", order.maker.as_ref(), &order.amount.to_le_bytes()],
-        bump = order.bump,
-    )]
-    pub order: Account<'info, Order>,
+        Ok(())
+    }
 
-    // ── SPL accounts (optional, depends on direction) ────────────
-    /// Escrow sGOR token account (direction 0)
-    #[account(mut)]
-    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    // ═══════════════════════════════════════════════════════════════════
+    // REPLACE ORDER — Atomic cancel-and-replace for re-quoting
+    // ═══════════════════════════════════════════════════════════════════
+    /// Re-quotes an open order in place. The order PDA is never closed;
+    /// only the escrow delta is settled:
+    ///   - Same direction: maker tops up or is refunded the difference
+    ///   - Direction change: old escrow fully refunded, new asset deposited.
+    ///     Leaving direction 0 also closes the sGOR escrow token account,
+    ///     returning its rent to the maker.
+    ///
+    /// Not available for HTLC orders, whose amount and timeout the
+    /// counterparty's leg was sized against.
+    pub fn replace_order(
+        ctx: Context<ReplaceOrder>,
+        new_amount: u64,
+        new_direction: u8,
+        new_expiration_slot: u64,
+    ) -> Result<()> {
+        // ── Validation ───────────────────────────────────────────────
+        validate_amount(new_amount)?;
+        require!(new_direction <= 1, BridgeError::InvalidDirection);
 
-    /// Taker's sGOR token account to send FROM (direction 1)
-    #[account(mut)]
-    pub taker_token_account: Option<Account<'info, TokenAccount>>,
+        let clock = Clock::get()?;
+        validate_expiration(clock.slot, new_expiration_slot)?;
 
-    /// Taker's sGOR token account to receive INTO (direction 0)
-    #[account(mut)]
-    pub taker_receive_token_account: Option<Account<'info, TokenAccount>>,
+        let order = &ctx.accounts.order;
+        require!(
+            order.status == OrderStatus::Open,
+            BridgeError::InvalidOrderState
+        );
+        require!(order.htlc.is_none(), BridgeError::HtlcTermsFixed);
+        require!(
+            !order.in_grace_window(clock.slot, ctx.accounts.config.fill_grace_slots),
+            BridgeError::OrderInGracePeriod
+        );
+        require!(
+            order.activation_slot < new_expiration_slot,
+            BridgeError::InvalidActivationSlot
+        );
 
-    /// Maker's sGOR token account to receive INTO (direction 1)
-    #[account(mut)]
-    pub maker_receive_token_account: Option<Account<'info, TokenAccount>>,
+        let old_amount = order.amount;
+        let old_direction = order.direction;
+        let old_expiration_slot = order.expiration_slot;
+        let seed_amount = order.seed_amount;
+        let maker_key = order.maker;
+        let bump = order.bump;
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            maker_key.as_ref(),
+            &seed_amount.to_le_bytes(),
+            &[bump],
+        ];
 
-#[derive(Accounts)]
-pub struct CancelOrder<'info> {
-    #[account(mut)]
-    pub maker: Signer<'info>,
+        // ── Settle escrow delta ──────────────────────────────────────
+        if new_direction == old_direction {
+            if new_amount > old_amount {
+                ctx.accounts.deposit(old_direction, new_amount - old_amount)?;
+            } else if new_amount < old_amount {
+                ctx.accounts.refund(old_direction, old_amount - new_amount, seeds)?;
+            }
+        } else if old_direction == 0 {
+            // Refund the escrow's whole balance, not just `old_amount`, so
+            // stray sGOR sent to it cannot block the close
+            let escrow_balance = ctx.accounts.escrow_token_account
+                .as_ref()
+                .ok_or(BridgeError::MissingEscrowTokenAccount)?
+                .amount;
+            ctx.accounts.refund(old_direction, escrow_balance, seeds)?;
+            ctx.accounts.close_escrow(seeds)?;
+            ctx.accounts.deposit(new_direction, new_amount)?;
+        } else {
+            ctx.accounts.refund(old_direction, old_amount, seeds)?;
+            ctx.accounts.deposit(new_direction, new_amount)?;
+        }
 
-    #[account(
+        // ── Update order in place ────────────────────────────────────
+        let order = &mut ctx.accounts.order;
+        order.amount = new_amount;
+        order.direction = new_direction;
+        order.expiration_slot = new_expiration_slot;
+
+        emit!(OrderReplaced {
+            order_key: order.key(),
+            maker: maker_key,
+            old_amount,
+            new_amount,
+            old_direction,
+            new_direction,
+            old_expiration_slot,
+            new_expiration_slot,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // UPDATE OPERATOR — Maker sets or clears the order's operator key
+    // ═══════════════════════════════════════════════════════════════════
+    pub fn update_operator(
+        ctx: Context<UpdateOperator>,
+        new_operator: Option<Pubkey>,
+    ) -> Result<()> {
+        let order = &mut ctx.accounts.order;
+        require!(
+            order.status == OrderStatus::Open,
+            BridgeError::InvalidOrderState
+        );
+
+        let old_operator = order.operator;
+        order.operator = new_operator;
+
+        emit!(OperatorUpdated {
+            order_key: order.key(),
+            maker: order.maker,
+            old_operator,
+            new_operator,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // MIGRATE ORDER — Upgrade v0 order accounts in place
+    // ═══════════════════════════════════════════════════════════════════
+    /// Grows a v0 order (the 59-byte layout without `version`) to
+    /// `Order::LEN` with `realloc`, zero-filling the new space, and stamps
+    /// it `ORDER_VERSION`. The v0 fields are a prefix of `Order`, so a
+    /// Borsh decode of the grown account reads them unchanged and every
+    /// later field from the zero fill, as a default. `seed_amount` is set
+    /// to `amount`, which v0 orders seeded with and never changed, and
+    /// `decimals` is read from the sGOR mint, since v0 orders moved sGOR
+    /// with unchecked transfers. The order takes the next `order_sequence`,
+    /// as if created now. The maker pays the rent difference. Maker only, and only for open
+    /// orders; the order keeps its escrow.
+    pub fn migrate_order(ctx: Context<MigrateOrder>) -> Result<()> {
+        let order_info = ctx.accounts.order.to_account_info();
+        let legacy = {
+            let data = order_info.try_borrow_data()?;
+            require!(
+                data.len() == Order::V0_LEN && data[..8] == Order::DISCRIMINATOR,
+                BridgeError::UnsupportedOrderVersion
+            );
+            LegacyOrder::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(legacy.maker, ctx.accounts.maker.key(), BridgeError::Unauthorized);
+        require!(legacy.status() == OrderStatus::Open, BridgeError::OrderAlreadyFilled);
+
+        let rent = Rent::get()?;
+        let delta = rent
+            .minimum_balance(Order::LEN)
+            .saturating_sub(rent.minimum_balance(Order::V0_LEN));
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.maker.to_account_info(),
+                    to: order_info.clone(),
+                },
+            ),
+            delta,
+        )?;
+        order_info.realloc(Order::LEN, true)?;
+
+        let mut order = Order::try_deserialize(&mut &order_info.try_borrow_data()?[..])?;
+        // The status byte overlays v0 `is_filled`; set it from the flag
+        // rather than relying on the two encodings lining up
+        order.status = legacy.status();
+        order.version = ORDER_VERSION;
+        order.seed_amount = order.amount;
+        order.decimals = ctx.accounts.sgor_mint.decimals;
+
+        let config = &mut ctx.accounts.config;
+        order.sequence = config.order_sequence;
+        config.order_sequence = order
+            .sequence
+            .checked_add(1)
+            .ok_or(BridgeError::Overflow)?;
+        order.try_serialize(&mut &mut order_info.try_borrow_mut_data()?[..])?;
+
+        emit!(OrderMigrated {
+            order_key: order_info.key(),
+            sequence: order.sequence,
+            maker: order.maker,
+            version: order.version,
+            status: order.status,
+        });
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════
+    // CANCEL LEGACY ORDER — Refund v0 orders without migrating them
+    // ═══════════════════════════════════════════════════════════════════
+    /// Decodes a v0 order by hand, refunds its escrow to the maker, and
+    /// closes it, so makers who only want out need not pay for
+    /// `migrate_order` first. Direction 1 escrows gGOR in the order's own
+    /// lamports, which the close returns with the rent. Maker only.
+    pub fn cancel_legacy_order(ctx: Context<CancelLegacyOrder>) -> Result<()> {
+        let order_info = ctx.accounts.order.to_account_info();
+
+        let legacy = {
+            let data = order_info.try_borrow_data()?;
+            require!(
+                data.len() == LegacyOrder::LEN && data[..8] == Order::DISCRIMINATOR,
+                BridgeError::NotLegacyOrder
+            );
+            LegacyOrder::deserialize(&mut &data[8..])?
+        };
+
+        require_keys_eq!(legacy.maker, ctx.accounts.maker.key(), BridgeError::Unauthorized);
+        require!(legacy.status() == OrderStatus::Open, BridgeError::OrderAlreadyFilled);
+        require!(legacy.direction <= 1, BridgeError::InvalidDirection);
+
+        let seeds: &[&[u8]] = &[
+            ORDER_SEED,
+            legacy.maker.as_ref(),
+            &legacy.amount.to_le_bytes(),
+            &[legacy.bump],
+        ];
+        let expected = Pubkey::create_program_address(seeds, ctx.program_id)
+            .map_err(|_| BridgeError::NotLegacyOrder)?;
+        require_keys_eq!(expected, order_info.key(), BridgeError::NotLegacyOrder);
+
+        if legacy.direction == 0 {
+            let escrow_ta = ctx.accounts.escrow_token_account
+                .as_ref()
+                .ok_or(BridgeError::MissingEscrowTokenAccount)?;
+            let maker_ta = ctx.accounts.maker_token_account
+                .as_ref()
+                .ok_or(BridgeError::MissingMakerTokenAccount)?;
+            check_sgor_transfer(escrow_ta, maker_ta, legacy.amount)?;
+
+            token::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: escrow_ta.to_account_info(),
+                        mint: ctx.accounts.sgor_mint.to_account_info(),
+                        to: maker_ta.to_account_info(),
+                        authority: order_info.clone(),
+                    },
+                    &[seeds],
+                ),
+                legacy.amount,
+                ctx.accounts.sgor_mint.decimals,
+            )?;
+        }
+
+        // `Account<Order>` cannot load a v0 order, so there is no `close`
+        // constraint; close it by hand instead
+        let maker_info = ctx.accounts.maker.to_account_info();
+        let lamports = order_info.lamports();
+        **maker_info.try_borrow_mut_lamports()? = maker_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(BridgeError::Overflow)?;
+        **order_info.try_borrow_mut_lamports()? = 0;
+        order_info.assign(&system_program::ID);
+        order_info.realloc(0, false)?;
+
+        emit!(LegacyOrderCancelled {
+            order_key: order_info.key(),
+            maker: legacy.maker,
+            amount: legacy.amount,
+            direction: legacy.direction,
+            status: OrderStatus::Cancelled,
+        });
+
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// SPL TRANSFER PRE-CHECKS
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Rejects an sGOR transfer that the token program would fail, with a
+/// specific `BridgeError` instead of a raw SPL error code. A failed CPI
+/// aborts the whole transaction and cannot be caught, so the common causes
+/// are checked up front. Must run before every sGOR `transfer_checked`.
+fn check_sgor_transfer(from: &TokenAccount, to: &TokenAccount, amount: u64) -> Result<()> {
+    require!(
+        from.mint == SGOR_MINT && to.mint == SGOR_MINT,
+        BridgeError::TokenMintMismatch
+    );
+    require!(!from.is_frozen(), BridgeError::SourceAccountFrozen);
+    require!(!to.is_frozen(), BridgeError::DestinationAccountFrozen);
+    require!(from.amount >= amount, BridgeError::InsufficientFunds);
+    Ok(())
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// ACCOUNT STRUCTS
+// ═══════════════════════════════════════════════════════════════════════
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [b"config"],
+        bump,
+        payer = admin,
+        space = BridgeConfig::LEN,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    /// This program; locates its program data account
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub program: Program<'info, GorbaganaBridge>,
+
+    /// Only the upgrade authority may initialize, so the admin cannot be
+    /// claimed by whoever calls first after deployment
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ BridgeError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: CreateOrderArgs)]
+pub struct CreateOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// Order PDA — deterministic from maker + amount
+    #[account(
+        init,
+        seeds = [ORDER_SEED, maker.key().as_ref(), &args.amount.to_le_bytes()],
+        bump,
+        payer = maker,
+        space = Order::LEN,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Escrow token account for sGOR (only needed for direction 0)
+    /// Initialized with the order PDA as authority
+    #[account(
+        init_if_needed,
+        token::mint = sgor_mint,
+        token::authority = order,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), &args.amount.to_le_bytes()],
+        bump,
+        payer = maker,
+    )]
+    pub escrow_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Maker's sGOR token account (only needed for direction 0)
+    #[account(mut)]
+    pub maker_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// sGOR mint account (escrow init and decimals for checked transfers)
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<Account<'info, Mint>>,
+
+    /// Writable for the global `order_sequence` counter
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// CHECK: Maker receives funds. Validated via order.maker constraint and
+    /// required to be a system-owned wallet so native payments can land.
+    #[account(
+        mut,
+        constraint = maker.key() == order.maker @ BridgeError::Unauthorized,
+        constraint = maker.owner == &system_program::ID @ BridgeError::MakerNotSystemAccount,
+    )]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.seed_amount.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    // ── SPL accounts (optional, depends on direction) ────────────
+    /// Escrow sGOR token account (direction 0)
+    #[account(mut)]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Taker's sGOR token account to send FROM (direction 1)
+    #[account(mut)]
+    pub taker_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Taker's sGOR token account to receive INTO (direction 0)
+    #[account(mut)]
+    pub taker_receive_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Maker's sGOR token account to receive INTO (direction 1). Must be
+    /// owned by the maker so the taker cannot pay themselves.
+    #[account(
+        mut,
+        constraint = maker_receive_token_account.owner == order.maker @ BridgeError::InvalidRefundAccount,
+    )]
+    pub maker_receive_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// sGOR mint (required by transfer_checked)
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Account<'info, Mint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, BridgeConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteFill<'info> {
+    #[account(constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion)]
+    pub order: Account<'info, Order>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct FillOrders<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// sGOR mint (required by transfer_checked)
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Account<'info, Mint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, BridgeConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    // Remaining accounts: FILL_GROUP_LEN accounts per order (see fill_orders)
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    /// Maker or the order's designated operator
+    #[account(
+        constraint = order.is_maker_or_operator(&authority.key()) @ BridgeError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Refund and rent destination. Always the order's maker
+    /// (enforced by has_one on order), whoever signs.
+    #[account(mut)]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
         mut,
         close = maker,
         has_one = maker @ BridgeError::Unauthorized,
-        seeds = [b"order", order.maker.as_ref(), &order.amount.to_le_bytes()],
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.seed_amount.to_le_bytes()],
         bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
     )]
     pub order: Account<'info, Order>,
 
-    /// Escrow sGOR token account (direction 0 only)
+    /// Escrow sGOR token account (direction 0 only). Presence is checked
+    /// in the handler since it depends on the order's direction.
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount,
+        constraint = escrow_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Maker's sGOR token account to receive refund (direction 0 only).
+    /// Must be owned by the maker so an operator cannot redirect funds.
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == order.maker @ BridgeError::InvalidRefundAccount,
+        constraint = maker_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint,
+    )]
+    pub maker_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// sGOR mint (required by transfer_checked)
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Account<'info, Mint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, BridgeConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimHtlcOrder<'info> {
+    /// The order's `htlc.recipient` (checked in the handler)
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// CHECK: Rent destination. Validated via has_one on order.
     #[account(mut)]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.seed_amount.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+
+    /// Escrow sGOR token account (direction 0 only)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount,
+    )]
     pub escrow_token_account: Option<Account<'info, TokenAccount>>,
 
-    /// Maker's sGOR token account to receive refund (direction 0 only)
+    /// Recipient's sGOR token account to receive INTO (direction 0 only)
+    #[account(
+        mut,
+        constraint = recipient_token_account.owner == recipient.key() @ BridgeError::InvalidTakerTokenAccount,
+    )]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// sGOR mint (required by transfer_checked)
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Account<'info, Mint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, BridgeConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReplaceOrder<'info> {
     #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.seed_amount.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Escrow sGOR token account (needed when the old or new direction is 0).
+    /// Created on demand when switching from direction 1 to direction 0.
+    #[account(
+        init_if_needed,
+        token::mint = sgor_mint,
+        token::authority = order,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), &order.seed_amount.to_le_bytes()],
+        bump,
+        payer = maker,
+    )]
+    pub escrow_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Maker's sGOR token account for top-ups and refunds (direction 0).
+    /// Must be owned by the maker so refunds cannot be redirected.
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == order.maker @ BridgeError::InvalidRefundAccount,
+        constraint = maker_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint,
+    )]
+    pub maker_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// sGOR mint account (escrow init and decimals for checked transfers)
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Box<Account<'info, Mint>>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, BridgeConfig>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> ReplaceOrder<'info> {
+    /// Moves `amount` from the maker into escrow in the asset for `direction`.
+    fn deposit(&self, direction: u8, amount: u64) -> Result<()> {
+        match direction {
+            0 => {
+                let escrow_ta = self.escrow_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingEscrowTokenAccount)?;
+                let maker_ta = self.maker_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingMakerTokenAccount)?;
+
+                check_sgor_transfer(maker_ta, escrow_ta, amount)?;
+
+                token::transfer_checked(
+                    CpiContext::new(
+                        self.token_program.to_account_info(),
+                        TransferChecked {
+                            from: maker_ta.to_account_info(),
+                            mint: self.sgor_mint.to_account_info(),
+                            to: escrow_ta.to_account_info(),
+                            authority: self.maker.to_account_info(),
+                        },
+                    ),
+                    amount,
+                    self.order.decimals,
+                )
+            }
+            1 => system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.maker.to_account_info(),
+                        to: self.order.to_account_info(),
+                    },
+                ),
+                amount,
+            ),
+            _ => Err(BridgeError::InvalidDirection.into()),
+        }
+    }
+
+    /// Returns `amount` of the escrowed asset for `direction` to the maker.
+    fn refund(&self, direction: u8, amount: u64, seeds: &[&[u8]]) -> Result<()> {
+        match direction {
+            0 => {
+                let escrow_ta = self.escrow_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingEscrowTokenAccount)?;
+                let maker_ta = self.maker_token_account
+                    .as_ref()
+                    .ok_or(BridgeError::MissingMakerTokenAccount)?;
+                check_sgor_transfer(escrow_ta, maker_ta, amount)?;
+
+                token::transfer_checked(
+                    CpiContext::new_with_signer(
+                        self.token_program.to_account_info(),
+                        TransferChecked {
+                            from: escrow_ta.to_account_info(),
+                            mint: self.sgor_mint.to_account_info(),
+                            to: maker_ta.to_account_info(),
+                            authority: self.order.to_account_info(),
+                        },
+                        &[seeds],
+                    ),
+                    amount,
+                    self.order.decimals,
+                )
+            }
+            1 => {
+                let order_info = self.order.to_account_info();
+                let maker_info = self.maker.to_account_info();
+
+                **order_info.try_borrow_mut_lamports()? -= amount;
+                **maker_info.try_borrow_mut_lamports()? += amount;
+                Ok(())
+            }
+            _ => Err(BridgeError::InvalidDirection.into()),
+        }
+    }
+
+    /// Closes the emptied sGOR escrow, returning its rent to the maker.
+    fn close_escrow(&self, seeds: &[&[u8]]) -> Result<()> {
+        let escrow_ta = self.escrow_token_account
+            .as_ref()
+            .ok_or(BridgeError::MissingEscrowTokenAccount)?;
+
+        token::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_ta.to_account_info(),
+                destination: self.maker.to_account_info(),
+                authority: self.order.to_account_info(),
+            },
+            &[seeds],
+        ))
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateOperator<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker @ BridgeError::Unauthorized,
+        seeds = [ORDER_SEED, order.maker.as_ref(), &order.seed_amount.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.version == ORDER_VERSION @ BridgeError::UnsupportedOrderVersion,
+    )]
+    pub order: Account<'info, Order>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: v0 order that `Account<Order>` cannot load yet;
+    /// discriminator, length, and maker are verified in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub order: UncheckedAccount<'info>,
+
+    /// sGOR mint, read for the order's `decimals`
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Account<'info, Mint>,
+
+    /// Writable for the global `order_sequence` counter
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, BridgeConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLegacyOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: v0 order; discriminator, length, maker, and PDA seeds are
+    /// verified in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub order: UncheckedAccount<'info>,
+
+    /// Escrow sGOR token account (direction 0 only)
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == order.key() @ BridgeError::InvalidEscrowAccount,
+        constraint = escrow_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Maker's sGOR token account to receive the refund (direction 0 only)
+    #[account(
+        mut,
+        constraint = maker_token_account.owner == maker.key() @ BridgeError::InvalidRefundAccount,
+        constraint = maker_token_account.mint == SGOR_MINT @ BridgeError::InvalidMint,
+    )]
     pub maker_token_account: Option<Account<'info, TokenAccount>>,
 
+    /// sGOR mint (required by transfer_checked)
+    #[account(address = SGOR_MINT @ BridgeError::InvalidMint)]
+    pub sgor_mint: Account<'info, Mint>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
@@ -420,14 +1592,25 @@ pub struct CancelOrder<'info> {
 // STATE
 // ═══════════════════════════════════════════════════════════════════════
 
+/// The fields up to `bump` are the v0 layout, in order; everything after
+/// `version` was added later and must stay after it, so `migrate_order`
+/// can grow a v0 account in place.
 #[account]
 pub struct Order {
     pub maker: Pubkey,       // 32
     pub amount: u64,         // 8
     pub direction: u8,       // 1  (0 = sGOR→gGOR, 1 = gGOR→sGOR)
     pub expiration_slot: u64, // 8
-    pub is_filled: bool,     // 1
+    pub status: OrderStatus, // 1  (v0 `is_filled`; mapped by LegacyOrder::status on migration)
     pub bump: u8,            // 1
+    pub version: u8,         // 1  (layout version, see ORDER_VERSION)
+    pub seed_amount: u64,    // 8  (creation amount used in PDA seeds; never changes)
+    pub decimals: u8,        // 1  (sGOR mint decimals at creation)
+    pub activation_slot: u64, // 8  (earliest fill slot; 0 = immediately, as for migrated orders)
+    pub operator: Option<Pubkey>, // 1 + 32 (may cancel; never receives funds; None when migrated)
+    pub sequence: u64,       // 8  (global creation order, see BridgeConfig)
+    pub memo: [u8; MEMO_LEN], // 64 (opaque; length-prefixed, zero-padded; empty when migrated)
+    pub htlc: Option<HtlcTerms>, // 1 + 64 (Some = claimed by preimage, see claim_htlc_order; None when migrated)
 }
 
 impl Order {
@@ -436,57 +1619,180 @@ impl Order {
         + 8   // amount
         + 1   // direction
         + 8   // expiration_slot
-        + 1   // is_filled
-        + 1;  // bump
-}
+        + 1   // status
+        + 1   // bump
+        + 1   // version
+        + 8   // seed_amount
+        + 1   // decimals
+        + 8   // activation_slot
+        + 33  // operator
+        + 8   // sequence
+        + MEMO_LEN // memo
+        + 1 + HtlcTerms::LEN; // htlc
+
+    /// Size of a v0 order, which ends at `bump`.
+    pub const V0_LEN: usize = 59;
+
+    /// True if `key` may cancel this order (the maker or its operator).
+    pub fn is_maker_or_operator(&self, key: &Pubkey) -> bool {
+        *key == self.maker || self.operator == Some(*key)
+    }
 
-// ═══════════════════════════════════════════════════════════════════════
-// ERRORS
-// ═══════════════════════════════════════════════════════════════════════
+    /// True once the order's activation slot has been reached.
+    pub fn is_active(&self, slot: u64) -> bool {
+        slot >= self.activation_slot
+    }
 
-#[error_code]
-pub enum BridgeError {
-    #[msg("Amount must be >= minimum order size.")]
-    InvalidAmount,
+    /// Validation and pricing for a fill of `fill_amount` at `slot`. The one
+    /// pricing path behind `fill_order`, `fill_orders`, and `quote_fill`, so
+    /// quotes and fills cannot diverge.
+    ///
+    /// Orders fill in full at par and the bridge charges no fee, so today
+    /// the taker pays `amount` and receives `amount`.
+    pub fn quote_fill(&self, fill_amount: u64, slot: u64, grace_slots: u64) -> Result<FillQuote> {
+        require!(self.htlc.is_none(), BridgeError::HtlcClaimRequired);
+        require!(
+            self.status.can_transition_to(OrderStatus::Filled),
+            BridgeError::InvalidOrderState
+        );
+        require!(self.is_active(slot), BridgeError::OrderNotActive);
+        // Fills land up to expiration_slot + grace (see `fill_deadline`)
+        require!(
+            slot <= self.fill_deadline(grace_slots),
+            BridgeError::OrderExpired
+        );
+        require!(
+            fill_amount == self.amount,
+            BridgeError::PartialFillNotSupported
+        );
 
-    #[msg("Invalid direction. Must be 0 (sGOR→gGOR) or 1 (gGOR→sGOR).")]
-    InvalidDirection,
+        Ok(FillQuote {
+            taker_payment: fill_amount,
+            fee: 0,
+            output_amount: fill_amount,
+            remaining_amount: self.amount - fill_amount,
+        })
+    }
 
-    #[msg("Invalid token mint for this direction.")]
-    InvalidMint,
+    /// Last slot at which the order can still be filled.
+    pub fn fill_deadline(&self, grace_slots: u64) -> u64 {
+        self.expiration_slot.saturating_add(grace_slots)
+    }
 
-    #[msg("Order has expired.")]
-    OrderExpired,
+    /// True while the order is expired but still fillable. Cancel and
+    /// replace are rejected here so they can never race a late fill.
+    pub fn in_grace_window(&self, slot: u64, grace_slots: u64) -> bool {
+        slot > self.expiration_slot && slot <= self.fill_deadline(grace_slots)
+    }
+}
 
-    #[msg("Order has already been filled.")]
-    OrderAlreadyFilled,
+/// Hash-time-lock terms of an HTLC order.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct HtlcTerms {
+    /// sha256 of the preimage that releases the escrow
+    pub hashlock: [u8; HASHLOCK_LEN],
+    /// Sole account the escrow can be released to
+    pub recipient: Pubkey,
+}
 
-    #[msg("Insufficient funds for swap.")]
-    InsufficientFunds,
+impl HtlcTerms {
+    pub const LEN: usize = HASHLOCK_LEN + 32;
+}
 
-    #[msg("Unauthorized — only the maker can perform this action.")]
-    Unauthorized,
+/// Arguments of `create_order`; see there for what each one does.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CreateOrderArgs {
+    pub amount: u64,
+    pub direction: u8, // 0 = sell sGOR, 1 = sell gGOR
+    pub expiration_slot: u64,
+    pub operator: Option<Pubkey>, // Some = may cancel on the maker's behalf
+    pub memo: Option<[u8; MEMO_LEN]>, // None = all zeros
+    pub activation_slot: Option<u64>, // Some = not fillable before this slot
+    pub htlc: Option<HtlcTerms>, // Some = released by preimage, not filled
+}
 
-    #[msg("Expiration slot is in the past.")]
-    ExpirationInPast,
+/// Singleton bridge parameters.
+///
+/// `order_sequence` is a single global counter rather than one sharded
+/// across several PDAs: shards would only give a per-shard order, which
+/// defeats sorting by creation. The cost is that every `create_order`
+/// write-locks this account, serialising creates within a slot. Fills and
+/// cancels only read the config, so taker throughput is unaffected.
+#[account]
+pub struct BridgeConfig {
+    pub admin: Pubkey,          // 32
+    pub fill_grace_slots: u64,  // 8
+    pub order_sequence: u64,    // 8  (next sequence number to assign)
+    pub bump: u8,               // 1
+    pub allow_self_fill: bool,  // 1  (test deployments only)
+}
 
-    #[msg("Expiration too far in the future (max ~24 hours).")]
-    ExpirationTooFar,
+impl BridgeConfig {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // admin
+        + 8   // fill_grace_slots
+        + 8   // order_sequence
+        + 1   // bump
+        + 1;  // allow_self_fill
+}
 
-    #[msg("Missing escrow token account (required for SPL direction).")]
-    MissingEscrowTokenAccount,
+/// Result of `Order::quote_fill`, returned by the `quote_fill` instruction.
+/// Amounts are in the base units of the asset each side moves.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FillQuote {
+    /// What the taker sends to the maker
+    pub taker_payment: u64,
+    /// Bridge fee deducted from the output
+    pub fee: u64,
+    /// What the taker receives from escrow
+    pub output_amount: u64,
+    /// Order size left open after this fill
+    pub remaining_amount: u64,
+}
 
-    #[msg("Missing maker token account (required for SPL direction).")]
-    MissingMakerTokenAccount,
+/// Order lifecycle. Transitions are validated by `can_transition_to`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Open,
+    Filled,
+    Cancelled,
+}
 
-    #[msg("Missing taker token account.")]
-    MissingTakerTokenAccount,
+impl OrderStatus {
+    /// Allowed transitions:
+    ///   Open → Filled | Cancelled
+    /// Filled and Cancelled are terminal.
+    pub fn can_transition_to(self, next: OrderStatus) -> bool {
+        use OrderStatus::*;
+        matches!((self, next), (Open, Filled) | (Open, Cancelled))
+    }
+}
 
-    #[msg("Missing taker receive token account.")]
-    MissingTakerReceiveTokenAccount,
+/// v0 order layout, before `version` was added. Only read by
+/// `migrate_order` and `cancel_legacy_order`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyOrder {
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub direction: u8,
+    pub expiration_slot: u64,
+    pub is_filled: bool,
+    pub bump: u8,
+}
 
-    #[msg("Missing maker receive token account.")]
-    MissingMakerReceiveTokenAccount,
+impl LegacyOrder {
+    pub const LEN: usize = Order::V0_LEN;
+
+    /// The `OrderStatus` equivalent of the v0 flags. v0 had no cancelled
+    /// state: a cancelled order was closed, so an unfilled v0 order is
+    /// always open.
+    pub fn status(&self) -> OrderStatus {
+        if self.is_filled {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::Open
+        }
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -496,25 +1802,109 @@ pub enum BridgeError {
 #[event]
 pub struct OrderCreated {
     pub order_key: Pubkey,
+    pub sequence: u64,
     pub maker: Pubkey,
     pub amount: u64,
     pub direction: u8,
+    pub decimals: u8,
     pub expiration_slot: u64,
+    pub activation_slot: u64,
+    pub operator: Option<Pubkey>,
+    pub memo: [u8; MEMO_LEN],
+    pub status: OrderStatus,
+    pub htlc: Option<HtlcTerms>,
 }
 
 #[event]
 pub struct OrderFilled {
     pub order_key: Pubkey,
+    pub sequence: u64,
     pub maker: Pubkey,
     pub taker: Pubkey,
     pub amount: u64,
     pub direction: u8,
+    pub memo: [u8; MEMO_LEN],
+    pub status: OrderStatus,
+    /// Taker was the maker (only possible with `allow_self_fill`)
+    pub self_fill: bool,
 }
 
 #[event]
 pub struct OrderCancelled {
     pub order_key: Pubkey,
+    pub sequence: u64,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub direction: u8,
+    pub status: OrderStatus,
+}
+
+/// Emitted by `cancel_legacy_order`. v0 orders have no sequence number,
+/// so they get their own event rather than `OrderCancelled` with a
+/// placeholder.
+#[event]
+pub struct LegacyOrderCancelled {
+    pub order_key: Pubkey,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub direction: u8,
+    pub status: OrderStatus,
+}
+
+#[event]
+pub struct OrderReplaced {
+    pub order_key: Pubkey,
+    pub maker: Pubkey,
+    pub old_amount: u64,
+    pub new_amount: u64,
+    pub old_direction: u8,
+    pub new_direction: u8,
+    pub old_expiration_slot: u64,
+    pub new_expiration_slot: u64,
+    pub status: OrderStatus,
+}
+
+#[event]
+pub struct OrdersFilled {
+    pub taker: Pubkey,
+    pub orders_filled: u32,
+    pub total_amount: u64,
+    pub max_total: u64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub fill_grace_slots: u64,
+    pub allow_self_fill: bool,
+}
+
+#[event]
+pub struct OperatorUpdated {
+    pub order_key: Pubkey,
+    pub maker: Pubkey,
+    pub old_operator: Option<Pubkey>,
+    pub new_operator: Option<Pubkey>,
+    pub status: OrderStatus,
+}
+
+#[event]
+pub struct OrderMigrated {
+    pub order_key: Pubkey,
+    pub sequence: u64,
+    pub maker: Pubkey,
+    pub version: u8,
+    pub status: OrderStatus,
+}
+
+#[event]
+pub struct HtlcClaimed {
+    pub order_key: Pubkey,
+    pub sequence: u64,
     pub maker: Pubkey,
+    pub recipient: Pubkey,
     pub amount: u64,
     pub direction: u8,
+    pub preimage: [u8; HASHLOCK_LEN],
+    pub status: OrderStatus,
 }
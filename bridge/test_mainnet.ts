@@ -14,6 +14,7 @@ import os from "os";
 
 const RPC_URL = "https://rpc.trashscan.io";
 const PROGRAM_ID = new PublicKey("FreEcfZtek5atZJCJ1ER8kGLXB1C17WKWXqsVcsn1kPq");
+const SGOR_MINT = new PublicKey("71Jvq4Epe2FCJ7JFSF7jLXdNk1Wy4Bhqd9iL6bEFELvg");
 
 // Load wallet
 function loadWallet(): Keypair {
@@ -71,13 +72,21 @@ async function main() {
     console.log("\n📝 Step 1: Creating order...");
     try {
         const tx = await program.methods
-            .createOrder(amount, direction, expirationSlot)
+            .createOrder({
+                amount,
+                direction,
+                expirationSlot,
+                operator: null,
+                memo: null,
+                activationSlot: null,
+                htlc: null,
+            })
             .accounts({
                 maker: wallet.publicKey,
                 order: orderPDA,
                 escrowTokenAccount: null,
                 makerTokenAccount: null,
-                sgorMint: null,
+                sgorMint: SGOR_MINT,
                 tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
                 rent: anchor.web3.SYSVAR_RENT_PUBKEY,
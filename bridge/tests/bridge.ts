@@ -11,9 +11,14 @@ import {
   createAssociatedTokenAccount,
   mintTo,
   getAccount,
+  freezeAccount,
+  thawAccount,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { assert, expect } from "chai";
+import { createHash, randomBytes } from "crypto";
+import * as fs from "fs";
+import * as path from "path";
 import { GorbaganaBridge } from "../target/types/gorbagana_bridge";
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -34,6 +39,21 @@ function deriveOrderPDA(
   );
 }
 
+function deriveConfigPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("config")], programId);
+}
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+function deriveProgramDataPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE
+  );
+}
+
 function deriveEscrowPDA(
   programId: PublicKey,
   maker: PublicKey,
@@ -49,6 +69,15 @@ function deriveEscrowPDA(
   );
 }
 
+/// HTLC secret and its sha256 hashlock
+function randomPreimage(): number[] {
+  return Array.from(randomBytes(32));
+}
+
+function hashlockOf(preimage: number[]): number[] {
+  return Array.from(createHash("sha256").update(Buffer.from(preimage)).digest());
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // Test Suite
 // ═══════════════════════════════════════════════════════════════════════
@@ -72,7 +101,13 @@ describe("gorbagana_bridge", () => {
   let makerSgorATA: PublicKey;
   let takerSgorATA: PublicKey;
 
+  // Bridge config (admin = provider wallet)
+  let configPDA: PublicKey;
+  let programDataPDA: PublicKey;
+  let unauthorizedInitError: string | undefined;
+
   const ORDER_AMOUNT = new anchor.BN(1_000_000_000); // 1 sGOR / 1 gGOR
+  const MIN_ORDER_AMOUNT = new anchor.BN(100_000); // bridge_common::MIN_ORDER_AMOUNT
   const EXPIRY_OFFSET = 500; // slots in the future
 
   // ─── Setup ───────────────────────────────────────────────────────
@@ -96,7 +131,7 @@ describe("gorbagana_bridge", () => {
       provider.connection,
       mintAuthority,
       mintAuthority.publicKey,
-      null,
+      mintAuthority.publicKey, // freeze authority, used by failure tests
       9 // 9 decimals like SOL
     );
 
@@ -130,6 +165,31 @@ describe("gorbagana_bridge", () => {
       mintAuthority,
       10_000_000_000
     );
+
+    [configPDA] = deriveConfigPDA(program.programId);
+    [programDataPDA] = deriveProgramDataPDA(program.programId);
+    if ((await provider.connection.getAccountInfo(configPDA)) === null) {
+      const initializeConfig = (admin: Keypair | null) => {
+        const builder = program.methods.initializeConfig().accounts({
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+          program: program.programId,
+          programData: programDataPDA,
+          systemProgram: SystemProgram.programId,
+        });
+        return admin ? builder.signers([admin]).rpc() : builder.rpc();
+      };
+
+      // The config can only be claimed once, so the rejection is
+      // recorded here and asserted in the Security suite
+      unauthorizedInitError = "";
+      try {
+        await initializeConfig(unauthorized);
+      } catch (e: any) {
+        unauthorizedInitError = e.message;
+      }
+      await initializeConfig(null);
+    }
   });
 
   // ─── Utility ─────────────────────────────────────────────────────
@@ -141,6 +201,12 @@ describe("gorbagana_bridge", () => {
     return provider.connection.getBalance(pubkey);
   }
 
+  async function waitForSlot(target: number) {
+    while ((await getCurrentSlot()) < target) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+  }
+
   // ═══════════════════════════════════════════════════════════════════
   // DIRECTION 0: Maker sells sGOR (SPL) → wants gGOR (native)
   // ═══════════════════════════════════════════════════════════════════
@@ -168,13 +234,22 @@ describe("gorbagana_bridge", () => {
       ).amount;
 
       await program.methods
-        .createOrder(ORDER_AMOUNT, 0, expirationSlot)
+        .createOrder({
+          amount: ORDER_AMOUNT,
+          direction: 0,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
         .accounts({
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: escrowPDA,
           makerTokenAccount: makerSgorATA,
           sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -207,7 +282,8 @@ describe("gorbagana_bridge", () => {
       assert.equal(orderAccount.maker.toBase58(), maker.publicKey.toBase58());
       assert.equal(orderAccount.amount.toString(), ORDER_AMOUNT.toString());
       assert.equal(orderAccount.direction, 0);
-      assert.equal(orderAccount.isFilled, false);
+      assert.deepEqual(orderAccount.status, { open: {} });
+      assert.equal(orderAccount.decimals, 9, "Order should record mint decimals");
     });
 
     it("fills the order (taker sends gGOR, receives sGOR)", async () => {
@@ -227,6 +303,8 @@ describe("gorbagana_bridge", () => {
           takerTokenAccount: null, // not needed for direction 0
           takerReceiveTokenAccount: takerSgorATA,
           makerReceiveTokenAccount: null, // not needed for direction 0
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -281,13 +359,22 @@ describe("gorbagana_bridge", () => {
       const makerLamportsBefore = await getLamports(maker.publicKey);
 
       await program.methods
-        .createOrder(amount, 1, expirationSlot)
+        .createOrder({
+          amount,
+          direction: 1,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
         .accounts({
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: null, // not needed for native direction
           makerTokenAccount: null,
-          sgorMint: null,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -307,7 +394,7 @@ describe("gorbagana_bridge", () => {
       const orderAccount = await program.account.order.fetch(orderPDA);
       assert.equal(orderAccount.direction, 1);
       assert.equal(orderAccount.amount.toString(), amount.toString());
-      assert.equal(orderAccount.isFilled, false);
+      assert.deepEqual(orderAccount.status, { open: {} });
     });
 
     it("fills the order (taker sends sGOR, receives gGOR native)", async () => {
@@ -324,6 +411,8 @@ describe("gorbagana_bridge", () => {
           takerTokenAccount: takerSgorATA,
           takerReceiveTokenAccount: null,
           makerReceiveTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -365,13 +454,22 @@ describe("gorbagana_bridge", () => {
 
       // Create order
       await program.methods
-        .createOrder(cancelAmount, 0, expirationSlot)
+        .createOrder({
+          amount: cancelAmount,
+          direction: 0,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
         .accounts({
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: escrowPDA,
           makerTokenAccount: makerSgorATA,
           sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -387,10 +485,13 @@ describe("gorbagana_bridge", () => {
       await program.methods
         .cancelOrder()
         .accounts({
+          authority: maker.publicKey,
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: escrowPDA,
           makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -428,13 +529,22 @@ describe("gorbagana_bridge", () => {
       );
 
       await program.methods
-        .createOrder(nativeAmount, 1, expirationSlot)
+        .createOrder({
+          amount: nativeAmount,
+          direction: 1,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
         .accounts({
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: null,
           makerTokenAccount: null,
-          sgorMint: null,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -447,10 +557,13 @@ describe("gorbagana_bridge", () => {
       await program.methods
         .cancelOrder()
         .accounts({
+          authority: maker.publicKey,
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: null,
           makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -469,6 +582,11 @@ describe("gorbagana_bridge", () => {
   // SECURITY TESTS
   // ═══════════════════════════════════════════════════════════════════
   describe("Security", () => {
+    it("only lets the upgrade authority initialize the config", function () {
+      if (unauthorizedInitError === undefined) this.skip();
+      assert.include(unauthorizedInitError, "UnauthorizedAdmin");
+    });
+
     it("rejects unauthorized cancellation", async () => {
       const secAmount = new anchor.BN(100_000_000);
       const currentSlot = await getCurrentSlot();
@@ -486,13 +604,22 @@ describe("gorbagana_bridge", () => {
       );
 
       await program.methods
-        .createOrder(secAmount, 0, expirationSlot)
+        .createOrder({
+          amount: secAmount,
+          direction: 0,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
         .accounts({
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: escrowPDA,
           makerTokenAccount: makerSgorATA,
           sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -504,10 +631,13 @@ describe("gorbagana_bridge", () => {
         await program.methods
           .cancelOrder()
           .accounts({
-            maker: unauthorized.publicKey, // wrong signer!
+            authority: unauthorized.publicKey, // wrong signer!
+            maker: maker.publicKey,
             order: orderPDA,
             escrowTokenAccount: escrowPDA,
             makerTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
@@ -529,10 +659,13 @@ describe("gorbagana_bridge", () => {
       await program.methods
         .cancelOrder()
         .accounts({
+          authority: maker.publicKey,
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: escrowPDA,
           makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -553,13 +686,22 @@ describe("gorbagana_bridge", () => {
 
       try {
         await program.methods
-          .createOrder(zeroAmount, 1, expirationSlot)
+          .createOrder({
+            amount: zeroAmount,
+            direction: 1,
+            expirationSlot,
+            operator: null,
+            memo: null,
+            activationSlot: null,
+            htlc: null,
+          })
           .accounts({
             maker: maker.publicKey,
             order: orderPDA,
             escrowTokenAccount: null,
             makerTokenAccount: null,
-            sgorMint: null,
+            sgorMint: sgorMint,
+            config: configPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -588,13 +730,22 @@ describe("gorbagana_bridge", () => {
 
       try {
         await program.methods
-          .createOrder(amt, 5, expirationSlot) // invalid direction
+          .createOrder({
+            amount: amt,
+            direction: 5, // invalid direction
+            expirationSlot,
+            operator: null,
+            memo: null,
+            activationSlot: null,
+            htlc: null,
+          })
           .accounts({
             maker: maker.publicKey,
             order: orderPDA,
             escrowTokenAccount: null,
             makerTokenAccount: null,
-            sgorMint: null,
+            sgorMint: sgorMint,
+            config: configPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -624,13 +775,22 @@ describe("gorbagana_bridge", () => {
       );
 
       await program.methods
-        .createOrder(expAmount, 1, expirationSlot)
+        .createOrder({
+          amount: expAmount,
+          direction: 1,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
         .accounts({
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: null,
           makerTokenAccount: null,
-          sgorMint: null,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -638,8 +798,8 @@ describe("gorbagana_bridge", () => {
         .signers([maker])
         .rpc();
 
-      // Wait for expiry
-      await new Promise((resolve) => setTimeout(resolve, 3000));
+      // Wait past expiry and the default fill grace window
+      await new Promise((resolve) => setTimeout(resolve, 5000));
 
       try {
         await program.methods
@@ -652,6 +812,8 @@ describe("gorbagana_bridge", () => {
             takerTokenAccount: takerSgorATA,
             takerReceiveTokenAccount: null,
             makerReceiveTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
@@ -669,10 +831,13 @@ describe("gorbagana_bridge", () => {
       await program.methods
         .cancelOrder()
         .accounts({
+          authority: maker.publicKey,
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: null,
           makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -699,13 +864,22 @@ describe("gorbagana_bridge", () => {
       const makerBefore = await getLamports(maker.publicKey);
 
       await program.methods
-        .createOrder(nativeAmt, 1, expirationSlot)
+        .createOrder({
+          amount: nativeAmt,
+          direction: 1,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
         .accounts({
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: null,
           makerTokenAccount: null,
-          sgorMint: null,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -726,15 +900,2422 @@ describe("gorbagana_bridge", () => {
       await program.methods
         .cancelOrder()
         .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // MAKER ACCOUNT VALIDATION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Maker Account Validation", () => {
+    it("rejects a token account passed as the maker", async () => {
+      const amt = new anchor.BN(110_000_000);
+      const currentSlot = await getCurrentSlot();
+      const expirationSlot = new anchor.BN(currentSlot + EXPIRY_OFFSET);
+
+      const [orderPDA] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+      const [escrowPDA] = deriveEscrowPDA(program.programId, maker.publicKey, amt);
+
+      await program.methods
+        .createOrder({
+          amount: amt,
+          direction: 0,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+
+      try {
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: makerSgorATA, // token account, not the maker wallet
+            order: orderPDA,
+            escrowTokenAccount: escrowPDA,
+            takerTokenAccount: null,
+            takerReceiveTokenAccount: takerSgorATA,
+            makerReceiveTokenAccount: null,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject a token account as the maker");
+      } catch (e: any) {
+        assert.isTrue(
+          e.message.includes("Unauthorized") ||
+            e.message.includes("MakerNotSystemAccount"),
+          `Expected maker validation error, got: ${e.message}`
+        );
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+
+    it("rejects a fill when the maker is no longer system-owned", async () => {
+      const amt = new anchor.BN(120_000_000);
+      const rogueMaker = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        rogueMaker.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+
+      const currentSlot = await getCurrentSlot();
+      const expirationSlot = new anchor.BN(currentSlot + EXPIRY_OFFSET);
+      const [orderPDA] = deriveOrderPDA(
+        program.programId,
+        rogueMaker.publicKey,
+        amt
+      );
+
+      await program.methods
+        .createOrder({
+          amount: amt,
+          direction: 1,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: rogueMaker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([rogueMaker])
+        .rpc();
+
+      // Hand the maker address over to the token program
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.assign({
+            accountPubkey: rogueMaker.publicKey,
+            programId: TOKEN_PROGRAM_ID,
+          })
+        ),
+        [rogueMaker]
+      );
+
+      try {
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: rogueMaker.publicKey,
+            order: orderPDA,
+            escrowTokenAccount: null,
+            takerTokenAccount: takerSgorATA,
+            takerReceiveTokenAccount: null,
+            makerReceiveTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject a maker that is not system-owned");
+      } catch (e: any) {
+        assert.include(e.message, "MakerNotSystemAccount");
+      }
+    });
+
+    it("rejects a direction 1 fill that pays the taker's own token account", async () => {
+      const amt = new anchor.BN(130_000_000);
+      const currentSlot = await getCurrentSlot();
+      const expirationSlot = new anchor.BN(currentSlot + EXPIRY_OFFSET);
+      const [orderPDA] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+
+      await program.methods
+        .createOrder({
+          amount: amt,
+          direction: 1,
+          expirationSlot,
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+
+      try {
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: maker.publicKey,
+            order: orderPDA,
+            escrowTokenAccount: null,
+            takerTokenAccount: takerSgorATA,
+            takerReceiveTokenAccount: null,
+            makerReceiveTokenAccount: takerSgorATA, // taker pays themselves
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject a receive account not owned by the maker");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+
+      try {
+        await program.methods
+          .fillOrders(amt)
+          .accounts({
+            taker: taker.publicKey,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(
+            [orderPDA, maker.publicKey, takerSgorATA, takerSgorATA].map(
+              (pubkey) => ({ pubkey, isSigner: false, isWritable: true })
+            )
+          )
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject a batch fill paying the taker");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
           maker: maker.publicKey,
           order: orderPDA,
           escrowTokenAccount: null,
           makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // REPLACE ORDER
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Replace Order", () => {
+    const seedAmount = new anchor.BN(400_000_000);
+    let orderPDA: PublicKey;
+    let escrowPDA: PublicKey;
+
+    before(async () => {
+      const currentSlot = await getCurrentSlot();
+      [orderPDA] = deriveOrderPDA(program.programId, maker.publicKey, seedAmount);
+      [escrowPDA] = deriveEscrowPDA(program.programId, maker.publicKey, seedAmount);
+
+      await program.methods
+        .createOrder({
+          amount: seedAmount,
+          direction: 0,
+          expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         })
         .signers([maker])
         .rpc();
     });
+
+    function replaceAccounts() {
+      return {
+        maker: maker.publicKey,
+        order: orderPDA,
+        escrowTokenAccount: escrowPDA,
+        makerTokenAccount: makerSgorATA,
+        sgorMint: sgorMint,
+        config: configPDA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      };
+    }
+
+    it("tops up the escrow when the amount grows in the same direction", async () => {
+      const newAmount = new anchor.BN(600_000_000);
+      const currentSlot = await getCurrentSlot();
+
+      await program.methods
+        .replaceOrder(newAmount, 0, new anchor.BN(currentSlot + EXPIRY_OFFSET))
+        .accounts(replaceAccounts())
+        .signers([maker])
+        .rpc();
+
+      const escrowBalance = (await getAccount(provider.connection, escrowPDA))
+        .amount;
+      assert.equal(escrowBalance.toString(), newAmount.toString());
+
+      const orderAccount = await program.account.order.fetch(orderPDA);
+      assert.equal(orderAccount.amount.toString(), newAmount.toString());
+      assert.equal(
+        orderAccount.seedAmount.toString(),
+        seedAmount.toString(),
+        "PDA seed amount must not change on replace"
+      );
+    });
+
+    it("refunds sGOR, closes the escrow and escrows gGOR when the direction flips", async () => {
+      const newAmount = new anchor.BN(200_000_000);
+      const currentSlot = await getCurrentSlot();
+      const orderLamportsBefore = await getLamports(orderPDA);
+      const makerLamportsBefore = await getLamports(maker.publicKey);
+      const escrowRent = await getLamports(escrowPDA);
+      const makerSgorBefore = (await getAccount(provider.connection, makerSgorATA))
+        .amount;
+
+      await program.methods
+        .replaceOrder(newAmount, 1, new anchor.BN(currentSlot + EXPIRY_OFFSET))
+        .accounts(replaceAccounts())
+        .signers([maker])
+        .rpc();
+
+      const makerSgorAfter = (await getAccount(provider.connection, makerSgorATA))
+        .amount;
+      assert.equal(
+        (makerSgorAfter - makerSgorBefore).toString(),
+        "600000000",
+        "Maker should get the whole sGOR escrow back"
+      );
+      assert.isNull(
+        await provider.connection.getAccountInfo(escrowPDA),
+        "Emptied sGOR escrow should be closed"
+      );
+      assert.equal(
+        (await getLamports(maker.publicKey)) - makerLamportsBefore,
+        escrowRent - newAmount.toNumber(),
+        "Maker should get the escrow rent back and pay only the gGOR escrow"
+      );
+
+      const orderLamportsAfter = await getLamports(orderPDA);
+      assert.equal(
+        orderLamportsAfter - orderLamportsBefore,
+        newAmount.toNumber(),
+        "Order PDA should hold the new native escrow"
+      );
+
+      const orderAccount = await program.account.order.fetch(orderPDA);
+      assert.equal(orderAccount.direction, 1);
+    });
+
+    it("rejects replacement by anyone but the maker", async () => {
+      const currentSlot = await getCurrentSlot();
+      try {
+        await program.methods
+          .replaceOrder(
+            new anchor.BN(300_000_000),
+            1,
+            new anchor.BN(currentSlot + EXPIRY_OFFSET)
+          )
+          .accounts({ ...replaceAccounts(), maker: unauthorized.publicKey })
+          .signers([unauthorized])
+          .rpc();
+        assert.fail("Should reject unauthorized replace");
+      } catch (e: any) {
+        assert.isTrue(
+          e.message.includes("Unauthorized") ||
+            e.message.includes("ConstraintSeeds"),
+          `Expected auth error, got: ${e.message}`
+        );
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // COMPUTE BUDGET
+  // ═══════════════════════════════════════════════════════════════════
+  // fill_order is composed inside routing transactions, so its compute
+  // usage is pinned twice: a hard budget, and a recorded baseline that
+  // fails the suite if usage grows by more than 10%. The baseline is
+  // written on first run; delete the entry to re-record it deliberately.
+  describe("Compute Budget", () => {
+    const FILL_D0_CU_BUDGET = 60_000;
+    const CU_REGRESSION_TOLERANCE = 1.1;
+    const BASELINE_PATH = path.join(__dirname, "cu-baseline.json");
+
+    function unitsConsumed(logs: string[]): number {
+      const pattern = new RegExp(
+        `Program ${program.programId.toBase58()} consumed (\\d+) of`
+      );
+      for (const line of logs) {
+        const match = line.match(pattern);
+        if (match) return Number(match[1]);
+      }
+      throw new Error("compute usage not found in simulation logs");
+    }
+
+    it("keeps direction 0 fill_order within budget and baseline", async () => {
+      const amt = new anchor.BN(130_000_000);
+      const currentSlot = await getCurrentSlot();
+      const [orderPDA] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+      const [escrowPDA] = deriveEscrowPDA(program.programId, maker.publicKey, amt);
+
+      await program.methods
+        .createOrder({
+          amount: amt,
+          direction: 0,
+          expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+
+      const sim = await program.methods
+        .fillOrder()
+        .accounts({
+          taker: taker.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          takerTokenAccount: null,
+          takerReceiveTokenAccount: takerSgorATA,
+          makerReceiveTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .simulate();
+
+      const used = unitsConsumed(sim.raw as string[]);
+      assert.isAtMost(used, FILL_D0_CU_BUDGET, "fill_order exceeded CU budget");
+
+      const baseline = fs.existsSync(BASELINE_PATH)
+        ? JSON.parse(fs.readFileSync(BASELINE_PATH, "utf8"))
+        : {};
+      if (baseline.fillOrderDirection0 === undefined) {
+        baseline.fillOrderDirection0 = used;
+        fs.writeFileSync(BASELINE_PATH, JSON.stringify(baseline, null, 2) + "\n");
+      } else {
+        assert.isAtMost(
+          used,
+          Math.floor(baseline.fillOrderDirection0 * CU_REGRESSION_TOLERANCE),
+          `fill_order CU grew >10% over baseline ${baseline.fillOrderDirection0}`
+        );
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // FILL ORDERS (batch sweep)
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Fill Orders", () => {
+    const amounts = [
+      new anchor.BN(140_000_000),
+      new anchor.BN(150_000_000),
+      new anchor.BN(160_000_000),
+    ];
+    const orders: { order: PublicKey; escrow: PublicKey }[] = [];
+
+    before(async () => {
+      const currentSlot = await getCurrentSlot();
+      for (const amt of amounts) {
+        const [order] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+        const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, amt);
+        await program.methods
+          .createOrder({
+            amount: amt,
+            direction: 0,
+            expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+            operator: null,
+            memo: null,
+            activationSlot: null,
+            htlc: null,
+          })
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc();
+        orders.push({ order, escrow });
+      }
+    });
+
+    function groupFor(o: { order: PublicKey; escrow: PublicKey }) {
+      return [o.order, maker.publicKey, o.escrow, takerSgorATA].map(
+        (pubkey) => ({ pubkey, isSigner: false, isWritable: true })
+      );
+    }
+
+    it("fills orders in sequence until max_total is reached", async () => {
+      const takerSgorBefore = (
+        await getAccount(provider.connection, takerSgorATA)
+      ).amount;
+
+      // Covers the first two orders but not the third
+      const maxTotal = amounts[0].add(amounts[1]).add(new anchor.BN(1));
+
+      await program.methods
+        .fillOrders(maxTotal)
+        .accounts({
+          taker: taker.publicKey,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(orders.flatMap(groupFor))
+        .signers([taker])
+        .rpc();
+
+      const takerSgorAfter = (
+        await getAccount(provider.connection, takerSgorATA)
+      ).amount;
+      assert.equal(
+        (takerSgorAfter - takerSgorBefore).toString(),
+        amounts[0].add(amounts[1]).toString()
+      );
+
+      assert.isNull(await provider.connection.getAccountInfo(orders[0].order));
+      assert.isNull(await provider.connection.getAccountInfo(orders[1].order));
+      const third = await program.account.order.fetch(orders[2].order);
+      assert.deepEqual(third.status, { open: {} });
+    });
+
+    it("aborts the whole sweep when one group is invalid", async () => {
+      const bad = groupFor(orders[2]);
+      bad[1] = { pubkey: unauthorized.publicKey, isSigner: false, isWritable: true };
+
+      try {
+        await program.methods
+          .fillOrders(amounts[2])
+          .accounts({
+            taker: taker.publicKey,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(bad)
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject a group with the wrong maker");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order: orders[2].order,
+          escrowTokenAccount: orders[2].escrow,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // FILL GRACE WINDOW
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Fill Grace Window", () => {
+    const GRACE = 20;
+
+    async function setGrace(slots: number) {
+      await program.methods
+        .updateConfig(new anchor.BN(slots))
+        .accounts({ admin: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+    }
+
+    async function createNative(amt: anchor.BN, expirationSlot: number) {
+      const [orderPDA] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+      await program.methods
+        .createOrder({
+          amount: amt,
+          direction: 1,
+          expirationSlot: new anchor.BN(expirationSlot),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+      return orderPDA;
+    }
+
+    function cancelNative(orderPDA: PublicKey) {
+      return program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    }
+
+    before(async () => setGrace(GRACE));
+    after(async () => setGrace(5));
+
+    it("rejects grace windows above the cap", async () => {
+      try {
+        await setGrace(10_000);
+        assert.fail("Should reject an oversized grace window");
+      } catch (e: any) {
+        assert.include(e.message, "GracePeriodTooLong");
+      }
+    });
+
+    it("rejects config updates from a non-admin", async () => {
+      try {
+        await program.methods
+          .updateConfig(new anchor.BN(1))
+          .accounts({ admin: unauthorized.publicKey, config: configPDA })
+          .signers([unauthorized])
+          .rpc();
+        assert.fail("Should reject non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+
+    it("accepts a fill after expiration_slot but within the grace window", async () => {
+      const amt = new anchor.BN(170_000_000);
+      const expirationSlot = (await getCurrentSlot()) + 3;
+      const orderPDA = await createNative(amt, expirationSlot);
+
+      await waitForSlot(expirationSlot + 1);
+
+      await program.methods
+        .fillOrder()
+        .accounts({
+          taker: taker.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: null,
+          takerTokenAccount: takerSgorATA,
+          takerReceiveTokenAccount: null,
+          makerReceiveTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(orderPDA));
+    });
+
+    it("rejects cancel inside the grace window and allows it after", async () => {
+      const amt = new anchor.BN(180_000_000);
+      const expirationSlot = (await getCurrentSlot()) + 3;
+      const orderPDA = await createNative(amt, expirationSlot);
+
+      await waitForSlot(expirationSlot + 1);
+      try {
+        await cancelNative(orderPDA);
+        assert.fail("Cancel must not race a late fill");
+      } catch (e: any) {
+        assert.include(e.message, "OrderInGracePeriod");
+      }
+
+      await waitForSlot(expirationSlot + GRACE + 1);
+      await cancelNative(orderPDA);
+      assert.isNull(await provider.connection.getAccountInfo(orderPDA));
+    });
+
+    it("rejects a fill past expiration_slot + grace", async () => {
+      const amt = new anchor.BN(190_000_000);
+      const expirationSlot = (await getCurrentSlot()) + 3;
+      const orderPDA = await createNative(amt, expirationSlot);
+
+      await waitForSlot(expirationSlot + GRACE + 1);
+      try {
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: maker.publicKey,
+            order: orderPDA,
+            escrowTokenAccount: null,
+            takerTokenAccount: takerSgorATA,
+            takerReceiveTokenAccount: null,
+            makerReceiveTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject fills past the grace window");
+      } catch (e: any) {
+        assert.include(e.message, "OrderExpired");
+      }
+
+      await cancelNative(orderPDA);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // OPERATOR KEY
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Operator Key", () => {
+    const amt = new anchor.BN(210_000_000);
+    let operator: Keypair;
+    let operatorSgorATA: PublicKey;
+    let orderPDA: PublicKey;
+    let escrowPDA: PublicKey;
+
+    before(async () => {
+      operator = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        operator.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+      operatorSgorATA = await createAssociatedTokenAccount(
+        provider.connection,
+        operator,
+        sgorMint,
+        operator.publicKey
+      );
+
+      [orderPDA] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+      [escrowPDA] = deriveEscrowPDA(program.programId, maker.publicKey, amt);
+      const currentSlot = await getCurrentSlot();
+
+      await program.methods
+        .createOrder({
+          amount: amt,
+          direction: 0,
+          expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+          operator: operator.publicKey,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+    });
+
+    function operatorCancel(overrides: Record<string, PublicKey>) {
+      return program.methods
+        .cancelOrder()
+        .accounts({
+          authority: operator.publicKey,
+          maker: maker.publicKey,
+          order: orderPDA,
+          escrowTokenAccount: escrowPDA,
+          makerTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          ...overrides,
+        })
+        .signers([operator])
+        .rpc();
+    }
+
+    it("operator cannot route the refund to its own token account", async () => {
+      try {
+        await operatorCancel({ makerTokenAccount: operatorSgorATA });
+        assert.fail("Operator must not redirect the refund");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+    });
+
+    it("operator cannot substitute itself as the maker", async () => {
+      try {
+        await operatorCancel({ maker: operator.publicKey });
+        assert.fail("Operator must not receive rent or native refunds");
+      } catch (e: any) {
+        assert.isTrue(
+          e.message.includes("Unauthorized") ||
+            e.message.includes("ConstraintHasOne"),
+          `Expected has_one failure, got: ${e.message}`
+        );
+      }
+    });
+
+    it("only the maker can update the operator", async () => {
+      try {
+        await program.methods
+          .updateOperator(null)
+          .accounts({ maker: operator.publicKey, order: orderPDA })
+          .signers([operator])
+          .rpc();
+        assert.fail("Operator must not change the operator");
+      } catch (e: any) {
+        assert.isTrue(
+          e.message.includes("Unauthorized") ||
+            e.message.includes("ConstraintSeeds"),
+          `Expected auth error, got: ${e.message}`
+        );
+      }
+    });
+
+    it("reports the order status when the operator changes", async () => {
+      let event: any = null;
+      const listener = program.addEventListener("operatorUpdated", (e) => {
+        if (e.orderKey.equals(orderPDA)) event = e;
+      });
+      try {
+        await program.methods
+          .updateOperator(operator.publicKey)
+          .accounts({ maker: maker.publicKey, order: orderPDA })
+          .signers([maker])
+          .rpc();
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+      assert.isNotNull(event);
+      assert.equal(event.newOperator.toBase58(), operator.publicKey.toBase58());
+      assert.deepEqual(event.status, { open: {} });
+    });
+
+    it("operator cancel refunds the maker", async () => {
+      const makerSgorBefore = (
+        await getAccount(provider.connection, makerSgorATA)
+      ).amount;
+
+      await operatorCancel({});
+
+      const makerSgorAfter = (
+        await getAccount(provider.connection, makerSgorATA)
+      ).amount;
+      assert.equal(
+        (makerSgorAfter - makerSgorBefore).toString(),
+        amt.toString()
+      );
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // CANCEL ACCOUNT VALIDATION (adversarial)
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Cancel Account Validation", () => {
+    const amtA = new anchor.BN(220_000_000);
+    const amtB = new anchor.BN(230_000_000);
+    let orderA: PublicKey;
+    let escrowA: PublicKey;
+    let orderB: PublicKey;
+    let escrowB: PublicKey;
+
+    before(async () => {
+      const currentSlot = await getCurrentSlot();
+      [orderA] = deriveOrderPDA(program.programId, maker.publicKey, amtA);
+      [escrowA] = deriveEscrowPDA(program.programId, maker.publicKey, amtA);
+      [orderB] = deriveOrderPDA(program.programId, maker.publicKey, amtB);
+      [escrowB] = deriveEscrowPDA(program.programId, maker.publicKey, amtB);
+
+      for (const [amt, order, escrow] of [
+        [amtA, orderA, escrowA],
+        [amtB, orderB, escrowB],
+      ] as [anchor.BN, PublicKey, PublicKey][]) {
+        await program.methods
+          .createOrder({
+            amount: amt,
+            direction: 0,
+            expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+            operator: null,
+            memo: null,
+            activationSlot: null,
+            htlc: null,
+          })
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: escrow,
+            makerTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc();
+      }
+    });
+
+    function cancel(order: PublicKey, escrow: PublicKey, refund: PublicKey) {
+      return program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: refund,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    }
+
+    it("rejects another order's escrow", async () => {
+      try {
+        await cancel(orderA, escrowB, makerSgorATA);
+        assert.fail("Should reject a foreign escrow");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidEscrowAccount");
+      }
+    });
+
+    it("rejects a refund account owned by someone else", async () => {
+      try {
+        await cancel(orderA, escrowA, takerSgorATA);
+        assert.fail("Should reject a refund account not owned by the maker");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidRefundAccount");
+      }
+    });
+
+    it("rejects a refund account of the wrong mint", async () => {
+      const otherMint = await createMint(
+        provider.connection,
+        mintAuthority,
+        mintAuthority.publicKey,
+        null,
+        9
+      );
+      const makerOtherATA = await createAssociatedTokenAccount(
+        provider.connection,
+        maker,
+        otherMint,
+        maker.publicKey
+      );
+      try {
+        await cancel(orderA, escrowA, makerOtherATA);
+        assert.fail("Should reject a wrong-mint refund account");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidMint");
+      }
+    });
+
+    it("cancels cleanly with the correct accounts", async () => {
+      await cancel(orderA, escrowA, makerSgorATA);
+      await cancel(orderB, escrowB, makerSgorATA);
+      assert.isNull(await provider.connection.getAccountInfo(orderA));
+      assert.isNull(await provider.connection.getAccountInfo(orderB));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // SPL TRANSFER FAILURE MAPPING
+  // ═══════════════════════════════════════════════════════════════════
+  describe("SPL Transfer Failure Mapping", () => {
+    const dir1Amount = new anchor.BN(240_000_000);
+    let dir1Order: PublicKey;
+
+    function freeze(account: PublicKey) {
+      return freezeAccount(
+        provider.connection,
+        mintAuthority,
+        account,
+        sgorMint,
+        mintAuthority
+      );
+    }
+
+    function thaw(account: PublicKey) {
+      return thawAccount(
+        provider.connection,
+        mintAuthority,
+        account,
+        sgorMint,
+        mintAuthority
+      );
+    }
+
+    function createDir0(amount: anchor.BN) {
+      return getCurrentSlot().then((currentSlot) =>
+        program.methods
+          .createOrder({
+            amount,
+            direction: 0,
+            expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+            operator: null,
+            memo: null,
+            activationSlot: null,
+            htlc: null,
+          })
+          .accounts({
+            maker: maker.publicKey,
+            order: deriveOrderPDA(program.programId, maker.publicKey, amount)[0],
+            escrowTokenAccount: deriveEscrowPDA(
+              program.programId,
+              maker.publicKey,
+              amount
+            )[0],
+            makerTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc()
+      );
+    }
+
+    function fillDir1() {
+      return program.methods
+        .fillOrder()
+        .accounts({
+          taker: taker.publicKey,
+          maker: maker.publicKey,
+          order: dir1Order,
+          escrowTokenAccount: null,
+          takerTokenAccount: takerSgorATA,
+          takerReceiveTokenAccount: null,
+          makerReceiveTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .rpc();
+    }
+
+    before(async () => {
+      const currentSlot = await getCurrentSlot();
+      [dir1Order] = deriveOrderPDA(program.programId, maker.publicKey, dir1Amount);
+      await program.methods
+        .createOrder({
+          amount: dir1Amount,
+          direction: 1,
+          expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: dir1Order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+    });
+
+    it("maps a frozen maker deposit account to SourceAccountFrozen", async () => {
+      await freeze(makerSgorATA);
+      try {
+        await createDir0(new anchor.BN(250_000_000));
+        assert.fail("Should reject a frozen source account");
+      } catch (e: any) {
+        assert.include(e.message, "SourceAccountFrozen");
+      } finally {
+        await thaw(makerSgorATA);
+      }
+    });
+
+    it("maps an oversized deposit to InsufficientFunds", async () => {
+      try {
+        await createDir0(new anchor.BN("1000000000000")); // 1000 sGOR
+        assert.fail("Should reject a deposit above the maker's balance");
+      } catch (e: any) {
+        assert.include(e.message, "InsufficientFunds");
+      }
+    });
+
+    it("maps a frozen taker source account to SourceAccountFrozen", async () => {
+      await freeze(takerSgorATA);
+      try {
+        await fillDir1();
+        assert.fail("Should reject a frozen taker account");
+      } catch (e: any) {
+        assert.include(e.message, "SourceAccountFrozen");
+      } finally {
+        await thaw(takerSgorATA);
+      }
+    });
+
+    it("maps a frozen maker receive account to DestinationAccountFrozen", async () => {
+      await freeze(makerSgorATA);
+      try {
+        await fillDir1();
+        assert.fail("Should reject a frozen destination account");
+      } catch (e: any) {
+        assert.include(e.message, "DestinationAccountFrozen");
+      } finally {
+        await thaw(makerSgorATA);
+      }
+    });
+
+    it("maps a wrong-mint receive account to TokenMintMismatch", async () => {
+      const amount = new anchor.BN(260_000_000);
+      await createDir0(amount);
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, amount);
+
+      const otherMint = await createMint(
+        provider.connection,
+        mintAuthority,
+        mintAuthority.publicKey,
+        null,
+        9
+      );
+      const takerOtherATA = await createAssociatedTokenAccount(
+        provider.connection,
+        taker,
+        otherMint,
+        taker.publicKey
+      );
+
+      try {
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: escrow,
+            takerTokenAccount: null,
+            takerReceiveTokenAccount: takerOtherATA,
+            makerReceiveTokenAccount: null,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject a receive account of another mint");
+      } catch (e: any) {
+        assert.include(e.message, "TokenMintMismatch");
+      }
+    });
+
+    it("fills once the accounts are thawed", async () => {
+      await fillDir1();
+      assert.isNull(await provider.connection.getAccountInfo(dir1Order));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER SEQUENCE
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Sequence", () => {
+    it("assigns strictly increasing sequence numbers across creates", async () => {
+      const amounts = [new anchor.BN(270_000_000), new anchor.BN(280_000_000)];
+      const sequences: number[] = [];
+      const seqBefore = (
+        await program.account.bridgeConfig.fetch(configPDA)
+      ).orderSequence.toNumber();
+
+      for (const amt of amounts) {
+        const currentSlot = await getCurrentSlot();
+        const [order] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+        await program.methods
+          .createOrder({
+            amount: amt,
+            direction: 1,
+            expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+            operator: null,
+            memo: null,
+            activationSlot: null,
+            htlc: null,
+          })
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: null,
+            makerTokenAccount: null,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc();
+        sequences.push(
+          (await program.account.order.fetch(order)).sequence.toNumber()
+        );
+      }
+
+      assert.equal(sequences[0], seqBefore);
+      assert.equal(sequences[1], seqBefore + 1);
+      const seqAfter = (
+        await program.account.bridgeConfig.fetch(configPDA)
+      ).orderSequence.toNumber();
+      assert.equal(seqAfter, seqBefore + 2);
+
+      // Clean up
+      for (const amt of amounts) {
+        const [order] = deriveOrderPDA(program.programId, maker.publicKey, amt);
+        await program.methods
+          .cancelOrder()
+          .accounts({
+            authority: maker.publicKey,
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: null,
+            makerTokenAccount: null,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([maker])
+          .rpc();
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER MEMO
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Memo", () => {
+    const MEMO_LEN = 64;
+
+    // Mirrors bridge_common::pack_memo: [len, utf8 bytes..., 0 padding]
+    function packMemo(text: string): number[] {
+      const bytes = Buffer.from(text, "utf8");
+      const memo = new Array(MEMO_LEN).fill(0);
+      memo[0] = bytes.length;
+      bytes.forEach((b, i) => (memo[i + 1] = b));
+      return memo;
+    }
+
+    function createWithMemo(amount: anchor.BN, memo: number[] | null) {
+      return getCurrentSlot().then((currentSlot) =>
+        program.methods
+          .createOrder({
+            amount,
+            direction: 1,
+            expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+            operator: null,
+            memo,
+            activationSlot: null,
+            htlc: null,
+          })
+          .accounts({
+            maker: maker.publicKey,
+            order: deriveOrderPDA(program.programId, maker.publicKey, amount)[0],
+            escrowTokenAccount: null,
+            makerTokenAccount: null,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc()
+      );
+    }
+
+    it("stores the memo and emits it on create and fill", async () => {
+      const amount = new anchor.BN(290_000_000);
+      const memo = packMemo("OTC deal #1234");
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+
+      const seen: { created?: number[]; filled?: number[] } = {};
+      const createdListener = program.addEventListener("orderCreated", (e) => {
+        if (e.orderKey.equals(order)) seen.created = Array.from(e.memo);
+      });
+      const filledListener = program.addEventListener("orderFilled", (e) => {
+        if (e.orderKey.equals(order)) seen.filled = Array.from(e.memo);
+      });
+
+      try {
+        await createWithMemo(amount, memo);
+        const orderAccount = await program.account.order.fetch(order);
+        assert.deepEqual(Array.from(orderAccount.memo), memo);
+
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: null,
+            takerTokenAccount: takerSgorATA,
+            takerReceiveTokenAccount: null,
+            makerReceiveTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+
+        await new Promise((r) => setTimeout(r, 1000));
+        assert.deepEqual(seen.created, memo);
+        assert.deepEqual(seen.filled, memo);
+      } finally {
+        await program.removeEventListener(createdListener);
+        await program.removeEventListener(filledListener);
+      }
+    });
+
+    it("stores an all-zero memo when none is given", async () => {
+      const amount = new anchor.BN(300_000_000);
+      await createWithMemo(amount, null);
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      const orderAccount = await program.account.order.fetch(order);
+      assert.deepEqual(Array.from(orderAccount.memo), new Array(MEMO_LEN).fill(0));
+
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+
+    it("rejects a memo with non-zero bytes past its length", async () => {
+      const memo = packMemo("abc");
+      memo[20] = 0x41;
+      try {
+        await createWithMemo(new anchor.BN(310_000_000), memo);
+        assert.fail("Should reject non-zero padding");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidMemo");
+      }
+    });
+
+    it("rejects a memo whose length byte overflows the buffer", async () => {
+      const memo = new Array(MEMO_LEN).fill(0);
+      memo[0] = MEMO_LEN;
+      try {
+        await createWithMemo(new anchor.BN(320_000_000), memo);
+        assert.fail("Should reject an oversized length prefix");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidMemo");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // MAKER RENT EXEMPTION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Maker Rent Exemption", () => {
+    it("rejects a native payment that leaves an empty maker below rent-exemption", async () => {
+      const freshMaker = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        freshMaker.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+
+      const freshATA = await createAssociatedTokenAccount(
+        provider.connection,
+        freshMaker,
+        sgorMint,
+        freshMaker.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        mintAuthority,
+        sgorMint,
+        freshATA,
+        mintAuthority,
+        MIN_ORDER_AMOUNT.toNumber()
+      );
+
+      const amount = MIN_ORDER_AMOUNT;
+      const [order] = deriveOrderPDA(program.programId, freshMaker.publicKey, amount);
+      const [escrow] = deriveEscrowPDA(program.programId, freshMaker.publicKey, amount);
+      const currentSlot = await getCurrentSlot();
+
+      await program.methods
+        .createOrder({
+          amount,
+          direction: 0,
+          expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: freshMaker.publicKey,
+          order,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: freshATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([freshMaker])
+        .rpc();
+
+      // Drain the maker wallet to exactly 0 lamports (5000 = signature fee)
+      const balance = await getLamports(freshMaker.publicKey);
+      const drain = new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: freshMaker.publicKey,
+          toPubkey: unauthorized.publicKey,
+          lamports: balance - 5000,
+        })
+      );
+      await anchor.web3.sendAndConfirmTransaction(provider.connection, drain, [
+        freshMaker,
+      ]);
+      assert.equal(await getLamports(freshMaker.publicKey), 0);
+
+      try {
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: freshMaker.publicKey,
+            order,
+            escrowTokenAccount: escrow,
+            takerTokenAccount: null,
+            takerReceiveTokenAccount: takerSgorATA,
+            makerReceiveTokenAccount: null,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+        assert.fail("Should reject a fill that leaves the maker below rent-exemption");
+      } catch (e: any) {
+        assert.include(e.message, "MakerBelowRentExempt");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // TIME-LOCKED ORDERS
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Activation Slot", () => {
+    const DELAY = 15;
+
+    function create(amount: anchor.BN, activation: number, expiration: number) {
+      return program.methods
+        .createOrder({
+          amount,
+          direction: 1,
+          expirationSlot: new anchor.BN(expiration),
+          operator: null,
+          memo: null,
+          activationSlot: new anchor.BN(activation),
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order: deriveOrderPDA(program.programId, maker.publicKey, amount)[0],
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+    }
+
+    function fill(order: PublicKey) {
+      return program.methods
+        .fillOrder()
+        .accounts({
+          taker: taker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          takerTokenAccount: takerSgorATA,
+          takerReceiveTokenAccount: null,
+          makerReceiveTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .rpc();
+    }
+
+    it("rejects an activation slot at or after expiration", async () => {
+      const currentSlot = await getCurrentSlot();
+      const expiration = currentSlot + EXPIRY_OFFSET;
+      try {
+        await create(new anchor.BN(330_000_000), expiration, expiration);
+        assert.fail("Should reject activation >= expiration");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidActivationSlot");
+      }
+    });
+
+    it("rejects fills before activation and accepts them after", async () => {
+      const amount = new anchor.BN(340_000_000);
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      const currentSlot = await getCurrentSlot();
+      const activation = currentSlot + DELAY;
+      await create(amount, activation, currentSlot + EXPIRY_OFFSET);
+
+      const orderAccount = await program.account.order.fetch(order);
+      assert.equal(orderAccount.activationSlot.toNumber(), activation);
+
+      try {
+        await fill(order);
+        assert.fail("Should reject a fill before activation");
+      } catch (e: any) {
+        assert.include(e.message, "OrderNotActive");
+      }
+
+      await waitForSlot(activation + 1);
+      await fill(order);
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+
+    it("allows cancelling before activation", async () => {
+      const amount = new anchor.BN(350_000_000);
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      const currentSlot = await getCurrentSlot();
+      await create(amount, currentSlot + EXPIRY_OFFSET - 1, currentSlot + EXPIRY_OFFSET);
+
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(order));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // QUOTE FILL
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Quote Fill", () => {
+    const amount = new anchor.BN(360_000_000);
+    let order: PublicKey;
+
+    before(async () => {
+      const currentSlot = await getCurrentSlot();
+      [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      await program.methods
+        .createOrder({
+          amount,
+          direction: 1,
+          expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+    });
+
+    it("returns the quote without touching the order", async () => {
+      const before = await provider.connection.getAccountInfo(order);
+      const quote = await program.methods
+        .quoteFill(amount)
+        .accounts({ order, config: configPDA })
+        .view();
+
+      assert.equal(quote.takerPayment.toString(), amount.toString());
+      assert.equal(quote.fee.toString(), "0");
+      assert.equal(quote.outputAmount.toString(), amount.toString());
+      assert.equal(quote.remainingAmount.toString(), "0");
+
+      const after = await provider.connection.getAccountInfo(order);
+      assert.isTrue(after!.data.equals(before!.data));
+      assert.equal(after!.lamports, before!.lamports);
+    });
+
+    it("rejects a partial fill amount", async () => {
+      try {
+        await program.methods
+          .quoteFill(amount.subn(1))
+          .accounts({ order, config: configPDA })
+          .view();
+        assert.fail("Should reject a partial fill");
+      } catch (e: any) {
+        assert.include(e.message, "PartialFillNotSupported");
+      }
+    });
+
+    it("matches what fill_order actually moves", async () => {
+      const quote = await program.methods
+        .quoteFill(amount)
+        .accounts({ order, config: configPDA })
+        .view();
+      const takerSgorBefore = (
+        await getAccount(provider.connection, takerSgorATA)
+      ).amount;
+
+      await program.methods
+        .fillOrder()
+        .accounts({
+          taker: taker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          takerTokenAccount: takerSgorATA,
+          takerReceiveTokenAccount: null,
+          makerReceiveTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .rpc();
+
+      const takerSgorAfter = (
+        await getAccount(provider.connection, takerSgorATA)
+      ).amount;
+      assert.equal(
+        (takerSgorBefore - takerSgorAfter).toString(),
+        quote.takerPayment.toString()
+      );
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // HTLC
+  // ═══════════════════════════════════════════════════════════════════
+  describe("HTLC", () => {
+    async function createHtlc(
+      amount: anchor.BN,
+      direction: number,
+      hashlock: number[],
+      expiryOffset = EXPIRY_OFFSET
+    ) {
+      const currentSlot = await getCurrentSlot();
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      const [escrow] = deriveEscrowPDA(program.programId, maker.publicKey, amount);
+      await program.methods
+        .createOrder({
+          amount,
+          direction,
+          expirationSlot: new anchor.BN(currentSlot + expiryOffset),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: { hashlock, recipient: taker.publicKey },
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: direction === 0 ? escrow : null,
+          makerTokenAccount: direction === 0 ? makerSgorATA : null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+      return { order, escrow };
+    }
+
+    function claim(
+      order: PublicKey,
+      escrow: PublicKey | null,
+      preimage: number[],
+      who: Keypair = taker
+    ) {
+      return program.methods
+        .claimHtlcOrder(preimage)
+        .accounts({
+          recipient: who.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: escrow,
+          recipientTokenAccount: escrow ? takerSgorATA : null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([who])
+        .rpc();
+    }
+
+    function cancel(order: PublicKey, escrow: PublicKey | null) {
+      return program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: escrow,
+          makerTokenAccount: escrow ? makerSgorATA : null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    }
+
+    it("releases escrowed gGOR to the recipient on the preimage", async () => {
+      const amount = new anchor.BN(370_000_000);
+      const preimage = randomPreimage();
+      const { order } = await createHtlc(amount, 1, hashlockOf(preimage));
+      const before = await getLamports(taker.publicKey);
+
+      let event: any = null;
+      const listener = program.addEventListener("htlcClaimed", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await claim(order, null, preimage);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      // The provider wallet pays the transaction fee
+      assert.equal(await getLamports(taker.publicKey), before + amount.toNumber());
+      assert.isNull(await provider.connection.getAccountInfo(order));
+      assert.deepEqual(Array.from(event.preimage), preimage);
+    });
+
+    it("releases escrowed sGOR to the recipient on the preimage", async () => {
+      const amount = new anchor.BN(371_000_000);
+      const preimage = randomPreimage();
+      const { order, escrow } = await createHtlc(amount, 0, hashlockOf(preimage));
+      const before = (await getAccount(provider.connection, takerSgorATA)).amount;
+
+      await claim(order, escrow, preimage);
+
+      const after = (await getAccount(provider.connection, takerSgorATA)).amount;
+      assert.equal((after - before).toString(), amount.toString());
+    });
+
+    it("rejects a wrong preimage or another claimant", async () => {
+      const amount = new anchor.BN(372_000_000);
+      const preimage = randomPreimage();
+      const { order } = await createHtlc(amount, 1, hashlockOf(preimage));
+
+      try {
+        await claim(order, null, randomPreimage());
+        assert.fail("Should reject a preimage that does not match");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidPreimage");
+      }
+      try {
+        await claim(order, null, preimage, unauthorized);
+        assert.fail("Should reject anyone but the recipient");
+      } catch (e: any) {
+        assert.include(e.message, "TakerNotAllowed");
+      }
+      assert.isNotNull(await program.account.order.fetchNullable(order));
+    });
+
+    it("keeps hash-locked orders off fill_order and replace_order", async () => {
+      const amount = new anchor.BN(373_000_000);
+      const { order } = await createHtlc(amount, 1, hashlockOf(randomPreimage()));
+
+      try {
+        await program.methods
+          .fillOrder()
+          .accounts({
+            taker: taker.publicKey,
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: null,
+            takerTokenAccount: takerSgorATA,
+            takerReceiveTokenAccount: null,
+            makerReceiveTokenAccount: makerSgorATA,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([taker])
+          .rpc();
+        assert.fail("Should require the preimage");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcClaimRequired");
+      }
+
+      try {
+        await program.methods
+          .replaceOrder(amount, 1, new anchor.BN((await getCurrentSlot()) + 5))
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: null,
+            makerTokenAccount: null,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should not re-quote an HTLC order");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcTermsFixed");
+      }
+    });
+
+    it("rejects a refund before expiry", async () => {
+      const amount = new anchor.BN(374_000_000);
+      const { order } = await createHtlc(amount, 1, hashlockOf(randomPreimage()));
+      try {
+        await cancel(order, null);
+        assert.fail("Should not refund before expiry");
+      } catch (e: any) {
+        assert.include(e.message, "HtlcNotExpired");
+      }
+    });
+
+    it("refunds the maker after the fill deadline and rejects a late claim", async () => {
+      const amount = new anchor.BN(375_000_000);
+      const preimage = randomPreimage();
+      const { order } = await createHtlc(amount, 1, hashlockOf(preimage), 5);
+      const { expirationSlot } = await program.account.order.fetch(order);
+      const { fillGraceSlots } = await program.account.bridgeConfig.fetch(configPDA);
+      await waitForSlot(expirationSlot.add(fillGraceSlots).toNumber() + 1);
+
+      try {
+        await claim(order, null, preimage);
+        assert.fail("Should reject a claim after the deadline");
+      } catch (e: any) {
+        assert.include(e.message, "OrderExpired");
+      }
+
+      const before = await getLamports(maker.publicKey);
+      await cancel(order, null);
+      assert.isAbove(await getLamports(maker.publicKey), before + amount.toNumber() - 10_000);
+    });
+
+    it("rejects a claim on an order without a hashlock", async () => {
+      const amount = new anchor.BN(376_000_000);
+      const currentSlot = await getCurrentSlot();
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      await program.methods
+        .createOrder({
+          amount,
+          direction: 1,
+          expirationSlot: new anchor.BN(currentSlot + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+
+      try {
+        await claim(order, null, randomPreimage());
+        assert.fail("Should reject a plain order");
+      } catch (e: any) {
+        assert.include(e.message, "NotHashLocked");
+      }
+      await cancel(order, null);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // SELF FILL
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Self Fill", () => {
+    function setAllowSelfFill(allow: boolean, admin: Keypair | null = null) {
+      const builder = program.methods
+        .setAllowSelfFill(allow)
+        .accounts({
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+        });
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    }
+
+    async function create(amount: anchor.BN) {
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      await program.methods
+        .createOrder({
+          amount,
+          direction: 1,
+          expirationSlot: new anchor.BN((await getCurrentSlot()) + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+      return order;
+    }
+
+    // The maker pays their own sGOR account and takes back their gGOR
+    function selfFill(order: PublicKey) {
+      return program.methods
+        .fillOrder()
+        .accounts({
+          taker: maker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          takerTokenAccount: makerSgorATA,
+          takerReceiveTokenAccount: null,
+          makerReceiveTokenAccount: makerSgorATA,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    }
+
+    it("rejects a maker filling their own order", async () => {
+      const order = await create(new anchor.BN(377_000_000));
+      try {
+        await selfFill(order);
+        assert.fail("Should reject a self-fill");
+      } catch (e: any) {
+        assert.include(e.message, "SelfFillNotAllowed");
+      }
+
+      try {
+        await program.methods
+          .fillOrders(new anchor.BN(377_000_000))
+          .accounts({
+            taker: maker.publicKey,
+            sgorMint: sgorMint,
+            config: configPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(
+            [order, maker.publicKey, makerSgorATA, makerSgorATA].map((pubkey) => ({
+              pubkey,
+              isSigner: false,
+              isWritable: true,
+            }))
+          )
+          .signers([maker])
+          .rpc();
+        assert.fail("Should reject a self-fill in a sweep");
+      } catch (e: any) {
+        assert.include(e.message, "SelfFillNotAllowed");
+      }
+
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: maker.publicKey,
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: sgorMint,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    });
+
+    it("allows and flags self-fills when the admin enables them", async () => {
+      await setAllowSelfFill(true);
+      let event: any = null;
+      const listener = program.addEventListener("orderFilled", (e) => {
+        event = e;
+      });
+      try {
+        const order = await create(new anchor.BN(378_000_000));
+        await selfFill(order);
+        await new Promise((r) => setTimeout(r, 1000));
+        assert.isNull(await provider.connection.getAccountInfo(order));
+        assert.isTrue(event.selfFill);
+        assert.equal(event.taker.toBase58(), maker.publicKey.toBase58());
+      } finally {
+        await program.removeEventListener(listener);
+        await setAllowSelfFill(false);
+      }
+    });
+
+    it("rejects self-fill updates from a non-admin", async () => {
+      try {
+        await setAllowSelfFill(true, unauthorized);
+        assert.fail("Should reject a non-admin update");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAdmin");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════
+  // ORDER VERSION
+  // ═══════════════════════════════════════════════════════════════════
+  describe("Order Version", () => {
+    const ORDER_VERSION = 1;
+    // Preloaded from tests/fixtures (see Anchor.toml): the sGOR mint and
+    // two direction-1 orders in the v0 layout, 3 and 4 gGOR (lamports)
+    // escrowed by the maker below
+    const SGOR_MINT = new PublicKey("71Jvq4Epe2FCJ7JFSF7jLXdNk1Wy4Bhqd9iL6bEFELvg");
+    const legacyMaker = Keypair.fromSeed(new Uint8Array(32).fill(46));
+    const LEGACY_AMOUNT = new anchor.BN(3_000_000);
+    const LEGACY_CANCEL_AMOUNT = new anchor.BN(4_000_000);
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(
+        legacyMaker.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+    });
+
+    function migrateOrder(order: PublicKey, signer: Keypair = legacyMaker) {
+      return program.methods
+        .migrateOrder()
+        .accounts({
+          maker: signer.publicKey,
+          order,
+          sgorMint: SGOR_MINT,
+          config: configPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    function cancelLegacyOrder(order: PublicKey) {
+      return program.methods
+        .cancelLegacyOrder()
+        .accounts({
+          maker: legacyMaker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: SGOR_MINT,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([legacyMaker])
+        .rpc();
+    }
+
+    it("stamps new orders with the current version", async () => {
+      const amount = new anchor.BN(380_000_000);
+      const [order] = deriveOrderPDA(program.programId, maker.publicKey, amount);
+      await program.methods
+        .createOrder({
+          amount,
+          direction: 1,
+          expirationSlot: new anchor.BN((await getCurrentSlot()) + EXPIRY_OFFSET),
+          operator: null,
+          memo: null,
+          activationSlot: null,
+          htlc: null,
+        })
+        .accounts({
+          maker: maker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: SGOR_MINT,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([maker])
+        .rpc();
+      assert.equal((await program.account.order.fetch(order)).version, ORDER_VERSION);
+
+      try {
+        await migrateOrder(order, maker);
+        assert.fail("Should reject a current-version order");
+      } catch (e: any) {
+        assert.include(e.message, "UnsupportedOrderVersion");
+      }
+      try {
+        await program.methods
+          .cancelLegacyOrder()
+          .accounts({
+            maker: maker.publicKey,
+            order,
+            escrowTokenAccount: null,
+            makerTokenAccount: null,
+            sgorMint: SGOR_MINT,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([maker])
+          .rpc();
+        assert.fail("Should reject a current-version order");
+      } catch (e: any) {
+        assert.include(e.message, "NotLegacyOrder");
+      }
+    });
+
+    it("rejects a v0 order in handlers until it is migrated", async () => {
+      const [order] = deriveOrderPDA(program.programId, legacyMaker.publicKey, LEGACY_AMOUNT);
+      try {
+        await program.methods
+          .updateOperator(null)
+          .accounts({ maker: legacyMaker.publicKey, order })
+          .signers([legacyMaker])
+          .rpc();
+        assert.fail("Should not load a v0 order");
+      } catch (e: any) {
+        assert.include(e.message, "AccountDidNotDeserialize");
+      }
+    });
+
+    it("only lets the maker migrate a v0 order", async () => {
+      const [order] = deriveOrderPDA(program.programId, legacyMaker.publicKey, LEGACY_AMOUNT);
+      try {
+        await migrateOrder(order, unauthorized);
+        assert.fail("Should reject a non-maker");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+    });
+
+    it("migrates a v0 order in place, keeping its escrow", async () => {
+      const [order] = deriveOrderPDA(program.programId, legacyMaker.publicKey, LEGACY_AMOUNT);
+      const rentBefore = await provider.connection.getMinimumBalanceForRentExemption(59);
+      assert.equal(
+        await getLamports(order),
+        rentBefore + LEGACY_AMOUNT.toNumber()
+      );
+
+      const { orderSequence } = await program.account.bridgeConfig.fetch(configPDA);
+      await migrateOrder(order);
+
+      const info = await provider.connection.getAccountInfo(order);
+      const rentAfter = await provider.connection.getMinimumBalanceForRentExemption(
+        info!.data.length
+      );
+      assert.equal(info!.lamports, rentAfter + LEGACY_AMOUNT.toNumber());
+
+      const migrated = await program.account.order.fetch(order);
+      assert.equal(migrated.version, ORDER_VERSION);
+      assert.ok(migrated.maker.equals(legacyMaker.publicKey));
+      assert.equal(migrated.amount.toString(), LEGACY_AMOUNT.toString());
+      assert.equal(migrated.seedAmount.toString(), LEGACY_AMOUNT.toString());
+      assert.equal(migrated.direction, 1);
+      assert.deepEqual(migrated.status, { open: {} });
+      // The fixture mint's decimals, for checked transfers on later fills
+      assert.equal(migrated.decimals, 9);
+      // Sequenced as if created at migration
+      assert.equal(migrated.sequence.toString(), orderSequence.toString());
+      assert.equal(
+        (await program.account.bridgeConfig.fetch(configPDA)).orderSequence.toString(),
+        orderSequence.addn(1).toString()
+      );
+      // An all-zero memo, the same as one omitted at creation
+      assert.isTrue(migrated.memo.every((b: number) => b === 0));
+      // v0 orders had no time lock, so they stay fillable right away
+      assert.equal(migrated.activationSlot.toNumber(), 0);
+      // Not hash-locked, so the cancel below needs no expiry
+      assert.isNull(migrated.htlc);
+
+      // Migrated orders start without an operator; the maker can add one
+      assert.isNull(migrated.operator);
+      await program.methods
+        .updateOperator(unauthorized.publicKey)
+        .accounts({ maker: legacyMaker.publicKey, order })
+        .signers([legacyMaker])
+        .rpc();
+      const withOperator = await program.account.order.fetch(order);
+      assert.equal(withOperator.operator.toBase58(), unauthorized.publicKey.toBase58());
+
+      // A migrated order goes through the normal cancel path
+      const makerBefore = await getLamports(legacyMaker.publicKey);
+      await program.methods
+        .cancelOrder()
+        .accounts({
+          authority: legacyMaker.publicKey,
+          maker: legacyMaker.publicKey,
+          order,
+          escrowTokenAccount: null,
+          makerTokenAccount: null,
+          sgorMint: SGOR_MINT,
+          config: configPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([legacyMaker])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(order));
+      assert.isAbove(
+        (await getLamports(legacyMaker.publicKey)) - makerBefore,
+        LEGACY_AMOUNT.toNumber()
+      );
+    });
+
+    it("refunds a v0 order without migrating it", async () => {
+      const [order] = deriveOrderPDA(program.programId, legacyMaker.publicKey, LEGACY_CANCEL_AMOUNT);
+      const orderLamports = await getLamports(order);
+      const makerBefore = await getLamports(legacyMaker.publicKey);
+
+      let event: any = null;
+      const listener = program.addEventListener("legacyOrderCancelled", (e) => {
+        if (e.orderKey.equals(order)) event = e;
+      });
+      try {
+        await cancelLegacyOrder(order);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      assert.isNull(await provider.connection.getAccountInfo(order));
+      assert.isTrue(event.maker.equals(legacyMaker.publicKey));
+      assert.equal(event.amount.toString(), LEGACY_CANCEL_AMOUNT.toString());
+      assert.deepEqual(event.status, { cancelled: {} });
+      // The escrowed gGOR and the rent, less the transaction fee
+      const gained = (await getLamports(legacyMaker.publicKey)) - makerBefore;
+      assert.isAbove(gained, LEGACY_CANCEL_AMOUNT.toNumber());
+      assert.isAtMost(gained, orderLamports);
+    });
   });
 });
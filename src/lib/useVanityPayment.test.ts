@@ -9,6 +9,7 @@ import {
   calculateEstimatedAttempts,
   calculateBatchCostGOR,
   calculateBatchCostLamports,
+  randomBatchId,
 } from './useVanityPayment';
 
 describe('Vanity Payment Calculations', () => {
@@ -180,3 +181,14 @@ describe('Cost Calculation Edge Cases', () => {
     expect(Math.abs(combined - prefixOnly) / prefixOnly).toBeLessThan(0.5);
   });
 });
+
+describe('randomBatchId', () => {
+  it('should fit in a u64 receipt seed', () => {
+    expect(randomBatchId().toArrayLike(Buffer, 'le', 8)).toHaveLength(8);
+  });
+
+  it('should not repeat across back-to-back calls', () => {
+    const ids = new Set(Array.from({ length: 100 }, () => randomBatchId().toString()));
+    expect(ids.size).toBe(100);
+  });
+});
@@ -7,8 +7,8 @@
  * UPDATED: Continuous charging mode - charges per address attempt, not per batch
  */
 
-import { useState, useCallback, useRef, useMemo } from 'react';
-import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { useState, useCallback, useRef, useMemo, useEffect } from 'react';
+import { Connection, Keypair, PublicKey, SystemProgram, Transaction } from '@solana/web3.js';
 import { useWallet, useConnection } from '@solana/wallet-adapter-react';
 import { gorbaganaRPC } from '../utils/gorbaganaRPC';
 import { parseTransactionError } from '../utils/errorMessages';
@@ -18,8 +18,8 @@ import IDL from '../idl/vanity_miner.json';
 // Vanity Miner program deployed on Gorbagana
 export const VANITY_PROGRAM_ID = new PublicKey('5YSYX6GX3wD2xTp6poLuP92FT8uiWeRFLwASsULXXYM4');
 
-// Treasury wallet receives mining fees
-const TREASURY_WALLET = new PublicKey('TMABDMgLHfmmRNyHgbHTP9P5XP1zrAMFfbRAef69o9f');
+// Mining accounts of this size predate per-user vaults and need migrate_vault
+const LEGACY_MINING_ACCOUNT_LEN = 8 + 54;
 
 // GOR has 9 decimals (like SOL)
 const GOR_DECIMALS = 9;
@@ -28,6 +28,17 @@ const LAMPORTS_PER_GOR = Math.pow(10, GOR_DECIMALS);
 // Base cost: 0.00001 GOR per address attempt (continuous mode)
 const COST_PER_ATTEMPT_LAMPORTS = 10; // ~0.00001 GOR in lamports
 
+// Moved to the browser-held session key to pay its transaction fees and
+// batch receipt rent; whatever is left is swept back when the session ends
+const SESSION_KEY_FUNDING_LAMPORTS = 0.05 * LAMPORTS_PER_GOR;
+
+// Base fee per signature, kept back when sweeping the session key
+const SIGNATURE_FEE_LAMPORTS = 5000;
+
+// The session key is persisted per wallet so a reload or crash cannot strand
+// the GOR funded to it; on-chain it can only charge within its spend cap
+const SESSION_KEY_STORAGE_PREFIX = 'vanity-session-key:';
+
 export interface MiningAccount {
   balance: number;        // GOR (human-readable)
   balanceLamports: number; // Raw lamports
@@ -99,6 +110,74 @@ export function calculateBatchCostLamports(prefixLen: number, suffixLen: number)
   return costPerAttempt * 5000; // 5000 attempts per batch for display
 }
 
+/**
+ * A random u64 batch id for charge_for_batch receipts. Random rather than
+ * time-based so batches charged in the same millisecond, or from another
+ * tab, never share a receipt.
+ */
+export function randomBatchId(): BN {
+  return new BN(crypto.getRandomValues(new Uint8Array(8)), 'le');
+}
+
+function loadSessionKey(wallet: string): Keypair | null {
+  try {
+    const stored = localStorage.getItem(SESSION_KEY_STORAGE_PREFIX + wallet);
+    return stored ? Keypair.fromSecretKey(Uint8Array.from(JSON.parse(stored))) : null;
+  } catch {
+    return null;
+  }
+}
+
+function storeSessionKey(wallet: string, sessionKey: Keypair | null) {
+  if (sessionKey) {
+    localStorage.setItem(SESSION_KEY_STORAGE_PREFIX + wallet, JSON.stringify(Array.from(sessionKey.secretKey)));
+  } else {
+    localStorage.removeItem(SESSION_KEY_STORAGE_PREFIX + wallet);
+  }
+}
+
+/**
+ * Send a session key's lamports, less its own transaction fee, back to
+ * `owner`. The key signs for itself, so the wallet is not prompted.
+ */
+async function sweepSessionKey(connection: Connection, sessionKey: Keypair, owner: PublicKey) {
+  const leftover = (await connection.getBalance(sessionKey.publicKey)) - SIGNATURE_FEE_LAMPORTS;
+  if (leftover <= 0) return;
+
+  const sweep = new Transaction().add(
+    SystemProgram.transfer({
+      fromPubkey: sessionKey.publicKey,
+      toPubkey: owner,
+      lamports: leftover,
+    }),
+  );
+  sweep.feePayer = sessionKey.publicKey;
+  sweep.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+  sweep.sign(sessionKey);
+  await gorbaganaRPC.confirmTransaction(await connection.sendRawTransaction(sweep.serialize()), 60000);
+}
+
+/**
+ * Derive the MatchRecord PDA for a user's match number `index`.
+ */
+export function getMatchRecordPDA(user: PublicKey, index: number): PublicKey {
+  const indexBytes = Buffer.alloc(4);
+  indexBytes.writeUInt32LE(index);
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("match"), user.toBuffer(), indexBytes],
+    VANITY_PROGRAM_ID
+  )[0];
+}
+
+/**
+ * Derive a page of a user's MatchRecord PDAs, `count` records from `start`.
+ * Records run from 0 up to the mining account's matchesFound; closed
+ * records simply no longer exist at their address.
+ */
+export function getMatchRecordPDAs(user: PublicKey, start: number, count: number): PublicKey[] {
+  return Array.from({ length: count }, (_, i) => getMatchRecordPDA(user, start + i));
+}
+
 export function useVanityPayment() {
   const { connected, publicKey, signTransaction, wallet } = useWallet();
   const { connection } = useConnection();
@@ -116,6 +195,11 @@ export function useVanityPayment() {
   const isMiningRef = useRef(false);
   const txInFlightRef = useRef(false);
 
+  // charge_for_batch is signed by the platform authority or the user's
+  // session key, never the wallet, so charges from the browser go through
+  // a session key held here for the length of one mining session
+  const sessionKeyRef = useRef<Keypair | null>(null);
+
   const provider = useMemo(() => {
     if (!connection || !publicKey || !signTransaction || !wallet) return null;
     return new AnchorProvider(connection, wallet.adapter as any, { preflightCommitment: 'confirmed' });
@@ -177,21 +261,36 @@ export function useVanityPayment() {
         [Buffer.from("mining"), publicKey.toBuffer()],
         program.programId
       );
+      const [vaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), publicKey.toBuffer()],
+        program.programId
+      );
 
-      try {
-        await program.methods.initializeUser()
+      const existing = await connection.getAccountInfo(miningAccountPDA);
+      if (!existing) {
+        await program.methods.initializeUser(null)
           .accounts({
             user: publicKey,
             miningAccount: miningAccountPDA,
+            vault: vaultPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+      } else if (existing.data.length === LEGACY_MINING_ACCOUNT_LEN) {
+        // Created before per-user vaults: move the balance out of the shared vault
+        const [legacyVaultPDA] = PublicKey.findProgramAddressSync(
+          [Buffer.from("vault")],
+          program.programId
+        );
+        await program.methods.migrateVault()
+          .accounts({
+            user: publicKey,
+            miningAccount: miningAccountPDA,
+            legacyVault: legacyVaultPDA,
+            vault: vaultPDA,
             systemProgram: SystemProgram.programId,
           })
           .rpc();
-      } catch (initErr: any) {
-        // Ignore if account already exists
-        if (!initErr.message.includes("already in use") && !JSON.stringify(initErr).includes("0x0")) {
-          console.error("Failed to initialize mining account on-chain:", initErr);
-          throw initErr;
-        }
       }
 
       await refreshBalance();
@@ -202,74 +301,238 @@ export function useVanityPayment() {
     } finally {
       setIsInitializing(false);
     }
-  }, [connected, publicKey, program, provider, refreshBalance]);
+  }, [connected, publicKey, program, provider, connection, refreshBalance]);
 
   /**
-   * Charge for a single address attempt (continuous mode).
-   * Returns true if payment succeeded, false if balance insufficient.
+   * Start an on-chain mining session charged through a fresh session key.
+   * One wallet transaction funds the key, registers it with a spend cap of
+   * `maxSpendLamports` until `durationSecs` from now, and starts a mining
+   * session with the same budget and length.
    */
-  const chargeForAttempt = useCallback(async (costLamports: number): Promise<boolean> => {
+  const startChargingSession = useCallback(async (maxSpendLamports: number, durationSecs: number): Promise<boolean> => {
     if (!connected || !publicKey || !program || !provider) {
-      setError('Program or mining account not ready');
+      setError('Wallet not connected or program not ready');
+      return false;
+    }
+
+    try {
+      const [miningAccountPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mining"), publicKey.toBuffer()],
+        program.programId
+      );
+      const [statsPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stats")],
+        program.programId
+      );
+
+      // set_session_key below replaces any earlier key on-chain; take back
+      // what an earlier key left behind first
+      const staleKey = loadSessionKey(publicKey.toBase58());
+      if (staleKey) await sweepSessionKey(connection, staleKey, publicKey);
+
+      const sessionKey = Keypair.generate();
+      const expiryTs = Math.floor(Date.now() / 1000) + durationSecs;
+      // Stored before it is funded, so the funds are never held by a key
+      // that exists only in memory
+      storeSessionKey(publicKey.toBase58(), sessionKey);
+
+      const tx = new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: publicKey,
+          toPubkey: sessionKey.publicKey,
+          lamports: SESSION_KEY_FUNDING_LAMPORTS,
+        }),
+        await program.methods.setSessionKey(sessionKey.publicKey, new BN(maxSpendLamports), new BN(expiryTs))
+          .accounts({
+            user: publicKey,
+            miningAccount: miningAccountPDA,
+          })
+          .instruction(),
+        await program.methods.startSession(new BN(durationSecs), new BN(maxSpendLamports))
+          .accounts({
+            user: publicKey,
+            miningAccount: miningAccountPDA,
+            stats: statsPDA,
+          })
+          .instruction(),
+      );
+      await provider.sendAndConfirm(tx);
+
+      sessionKeyRef.current = sessionKey;
+      return true;
+    } catch (err: any) {
+      console.error('Failed to start charging session:', err);
+      setError(`Failed to start mining session: ${parseTransactionError(err)}`);
+      return false;
+    }
+  }, [connected, publicKey, program, provider, connection]);
+
+  /**
+   * Charge for a batch of `attempts` address attempts. The program prices
+   * the batch from its pricing config; the session key signs and pays the
+   * fee, so the wallet is not prompted. Returns true if payment succeeded,
+   * false if the balance or session budget is exhausted.
+   */
+  const chargeForBatch = useCallback(async (attempts: number): Promise<boolean> => {
+    const sessionKey = sessionKeyRef.current;
+    if (!publicKey || !program || !sessionKey) {
+      setError('Mining session not started');
       return false;
     }
 
     try {
+      const [configPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("config")],
+        program.programId
+      );
+      const [pricingPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("pricing")],
+        program.programId
+      );
+      const [statsPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stats")],
+        program.programId
+      );
       const [miningAccountPDA] = PublicKey.findProgramAddressSync(
         [Buffer.from("mining"), publicKey.toBuffer()],
         program.programId
       );
       const [vaultPDA] = PublicKey.findProgramAddressSync(
-        [Buffer.from("vault")],
+        [Buffer.from("vault"), publicKey.toBuffer()],
+        program.programId
+      );
+
+      // One id per call: a charge whose confirmation is lost cannot land twice
+      const batchId = randomBatchId();
+      const [receiptPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("batch"), publicKey.toBuffer(), batchId.toArrayLike(Buffer, 'le', 8)],
         program.programId
       );
 
-      const signature = await program.methods.chargeForBatch(new BN(costLamports))
+      const config = await program.account.config.fetch(configPDA);
+      const account = await program.account.miningAccount.fetch(miningAccountPDA);
+      const referrer = (account.referrer as PublicKey).equals(PublicKey.default)
+        ? null
+        : (account.referrer as PublicKey);
+
+      const tx = await program.methods.chargeForBatch(new BN(attempts), false, batchId)
         .accounts({
+          authority: sessionKey.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
           user: publicKey,
           miningAccount: miningAccountPDA,
           vault: vaultPDA,
-          treasury: TREASURY_WALLET,
+          treasury: config.treasury as PublicKey,
+          referrer,
+          splConfig: null,
+          sgorMint: null,
+          splVault: null,
+          treasuryTokenAccount: null,
+          referrerTokenAccount: null,
+          tokenProgram: null,
+          receipt: receiptPDA,
+          stats: statsPDA,
           systemProgram: SystemProgram.programId,
         })
-        .rpc();
+        .transaction();
+      tx.feePayer = sessionKey.publicKey;
+      tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+      tx.sign(sessionKey);
+      const signature = await connection.sendRawTransaction(tx.serialize());
 
       // Wait for confirmation with increased timeout
       await gorbaganaRPC.confirmTransaction(signature, 60000);
 
-      // Update local state
-      const costGOR = costLamports / LAMPORTS_PER_GOR;
-      sessionSpentRef.current += costGOR;
-
-      setMiningAccount(prev => {
-        if (!prev) return prev;
-        return {
-          ...prev,
-          balance: prev.balance - costGOR,
-          balanceLamports: prev.balanceLamports - costLamports,
-          totalSpent: prev.totalSpent + costGOR,
-        };
-      });
+      // The cost is priced on-chain, so read it back rather than estimate it
+      const after = await program.account.miningAccount.fetch(miningAccountPDA);
+      const costLamports = (after.totalSpent as BN).sub(account.totalSpent as BN).toNumber();
+      sessionSpentRef.current += costLamports / LAMPORTS_PER_GOR;
+      await refreshBalance();
 
       return true;
     } catch (err: any) {
-      console.error('Attempt payment failed via smart contract:', err);
-      // Check if it's an insufficient balance error
-      if (err.message.includes('InsufficientBalance') || err.message.includes('insufficient')) {
-        return false; // Signal that balance is insufficient
+      console.error('Batch payment failed via smart contract:', err);
+      // Out of balance, or the session's cap or budget is used up
+      if (/InsufficientBalance|SessionSpendExceeded|SessionBudgetExceeded|SessionExpired|MiningSessionExpired|insufficient/.test(err.message ?? '')) {
+        return false;
       }
       setError(`Payment failed: ${parseTransactionError(err)}`);
       return false;
     }
-  }, [connected, publicKey, program, provider]);
+  }, [publicKey, program, connection, refreshBalance]);
 
   /**
-   * Charge for a batch of attempts (for display/estimation purposes).
-   * Used for initial validation before starting mining.
+   * End the mining session: stop it and revoke the session key in one
+   * wallet transaction, then sweep the key's leftover lamports back.
+   * Either instruction is skipped if already done, so this also cleans up
+   * after a session that ran out or a key restored from an earlier visit.
    */
-  const chargeForBatch = useCallback(async (costLamports: number): Promise<boolean> => {
-    return chargeForAttempt(costLamports);
-  }, [chargeForAttempt]);
+  const endChargingSession = useCallback(async (): Promise<boolean> => {
+    const sessionKey = sessionKeyRef.current;
+    if (!publicKey || !program || !provider || !sessionKey) return false;
+
+    try {
+      const [miningAccountPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mining"), publicKey.toBuffer()],
+        program.programId
+      );
+      const [statsPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stats")],
+        program.programId
+      );
+
+      const account = await program.account.miningAccount.fetch(miningAccountPDA);
+      const tx = new Transaction();
+      if (account.isActive) {
+        tx.add(
+          await program.methods.stopSession()
+            .accounts({
+              user: publicKey,
+              miningAccount: miningAccountPDA,
+              stats: statsPDA,
+            })
+            .instruction(),
+        );
+      }
+      if ((account.sessionKey as PublicKey).equals(sessionKey.publicKey)) {
+        tx.add(
+          await program.methods.revokeSessionKey()
+            .accounts({
+              user: publicKey,
+              miningAccount: miningAccountPDA,
+            })
+            .instruction(),
+        );
+      }
+      if (tx.instructions.length > 0) await provider.sendAndConfirm(tx);
+      sessionKeyRef.current = null;
+
+      await sweepSessionKey(connection, sessionKey, publicKey);
+      storeSessionKey(publicKey.toBase58(), null);
+
+      await refreshBalance();
+      return true;
+    } catch (err: any) {
+      console.error('Failed to end charging session:', err);
+      setError(`Failed to end mining session: ${parseTransactionError(err)}`);
+      return false;
+    }
+  }, [publicKey, program, provider, connection, refreshBalance]);
+
+  // Pick up a session key persisted by an earlier visit, so it can keep
+  // charging or be revoked and swept like one started here
+  useEffect(() => {
+    sessionKeyRef.current = walletAddress ? loadSessionKey(walletAddress) : null;
+  }, [walletAddress]);
+
+  // Revoke the session key and reclaim its funds when the hook unmounts.
+  // Kept in a ref so the cleanup sees the current wallet and program.
+  const endChargingSessionRef = useRef(endChargingSession);
+  useEffect(() => { endChargingSessionRef.current = endChargingSession; }, [endChargingSession]);
+  useEffect(() => () => {
+    if (sessionKeyRef.current) void endChargingSessionRef.current();
+  }, []);
 
   /**
    * Deposit GOR into the mining account.
@@ -297,7 +560,7 @@ export function useVanityPayment() {
         program.programId
       );
       const [vaultPDA] = PublicKey.findProgramAddressSync(
-        [Buffer.from("vault")],
+        [Buffer.from("vault"), publicKey.toBuffer()],
         program.programId
       );
 
@@ -354,13 +617,14 @@ export function useVanityPayment() {
         program.programId
       );
       const [vaultPDA] = PublicKey.findProgramAddressSync(
-        [Buffer.from("vault")],
+        [Buffer.from("vault"), publicKey.toBuffer()],
         program.programId
       );
 
       await program.methods.withdraw()
         .accounts({
           user: publicKey,
+          destination: null,
           miningAccount: miningAccountPDA,
           vault: vaultPDA,
           systemProgram: SystemProgram.programId,
@@ -439,8 +703,9 @@ export function useVanityPayment() {
     isWithdrawing,
     error,
     initializeMining,
+    startChargingSession,
     chargeForBatch,
-    chargeForAttempt,
+    endChargingSession,
     refreshBalance,
     recordMatch,
     setMiningActive,
@@ -21,7 +21,7 @@ import {
 } from 'lucide-react';
 import { useNetwork, GORBAGANA_CONFIG } from '../contexts/NetworkContext';
 import { useWallet } from '@solana/wallet-adapter-react';
-import { useBridgeService, BridgeOrder } from '../services/bridgeService';
+import { useBridgeService, BridgeOrder, isOrderOpen } from '../services/bridgeService';
 import { useAnchor } from '../contexts/AnchorContext';
 import { PublicKey } from '@solana/web3.js';
 import { gorbaganaRPC, formatGOR } from '../utils/gorbaganaRPC';
@@ -41,8 +41,8 @@ interface Donor {
 const Bridge: React.FC = () => {
   useEffect(() => audioManager.playOnInteraction('page_bridge'), []);
   const { connected } = useWallet();
-  const { fetchAllOrders, createOrderGGOR, createOrderSGOR, fillOrder, cancelOrder } = useBridgeService();
-  const { program } = useAnchor();
+  const { fetchAllOrders, createOrderGGOR, createOrderSGOR, fillOrder, lockSolanaOrder, cancelOrder } = useBridgeService();
+  const { program, solanaProgram } = useAnchor();
   const { rpcEndpoint: currentRpcEndpoint, isDevnet, currentNetwork } = useNetwork();
 
   // --- FUNDRAISER STATE ---
@@ -223,7 +223,9 @@ const Bridge: React.FC = () => {
         alert('Program not initialized. Please connect your wallet.');
         return;
       }
-      const currentSlot = await program.provider.connection.getSlot();
+      // sGOR orders on devnet live on Solana, so expire by Solana slots
+      const orderProgram = isDevnet && createDirection === 'sGOR' && solanaProgram ? solanaProgram : program;
+      const currentSlot = await orderProgram.provider.connection.getSlot();
       const expirationSlot = currentSlot + 216000; // ~24 hours
 
       if (createDirection === 'gGOR') {
@@ -255,10 +257,12 @@ const Bridge: React.FC = () => {
 
     try {
       // Pre-check: re-fetch the order to verify it's still available
-      if (program) {
+      const isSolanaOrder = selectedOrder.network === 'SOLANA';
+      const orderProgram = isSolanaOrder ? solanaProgram : program;
+      if (orderProgram) {
         try {
-          const currentOrder = await program.account.order.fetch(selectedOrder.orderPDA);
-          if (currentOrder.isFilled) {
+          const currentOrder = await orderProgram.account.order.fetch(selectedOrder.orderPDA);
+          if (!isOrderOpen(currentOrder.status)) {
             // Order already filled - remove from UI immediately and notify user
             setOrders(prev => prev.filter(o => !o.orderPDA.equals(selectedOrder.orderPDA)));
             setIsTradeModalOpen(false);
@@ -279,14 +283,25 @@ const Bridge: React.FC = () => {
         }
       }
 
-      await fillOrder(selectedOrder.orderPDA);
+      // Solana orders release the escrow only on a relayer's attestation of
+      // the Gorbagana payment, so the taker locks the order and a relayer
+      // settles it once the gGOR lands
+      if (isSolanaOrder) {
+        await lockSolanaOrder(selectedOrder.orderPDA);
+      } else {
+        await fillOrder(selectedOrder.orderPDA);
+      }
 
       // Optimistic UI update: immediately remove the filled order from state
       const filledOrderKey = selectedOrder.orderPDA.toBase58();
       setOrders(prev => prev.filter(o => o.orderPDA.toBase58() !== filledOrderKey));
       setIsTradeModalOpen(false);
       setSelectedOrder(null);
-      alert('Order filled successfully!');
+      alert(
+        isSolanaOrder
+          ? 'Order locked! Send the gGOR to the maker on Gorbagana; a relayer releases your sGOR once it lands.'
+          : 'Order filled successfully!'
+      );
 
       // Also do a background refresh to sync with chain state
       setTimeout(async () => {
@@ -57,7 +57,6 @@ const VanityGenerator: React.FC = () => {
     isWithdrawing,
     error: paymentError,
     initializeMining,
-    chargeForAttempt,
     refreshBalance,
     recordMatch: recordMatchPayment,
     setMiningActive,
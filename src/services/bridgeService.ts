@@ -4,6 +4,7 @@ import {
   getAssociatedTokenAddress,
   TOKEN_PROGRAM_ID,
   createAssociatedTokenAccountInstruction,
+  createAssociatedTokenAccountIdempotentInstruction,
 } from '@solana/spl-token';
 import { useCallback } from 'react';
 import { useAnchor } from '../contexts/AnchorContext';
@@ -13,8 +14,7 @@ import { useNetwork } from '../contexts/NetworkContext';
 const GORBAGANA_PROGRAM_ID = new PublicKey('FreEcfZtek5atZJCJ1ER8kGLXB1C17WKWXqsVcsn1kPq');
 const SGOR_MINT_MAINNET = new PublicKey('71Jvq4Epe2FCJ7JFSF7jLXdNk1Wy4Bhqd9iL6bEFELvg');
 
-// Solana Devnet Bridge Program
-const SOLANA_DEVNET_PROGRAM_ID = new PublicKey('66xqiDYSQZh7A3wyS3n2962Fx1aU8N3nbHjaZUCrXq6M');
+// Solana Devnet Bridge (program ID comes from src/idl/solana_bridge.json)
 const SGOR_MINT_DEVNET = new PublicKey('5b2P7TQTDQG4nUzrUUSAuv92NT85Ka4oBFXWcTs9A5zk');
 
 export interface BridgeOrder {
@@ -29,16 +29,38 @@ export interface BridgeOrder {
   network?: 'GORBAGANA' | 'SOLANA';
 }
 
+/**
+ * A registered relayer's attestation that the taker paid the maker on
+ * Gorbagana. Solana `fill_order` only releases the escrow with the relayer
+ * co-signing.
+ */
+export interface RelayerAttestation {
+  relayer: PublicKey;
+  /** Signature of the taker's Gorbagana payment (64 bytes) */
+  gorbaganaTxSig: Uint8Array;
+  /** Adds the relayer's signature to the taker-signed fill transaction */
+  cosign: (tx: Transaction) => Promise<Transaction>;
+}
+
+/** Random u64 order id; Solana order PDAs are keyed by maker + order id. */
+const randomOrderId = (): BN => new BN(crypto.getRandomValues(new Uint8Array(8)), 'le');
+
+// Derive a Solana bridge PDA
+const deriveSolanaPDA = (programId: PublicKey, ...seeds: Buffer[]): PublicKey =>
+  PublicKey.findProgramAddressSync(seeds, programId)[0];
+
+/** Order status is an Anchor enum, decoded as `{ open: {} }`, `{ filled: {} }`, ... */
+export const isOrderOpen = (status: Record<string, unknown>): boolean => 'open' in status;
+
 export const useBridgeService = () => {
   const { program, gorbaganaProvider: provider, solanaProgram } = useAnchor();
   const { isDevnet, currentNetwork } = useNetwork();
 
-  // Select the correct program, program ID, and mint based on network
+  // Select the correct program and mint based on network
   const activeProgram = isDevnet ? solanaProgram : program;
-  const PROGRAM_ID = isDevnet ? SOLANA_DEVNET_PROGRAM_ID : GORBAGANA_PROGRAM_ID;
   const SGOR_MINT = isDevnet ? SGOR_MINT_DEVNET : SGOR_MINT_MAINNET;
 
-  // Derive Order PDA
+  // Derive Gorbagana Order PDA (seeded by the creation amount, `Order.seedAmount`)
   const deriveOrderPDA = (maker: PublicKey, amount: BN): PublicKey => {
     const [pda] = PublicKey.findProgramAddressSync(
       [
@@ -46,12 +68,12 @@ export const useBridgeService = () => {
         maker.toBuffer(),
         amount.toArrayLike(Buffer, 'le', 8),
       ],
-      PROGRAM_ID
+      GORBAGANA_PROGRAM_ID
     );
     return pda;
   };
 
-  // Derive Escrow token account PDA (for direction 0 sGOR escrow)
+  // Derive Gorbagana Escrow token account PDA (for direction 0 sGOR escrow)
   const deriveEscrowPDA = (maker: PublicKey, amount: BN): PublicKey => {
     const [pda] = PublicKey.findProgramAddressSync(
       [
@@ -59,11 +81,17 @@ export const useBridgeService = () => {
         maker.toBuffer(),
         amount.toArrayLike(Buffer, 'le', 8),
       ],
-      PROGRAM_ID
+      GORBAGANA_PROGRAM_ID
     );
     return pda;
   };
 
+  // Gorbagana bridge config PDA (read on create/fill/cancel)
+  const [gorbaganaConfigPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from('config')],
+    GORBAGANA_PROGRAM_ID
+  );
+
   // Helper to check if an account exists on-chain
   const accountExists = async (pubkey: PublicKey, connection: any): Promise<boolean> => {
     const info = await connection.getAccountInfo(pubkey);
@@ -80,13 +108,22 @@ export const useBridgeService = () => {
 
     // Direction 1: gGOR escrow — no SPL token accounts needed (pass null for Optional accounts)
     const tx = await program.methods
-      .createOrder(amountBN, 1, new BN(expirationSlot))
+      .createOrder({
+        amount: amountBN,
+        direction: 1,
+        expirationSlot: new BN(expirationSlot),
+        operator: null,
+        memo: null,
+        activationSlot: null,
+        htlc: null,
+      })
       .accounts({
         maker: wallet.publicKey,
         order: orderPDA,
         escrowTokenAccount: null,
         makerTokenAccount: null,
-        sgorMint: null,
+        sgorMint: SGOR_MINT_MAINNET,
+        config: gorbaganaConfigPDA,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
@@ -119,37 +156,72 @@ export const useBridgeService = () => {
 
     const wallet = currentProvider.wallet;
     const amountBN = new BN(amount);
-    const orderPDA = deriveOrderPDA(wallet.publicKey, amountBN);
-    const escrowPDA = deriveEscrowPDA(wallet.publicKey, amountBN);
     const makerATA = await getAssociatedTokenAddress(SGOR_MINT, wallet.publicKey);
 
+    // Solana orders are keyed by order id, Gorbagana orders by amount
+    const orderId = randomOrderId();
+    const programId = currentProgram.programId;
+    const orderPDA = isDevnet
+      ? deriveSolanaPDA(programId, Buffer.from('order'), wallet.publicKey.toBuffer(), orderId.toArrayLike(Buffer, 'le', 8))
+      : deriveOrderPDA(wallet.publicKey, amountBN);
+    const escrowPDA = isDevnet
+      ? deriveSolanaPDA(programId, Buffer.from('escrow'), wallet.publicKey.toBuffer(), orderId.toArrayLike(Buffer, 'le', 8), SGOR_MINT.toBuffer())
+      : deriveEscrowPDA(wallet.publicKey, amountBN);
+
     // Gorbagana recipient defaults to maker's wallet if not specified
     const gorRecipient = gorbaganaRecipient || wallet.publicKey;
 
     const tx = isDevnet
-      // Solana devnet bridge has different args: (amount, expiration_slot, gorbagana_recipient)
+      // Solana devnet bridge: CreateOrderParams (public, fixed-price order at par)
       ? await currentProgram.methods
-          .createOrder(amountBN, new BN(expirationSlot), gorRecipient)
+          .createOrder({
+            orderId,
+            amount: amountBN,
+            expectedGgorAmount: amountBN,
+            expiry: { slot: { slot: new BN(expirationSlot) } },
+            gorbaganaRecipient: gorRecipient,
+            allowedTaker: null,
+            endExpectedGgorAmount: null,
+            clientOrderId: new BN(0),
+            hashlock: null,
+            activationSlot: null,
+            operator: null,
+            payoutSplits: [],
+          })
           .accounts({
             maker: wallet.publicKey,
             order: orderPDA,
+            makerRegistry: deriveSolanaPDA(programId, Buffer.from('maker_registry'), wallet.publicKey.toBuffer()),
+            stats: deriveSolanaPDA(programId, Buffer.from('stats')),
             escrowTokenAccount: escrowPDA,
             makerTokenAccount: makerATA,
             sgorMint: SGOR_MINT,
+            mintAllow: deriveSolanaPDA(programId, Buffer.from('mint_allow'), SGOR_MINT.toBuffer()),
+            config: deriveSolanaPDA(programId, Buffer.from('config')),
+            makerDeny: deriveSolanaPDA(programId, Buffer.from('deny'), wallet.publicKey.toBuffer()),
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             rent: SYSVAR_RENT_PUBKEY,
           })
           .transaction()
-      // Gorbagana bridge: (amount, direction, expiration_slot)
+      // Gorbagana bridge: CreateOrderArgs
       : await currentProgram.methods
-          .createOrder(amountBN, 0, new BN(expirationSlot))
+          .createOrder({
+            amount: amountBN,
+            direction: 0,
+            expirationSlot: new BN(expirationSlot),
+            operator: null,
+            memo: null,
+            activationSlot: null,
+            htlc: null,
+          })
           .accounts({
             maker: wallet.publicKey,
             order: orderPDA,
             escrowTokenAccount: escrowPDA,
             makerTokenAccount: makerATA,
             sgorMint: SGOR_MINT,
+            config: gorbaganaConfigPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             rent: SYSVAR_RENT_PUBKEY,
@@ -180,8 +252,8 @@ export const useBridgeService = () => {
     // Fetch order to get direction and maker
     const order = await program.account.order.fetch(orderPDA);
 
-    // Check if order is already filled
-    if (order.isFilled) {
+    // Check if order is still open
+    if (!isOrderOpen(order.status)) {
       throw new Error('Order has already been filled.');
     }
 
@@ -196,7 +268,7 @@ export const useBridgeService = () => {
     if (order.direction === 0) {
       // Direction 0: Maker sold sGOR, wants gGOR
       // Taker sends gGOR (native) → Maker, receives sGOR (SPL) from escrow
-      const escrowPDA = deriveEscrowPDA(order.maker, order.amount);
+      const escrowPDA = deriveEscrowPDA(order.maker, order.seedAmount);
 
       // Verify escrow exists and has funds
       const escrowExists = await accountExists(escrowPDA, provider.connection);
@@ -205,14 +277,14 @@ export const useBridgeService = () => {
       }
 
       // Ensure taker's receive ATA exists (where taker will receive sGOR)
-      const takerReceiveATA = await getAssociatedTokenAddress(SGOR_MINT, wallet.publicKey);
+      const takerReceiveATA = await getAssociatedTokenAddress(SGOR_MINT_MAINNET, wallet.publicKey);
       if (!(await accountExists(takerReceiveATA, provider.connection))) {
         tx.add(
           createAssociatedTokenAccountInstruction(
             wallet.publicKey,
             takerReceiveATA,
             wallet.publicKey,
-            SGOR_MINT
+            SGOR_MINT_MAINNET
           )
         );
       }
@@ -233,6 +305,8 @@ export const useBridgeService = () => {
           takerTokenAccount: null,
           takerReceiveTokenAccount: takerReceiveATA,
           makerReceiveTokenAccount: null,
+          sgorMint: SGOR_MINT_MAINNET,
+          config: gorbaganaConfigPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -243,7 +317,7 @@ export const useBridgeService = () => {
     } else {
       // Direction 1: Maker sold gGOR (native in PDA), wants sGOR
       // Taker sends sGOR (SPL) → Maker, receives gGOR (native) from PDA
-      const takerATA = await getAssociatedTokenAddress(SGOR_MINT, wallet.publicKey);
+      const takerATA = await getAssociatedTokenAddress(SGOR_MINT_MAINNET, wallet.publicKey);
       const takerATAExists = await accountExists(takerATA, provider.connection);
 
       if (!takerATAExists) {
@@ -259,14 +333,14 @@ export const useBridgeService = () => {
       }
 
       // Ensure maker's receive ATA exists (where maker will receive sGOR)
-      const makerATA = await getAssociatedTokenAddress(SGOR_MINT, order.maker);
+      const makerATA = await getAssociatedTokenAddress(SGOR_MINT_MAINNET, order.maker);
       if (!(await accountExists(makerATA, provider.connection))) {
         tx.add(
           createAssociatedTokenAccountInstruction(
             wallet.publicKey,
             makerATA,
             order.maker,
-            SGOR_MINT
+            SGOR_MINT_MAINNET
           )
         );
       }
@@ -281,6 +355,8 @@ export const useBridgeService = () => {
           takerTokenAccount: takerATA,
           takerReceiveTokenAccount: null,
           makerReceiveTokenAccount: makerATA,
+          sgorMint: SGOR_MINT_MAINNET,
+          config: gorbaganaConfigPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -327,7 +403,146 @@ export const useBridgeService = () => {
     return txid;
   };
 
-  // Cancel Order — returns escrowed funds to maker
+  // Sends a Solana bridge transaction signed by the connected wallet (and,
+  // for fills, co-signed by the attesting relayer)
+  const sendSolanaTransaction = async (
+    tx: Transaction,
+    cosign?: (tx: Transaction) => Promise<Transaction>
+  ): Promise<string> => {
+    const solanaProvider = solanaProgram!.provider as any;
+    const wallet = solanaProvider.wallet;
+
+    const latestBlockhash = await solanaProvider.connection.getLatestBlockhash('confirmed');
+    tx.recentBlockhash = latestBlockhash.blockhash;
+    tx.feePayer = wallet.publicKey;
+
+    let signedTx = await wallet.signTransaction(tx);
+    if (cosign) signedTx = await cosign(signedTx);
+    const txid = await solanaProvider.connection.sendRawTransaction(signedTx.serialize(), {
+      skipPreflight: false,
+      preflightCommitment: 'confirmed',
+      maxRetries: 3,
+    });
+
+    const { confirmTransaction } = await import('../utils/confirmTx');
+    await confirmTransaction(solanaProvider.connection, txid);
+
+    return txid;
+  };
+
+  // Fill Solana Order — taker receives the escrowed sGOR once a relayer
+  // attests the taker's gGOR payment to the maker on Gorbagana
+  const fillSolanaOrder = async (
+    orderPDA: PublicKey,
+    attestation: RelayerAttestation,
+    takerGorbaganaAddress?: PublicKey
+  ) => {
+    if (!solanaProgram) throw new Error('Wallet not connected');
+    const connection = solanaProgram.provider.connection;
+    const taker: PublicKey = (solanaProgram.provider as any).wallet.publicKey;
+    const programId = solanaProgram.programId;
+
+    const order = await solanaProgram.account.order.fetch(orderPDA);
+    if (!isOrderOpen(order.status)) {
+      throw new Error('Order is no longer open.');
+    }
+    const feeConfigPDA = deriveSolanaPDA(programId, Buffer.from('fee_config'));
+    const feeConfig = await solanaProgram.account.feeConfig.fetch(feeConfigPDA);
+
+    const tx = new Transaction();
+
+    // Token accounts of the order's mint the fill pays into; missing ones are
+    // created by the taker
+    const ensureATA = async (owner: PublicKey): Promise<PublicKey> => {
+      const ata = await getAssociatedTokenAddress(order.mint, owner, true);
+      if (!(await accountExists(ata, connection))) {
+        // Idempotent: a split recipient may share an account with the taker
+        tx.add(createAssociatedTokenAccountIdempotentInstruction(taker, ata, owner, order.mint));
+      }
+      return ata;
+    };
+    const takerATA = await ensureATA(taker);
+    const feeATA = await ensureATA(feeConfig.feeRecipient);
+
+    // Payout legs: one token account per maker-defined split, in order
+    // (a default recipient means the filling taker)
+    const payoutAccounts: PublicKey[] = [];
+    for (const split of order.payoutSplits.slice(0, order.payoutSplitCount)) {
+      payoutAccounts.push(
+        split.recipient.equals(PublicKey.default) ? takerATA : await ensureATA(split.recipient)
+      );
+    }
+
+    const sig = Buffer.from(attestation.gorbaganaTxSig);
+    const fillInstruction = await solanaProgram.methods
+      .fillOrder(
+        Array.from(sig),
+        takerGorbaganaAddress || taker,
+        // The quote the taker paid against, so a recreated order cannot take the fill
+        order.amount,
+        order.gorbaganaRecipient,
+        new BN(0)
+      )
+      .accounts({
+        taker,
+        relayer: attestation.relayer,
+        relayerEntry: deriveSolanaPDA(programId, Buffer.from('relayer'), attestation.relayer.toBuffer()),
+        config: deriveSolanaPDA(programId, Buffer.from('config')),
+        takerDeny: deriveSolanaPDA(programId, Buffer.from('deny'), taker.toBuffer()),
+        makerDeny: deriveSolanaPDA(programId, Buffer.from('deny'), order.maker.toBuffer()),
+        maker: order.maker,
+        order: orderPDA,
+        makerRegistry: deriveSolanaPDA(programId, Buffer.from('maker_registry'), order.maker.toBuffer()),
+        stats: deriveSolanaPDA(programId, Buffer.from('stats')),
+        escrowTokenAccount: order.escrowTokenAccount,
+        takerTokenAccount: takerATA,
+        feeConfig: feeConfigPDA,
+        feeTokenAccount: feeATA,
+        receipt: deriveSolanaPDA(programId, Buffer.from('receipt'), sig.subarray(0, 32), sig.subarray(32)),
+        orderReceipt: null,
+        sgorMint: order.mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        payoutAccounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+      )
+      .instruction();
+    tx.add(fillInstruction);
+
+    return sendSolanaTransaction(tx, attestation.cosign);
+  };
+
+  // Lock Solana Order — reserves the order for the taker (posting the lock
+  // bond) while they pay the maker on Gorbagana; a relayer then settles it
+  const lockSolanaOrder = async (orderPDA: PublicKey, takerGorbaganaAddress?: PublicKey) => {
+    if (!solanaProgram) throw new Error('Wallet not connected');
+    const taker: PublicKey = (solanaProgram.provider as any).wallet.publicKey;
+    const programId = solanaProgram.programId;
+
+    const order = await solanaProgram.account.order.fetch(orderPDA);
+    if (!isOrderOpen(order.status)) {
+      throw new Error('Order is no longer open.');
+    }
+    const configPDA = deriveSolanaPDA(programId, Buffer.from('config'));
+    const config = await solanaProgram.account.bridgeConfig.fetch(configPDA);
+
+    const tx = await solanaProgram.methods
+      .lockOrder(config.maxLockSlots, takerGorbaganaAddress || taker)
+      .accounts({
+        taker,
+        order: orderPDA,
+        config: configPDA,
+        takerDeny: deriveSolanaPDA(programId, Buffer.from('deny'), taker.toBuffer()),
+        makerDeny: deriveSolanaPDA(programId, Buffer.from('deny'), order.maker.toBuffer()),
+        systemProgram: SystemProgram.programId,
+      })
+      .transaction();
+
+    return sendSolanaTransaction(tx);
+  };
+
+  // Cancel Order — returns escrowed funds to maker (signed by the maker or its operator)
   const cancelOrder = async (orderPDA: PublicKey) => {
     if (!program || !provider) throw new Error('Wallet not connected');
     const wallet = provider.wallet;
@@ -336,8 +551,8 @@ export const useBridgeService = () => {
 
     if (order.direction === 0) {
       // Direction 0: Return sGOR (SPL) from escrow to maker
-      const escrowPDA = deriveEscrowPDA(wallet.publicKey, order.amount);
-      const makerATA = await getAssociatedTokenAddress(SGOR_MINT, wallet.publicKey);
+      const escrowPDA = deriveEscrowPDA(order.maker, order.seedAmount);
+      const makerATA = await getAssociatedTokenAddress(SGOR_MINT_MAINNET, order.maker);
 
       // Ensure maker ATA exists (should, since they deposited from it)
       if (!(await accountExists(makerATA, provider.connection))) {
@@ -345,8 +560,8 @@ export const useBridgeService = () => {
           createAssociatedTokenAccountInstruction(
             wallet.publicKey,
             makerATA,
-            wallet.publicKey,
-            SGOR_MINT
+            order.maker,
+            SGOR_MINT_MAINNET
           )
         );
       }
@@ -354,10 +569,13 @@ export const useBridgeService = () => {
       const cancelInstruction = await program.methods
         .cancelOrder()
         .accounts({
-          maker: wallet.publicKey,
+          authority: wallet.publicKey,
+          maker: order.maker,
           order: orderPDA,
           escrowTokenAccount: escrowPDA,
           makerTokenAccount: makerATA,
+          sgorMint: SGOR_MINT_MAINNET,
+          config: gorbaganaConfigPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -369,10 +587,13 @@ export const useBridgeService = () => {
       const cancelInstruction = await program.methods
         .cancelOrder()
         .accounts({
-          maker: wallet.publicKey,
+          authority: wallet.publicKey,
+          maker: order.maker,
           order: orderPDA,
           escrowTokenAccount: null,
           makerTokenAccount: null,
+          sgorMint: SGOR_MINT_MAINNET,
+          config: gorbaganaConfigPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -412,7 +633,7 @@ export const useBridgeService = () => {
             amount: acc.account.amount,
             direction: acc.account.direction || 0,
             expirationSlot: acc.account.expirationSlot,
-            isFilled: acc.account.isFilled,
+            isFilled: !isOrderOpen(acc.account.status),
             bump: acc.account.bump,
             network: 'GORBAGANA' as const,
           }))
@@ -431,9 +652,11 @@ export const useBridgeService = () => {
             orderPDA: acc.publicKey,
             maker: acc.account.maker,
             amount: acc.account.amount,
-            direction: acc.account.direction || 0,
-            expirationSlot: acc.account.expirationSlot,
-            isFilled: acc.account.isFilled,
+            // Solana orders always escrow sGOR
+            direction: 0,
+            // Timestamp-expiry orders carry no slot
+            expirationSlot: 'slot' in acc.account.expiry ? acc.account.expiry.slot.slot : new BN(0),
+            isFilled: !isOrderOpen(acc.account.status),
             bump: acc.account.bump,
             network: 'SOLANA' as const,
           }))
@@ -450,6 +673,8 @@ export const useBridgeService = () => {
     createOrderGGOR,
     createOrderSGOR,
     fillOrder,
+    fillSolanaOrder,
+    lockSolanaOrder,
     cancelOrder,
     fetchAllOrders,
     deriveOrderPDA,
@@ -0,0 +1,69 @@
+// Anchor migration/deploy script
+// Creates the miner config on first deploy, keeping the treasury that was
+// previously hardcoded in the program. Rotate it later with update_treasury.
+// Also tops up the old shared vault so it stays rent-exempt while users
+// move their balances out with migrate_vault.
+const anchor = require("@coral-xyz/anchor");
+
+const TREASURY = new anchor.web3.PublicKey("TMABDMgLHfmmRNyHgbHTP9P5XP1zrAMFfbRAef69o9f");
+
+// Base rate of 10 lamports per attempt, as quoted by the app
+const COST_PER_MILLION_ATTEMPTS = 10_000_000;
+
+// 8-byte discriminator + MiningAccount before per-user vaults
+const LEGACY_MINING_ACCOUNT_LEN = 8 + 54;
+
+module.exports = async function (provider: any) {
+  anchor.setProvider(provider);
+  const program = anchor.workspace.VanityMiner;
+  const [config] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+
+  if ((await provider.connection.getAccountInfo(config)) === null) {
+    await program.methods
+      .initializeConfig(provider.wallet.publicKey, TREASURY)
+      .accounts({ payer: provider.wallet.publicKey, config })
+      .rpc();
+    console.log("Initialized config:", config.toBase58());
+  }
+
+  const [pricing] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("pricing")],
+    program.programId
+  );
+  if ((await provider.connection.getAccountInfo(pricing)) === null) {
+    await program.methods
+      .updatePricing(new anchor.BN(COST_PER_MILLION_ATTEMPTS), new anchor.BN(0), 0)
+      .accounts({ authority: provider.wallet.publicKey, config, pricing })
+      .rpc();
+    console.log("Initialized pricing:", pricing.toBase58());
+  }
+  const [legacyVault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("vault")],
+    program.programId
+  );
+  const legacyAccounts = await provider.connection.getProgramAccounts(program.programId, {
+    filters: [{ dataSize: LEGACY_MINING_ACCOUNT_LEN }],
+  });
+  let owed = new anchor.BN(await provider.connection.getMinimumBalanceForRentExemption(0));
+  for (const { account } of legacyAccounts) {
+    owed = owed.add(new anchor.BN(account.data.subarray(8 + 32, 8 + 40), "le")); // balance
+  }
+  const held = new anchor.BN(await provider.connection.getBalance(legacyVault));
+  if (legacyAccounts.length > 0 && held.lt(owed)) {
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: legacyVault,
+          lamports: owed.sub(held).toNumber(),
+        })
+      )
+    );
+    console.log("Topped up shared vault by", owed.sub(held).toString(), "lamports");
+  }
+
+  console.log("Deployed to:", provider.connection.rpcEndpoint);
+};
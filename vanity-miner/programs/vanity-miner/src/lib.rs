@@ -4,19 +4,427 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{
+    self, CloseAccount as CloseTokenAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+// Imported rather than written as a path in `InitializeConfig`: the IDL
+// build resolves `Program<'info, T>` by `T`'s last segment alone
+use crate::program::VanityMiner;
 
 declare_id!("5YSYX6GX3wD2xTp6poLuP92FT8uiWeRFLwASsULXXYM4");
 
-// Platform treasury wallet
-const TREASURY: &str = "TMABDMgLHfmmRNyHgbHTP9P5XP1zrAMFfbRAef69o9f";
+// Hard cap on a single charge (1 GOR), whatever the pricing config says
+pub const MAX_BATCH_COST: u64 = 1_000_000_000;
+
+// Most volume discount tiers the pricing config holds
+pub const MAX_DISCOUNT_TIERS: usize = 4;
+
+// Most subscription tiers the pricing config holds
+pub const MAX_SUBSCRIPTION_TIERS: usize = 4;
+
+// Longest prefix or suffix a job may ask for
+pub const MAX_PATTERN_LEN: usize = 8;
+
+// Miners kept on the leaderboard
+pub const LEADERBOARD_SIZE: usize = 25;
+
+// Most members a team account holds, founder included
+pub const MAX_TEAM_MEMBERS: usize = 8;
+
+// Inactivity before `sweep_abandoned` may close an account, until the
+// authority changes it (2 years)
+pub const DEFAULT_SWEEP_AFTER: i64 = 2 * 365 * 24 * 60 * 60;
+
+// Shortest inactivity period the authority can set (1 year)
+pub const MIN_SWEEP_AFTER: i64 = 365 * 24 * 60 * 60;
+
+// How long a batch receipt must be kept before the user can close it (30 days)
+pub const BATCH_RECEIPT_RETENTION: i64 = 30 * 24 * 60 * 60;
+
+// Bitcoin base58 alphabet, as used for Solana addresses
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Longest base58 encoding of a 32-byte key
+const MAX_BASE58_LEN: usize = 44;
 
 #[program]
 pub mod vanity_miner {
     use super::*;
 
+    /// Create the global config PDA (["config"]) naming the platform
+    /// authority that charges mining batches and the treasury they pay.
+    /// Signed by the program's upgrade authority. Deposits start out
+    /// unlimited; see `update_deposit_limits`.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        authority: Pubkey,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.authority = authority;
+        config.treasury = treasury;
+        config.bump = ctx.bumps.config;
+        config.min_deposit = 0;
+        config.max_deposit_per_tx = 0;
+        config.paused = false;
+        config.max_charge_per_call = MAX_BATCH_COST;
+        config.sweep_after_secs = DEFAULT_SWEEP_AFTER;
+
+        emit!(ConfigInitializedEvent {
+            payer: ctx.accounts.payer.key(),
+            authority,
+            treasury,
+        });
+
+        Ok(())
+    }
+
+    /// Bound the size of a single GOR deposit. A `max_deposit_per_tx` of
+    /// zero means no cap. Signed by the authority.
+    pub fn update_deposit_limits(
+        ctx: Context<UpdateConfig>,
+        min_deposit: u64,
+        max_deposit_per_tx: u64,
+    ) -> Result<()> {
+        require!(
+            max_deposit_per_tx == 0 || min_deposit <= max_deposit_per_tx,
+            ErrorCode::InvalidDepositLimits
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_min_deposit = config.min_deposit;
+        let old_max_deposit_per_tx = config.max_deposit_per_tx;
+        config.min_deposit = min_deposit;
+        config.max_deposit_per_tx = max_deposit_per_tx;
+
+        emit!(DepositLimitsUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            old_min_deposit,
+            old_max_deposit_per_tx,
+            min_deposit,
+            max_deposit_per_tx,
+        });
+
+        Ok(())
+    }
+
+    /// Set how long a mining account must go unused before
+    /// `sweep_abandoned` may close it. Never below `MIN_SWEEP_AFTER`, so a
+    /// leaked authority key cannot sweep accounts that are merely quiet.
+    /// Signed by the authority.
+    pub fn update_sweep_period(ctx: Context<UpdateConfig>, sweep_after_secs: i64) -> Result<()> {
+        require!(
+            sweep_after_secs >= MIN_SWEEP_AFTER,
+            ErrorCode::SweepPeriodTooShort
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_sweep_after_secs = config.sweep_after_secs;
+        config.sweep_after_secs = sweep_after_secs;
+
+        emit!(SweepPeriodUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            old_sweep_after_secs,
+            sweep_after_secs,
+        });
+
+        Ok(())
+    }
+
+    /// Cap what a single `charge_for_batch` may take, below the hard
+    /// `MAX_BATCH_COST`, to bound the damage of a leaked authority key or a
+    /// pricing typo. Signed by the authority.
+    pub fn update_max_charge(ctx: Context<UpdateConfig>, max_charge_per_call: u64) -> Result<()> {
+        require!(
+            max_charge_per_call > 0 && max_charge_per_call <= MAX_BATCH_COST,
+            ErrorCode::InvalidChargeCap
+        );
+
+        let config = &mut ctx.accounts.config;
+        let old_max_charge_per_call = config.max_charge_per_call;
+        config.max_charge_per_call = max_charge_per_call;
+
+        emit!(MaxChargeUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            old_max_charge_per_call,
+            max_charge_per_call,
+        });
+
+        Ok(())
+    }
+
+    /// Stop deposits, charges and match recording, e.g. while a leaked
+    /// authority key or bad pricing config is dealt with. Withdrawals keep
+    /// working so user funds are never trapped. Signed by the authority.
+    pub fn pause(ctx: Context<UpdateConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, ErrorCode::ProgramPaused);
+        config.paused = true;
+
+        emit!(ProgramPausedEvent {
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Lift a `pause`. Signed by the authority.
+    pub fn unpause(ctx: Context<UpdateConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(config.paused, ErrorCode::ProgramNotPaused);
+        config.paused = false;
+
+        emit!(ProgramUnpausedEvent {
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Point batch charges at a new treasury wallet. Signed by the authority.
+    /// The first treasury is named by the upgrade authority in
+    /// `initialize_config`, so no other caller can route charges elsewhere.
+    pub fn update_treasury(ctx: Context<UpdateConfig>, new_treasury: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_treasury = config.treasury;
+        config.treasury = new_treasury;
+
+        emit!(TreasuryUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            old_treasury,
+            new_treasury,
+        });
+
+        Ok(())
+    }
+
+    /// Set batch pricing, creating the pricing PDA (["pricing"]) on first
+    /// use. `referral_bps` is the share of each charge paid to the user's
+    /// referrer, if any. Signed by the authority.
+    pub fn update_pricing(
+        ctx: Context<UpdatePricing>,
+        cost_per_million_attempts: u64,
+        flat_fee: u64,
+        referral_bps: u16,
+    ) -> Result<()> {
+        require!(referral_bps <= 10_000, ErrorCode::InvalidReferralBps);
+
+        // All zero when the pricing PDA is first created
+        let pricing = &mut ctx.accounts.pricing;
+        let old_cost_per_million_attempts = pricing.cost_per_million_attempts;
+        let old_flat_fee = pricing.flat_fee;
+        let old_referral_bps = pricing.referral_bps;
+        pricing.cost_per_million_attempts = cost_per_million_attempts;
+        pricing.flat_fee = flat_fee;
+        pricing.referral_bps = referral_bps;
+        pricing.bump = ctx.bumps.pricing;
+
+        emit!(PricingUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            old_cost_per_million_attempts,
+            old_flat_fee,
+            old_referral_bps,
+            cost_per_million_attempts,
+            flat_fee,
+            referral_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Replace the volume discount tiers. Thresholds must strictly increase
+    /// and discounts must not decrease; an empty list removes discounts.
+    /// Signed by the authority.
+    pub fn update_discount_tiers(
+        ctx: Context<UpdateDiscountTiers>,
+        tiers: Vec<DiscountTier>,
+    ) -> Result<()> {
+        require!(
+            tiers.len() <= MAX_DISCOUNT_TIERS,
+            ErrorCode::TooManyDiscountTiers
+        );
+        for (i, tier) in tiers.iter().enumerate() {
+            require!(tier.discount_bps <= 10_000, ErrorCode::InvalidDiscountTiers);
+            if i > 0 {
+                let prev = &tiers[i - 1];
+                require!(
+                    tier.threshold > prev.threshold && tier.discount_bps >= prev.discount_bps,
+                    ErrorCode::InvalidDiscountTiers
+                );
+            }
+        }
+
+        let pricing = &mut ctx.accounts.pricing;
+        let old_tiers = pricing.tiers[..pricing.tier_count as usize].to_vec();
+        pricing.tier_count = tiers.len() as u8;
+        pricing.tiers = [DiscountTier::default(); MAX_DISCOUNT_TIERS];
+        pricing.tiers[..tiers.len()].copy_from_slice(&tiers);
+
+        emit!(DiscountTiersUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            old_tiers,
+            tiers,
+        });
+
+        Ok(())
+    }
+
+    /// Replace the subscription tiers offered by `purchase_subscription`.
+    /// Every tier needs a price and a duration; an empty list stops sales.
+    /// Running subscriptions keep the expiry they were bought with.
+    /// Signed by the authority.
+    pub fn update_subscription_tiers(
+        ctx: Context<UpdateSubscriptionTiers>,
+        tiers: Vec<SubscriptionTier>,
+    ) -> Result<()> {
+        require!(
+            tiers.len() <= MAX_SUBSCRIPTION_TIERS,
+            ErrorCode::TooManySubscriptionTiers
+        );
+        for tier in tiers.iter() {
+            require!(
+                tier.price > 0 && tier.duration_secs > 0,
+                ErrorCode::InvalidSubscriptionTiers
+            );
+        }
+
+        let pricing = &mut ctx.accounts.pricing;
+        let old_tiers =
+            pricing.subscription_tiers[..pricing.subscription_tier_count as usize].to_vec();
+        pricing.subscription_tier_count = tiers.len() as u8;
+        pricing.subscription_tiers = [SubscriptionTier::default(); MAX_SUBSCRIPTION_TIERS];
+        pricing.subscription_tiers[..tiers.len()].copy_from_slice(&tiers);
+
+        emit!(SubscriptionTiersUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            old_tiers,
+            tiers,
+        });
+
+        Ok(())
+    }
+
+    /// Set the accepted sGOR mint and its exchange rate, creating the SPL
+    /// config PDA (["spl_config"]) on first use. The mint is fixed once set,
+    /// since user token vaults hold it. Signed by the authority.
+    pub fn update_spl_config(ctx: Context<UpdateSplConfig>, spl_per_gor: u64) -> Result<()> {
+        require!(spl_per_gor > 0, ErrorCode::InvalidAmount);
+
+        let spl_config = &mut ctx.accounts.spl_config;
+        let sgor_mint = ctx.accounts.sgor_mint.key();
+        require!(
+            spl_config.sgor_mint == Pubkey::default() || spl_config.sgor_mint == sgor_mint,
+            ErrorCode::SgorMintLocked
+        );
+        // Zero when the SPL config PDA is first created
+        let old_spl_per_gor = spl_config.spl_per_gor;
+        spl_config.sgor_mint = sgor_mint;
+        spl_config.spl_per_gor = spl_per_gor;
+        spl_config.bump = ctx.bumps.spl_config;
+
+        emit!(SplConfigUpdatedEvent {
+            authority: ctx.accounts.authority.key(),
+            sgor_mint,
+            old_spl_per_gor,
+            spl_per_gor,
+        });
+
+        Ok(())
+    }
+
+    /// Create the global stats PDA (["stats"]). Signed by the authority.
+    /// Must run before any user instruction: `initialize_user`, deposits,
+    /// withdrawals and charges update the stats account and fail until it
+    /// exists, so users cannot sign up before this.
+    pub fn initialize_stats(ctx: Context<InitializeStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.stats;
+        stats.total_users = 0;
+        stats.active_miners = 0;
+        stats.total_deposited = 0;
+        stats.total_withdrawn = 0;
+        stats.total_spent = 0;
+        stats.total_matches = 0;
+        stats.bump = ctx.bumps.stats;
+        Ok(())
+    }
+
+    /// Create the top-miners leaderboard PDA (["leaderboard"]). Signed by
+    /// the authority. Matches from before it exists are picked up by
+    /// `rebuild_leaderboard`.
+    pub fn initialize_leaderboard(ctx: Context<InitializeLeaderboard>) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.count = 0;
+        leaderboard.entries = [LeaderboardEntry::default(); LEADERBOARD_SIZE];
+        leaderboard.bump = ctx.bumps.leaderboard;
+        Ok(())
+    }
+
+    /// Resync the leaderboard from the mining accounts passed in
+    /// remaining_accounts. Signed by the authority. With `reset`, the board
+    /// is cleared first; without it, the accounts are merged into the
+    /// current ranking, so a full resync can span several transactions.
+    pub fn rebuild_leaderboard(ctx: Context<RebuildLeaderboard>, reset: bool) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        if reset {
+            leaderboard.count = 0;
+            leaderboard.entries = [LeaderboardEntry::default(); LEADERBOARD_SIZE];
+        }
+
+        for info in ctx.remaining_accounts.iter() {
+            require_keys_eq!(
+                *info.owner,
+                crate::ID,
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+            );
+            let account = MiningAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            leaderboard.update(account.owner, account.matches_found, account.total_spent);
+        }
+
+        emit!(LeaderboardRebuiltEvent {
+            authority: ctx.accounts.authority.key(),
+            reset,
+            accounts: ctx.remaining_accounts.len() as u32,
+            count: leaderboard.count,
+        });
+
+        Ok(())
+    }
+
+    /// Hand the platform authority to a new key. Signed by the current one.
+    pub fn set_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_authority = config.authority;
+        config.authority = new_authority;
+
+        emit!(AuthorityUpdatedEvent {
+            old_authority,
+            new_authority,
+        });
+
+        Ok(())
+    }
+
     /// Initialize a new mining account for the user.
-    /// Creates a PDA seeded with ["mining", user_pubkey].
-    pub fn initialize_user(ctx: Context<InitializeUser>) -> Result<()> {
+    /// Creates a PDA seeded with ["mining", user_pubkey] and funds the rent
+    /// reserve of the user's vault PDA ["vault", user_pubkey].
+    /// `referrer` earns the configured referral share of every charge.
+    pub fn initialize_user(ctx: Context<InitializeUser>, referrer: Option<Pubkey>) -> Result<()> {
+        let referrer = referrer.unwrap_or_default();
+        require!(
+            referrer != ctx.accounts.user.key(),
+            ErrorCode::SelfReferral
+        );
+
+        fund_vault_reserve(
+            &ctx.accounts.user,
+            &ctx.accounts.vault,
+            &ctx.accounts.system_program,
+        )?;
+        let timestamp = Clock::get()?.unix_timestamp;
+
         let mining_account = &mut ctx.accounts.mining_account;
         mining_account.owner = ctx.accounts.user.key();
         mining_account.balance = 0;
@@ -24,6 +432,265 @@ pub mod vanity_miner {
         mining_account.matches_found = 0;
         mining_account.is_active = false;
         mining_account.bump = ctx.bumps.mining_account;
+        mining_account.vault_bump = ctx.bumps.vault;
+        mining_account.referrer = referrer;
+        mining_account.spl_balance = 0;
+        mining_account.clear_session();
+        mining_account.mining_deadline = 0;
+        mining_account.mining_budget = 0;
+        mining_account.mining_spent = 0;
+        mining_account.subscription_expiry_ts = 0;
+        mining_account.subscription_tier = 0;
+        mining_account.last_activity_ts = timestamp;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_users = stats
+            .total_users
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(UserInitialized {
+            user: ctx.accounts.user.key(),
+            mining_account: mining_account.key(),
+            timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Let `key` charge batches and record matches for this account until
+    /// `expiry_ts`, spending at most `max_spend` GOR lamports in total, so
+    /// a remote miner need not hold the owner's key. Replaces any previous
+    /// session key. The session key can never withdraw.
+    pub fn set_session_key(
+        ctx: Context<ManageSessionKey>,
+        key: Pubkey,
+        max_spend: u64,
+        expiry_ts: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            key != Pubkey::default() && key != ctx.accounts.user.key(),
+            ErrorCode::InvalidSessionKey
+        );
+        require!(expiry_ts > now, ErrorCode::InvalidSessionKey);
+
+        let mining_account = &mut ctx.accounts.mining_account;
+        mining_account.session_key = key;
+        mining_account.session_max_spend = max_spend;
+        mining_account.session_spent = 0;
+        mining_account.session_expiry = expiry_ts;
+        mining_account.last_activity_ts = now;
+
+        emit!(SessionKeySetEvent {
+            user: ctx.accounts.user.key(),
+            key,
+            max_spend,
+            expiry_ts,
+        });
+
+        Ok(())
+    }
+
+    /// Remove the session key.
+    pub fn revoke_session_key(ctx: Context<ManageSessionKey>) -> Result<()> {
+        let mining_account = &mut ctx.accounts.mining_account;
+        let key = mining_account.session_key;
+        require!(key != Pubkey::default(), ErrorCode::NoSessionKey);
+        mining_account.clear_session();
+        mining_account.last_activity_ts = Clock::get()?.unix_timestamp;
+
+        emit!(SessionKeyRevokedEvent {
+            user: ctx.accounts.user.key(),
+            key,
+        });
+
+        Ok(())
+    }
+
+    /// Start a mining session: batches can be charged for the next
+    /// `max_duration_secs` seconds, up to `max_spend` GOR lamports in all,
+    /// so a session the user forgets to stop cannot bill them indefinitely.
+    /// A session that ran out can be replaced without stopping it first.
+    pub fn start_session(
+        ctx: Context<SetMiningActive>,
+        max_duration_secs: i64,
+        max_spend: u64,
+    ) -> Result<()> {
+        require!(
+            max_duration_secs > 0 && max_spend > 0,
+            ErrorCode::InvalidAmount
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let deadline = now
+            .checked_add(max_duration_secs)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let mining_account = &mut ctx.accounts.mining_account;
+        let was_active = mining_account.is_active;
+        require!(
+            !was_active || now >= mining_account.mining_deadline,
+            ErrorCode::AlreadyMining
+        );
+        mining_account.is_active = true;
+        mining_account.mining_deadline = deadline;
+        mining_account.mining_budget = max_spend;
+        mining_account.mining_spent = 0;
+        mining_account.last_activity_ts = now;
+
+        if !was_active {
+            let stats = &mut ctx.accounts.stats;
+            stats.active_miners = stats
+                .active_miners
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        emit!(MiningStarted {
+            user: ctx.accounts.user.key(),
+            deadline,
+            budget: max_spend,
+        });
+
+        Ok(())
+    }
+
+    /// End the mining session early, or clear one that ran out.
+    pub fn stop_session(ctx: Context<SetMiningActive>) -> Result<()> {
+        let mining_account = &mut ctx.accounts.mining_account;
+        require!(mining_account.is_active, ErrorCode::NotMining);
+        mining_account.is_active = false;
+        let spent = mining_account.mining_spent;
+        let budget = mining_account.mining_budget;
+        mining_account.mining_deadline = 0;
+        mining_account.mining_budget = 0;
+        mining_account.mining_spent = 0;
+        mining_account.last_activity_ts = Clock::get()?.unix_timestamp;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.active_miners = stats
+            .active_miners
+            .checked_sub(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(MiningStopped {
+            user: ctx.accounts.user.key(),
+            spent,
+            budget,
+        });
+
+        Ok(())
+    }
+
+    /// Move a user's balance out of the old shared ["vault"] PDA into their
+    /// own vault, and grow the mining account to the current layout.
+    /// Required once for accounts created before per-user vaults. The shared
+    /// vault must hold its own rent reserve (see migrations/deploy.ts) so
+    /// that it stays rent-exempt until the last user has moved out.
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let info = ctx.accounts.mining_account.to_account_info();
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() == 8 + LegacyMiningAccount::SIZE,
+                ErrorCode::AlreadyMigrated
+            );
+            require!(
+                data[..8] == MiningAccount::DISCRIMINATOR,
+                ErrorCode::Unauthorized
+            );
+            LegacyMiningAccount::deserialize(&mut &data[8..])
+                .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))?
+        };
+        require!(
+            legacy.owner == ctx.accounts.user.key(),
+            ErrorCode::Unauthorized
+        );
+
+        fund_vault_reserve(
+            &ctx.accounts.user,
+            &ctx.accounts.vault,
+            &ctx.accounts.system_program,
+        )?;
+
+        // The shared vault must still cover this user's share
+        require_solvent(&ctx.accounts.legacy_vault, legacy.balance)?;
+
+        // Transfer the user's share using the shared vault PDA as signer
+        let legacy_vault_bump = ctx.bumps.legacy_vault;
+        let legacy_vault_seeds: &[&[u8]] = &[b"vault", &[legacy_vault_bump]];
+
+        if legacy.balance > 0 {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.legacy_vault.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[legacy_vault_seeds],
+                ),
+                legacy.balance,
+            )?;
+        }
+        require_solvent(&ctx.accounts.vault, legacy.balance)?;
+
+        // Grow the account; the user covers the extra rent
+        let new_len = 8 + MiningAccount::SIZE;
+        let top_up = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(info.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.user.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        info.realloc(new_len, false)?;
+
+        let migrated = MiningAccount {
+            owner: legacy.owner,
+            balance: legacy.balance,
+            total_spent: legacy.total_spent,
+            matches_found: legacy.matches_found,
+            // The old layout has no deadline or budget, so a session it
+            // had running cannot carry over; the user starts a new one
+            is_active: false,
+            bump: legacy.bump,
+            vault_bump: ctx.bumps.vault,
+            referrer: Pubkey::default(),
+            spl_balance: 0,
+            session_key: Pubkey::default(),
+            session_max_spend: 0,
+            session_spent: 0,
+            session_expiry: 0,
+            mining_deadline: 0,
+            mining_budget: 0,
+            mining_spent: 0,
+            subscription_expiry_ts: 0,
+            subscription_tier: 0,
+            last_activity_ts: Clock::get()?.unix_timestamp,
+        };
+        migrated.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        // Accounts from before the stats PDA are counted as they migrate
+        let stats = &mut ctx.accounts.stats;
+        stats.total_users = stats
+            .total_users
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(VaultMigratedEvent {
+            user: ctx.accounts.user.key(),
+            amount: legacy.balance,
+        });
+
         Ok(())
     }
 
@@ -31,6 +698,12 @@ pub mod vanity_miner {
     /// Transfers native GOR from user to the program vault PDA.
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         require!(amount > 0, ErrorCode::InvalidAmount);
+        let config = &ctx.accounts.config;
+        require!(amount >= config.min_deposit, ErrorCode::DepositTooSmall);
+        require!(
+            config.max_deposit_per_tx == 0 || amount <= config.max_deposit_per_tx,
+            ErrorCode::DepositTooLarge
+        );
 
         // Transfer native GOR (lamports) from user to vault
         system_program::transfer(
@@ -49,6 +722,14 @@ pub mod vanity_miner {
             .balance
             .checked_add(amount)
             .ok_or(ErrorCode::Overflow)?;
+        mining_account.last_activity_ts = Clock::get()?.unix_timestamp;
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_deposited = stats
+            .total_deposited
+            .checked_add(amount as u128)
+            .ok_or(ErrorCode::Overflow)?;
 
         emit!(DepositEvent {
             user: ctx.accounts.user.key(),
@@ -59,29 +740,88 @@ pub mod vanity_miner {
         Ok(())
     }
 
-    /// Charge for a mining batch.
-    /// Deducts `cost` from user's mining balance and transfers from vault to treasury.
-    pub fn charge_for_batch(ctx: Context<ChargeForBatch>, cost: u64) -> Result<()> {
+    /// Deposit sGOR into the mining account's separate SPL balance.
+    /// Transfers from the user's token account into their token vault PDA
+    /// ["spl_vault", user_pubkey], created on first use.
+    pub fn deposit_spl(ctx: Context<DepositSpl>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.sgor_mint.to_account_info(),
+                    to: ctx.accounts.spl_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.sgor_mint.decimals,
+        )?;
+
         let mining_account = &mut ctx.accounts.mining_account;
+        mining_account.spl_balance = mining_account
+            .spl_balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        mining_account.last_activity_ts = Clock::get()?.unix_timestamp;
+
+        emit!(SplDepositEvent {
+            user: ctx.accounts.user.key(),
+            amount,
+            new_balance: mining_account.spl_balance,
+        });
+
+        Ok(())
+    }
 
+    /// Buy subscription `tier` from the pricing config, paying its flat price
+    /// from the GOR balance to the treasury. Until the subscription expires,
+    /// `charge_for_batch` bills nothing; a mining session is still needed.
+    /// Buying while a subscription is running extends it from its current
+    /// expiry, and the account takes the tier of the latest purchase.
+    /// The price counts towards `total_spent` but earns no referral share.
+    pub fn purchase_subscription(ctx: Context<PurchaseSubscription>, tier: u8) -> Result<()> {
+        let pricing = &ctx.accounts.pricing;
         require!(
-            mining_account.balance >= cost,
-            ErrorCode::InsufficientBalance
+            tier < pricing.subscription_tier_count,
+            ErrorCode::InvalidSubscriptionTier
         );
+        let SubscriptionTier {
+            price,
+            duration_secs,
+        } = pricing.subscription_tiers[tier as usize];
 
+        let mining_account = &mut ctx.accounts.mining_account;
+        require!(
+            mining_account.balance >= price,
+            ErrorCode::InsufficientBalance
+        );
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
         mining_account.balance = mining_account
             .balance
-            .checked_sub(cost)
+            .checked_sub(price)
             .ok_or(ErrorCode::Overflow)?;
         mining_account.total_spent = mining_account
             .total_spent
-            .checked_add(cost)
+            .checked_add(price)
             .ok_or(ErrorCode::Overflow)?;
 
-        // Transfer from vault to treasury using vault PDA as signer
-        let vault_bump = ctx.bumps.vault;
-        let vault_seeds: &[&[u8]] = &[b"vault", &[vault_bump]];
+        // Extend a running subscription rather than overlap it
+        let now = Clock::get()?.unix_timestamp;
+        let expiry_ts = mining_account
+            .subscription_expiry_ts
+            .max(now)
+            .checked_add(duration_secs)
+            .ok_or(ErrorCode::Overflow)?;
+        mining_account.subscription_expiry_ts = expiry_ts;
+        mining_account.subscription_tier = tier;
+        mining_account.last_activity_ts = now;
 
+        // Transfer from vault to treasury using vault PDA as signer
+        let user_key = ctx.accounts.user.key();
+        let vault_seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[mining_account.vault_bump]];
         system_program::transfer(
             CpiContext::new_with_signer(
                 ctx.accounts.system_program.to_account_info(),
@@ -91,223 +831,3663 @@ pub mod vanity_miner {
                 },
                 &[vault_seeds],
             ),
-            cost,
+            price,
         )?;
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
 
-        emit!(BatchChargedEvent {
-            user: ctx.accounts.user.key(),
-            cost,
+        let stats = &mut ctx.accounts.stats;
+        stats.total_spent = stats
+            .total_spent
+            .checked_add(price as u128)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(SubscriptionPurchased {
+            user: user_key,
+            tier,
+            price,
+            expiry_ts,
             remaining_balance: mining_account.balance,
         });
 
         Ok(())
     }
 
-    /// Withdraw remaining balance back to the user.
-    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+    /// Charge for a mining batch of `attempts` address attempts.
+    /// Prices the batch from the pricing config, deducts it from user's mining
+    /// balance and transfers from vault to treasury, less the referral share
+    /// paid to the user's referrer. A GOR share that would leave an unfunded
+    /// referrer below rent exemption goes to the treasury instead.
+    /// The volume discount is looked up from `total_spent` before this
+    /// charge, so the charge that crosses a tier threshold is still priced
+    /// at the tier below it.
+    /// With `from_spl` the batch is paid from the sGOR balance instead, at the
+    /// SPL config's exchange rate, and the SPL accounts must be passed.
+    /// Signed by the platform authority once the batch is done, or by the
+    /// user's session key within its spend cap; the user does not sign.
+    /// Either way the user must have a mining session running, and the
+    /// charge counts against its budget.
+    /// Each charge writes a receipt PDA ["batch", user, batch_id], paid for
+    /// by the signer, so retrying a charge whose confirmation was lost fails
+    /// with `DuplicateBatch` instead of billing the user twice.
+    /// While the user's subscription runs the batch costs nothing: no
+    /// balance moves, and the event and receipt record a cost of 0.
+    pub fn charge_for_batch(
+        ctx: Context<ChargeForBatch>,
+        attempts: u64,
+        from_spl: bool,
+        batch_id: u64,
+    ) -> Result<()> {
+        require!(attempts > 0, ErrorCode::InvalidAmount);
+        let clock = Clock::get()?;
+        let subscribed = ctx.accounts.mining_account.is_subscribed(clock.unix_timestamp);
+        let pricing = &ctx.accounts.pricing;
+        let gross_cost = pricing.cost_of(attempts)?;
+        let cost = if subscribed {
+            0
+        } else {
+            pricing.discounted(gross_cost, ctx.accounts.mining_account.total_spent)?
+        };
+        require!(cost <= MAX_BATCH_COST, ErrorCode::CostAboveCap);
+        require!(
+            cost <= ctx.accounts.config.max_charge_per_call,
+            ErrorCode::ChargeTooLarge
+        );
+        let referral_bps = pricing.referral_bps;
+
+        // A receipt that already has a user was written by an earlier charge
+        let receipt = &mut ctx.accounts.receipt;
+        require!(receipt.user == Pubkey::default(), ErrorCode::DuplicateBatch);
+        receipt.user = ctx.accounts.user.key();
+        receipt.payer = ctx.accounts.authority.key();
+        receipt.batch_id = batch_id;
+        receipt.cost = cost;
+        receipt.attempts = attempts;
+        receipt.slot = clock.slot;
+        receipt.timestamp = clock.unix_timestamp;
+        receipt.bump = ctx.bumps.receipt;
+
         let mining_account = &mut ctx.accounts.mining_account;
-        let amount = mining_account.balance;
 
-        require!(amount > 0, ErrorCode::NoBalance);
+        let signer = ctx.accounts.authority.key();
+        if signer != ctx.accounts.config.authority {
+            require!(
+                signer == mining_account.session_key && signer != Pubkey::default(),
+                ErrorCode::UnauthorizedAuthority
+            );
+            mining_account.spend_session(cost, clock.unix_timestamp)?;
+            // Only the user's own key counts as activity; an authority
+            // charge must not hold off `sweep_abandoned`
+            mining_account.last_activity_ts = clock.unix_timestamp;
+        }
+        mining_account.spend_mining_budget(cost, clock.unix_timestamp)?;
+
+        // Lifetime spend is kept in GOR, whichever balance pays
+        mining_account.total_spent = mining_account
+            .total_spent
+            .checked_add(cost)
+            .ok_or(ErrorCode::Overflow)?;
 
-        // Transfer from vault back to user using vault PDA as signer
-        let vault_bump = ctx.bumps.vault;
-        let vault_seeds: &[&[u8]] = &[b"vault", &[vault_bump]];
+        let stats = &mut ctx.accounts.stats;
+        stats.total_spent = stats
+            .total_spent
+            .checked_add(cost as u128)
+            .ok_or(ErrorCode::Overflow)?;
 
-        system_program::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.vault.to_account_info(),
-                    to: ctx.accounts.user.to_account_info(),
+        let user_key = ctx.accounts.user.key();
+
+        // Covered by the subscription; the batch is still counted
+        if subscribed {
+            emit!(BatchChargedEvent {
+                user: user_key,
+                batch_id,
+                attempts,
+                gross_cost,
+                cost,
+                from_spl,
+                paid: 0,
+                referral: 0,
+                remaining_balance: if from_spl {
+                    mining_account.spl_balance
+                } else {
+                    mining_account.balance
                 },
-                &[vault_seeds],
-            ),
-            amount,
-        )?;
+            });
 
-        mining_account.balance = 0;
+            return Ok(());
+        }
 
-        emit!(WithdrawEvent {
+        let vault_seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[mining_account.vault_bump]];
+
+        if from_spl {
+            let spl_config = ctx
+                .accounts
+                .spl_config
+                .as_ref()
+                .ok_or(ErrorCode::SplAccountMissing)?;
+            let sgor_mint = ctx
+                .accounts
+                .sgor_mint
+                .as_ref()
+                .ok_or(ErrorCode::SplAccountMissing)?;
+            let spl_vault = ctx
+                .accounts
+                .spl_vault
+                .as_ref()
+                .ok_or(ErrorCode::SplAccountMissing)?;
+            let treasury_token_account = ctx
+                .accounts
+                .treasury_token_account
+                .as_ref()
+                .ok_or(ErrorCode::SplAccountMissing)?;
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(ErrorCode::SplAccountMissing)?;
+            require!(
+                sgor_mint.key() == spl_config.sgor_mint,
+                ErrorCode::InvalidMint
+            );
+            require!(
+                treasury_token_account.owner == ctx.accounts.config.treasury
+                    && treasury_token_account.mint == spl_config.sgor_mint,
+                ErrorCode::InvalidTreasury
+            );
+
+            let amount = spl_config.to_spl(cost)?;
+            require!(
+                mining_account.spl_balance >= amount,
+                ErrorCode::InsufficientBalance
+            );
+            mining_account.spl_balance = mining_account
+                .spl_balance
+                .checked_sub(amount)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // Referral share goes to the referrer's sGOR token account,
+            // which is only needed when there is a share to pay
+            let referral = if mining_account.referrer == Pubkey::default() {
+                0
+            } else {
+                referral_share(amount, referral_bps)?
+            };
+            if referral > 0 {
+                let referrer_token_account = ctx
+                    .accounts
+                    .referrer_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidReferrer)?;
+                require!(
+                    referrer_token_account.owner == mining_account.referrer
+                        && referrer_token_account.mint == spl_config.sgor_mint,
+                    ErrorCode::InvalidReferrer
+                );
+            }
+            let to_treasury = amount.checked_sub(referral).ok_or(ErrorCode::Overflow)?;
+
+            transfer_from_spl_vault(
+                token_program,
+                spl_vault,
+                sgor_mint,
+                treasury_token_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                vault_seeds,
+                to_treasury,
+            )?;
+            if referral > 0 {
+                if let Some(referrer_token_account) = ctx.accounts.referrer_token_account.as_ref() {
+                    transfer_from_spl_vault(
+                        token_program,
+                        spl_vault,
+                        sgor_mint,
+                        referrer_token_account.to_account_info(),
+                        ctx.accounts.vault.to_account_info(),
+                        vault_seeds,
+                        referral,
+                    )?;
+                }
+            }
+
+            emit!(BatchChargedEvent {
+                user: user_key,
+                batch_id,
+                attempts,
+                gross_cost,
+                cost,
+                from_spl,
+                paid: amount,
+                referral,
+                remaining_balance: mining_account.spl_balance,
+            });
+
+            return Ok(());
+        }
+
+        require!(
+            mining_account.balance >= cost,
+            ErrorCode::InsufficientBalance
+        );
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
+        mining_account.balance = mining_account
+            .balance
+            .checked_sub(cost)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // Referral share, when the user has a referrer and one is configured
+        let mut referral = if mining_account.referrer == Pubkey::default() {
+            0
+        } else {
+            referral_share(cost, referral_bps)?
+        };
+        if referral > 0 {
+            let referrer = ctx
+                .accounts
+                .referrer
+                .as_ref()
+                .ok_or(ErrorCode::InvalidReferrer)?;
+            require!(
+                referrer.key() == mining_account.referrer,
+                ErrorCode::InvalidReferrer
+            );
+            // An unfunded referrer can't be credited less than rent
+            // exemption; that cut stays with the treasury
+            if referrer.lamports().saturating_add(referral) < Rent::get()?.minimum_balance(0) {
+                referral = 0;
+            }
+        }
+        let to_treasury = cost.checked_sub(referral).ok_or(ErrorCode::Overflow)?;
+
+        // Transfer from vault to treasury and referrer using vault PDA as signer
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            to_treasury,
+        )?;
+        if referral > 0 {
+            if let Some(referrer) = ctx.accounts.referrer.as_ref() {
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.vault.to_account_info(),
+                            to: referrer.to_account_info(),
+                        },
+                        &[vault_seeds],
+                    ),
+                    referral,
+                )?;
+            }
+        }
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
+
+        emit!(BatchChargedEvent {
+            user: user_key,
+            batch_id,
+            attempts,
+            gross_cost,
+            cost,
+            from_spl,
+            paid: cost,
+            referral,
+            remaining_balance: mining_account.balance,
+        });
+
+        Ok(())
+    }
+
+    /// Pay `amount` back from the treasury into the user's vault for a
+    /// batch that was charged but failed on our side, crediting `balance`
+    /// and taking it off `total_spent`. `reference_id` ties the refund to
+    /// the support case and is only echoed in the event.
+    /// Signed by the authority and co-signed by the treasury wallet: the
+    /// treasury is an ordinary wallet the program cannot debit, and neither
+    /// key alone can both move treasury funds and credit a balance.
+    /// A refund is capped at the user's lifetime spend, so repeated refunds
+    /// can never return more than was charged.
+    pub fn refund_batch(ctx: Context<RefundBatch>, amount: u64, reference_id: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            amount <= ctx.accounts.mining_account.total_spent,
+            ErrorCode::RefundExceedsSpend
+        );
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.treasury.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let mining_account = &mut ctx.accounts.mining_account;
+        mining_account.balance = mining_account
+            .balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        mining_account.total_spent = mining_account
+            .total_spent
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
+
+        // Charges from before the stats PDA existed are not in its total
+        let stats = &mut ctx.accounts.stats;
+        stats.total_spent = stats.total_spent.saturating_sub(amount as u128);
+
+        emit!(BatchRefunded {
             user: ctx.accounts.user.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            reference_id,
+            new_balance: mining_account.balance,
+            total_spent: mining_account.total_spent,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw remaining balance back to the user, or to `destination`
+    /// when given.
+    /// Signed by the owner only; a session key cannot withdraw.
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let destination = match ctx.accounts.destination.as_ref() {
+            Some(destination) => {
+                require!(
+                    destination.key() != ctx.accounts.vault.key(),
+                    ErrorCode::InvalidDestination
+                );
+                destination.to_account_info()
+            }
+            None => ctx.accounts.user.to_account_info(),
+        };
+
+        let mining_account = &mut ctx.accounts.mining_account;
+        let amount = mining_account.balance;
+
+        require!(amount > 0, ErrorCode::NoBalance);
+        require_solvent(&ctx.accounts.vault, amount)?;
+
+        // Transfer from vault to the destination using vault PDA as signer.
+        // The rent reserve stays behind, so the vault never drops below it.
+        let user_key = ctx.accounts.user.key();
+        let vault_seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[mining_account.vault_bump]];
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: destination.clone(),
+                },
+                &[vault_seeds],
+            ),
+            amount,
+        )?;
+
+        mining_account.balance = 0;
+        mining_account.last_activity_ts = Clock::get()?.unix_timestamp;
+        require_solvent(&ctx.accounts.vault, 0)?;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_withdrawn = stats
+            .total_withdrawn
+            .checked_add(amount as u128)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(WithdrawEvent {
+            user: user_key,
+            amount,
+            destination: destination.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw the sGOR balance back to the user's token account and close
+    /// the token vault, returning its rent to the user.
+    pub fn withdraw_spl(ctx: Context<WithdrawSpl>) -> Result<()> {
+        // Anything sent to the vault directly is the user's too
+        let amount = ctx.accounts.spl_vault.amount;
+        require!(amount > 0, ErrorCode::NoBalance);
+
+        let user_key = ctx.accounts.user.key();
+        let vault_seeds: &[&[u8]] = &[
+            b"vault",
+            user_key.as_ref(),
+            &[ctx.accounts.mining_account.vault_bump],
+        ];
+
+        transfer_from_spl_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.spl_vault,
+            &ctx.accounts.sgor_mint,
+            ctx.accounts.user_token_account.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            vault_seeds,
+            amount,
+        )?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseTokenAccount {
+                account: ctx.accounts.spl_vault.to_account_info(),
+                destination: ctx.accounts.user.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[vault_seeds],
+        ))?;
+
+        ctx.accounts.mining_account.spl_balance = 0;
+        ctx.accounts.mining_account.last_activity_ts = Clock::get()?.unix_timestamp;
+
+        emit!(SplWithdrawEvent {
+            user: user_key,
             amount,
         });
 
         Ok(())
     }
 
-    /// Record a vanity address match found by the user.
-    pub fn record_match(ctx: Context<RecordMatch>, address: String) -> Result<()> {
-        let mining_account = &mut ctx.accounts.mining_account;
-        mining_account.matches_found = mining_account
-            .matches_found
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
+    /// Close the mining account once the user is done mining.
+    /// Returns any remaining balance and the vault's rent reserve, and the
+    /// account's rent, to the user. `initialize_user` starts over afresh.
+    /// Match records are kept; a fresh account numbers its matches from 0
+    /// again, so close the old records first to free their indexes.
+    /// Any sGOR balance must be withdrawn with `withdraw_spl` first.
+    pub fn close_account(ctx: Context<CloseAccount>) -> Result<()> {
+        let mining_account = &ctx.accounts.mining_account;
+        require!(
+            mining_account.spl_balance == 0,
+            ErrorCode::SplBalanceNotEmpty
+        );
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
+        let vault_lamports = ctx.accounts.vault.lamports();
+
+        // Empty the vault using vault PDA as signer
+        let user_key = ctx.accounts.user.key();
+        let vault_seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[mining_account.vault_bump]];
+
+        if vault_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.user.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                vault_lamports,
+            )?;
+        }
+
+        // The remaining balance leaves as a withdrawal
+        let stats = &mut ctx.accounts.stats;
+        stats.total_withdrawn = stats
+            .total_withdrawn
+            .checked_add(mining_account.balance as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        stats.total_users = stats
+            .total_users
+            .checked_sub(1)
+            .ok_or(ErrorCode::Overflow)?;
+        if mining_account.is_active {
+            stats.active_miners = stats
+                .active_miners
+                .checked_sub(1)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        emit!(AccountClosed {
+            user: user_key,
+            refunded: mining_account.balance,
+            total_spent: mining_account.total_spent,
+            matches_found: mining_account.matches_found,
+        });
+
+        Ok(())
+    }
+
+    /// Close a mining account its owner has not used for the config's
+    /// `sweep_after_secs`. The residual GOR balance goes to the treasury;
+    /// the vault's rent reserve and the account's rent go back to the
+    /// owner. Accounts still holding sGOR are left alone.
+    /// Signed by the authority.
+    pub fn sweep_abandoned(ctx: Context<SweepAbandoned>) -> Result<()> {
+        let mining_account = &ctx.accounts.mining_account;
+        require!(
+            mining_account.spl_balance == 0,
+            ErrorCode::SplBalanceNotEmpty
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let inactive_secs = now
+            .checked_sub(mining_account.last_activity_ts)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            inactive_secs >= ctx.accounts.config.sweep_after_secs,
+            ErrorCode::AccountNotAbandoned
+        );
+        require_solvent(&ctx.accounts.vault, mining_account.balance)?;
+
+        // Empty the vault using vault PDA as signer: the balance to the
+        // treasury, the rest back to the owner
+        let user_key = ctx.accounts.user.key();
+        let vault_seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[mining_account.vault_bump]];
+        let swept = mining_account.balance;
+
+        if swept > 0 {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                swept,
+            )?;
+        }
+        let reserve = ctx.accounts.vault.lamports();
+        if reserve > 0 {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.user.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                reserve,
+            )?;
+        }
+
+        // The swept balance leaves like a withdrawal
+        let stats = &mut ctx.accounts.stats;
+        stats.total_withdrawn = stats
+            .total_withdrawn
+            .checked_add(swept as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        stats.total_users = stats
+            .total_users
+            .checked_sub(1)
+            .ok_or(ErrorCode::Overflow)?;
+        if mining_account.is_active {
+            stats.active_miners = stats
+                .active_miners
+                .checked_sub(1)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        emit!(AccountSwept {
+            user: user_key,
+            authority: ctx.accounts.authority.key(),
+            treasury: ctx.accounts.treasury.key(),
+            swept,
+            last_activity_ts: mining_account.last_activity_ts,
+            inactive_secs,
+            total_spent: mining_account.total_spent,
+            matches_found: mining_account.matches_found,
+        });
+
+        Ok(())
+    }
+
+    /// Offer the mining account to `new_owner`, who completes the move with
+    /// `accept_transfer`. The proposal PDA ["transfer", user_pubkey] stays
+    /// open until then or until `cancel_transfer`.
+    pub fn propose_transfer(ctx: Context<ProposeTransfer>, new_owner: Pubkey) -> Result<()> {
+        require!(
+            new_owner != ctx.accounts.user.key() && new_owner != Pubkey::default(),
+            ErrorCode::InvalidNewOwner
+        );
+
+        let transfer = &mut ctx.accounts.transfer;
+        transfer.user = ctx.accounts.user.key();
+        transfer.new_owner = new_owner;
+        transfer.bump = ctx.bumps.transfer;
+
+        emit!(TransferProposedEvent {
+            user: transfer.user,
+            new_owner,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw a pending transfer proposal and reclaim its rent.
+    pub fn cancel_transfer(ctx: Context<CancelTransfer>) -> Result<()> {
+        emit!(TransferCancelledEvent {
+            user: ctx.accounts.user.key(),
+            new_owner: ctx.accounts.transfer.new_owner,
+        });
+        Ok(())
+    }
+
+    /// Take over a mining account proposed to the signer. Recreates it
+    /// under the new owner's seeds with the same balance and history, moves
+    /// the whole vault (balance and rent reserve) to the new owner's vault,
+    /// and closes the old account with rent to the old owner.
+    /// Match records stay under the old key, and an sGOR balance must be
+    /// withdrawn first.
+    pub fn accept_transfer(ctx: Context<AcceptTransfer>) -> Result<()> {
+        let old = &ctx.accounts.mining_account;
+        let new_owner = ctx.accounts.new_owner.key();
+        require!(old.spl_balance == 0, ErrorCode::SplBalanceNotEmpty);
+        require!(old.referrer != new_owner, ErrorCode::SelfReferral);
+        require_solvent(&ctx.accounts.vault, old.balance)?;
+
+        // Empty the old vault into the new one using old vault PDA as signer
+        let user_key = ctx.accounts.user.key();
+        let vault_seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[old.vault_bump]];
+        let vault_lamports = ctx.accounts.vault.lamports();
+
+        if vault_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.new_vault.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                vault_lamports,
+            )?;
+        }
+        require_solvent(&ctx.accounts.new_vault, old.balance)?;
+
+        let new_account = &mut ctx.accounts.new_mining_account;
+        new_account.owner = new_owner;
+        new_account.balance = old.balance;
+        new_account.total_spent = old.total_spent;
+        new_account.matches_found = old.matches_found;
+        new_account.is_active = old.is_active;
+        new_account.bump = ctx.bumps.new_mining_account;
+        new_account.vault_bump = ctx.bumps.new_vault;
+        new_account.referrer = old.referrer;
+        new_account.spl_balance = 0;
+        // The old owner's session key does not carry over; the mining
+        // session does, like the rest of the account
+        new_account.clear_session();
+        new_account.mining_deadline = old.mining_deadline;
+        new_account.mining_budget = old.mining_budget;
+        new_account.mining_spent = old.mining_spent;
+        new_account.subscription_expiry_ts = old.subscription_expiry_ts;
+        new_account.subscription_tier = old.subscription_tier;
+        new_account.last_activity_ts = Clock::get()?.unix_timestamp;
+
+        emit!(OwnershipTransferredEvent {
+            old_owner: user_key,
+            new_owner,
+            balance: old.balance,
+        });
+
+        Ok(())
+    }
+
+    /// Record a vanity address match found by the user.
+    /// Signed by the user or their session key, and co-signed by the
+    /// platform authority. `address` must be the base58
+    /// form of a 32-byte key and match the `pattern` the user mined for.
+    /// The match is kept in a MatchRecord PDA (see `MatchRecord::pda`),
+    /// indexed by the user's match count, with rent paid by the signer.
+    pub fn record_match(
+        ctx: Context<RecordMatch>,
+        address: String,
+        pattern: VanityPattern,
+        batch_id: u64,
+    ) -> Result<()> {
+        require!(
+            address.len() <= MAX_BASE58_LEN,
+            ErrorCode::MatchAddressTooLong
+        );
+        let key = base58_decode(address.as_bytes())
+            .map(Pubkey::new_from_array)
+            .ok_or(ErrorCode::MatchAddressInvalid)?;
+        pattern.validate()?;
+        require!(pattern.matches(&key), ErrorCode::PatternMismatch);
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let mining_account = &mut ctx.accounts.mining_account;
+        if ctx.accounts.signer.key() != mining_account.owner {
+            mining_account.check_session(ctx.accounts.signer.key(), timestamp)?;
+        }
+        mining_account.last_activity_ts = timestamp;
+
+        let record = &mut ctx.accounts.match_record;
+        record.user = ctx.accounts.user.key();
+        record.index = mining_account.matches_found;
+        record.address = key;
+        record.timestamp = timestamp;
+        record.pattern = pattern;
+        record.batch_id = batch_id;
+        record.bump = ctx.bumps.match_record;
+
+        mining_account.matches_found = mining_account
+            .matches_found
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_matches = stats
+            .total_matches
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        ctx.accounts.leaderboard.update(
+            mining_account.owner,
+            mining_account.matches_found,
+            mining_account.total_spent,
+        );
+
+        emit!(MatchFound {
+            user: ctx.accounts.user.key(),
+            address,
+            timestamp,
+            total_matches: mining_account.matches_found,
+        });
+
+        Ok(())
+    }
+
+    /// Delete a batch receipt once it is `BATCH_RECEIPT_RETENTION` old and
+    /// return its rent to whoever paid for it. Its batch id can be charged
+    /// again after.
+    pub fn close_batch_receipt(ctx: Context<CloseBatchReceipt>) -> Result<()> {
+        ctx.accounts
+            .receipt
+            .require_expired(Clock::get()?.unix_timestamp)
+    }
+
+    /// Delete a match record and return its rent to the user.
+    pub fn close_match_record(_ctx: Context<CloseMatchRecord>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Post a bounty for an address matching `pattern`.
+    /// Escrows `reward` on the job PDA ["job", creator, job_id] until a miner
+    /// commits a match or the creator cancels after `expiry_slot`.
+    /// `delivery_timeout_slots` is how long the creator then has to confirm
+    /// or dispute delivery of the key before the miner can take the reward.
+    pub fn create_job(
+        ctx: Context<CreateJob>,
+        job_id: u64,
+        pattern: VanityPattern,
+        reward: u64,
+        expiry_slot: u64,
+        delivery_timeout_slots: u64,
+    ) -> Result<()> {
+        pattern.validate()?;
+        require!(reward > 0, ErrorCode::InvalidAmount);
+        require!(
+            expiry_slot > Clock::get()?.slot,
+            ErrorCode::InvalidExpiry
+        );
+        require!(
+            delivery_timeout_slots > 0,
+            ErrorCode::InvalidDeliveryTimeout
+        );
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.job.to_account_info(),
+                },
+            ),
+            reward,
+        )?;
+
+        let job = &mut ctx.accounts.job;
+        job.creator = ctx.accounts.creator.key();
+        job.job_id = job_id;
+        job.pattern = pattern;
+        job.reward = reward;
+        job.expiry_slot = expiry_slot;
+        job.status = JobStatus::Open;
+        job.miner = Pubkey::default();
+        job.matched = Pubkey::default();
+        job.bump = ctx.bumps.job;
+        job.delivery_timeout_slots = delivery_timeout_slots;
+        job.key_hash = [0; 32];
+        job.committed_slot = 0;
+
+        emit!(JobCreated {
+            job: job.key(),
+            creator: job.creator,
+            reward,
+            expiry_slot,
+            delivery_timeout_slots,
+        });
+
+        Ok(())
+    }
+
+    /// Claim a job with a matching address, locking its reward to the miner
+    /// until the creator confirms delivery of the key.
+    /// The candidate keypair signs, which proves the miner holds its key and
+    /// stops anyone copying the submission from the mempool.
+    /// `encrypted_key_hash` commits to the encrypted key the miner hands over
+    /// off-chain, so an admin resolving a dispute can check what was sent.
+    pub fn submit_match_commitment(
+        ctx: Context<SubmitMatchCommitment>,
+        encrypted_key_hash: [u8; 32],
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        require!(job.status == JobStatus::Open, ErrorCode::JobAlreadyFilled);
+        let slot = Clock::get()?.slot;
+        require!(slot <= job.expiry_slot, ErrorCode::JobExpired);
+
+        let candidate = ctx.accounts.candidate.key();
+        require!(
+            job.pattern.matches(&candidate),
+            ErrorCode::PatternMismatch
+        );
+
+        job.status = JobStatus::Committed;
+        job.miner = ctx.accounts.miner.key();
+        job.matched = candidate;
+        job.key_hash = encrypted_key_hash;
+        job.committed_slot = slot;
+
+        emit!(MatchCommitted {
+            job: job.key(),
+            miner: job.miner,
+            matched: candidate,
+            encrypted_key_hash,
+            release_slot: job.release_slot()?,
+        });
+
+        Ok(())
+    }
+
+    /// Confirm the key was received and works, paying the miner.
+    /// Signed by the job's creator.
+    pub fn confirm_delivery(ctx: Context<ConfirmDelivery>) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        require!(
+            job.status == JobStatus::Committed,
+            ErrorCode::InvalidJobStatus
+        );
+
+        pay_out(&job.to_account_info(), &ctx.accounts.miner, job.reward)?;
+        job.status = JobStatus::Released;
+
+        emit!(JobFilled {
+            job: job.key(),
+            miner: job.miner,
+            matched: job.matched,
+            reward: job.reward,
+        });
+
+        Ok(())
+    }
+
+    /// Freeze a committed job's reward for the authority to settle, when the
+    /// key was not delivered or does not match. Signed by the job's creator,
+    /// before the delivery timeout.
+    pub fn dispute(ctx: Context<DisputeDelivery>) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        require!(
+            job.status == JobStatus::Committed,
+            ErrorCode::InvalidJobStatus
+        );
+        require!(
+            Clock::get()?.slot < job.release_slot()?,
+            ErrorCode::DeliveryWindowClosed
+        );
+
+        job.status = JobStatus::Disputed;
+
+        emit!(DeliveryDisputed {
+            job: job.key(),
+            creator: job.creator,
+            miner: job.miner,
+        });
+
+        Ok(())
+    }
+
+    /// Pay the miner once the creator has let the delivery timeout pass
+    /// without confirming or disputing. Anyone may send it.
+    pub fn release_after_timeout(ctx: Context<ReleaseAfterTimeout>) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        require!(
+            job.status == JobStatus::Committed,
+            ErrorCode::InvalidJobStatus
+        );
+        require!(
+            Clock::get()?.slot >= job.release_slot()?,
+            ErrorCode::DeliveryWindowOpen
+        );
+
+        pay_out(&job.to_account_info(), &ctx.accounts.miner, job.reward)?;
+        job.status = JobStatus::Released;
+
+        emit!(JobFilled {
+            job: job.key(),
+            miner: job.miner,
+            matched: job.matched,
+            reward: job.reward,
+        });
+
+        Ok(())
+    }
+
+    /// Settle a disputed job, paying the reward to the miner or back to the
+    /// creator. Signed by the authority.
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, to_miner: bool) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        require!(
+            job.status == JobStatus::Disputed,
+            ErrorCode::InvalidJobStatus
+        );
+
+        let recipient = if to_miner {
+            &ctx.accounts.miner
+        } else {
+            &ctx.accounts.creator
+        };
+        pay_out(&job.to_account_info(), recipient, job.reward)?;
+        job.status = if to_miner {
+            JobStatus::Released
+        } else {
+            JobStatus::Refunded
+        };
+
+        emit!(DisputeResolved {
+            job: job.key(),
+            authority: ctx.accounts.authority.key(),
+            to_miner,
+            amount: job.reward,
+        });
+
+        Ok(())
+    }
+
+    /// Close a job, returning its rent to the creator along with the reward
+    /// if nobody claimed it. An unclaimed job can only be closed after it
+    /// expires, and a claimed one once its reward has been paid out.
+    pub fn cancel_job(ctx: Context<CancelJob>) -> Result<()> {
+        let job = &ctx.accounts.job;
+        match job.status {
+            JobStatus::Open => require!(
+                Clock::get()?.slot > job.expiry_slot,
+                ErrorCode::JobNotExpired
+            ),
+            JobStatus::Released | JobStatus::Refunded => {}
+            JobStatus::Committed | JobStatus::Disputed => return err!(ErrorCode::JobInEscrow),
+        }
+
+        emit!(JobCancelled {
+            job: job.key(),
+            creator: job.creator,
+            refunded: if job.status == JobStatus::Open { job.reward } else { 0 },
+        });
+
+        Ok(())
+    }
+
+    /// Create a team PDA ["team", founder, team_id] that pools GOR for its
+    /// members' mining. The founder is the first member; `members` adds up
+    /// to `MAX_TEAM_MEMBERS - 1` more. The pooled balance is held on the
+    /// team PDA itself.
+    pub fn create_team(ctx: Context<CreateTeam>, team_id: u64, members: Vec<Pubkey>) -> Result<()> {
+        let founder = ctx.accounts.founder.key();
+        let team = &mut ctx.accounts.team;
+        team.founder = founder;
+        team.team_id = team_id;
+        team.balance = 0;
+        team.total_spent = 0;
+        team.matches_found = 0;
+        team.member_count = 0;
+        team.members = [TeamMember::default(); MAX_TEAM_MEMBERS];
+        team.bump = ctx.bumps.team;
+
+        team.add(founder)?;
+        for member in members.iter() {
+            team.add(*member)?;
+        }
+
+        emit!(TeamCreated {
+            team: team.key(),
+            founder: team.founder,
+            members: team.members[..team.member_count as usize]
+                .iter()
+                .map(|m| m.member)
+                .collect(),
+        });
+
+        Ok(())
+    }
+
+    /// Add `member` to the team. Signed by the founder.
+    pub fn add_member(ctx: Context<AddTeamMember>, member: Pubkey) -> Result<()> {
+        let team = &mut ctx.accounts.team;
+        team.add(member)?;
+
+        emit!(TeamMemberAdded {
+            team: team.key(),
+            member,
+        });
+
+        Ok(())
+    }
+
+    /// Remove a member, paying them their pro-rata share of the balance
+    /// first (see `withdraw_team`). The founder cannot be removed.
+    /// Signed by the founder.
+    pub fn remove_member(ctx: Context<RemoveTeamMember>) -> Result<()> {
+        let team = &mut ctx.accounts.team;
+        let member = ctx.accounts.member.key();
+        require!(member != team.founder, ErrorCode::CannotRemoveFounder);
+        let index = team.position(&member).ok_or(ErrorCode::NotTeamMember)?;
+
+        let refunded = team.share_of(index)?;
+        team.balance = team
+            .balance
+            .checked_sub(refunded)
+            .ok_or(ErrorCode::Overflow)?;
+        team.remove(index);
+        if refunded > 0 {
+            pay_out(&team.to_account_info(), &ctx.accounts.member, refunded)?;
+        }
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_withdrawn = stats
+            .total_withdrawn
+            .checked_add(refunded as u128)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(TeamMemberRemoved {
+            team: team.key(),
+            member,
+            refunded,
+        });
+
+        Ok(())
+    }
+
+    /// Deposit GOR into the team's pooled balance, minting the signing
+    /// member share units at the current balance per unit (see
+    /// `TeamAccount::mint_units`), so spend before the deposit stays with
+    /// the members who funded it. Subject to the same limits as `deposit`.
+    pub fn deposit_to_team(ctx: Context<DepositToTeam>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        let config = &ctx.accounts.config;
+        require!(amount >= config.min_deposit, ErrorCode::DepositTooSmall);
+        require!(
+            config.max_deposit_per_tx == 0 || amount <= config.max_deposit_per_tx,
+            ErrorCode::DepositTooLarge
+        );
+
+        let member = ctx.accounts.member.key();
+        let index = ctx
+            .accounts
+            .team
+            .position(&member)
+            .ok_or(ErrorCode::NotTeamMember)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.member.to_account_info(),
+                    to: ctx.accounts.team.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let team = &mut ctx.accounts.team;
+        // Priced against the balance before this deposit lands
+        team.mint_units(index, amount)?;
+        team.balance = team
+            .balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_deposited = stats
+            .total_deposited
+            .checked_add(amount as u128)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(TeamDeposit {
+            team: team.key(),
+            member,
+            amount,
+            new_balance: team.balance,
+        });
+
+        Ok(())
+    }
+
+    /// Charge a mining batch to the team's pooled balance, priced like
+    /// `charge_for_batch` with volume discounts looked up from the team's
+    /// lifetime spend. Signed by the platform authority only: teams have
+    /// no session keys, mining sessions or referrers.
+    /// Writes a receipt PDA ["batch", team, batch_id], so a retried charge
+    /// fails with `DuplicateBatch`; see `close_team_batch_receipt`.
+    pub fn charge_team_batch(
+        ctx: Context<ChargeTeamBatch>,
+        attempts: u64,
+        batch_id: u64,
+    ) -> Result<()> {
+        require!(attempts > 0, ErrorCode::InvalidAmount);
+        let pricing = &ctx.accounts.pricing;
+        let gross_cost = pricing.cost_of(attempts)?;
+        let cost = pricing.discounted(gross_cost, ctx.accounts.team.total_spent)?;
+        require!(cost <= MAX_BATCH_COST, ErrorCode::CostAboveCap);
+        require!(
+            cost <= ctx.accounts.config.max_charge_per_call,
+            ErrorCode::ChargeTooLarge
+        );
+
+        let receipt = &mut ctx.accounts.receipt;
+        require!(receipt.user == Pubkey::default(), ErrorCode::DuplicateBatch);
+        let clock = Clock::get()?;
+        receipt.user = ctx.accounts.team.key();
+        receipt.payer = ctx.accounts.authority.key();
+        receipt.batch_id = batch_id;
+        receipt.cost = cost;
+        receipt.attempts = attempts;
+        receipt.slot = clock.slot;
+        receipt.timestamp = clock.unix_timestamp;
+        receipt.bump = ctx.bumps.receipt;
+
+        let team = &mut ctx.accounts.team;
+        require!(team.balance >= cost, ErrorCode::InsufficientBalance);
+        team.balance = team
+            .balance
+            .checked_sub(cost)
+            .ok_or(ErrorCode::Overflow)?;
+        team.total_spent = team
+            .total_spent
+            .checked_add(cost)
+            .ok_or(ErrorCode::Overflow)?;
+        pay_out(&team.to_account_info(), &ctx.accounts.treasury, cost)?;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_spent = stats
+            .total_spent
+            .checked_add(cost as u128)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(TeamBatchCharged {
+            team: team.key(),
+            batch_id,
+            attempts,
+            gross_cost,
+            cost,
+            remaining_balance: team.balance,
+        });
+
+        Ok(())
+    }
+
+    /// Delete a team batch receipt once it is `BATCH_RECEIPT_RETENTION` old
+    /// and return its rent to whoever paid for it, as `close_batch_receipt`
+    /// does for users. Signed by the founder.
+    pub fn close_team_batch_receipt(ctx: Context<CloseTeamBatchReceipt>) -> Result<()> {
+        ctx.accounts
+            .receipt
+            .require_expired(Clock::get()?.unix_timestamp)
+    }
+
+    /// Pay the signing member their pro-rata share of the team balance:
+    /// `balance * units / total units`, rounded down. Their units are then
+    /// redeemed, which leaves every other member's share unchanged; the last
+    /// member holding units takes the rounding dust.
+    pub fn withdraw_team(ctx: Context<WithdrawTeam>) -> Result<()> {
+        let team = &mut ctx.accounts.team;
+        let member = ctx.accounts.member.key();
+        let index = team.position(&member).ok_or(ErrorCode::NotTeamMember)?;
+
+        let amount = team.share_of(index)?;
+        require!(amount > 0, ErrorCode::NoBalance);
+        team.balance = team
+            .balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        team.members[index].units = 0;
+        pay_out(&team.to_account_info(), &ctx.accounts.member, amount)?;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_withdrawn = stats
+            .total_withdrawn
+            .checked_add(amount as u128)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(TeamWithdrawal {
+            team: team.key(),
+            member,
+            amount,
+            remaining_balance: team.balance,
+        });
+
+        Ok(())
+    }
+
+    /// Record a vanity address match found under a team, noting the member
+    /// who submitted it. Signed by the member and co-signed by the platform
+    /// authority, as in `record_match`. The record is a TeamMatchRecord PDA
+    /// ["team_match", team, index], indexed by the team's match count, with
+    /// rent paid by the member. Team matches do not enter the leaderboard.
+    pub fn record_team_match(
+        ctx: Context<RecordTeamMatch>,
+        address: String,
+        pattern: VanityPattern,
+        batch_id: u64,
+    ) -> Result<()> {
+        require!(
+            address.len() <= MAX_BASE58_LEN,
+            ErrorCode::MatchAddressTooLong
+        );
+        let key = base58_decode(address.as_bytes())
+            .map(Pubkey::new_from_array)
+            .ok_or(ErrorCode::MatchAddressInvalid)?;
+        pattern.validate()?;
+        require!(pattern.matches(&key), ErrorCode::PatternMismatch);
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let team = &mut ctx.accounts.team;
+        let member = ctx.accounts.member.key();
+        require!(team.position(&member).is_some(), ErrorCode::NotTeamMember);
+
+        let record = &mut ctx.accounts.match_record;
+        record.team = team.key();
+        record.member = member;
+        record.index = team.matches_found;
+        record.address = key;
+        record.timestamp = timestamp;
+        record.pattern = pattern;
+        record.batch_id = batch_id;
+        record.bump = ctx.bumps.match_record;
+
+        team.matches_found = team
+            .matches_found
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let stats = &mut ctx.accounts.stats;
+        stats.total_matches = stats
+            .total_matches
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(TeamMatchFound {
+            team: team.key(),
+            member,
+            address,
+            timestamp,
+            total_matches: team.matches_found,
+        });
+
+        Ok(())
+    }
+}
+
+/// Fails unless `vault` holds `liabilities` on top of its rent-exempt
+/// reserve. Checked around every vault debit, so that a drift between
+/// tracked balances and vault lamports surfaces as an error instead of
+/// failing a later withdrawal or paying out of the reserve.
+/// Vaults are per user and owe nothing but that user's GOR `balance`, so
+/// the mining account already is the vault's liability ledger; a separate
+/// `total_liabilities` counter would only mirror it, and a drift in one
+/// vault cannot be paid out of another user's funds.
+fn require_solvent(vault: &AccountInfo, liabilities: u64) -> Result<()> {
+    let required = Rent::get()?
+        .minimum_balance(0)
+        .checked_add(liabilities)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(vault.lamports() >= required, ErrorCode::VaultInsolvent);
+    Ok(())
+}
+
+/// Move `amount` lamports out of an account this program owns
+fn pay_out(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let remaining = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let paid = to.lamports().checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = paid;
+    Ok(())
+}
+
+/// Top the vault up to its rent-exempt minimum, which is kept out of the
+/// tracked balance so charges and withdrawals can always drain the balance.
+fn fund_vault_reserve<'info>(
+    user: &Signer<'info>,
+    vault: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let reserve = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(vault.lamports());
+    if reserve > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: user.to_account_info(),
+                    to: vault.to_account_info(),
+                },
+            ),
+            reserve,
+        )?;
+    }
+    Ok(())
+}
+
+/// Referrer's `bps` share of a charge of `amount`, rounded down
+fn referral_share(amount: u64, bps: u16) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / 10_000;
+    Ok(u64::try_from(share).map_err(|_| ErrorCode::Overflow)?)
+}
+
+/// Move `amount` sGOR out of a user's token vault, signed by their GOR
+/// vault PDA, which is the token vault's authority
+fn transfer_from_spl_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    spl_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    vault_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: spl_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to,
+                authority: vault,
+            },
+            &[vault_seeds],
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Base58-encode a 32-byte key into `out`, returning the encoded length.
+/// Long division over the key bytes; a few thousand compute units.
+fn base58_encode(bytes: &[u8; 32], out: &mut [u8; MAX_BASE58_LEN]) -> usize {
+    // Digits in base 58, least significant first
+    let mut digits = [0u8; MAX_BASE58_LEN];
+    let mut len = 0;
+    for &byte in bytes.iter() {
+        let mut carry = byte as u32;
+        for digit in digits[..len].iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits[len] = (carry % 58) as u8;
+            len += 1;
+            carry /= 58;
+        }
+    }
+
+    // Each leading zero byte encodes as a leading '1'
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut n = 0;
+    for _ in 0..zeros {
+        out[n] = BASE58_ALPHABET[0];
+        n += 1;
+    }
+    for &digit in digits[..len].iter().rev() {
+        out[n] = BASE58_ALPHABET[digit as usize];
+        n += 1;
+    }
+    n
+}
+
+/// Decode base58 into a 32-byte key. `None` unless `encoded` is exactly
+/// the canonical encoding of 32 bytes.
+fn base58_decode(encoded: &[u8]) -> Option<[u8; 32]> {
+    // Bytes of the value, least significant first
+    let mut bytes = [0u8; 32];
+    let mut len = 0;
+    for &c in encoded.iter() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes[..len].iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            if len == bytes.len() {
+                return None;
+            }
+            bytes[len] = carry as u8;
+            len += 1;
+            carry >>= 8;
+        }
+    }
+
+    // Each leading '1' stands for a leading zero byte
+    let zeros = encoded.iter().take_while(|&&c| c == BASE58_ALPHABET[0]).count();
+    if zeros + len != 32 {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    for (i, &byte) in bytes[..len].iter().enumerate() {
+        key[31 - i] = byte;
+    }
+    Some(key)
+}
+
+// === Account Structs ===
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Config::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// This program; locates its program data account
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub program: Program<'info, VanityMiner>,
+
+    /// Only the upgrade authority may create the config, so the first
+    /// caller after deployment cannot name themselves the authority
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(payer.key())
+            @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePricing<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PricingConfig::SIZE,
+        seeds = [b"pricing"],
+        bump
+    )]
+    pub pricing: Account<'info, PricingConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDiscountTiers<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"pricing"], bump = pricing.bump)]
+    pub pricing: Account<'info, PricingConfig>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSubscriptionTiers<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"pricing"], bump = pricing.bump)]
+    pub pricing: Account<'info, PricingConfig>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSplConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + SplConfig::SIZE,
+        seeds = [b"spl_config"],
+        bump
+    )]
+    pub spl_config: Account<'info, SplConfig>,
+
+    pub sgor_mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStats<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GlobalStats::SIZE,
+        seeds = [b"stats"],
+        bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLeaderboard<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Leaderboard::SIZE,
+        seeds = [b"leaderboard"],
+        bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RebuildLeaderboard<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"leaderboard"],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+}
+
+#[derive(Accounts)]
+pub struct ManageSessionKey<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetMiningActive<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeUser<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + MiningAccount::SIZE,
+        seeds = [b"mining", user.key().as_ref()],
+        bump
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's vault PDA. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Mining account in the pre-vault layout, which no longer
+    /// deserializes as `MiningAccount`. Validated by seeds and owner here,
+    /// and by length, discriminator and owner field in the handler.
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub mining_account: UncheckedAccount<'info>,
+
+    /// CHECK: Shared vault PDA used before per-user vaults. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        bump
+    )]
+    pub legacy_vault: UncheckedAccount<'info>,
+
+    /// CHECK: User's vault PDA. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault for holding deposited GOR. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSpl<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault; authority of the token vault. Validated by seeds.
+    #[account(
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"spl_config"], bump = spl_config.bump)]
+    pub spl_config: Account<'info, SplConfig>,
+
+    #[account(address = spl_config.sgor_mint @ ErrorCode::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's sGOR token vault
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = sgor_mint,
+        token::authority = vault,
+        token::token_program = token_program,
+        seeds = [b"spl_vault", user.key().as_ref()],
+        bump
+    )]
+    pub spl_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's sGOR token account (source of the deposit)
+    #[account(
+        mut,
+        token::mint = sgor_mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseSubscription<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(seeds = [b"pricing"], bump = pricing.bump)]
+    pub pricing: Account<'info, PricingConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: Platform treasury wallet. Must match the config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(attempts: u64, from_spl: bool, batch_id: u64)]
+pub struct ChargeForBatch<'info> {
+    /// Platform authority from the config, or the user's session key;
+    /// checked in the handler. Pays the receipt's rent.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(seeds = [b"pricing"], bump = pricing.bump)]
+    pub pricing: Account<'info, PricingConfig>,
+
+    /// CHECK: User being charged. Does not sign; must own the mining account.
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: Platform treasury wallet. Must match the config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: User's referrer wallet; required when the charge carries a
+    /// referral share and checked against the mining account in the handler.
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    // SPL accounts, required when charging the sGOR balance and checked
+    // against the SPL config in the handler
+
+    #[account(seeds = [b"spl_config"], bump = spl_config.bump)]
+    pub spl_config: Option<Account<'info, SplConfig>>,
+
+    pub sgor_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// User's sGOR token vault
+    #[account(
+        mut,
+        seeds = [b"spl_vault", user.key().as_ref()],
+        bump
+    )]
+    pub spl_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury's sGOR token account
+    #[account(mut)]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Referrer's sGOR token account; required when the charge carries a
+    /// referral share
+    #[account(mut)]
+    pub referrer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Receipt for `batch_id`; checked unused in the handler
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BatchReceipt::SIZE,
+        seeds = [b"batch", user.key().as_ref(), &batch_id.to_le_bytes()],
+        bump
+    )]
+    pub receipt: Box<Account<'info, BatchReceipt>>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundBatch<'info> {
+    pub authority: Signer<'info>,
+
+    /// Treasury wallet from the config; co-signs to fund the refund
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: User being refunded. Does not sign; must own the mining account.
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Wallet to receive the withdrawal instead of the user
+    #[account(mut)]
+    pub destination: Option<SystemAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSpl<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault; authority of the token vault. Validated by seeds.
+    #[account(
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"spl_config"], bump = spl_config.bump)]
+    pub spl_config: Account<'info, SplConfig>,
+
+    #[account(address = spl_config.sgor_mint @ ErrorCode::InvalidMint)]
+    pub sgor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's sGOR token vault
+    #[account(
+        mut,
+        seeds = [b"spl_vault", user.key().as_ref()],
+        bump
+    )]
+    pub spl_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's sGOR token account (receives the withdrawal)
+    #[account(
+        mut,
+        token::mint = sgor_mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAccount<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepAbandoned<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Owner of the abandoned account; gets the rent back. Checked
+    /// by the mining account's constraint.
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: User's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: Platform treasury wallet. Must match the config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeTransfer<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + OwnershipTransfer::SIZE,
+        seeds = [b"transfer", user.key().as_ref()],
+        bump
+    )]
+    pub transfer: Account<'info, OwnershipTransfer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTransfer<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"transfer", user.key().as_ref()],
+        bump = transfer.bump,
+        has_one = user @ ErrorCode::Unauthorized,
+    )]
+    pub transfer: Account<'info, OwnershipTransfer>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptTransfer<'info> {
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+
+    /// CHECK: Current owner; receives the closed accounts' rent. Checked
+    /// against the proposal.
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"transfer", user.key().as_ref()],
+        bump = transfer.bump,
+        has_one = user @ ErrorCode::Unauthorized,
+        has_one = new_owner @ ErrorCode::Unauthorized,
+    )]
+    pub transfer: Account<'info, OwnershipTransfer>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: Current owner's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump = mining_account.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = new_owner,
+        space = 8 + MiningAccount::SIZE,
+        seeds = [b"mining", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_mining_account: Account<'info, MiningAccount>,
+
+    /// CHECK: New owner's PDA vault. Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CreateJob<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Job::SIZE,
+        seeds = [b"job", creator.key().as_ref(), &job_id.to_le_bytes()],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitMatchCommitment<'info> {
+    /// Receives the reward once delivery is settled
+    pub miner: Signer<'info>,
+
+    /// Address found by the miner; may be the miner itself
+    pub candidate: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"job", job.creator.as_ref(), &job.job_id.to_le_bytes()],
+        bump = job.bump
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmDelivery<'info> {
+    pub creator: Signer<'info>,
+
+    /// CHECK: Miner who committed the match; checked against the job
+    #[account(mut)]
+    pub miner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"job", creator.key().as_ref(), &job.job_id.to_le_bytes()],
+        bump = job.bump,
+        has_one = creator @ ErrorCode::Unauthorized,
+        has_one = miner @ ErrorCode::InvalidMiner,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeDelivery<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"job", creator.key().as_ref(), &job.job_id.to_le_bytes()],
+        bump = job.bump,
+        has_one = creator @ ErrorCode::Unauthorized,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseAfterTimeout<'info> {
+    /// CHECK: Miner who committed the match; checked against the job
+    #[account(mut)]
+    pub miner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"job", job.creator.as_ref(), &job.job_id.to_le_bytes()],
+        bump = job.bump,
+        has_one = miner @ ErrorCode::InvalidMiner,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Job's creator; checked against the job
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    /// CHECK: Miner who committed the match; checked against the job
+    #[account(mut)]
+    pub miner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"job", creator.key().as_ref(), &job.job_id.to_le_bytes()],
+        bump = job.bump,
+        has_one = creator @ ErrorCode::Unauthorized,
+        has_one = miner @ ErrorCode::InvalidMiner,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct CancelJob<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"job", creator.key().as_ref(), &job.job_id.to_le_bytes()],
+        bump = job.bump,
+        has_one = creator @ ErrorCode::Unauthorized,
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct RecordMatch<'info> {
+    /// The user or their session key; pays the record's rent
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Owner of the mining account; checked by its constraint
+    pub user: UncheckedAccount<'info>,
+
+    /// Platform authority, vouching that the user found the match
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"mining", user.key().as_ref()],
+        bump = mining_account.bump,
+        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mining_account: Account<'info, MiningAccount>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + MatchRecord::SIZE,
+        seeds = [b"match", user.key().as_ref(), &mining_account.matches_found.to_le_bytes()],
+        bump
+    )]
+    pub match_record: Account<'info, MatchRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    #[account(
+        mut,
+        seeds = [b"leaderboard"],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBatchReceipt<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"batch", user.key().as_ref(), &receipt.batch_id.to_le_bytes()],
+        bump = receipt.bump,
+        has_one = user @ ErrorCode::Unauthorized,
+        has_one = payer @ ErrorCode::Unauthorized,
+    )]
+    pub receipt: Account<'info, BatchReceipt>,
+
+    /// Whoever paid the receipt's rent when the batch was charged
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMatchRecord<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"match", user.key().as_ref(), &match_record.index.to_le_bytes()],
+        bump = match_record.bump,
+        has_one = user @ ErrorCode::Unauthorized,
+    )]
+    pub match_record: Account<'info, MatchRecord>,
+}
+
+#[derive(Accounts)]
+#[instruction(team_id: u64)]
+pub struct CreateTeam<'info> {
+    #[account(mut)]
+    pub founder: Signer<'info>,
+
+    #[account(
+        init,
+        payer = founder,
+        space = 8 + TeamAccount::SIZE,
+        seeds = [b"team", founder.key().as_ref(), &team_id.to_le_bytes()],
+        bump
+    )]
+    pub team: Account<'info, TeamAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddTeamMember<'info> {
+    pub founder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"team", founder.key().as_ref(), &team.team_id.to_le_bytes()],
+        bump = team.bump,
+        has_one = founder @ ErrorCode::Unauthorized,
+    )]
+    pub team: Account<'info, TeamAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveTeamMember<'info> {
+    pub founder: Signer<'info>,
+
+    /// CHECK: Member being removed; receives their share of the balance.
+    /// Checked against the team in the handler.
+    #[account(mut)]
+    pub member: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"team", founder.key().as_ref(), &team.team_id.to_le_bytes()],
+        bump = team.bump,
+        has_one = founder @ ErrorCode::Unauthorized,
+    )]
+    pub team: Account<'info, TeamAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct DepositToTeam<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"team", team.founder.as_ref(), &team.team_id.to_le_bytes()],
+        bump = team.bump
+    )]
+    pub team: Account<'info, TeamAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(attempts: u64, batch_id: u64)]
+pub struct ChargeTeamBatch<'info> {
+    /// Platform authority; pays the receipt's rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(seeds = [b"pricing"], bump = pricing.bump)]
+    pub pricing: Account<'info, PricingConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"team", team.founder.as_ref(), &team.team_id.to_le_bytes()],
+        bump = team.bump
+    )]
+    pub team: Account<'info, TeamAccount>,
+
+    /// CHECK: Platform treasury wallet. Must match the config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Receipt for `batch_id`; checked unused in the handler
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BatchReceipt::SIZE,
+        seeds = [b"batch", team.key().as_ref(), &batch_id.to_le_bytes()],
+        bump
+    )]
+    pub receipt: Account<'info, BatchReceipt>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTeamBatchReceipt<'info> {
+    pub founder: Signer<'info>,
+
+    #[account(
+        seeds = [b"team", founder.key().as_ref(), &team.team_id.to_le_bytes()],
+        bump = team.bump,
+        has_one = founder @ ErrorCode::Unauthorized,
+    )]
+    pub team: Account<'info, TeamAccount>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"batch", team.key().as_ref(), &receipt.batch_id.to_le_bytes()],
+        bump = receipt.bump,
+        has_one = payer @ ErrorCode::Unauthorized,
+    )]
+    pub receipt: Account<'info, BatchReceipt>,
+
+    /// Whoever paid the receipt's rent when the batch was charged
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTeam<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"team", team.founder.as_ref(), &team.team_id.to_le_bytes()],
+        bump = team.bump
+    )]
+    pub team: Account<'info, TeamAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct RecordTeamMatch<'info> {
+    /// Team member who found the match; pays the record's rent
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    /// Platform authority, vouching that the member found the match
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority,
+        constraint = !config.paused @ ErrorCode::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"team", team.founder.as_ref(), &team.team_id.to_le_bytes()],
+        bump = team.bump
+    )]
+    pub team: Account<'info, TeamAccount>,
+
+    #[account(
+        init,
+        payer = member,
+        space = 8 + TeamMatchRecord::SIZE,
+        seeds = [b"team_match", team.key().as_ref(), &team.matches_found.to_le_bytes()],
+        bump
+    )]
+    pub match_record: Account<'info, TeamMatchRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"stats"],
+        bump = stats.bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// === State ===
+
+#[account]
+pub struct Config {
+    pub authority: Pubkey,       // 32 bytes - Charges mining batches
+    pub treasury: Pubkey,        // 32 bytes - Receives batch charges
+    pub bump: u8,                // 1 byte  - PDA bump seed
+    pub min_deposit: u64,        // 8 bytes - Smallest GOR deposit, lamports
+    pub max_deposit_per_tx: u64, // 8 bytes - Largest GOR deposit; 0 = no cap
+    pub paused: bool,            // 1 byte  - Deposits, charges and matches halted
+    pub max_charge_per_call: u64, // 8 bytes - Largest single batch charge, lamports
+    pub sweep_after_secs: i64,   // 8 bytes - Inactivity before an account can be swept
+}
+
+impl Config {
+    pub const SIZE: usize = 32 + 32 + 1 + 8 + 8 + 1 + 8 + 8; // 98 bytes
+}
+
+#[account]
+pub struct PricingConfig {
+    pub cost_per_million_attempts: u64, // 8 bytes - GOR lamports
+    pub flat_fee: u64,                  // 8 bytes - GOR lamports per batch
+    pub bump: u8,                       // 1 byte  - PDA bump seed
+    pub referral_bps: u16,              // 2 bytes - Referrer's share of a charge
+    pub tier_count: u8,                 // 1 byte  - Tiers in use
+    pub tiers: [DiscountTier; MAX_DISCOUNT_TIERS], // 40 bytes - Ascending thresholds
+    pub subscription_tier_count: u8,    // 1 byte  - Subscription tiers on sale
+    pub subscription_tiers: [SubscriptionTier; MAX_SUBSCRIPTION_TIERS], // 64 bytes - Indexed by tier
+}
+
+impl PricingConfig {
+    pub const SIZE: usize = 8 + 8 + 1 + 2 + 1 + DiscountTier::SIZE * MAX_DISCOUNT_TIERS
+        + 1 + SubscriptionTier::SIZE * MAX_SUBSCRIPTION_TIERS; // 125 bytes
+
+    /// `gross` less the discount of the highest tier whose threshold
+    /// `total_spent` has reached. The discount rounds down.
+    pub fn discounted(&self, gross: u64, total_spent: u64) -> Result<u64> {
+        let discount_bps = self.tiers[..self.tier_count as usize]
+            .iter()
+            .rev()
+            .find(|tier| total_spent >= tier.threshold)
+            .map_or(0, |tier| tier.discount_bps);
+        let discount = (gross as u128)
+            .checked_mul(discount_bps as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / 10_000;
+        Ok(gross
+            .checked_sub(discount as u64)
+            .ok_or(ErrorCode::Overflow)?)
+    }
+
+    /// Lamports owed for a batch: the flat fee plus the per-attempt rate,
+    /// rounded up so that small batches are never free.
+    pub fn cost_of(&self, attempts: u64) -> Result<u64> {
+        let variable = (attempts as u128)
+            .checked_mul(self.cost_per_million_attempts as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .checked_add(999_999)
+            .ok_or(ErrorCode::Overflow)?
+            / 1_000_000;
+        let total = variable
+            .checked_add(self.flat_fee as u128)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(u64::try_from(total).map_err(|_| ErrorCode::Overflow)?)
+    }
+}
+
+/// Discount applied once a user's lifetime spend reaches `threshold`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DiscountTier {
+    pub threshold: u64,      // 8 bytes - Lifetime spend in GOR lamports
+    pub discount_bps: u16,   // 2 bytes - Off the gross cost
+}
+
+impl DiscountTier {
+    pub const SIZE: usize = 8 + 2; // 10 bytes
+}
+
+/// Flat-rate mining period sold by `purchase_subscription`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct SubscriptionTier {
+    pub price: u64,          // 8 bytes - GOR lamports
+    pub duration_secs: i64,  // 8 bytes - Added to the subscription expiry
+}
+
+impl SubscriptionTier {
+    pub const SIZE: usize = 8 + 8; // 16 bytes
+}
+
+#[account]
+pub struct SplConfig {
+    pub sgor_mint: Pubkey,   // 32 bytes - Accepted sGOR mint
+    pub spl_per_gor: u64,    // 8 bytes - sGOR base units per whole GOR
+    pub bump: u8,            // 1 byte  - PDA bump seed
+}
+
+impl SplConfig {
+    pub const SIZE: usize = 32 + 8 + 1; // 41 bytes
+
+    /// sGOR base units owed for a cost in lamports, rounded up
+    pub fn to_spl(&self, lamports: u64) -> Result<u64> {
+        let amount = (lamports as u128)
+            .checked_mul(self.spl_per_gor as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .checked_add(999_999_999)
+            .ok_or(ErrorCode::Overflow)?
+            / 1_000_000_000;
+        Ok(u64::try_from(amount).map_err(|_| ErrorCode::Overflow)?)
+    }
+}
+
+#[account]
+pub struct MiningAccount {
+    pub owner: Pubkey,             // 32 bytes
+    pub balance: u64,              // 8 bytes - GOR lamports
+    pub total_spent: u64,          // 8 bytes - Lifetime spent
+    pub matches_found: u32,        // 4 bytes - Total matches
+    pub is_active: bool,           // 1 byte  - Mining session started
+    pub bump: u8,                  // 1 byte  - PDA bump seed
+    pub vault_bump: u8,            // 1 byte  - User vault PDA bump seed
+    pub referrer: Pubkey,          // 32 bytes - Earns the referral share; default = none
+    pub spl_balance: u64,          // 8 bytes - sGOR base units
+    pub session_key: Pubkey,       // 32 bytes - Delegate for charges; default = none
+    pub session_max_spend: u64,    // 8 bytes - GOR lamports the session may spend
+    pub session_spent: u64,        // 8 bytes - Spent by the session so far
+    pub session_expiry: i64,       // 8 bytes - Unix timestamp
+    pub mining_deadline: i64,      // 8 bytes - Mining session end, unix timestamp
+    pub mining_budget: u64,        // 8 bytes - GOR lamports the mining session may spend
+    pub mining_spent: u64,         // 8 bytes - Spent in the mining session so far
+    pub subscription_expiry_ts: i64, // 8 bytes - Batches are free until then
+    pub subscription_tier: u8,     // 1 byte  - Tier of the latest purchase
+    pub last_activity_ts: i64,     // 8 bytes - Last user or session-key instruction, unix timestamp
+}
+
+impl MiningAccount {
+    pub const SIZE: usize =
+        32 + 8 + 8 + 4 + 1 + 1 + 1 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8; // 192 bytes
+
+    pub fn is_subscribed(&self, now: i64) -> bool {
+        now < self.subscription_expiry_ts
+    }
+
+    pub fn clear_session(&mut self) {
+        self.session_key = Pubkey::default();
+        self.session_max_spend = 0;
+        self.session_spent = 0;
+        self.session_expiry = 0;
+    }
+
+    /// Fails unless `key` is the session key and it has not expired
+    pub fn check_session(&self, key: Pubkey, now: i64) -> Result<()> {
+        require!(
+            self.session_key != Pubkey::default() && key == self.session_key,
+            ErrorCode::Unauthorized
+        );
+        require!(now < self.session_expiry, ErrorCode::SessionExpired);
+        Ok(())
+    }
+
+    /// Count `cost` against the session's spend cap
+    pub fn spend_session(&mut self, cost: u64, now: i64) -> Result<()> {
+        require!(now < self.session_expiry, ErrorCode::SessionExpired);
+        let spent = self
+            .session_spent
+            .checked_add(cost)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            spent <= self.session_max_spend,
+            ErrorCode::SessionSpendExceeded
+        );
+        self.session_spent = spent;
+        Ok(())
+    }
+
+    /// Count `cost` against the running mining session's budget
+    pub fn spend_mining_budget(&mut self, cost: u64, now: i64) -> Result<()> {
+        require!(self.is_active, ErrorCode::NotMining);
+        require!(now < self.mining_deadline, ErrorCode::MiningSessionExpired);
+        let spent = self
+            .mining_spent
+            .checked_add(cost)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            spent <= self.mining_budget,
+            ErrorCode::SessionBudgetExceeded
+        );
+        self.mining_spent = spent;
+        Ok(())
+    }
+}
+
+/// `MiningAccount` as written before per-user vaults; read by `migrate_vault`
+#[derive(AnchorDeserialize)]
+pub struct LegacyMiningAccount {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub total_spent: u64,
+    pub matches_found: u32,
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl LegacyMiningAccount {
+    pub const SIZE: usize = 32 + 8 + 8 + 4 + 1 + 1; // 54 bytes
+}
+
+/// Pending hand-over of `user`'s mining account to `new_owner`
+#[account]
+pub struct OwnershipTransfer {
+    pub user: Pubkey,        // 32 bytes - Current owner
+    pub new_owner: Pubkey,   // 32 bytes - Must sign `accept_transfer`
+    pub bump: u8,            // 1 byte  - PDA bump seed
+}
+
+impl OwnershipTransfer {
+    pub const SIZE: usize = 32 + 32 + 1; // 65 bytes
+}
+
+#[account]
+pub struct Job {
+    pub creator: Pubkey,         // 32 bytes - Posted and funded the job
+    pub job_id: u64,             // 8 bytes  - Creator-chosen id, in the seeds
+    pub pattern: VanityPattern,  // 25 bytes - What a match must look like
+    pub reward: u64,             // 8 bytes  - GOR lamports escrowed on the job
+    pub expiry_slot: u64,        // 8 bytes  - Last slot a match is accepted
+    pub status: JobStatus,       // 1 byte   - Delivery escrow state
+    pub miner: Pubkey,           // 32 bytes - Committed miner, once claimed
+    pub matched: Pubkey,         // 32 bytes - Matching address, once claimed
+    pub bump: u8,                // 1 byte   - PDA bump seed
+    pub delivery_timeout_slots: u64, // 8 bytes - Creator's window to confirm or dispute
+    pub key_hash: [u8; 32],      // 32 bytes - Miner's commitment to the encrypted key
+    pub committed_slot: u64,     // 8 bytes  - When the match was committed
+}
+
+impl Job {
+    pub const SIZE: usize =
+        32 + 8 + VanityPattern::SIZE + 8 + 8 + 1 + 32 + 32 + 1 + 8 + 32 + 8; // 195 bytes
+
+    /// First slot at which an unconfirmed, undisputed reward goes to the miner
+    pub fn release_slot(&self) -> Result<u64> {
+        let slot = self
+            .committed_slot
+            .checked_add(self.delivery_timeout_slots)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(slot)
+    }
+}
+
+/// Open -> Committed -> Released, or Committed -> Disputed -> Released
+/// or Refunded
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    /// Waiting for a match
+    Open,
+    /// Match committed; reward locked to the miner pending delivery
+    Committed,
+    /// Creator disputed delivery; waiting for the authority
+    Disputed,
+    /// Reward paid to the miner
+    Released,
+    /// Reward returned to the creator after a dispute
+    Refunded,
+}
+
+#[account]
+pub struct MatchRecord {
+    pub user: Pubkey,            // 32 bytes - Who found the match
+    pub index: u32,              // 4 bytes  - User's match count at the time
+    pub address: Pubkey,         // 32 bytes - Matching address
+    pub timestamp: i64,          // 8 bytes  - When it was recorded
+    pub pattern: VanityPattern,  // 25 bytes - Pattern it matched
+    pub batch_id: u64,           // 8 bytes  - Mining batch that found it
+    pub bump: u8,                // 1 byte   - PDA bump seed
+}
+
+impl MatchRecord {
+    pub const SIZE: usize = 32 + 4 + 32 + 8 + VanityPattern::SIZE + 8 + 1; // 110 bytes
+
+    /// Address of `user`'s record number `index`. Records run from 0 up to
+    /// the mining account's `matches_found`, so clients can page through
+    /// them by index.
+    pub fn pda(user: &Pubkey, index: u32) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"match", user.as_ref(), &index.to_le_bytes()], &crate::ID)
+    }
+}
+
+/// Proof that `user` was charged for `batch_id`
+#[account]
+pub struct BatchReceipt {
+    pub user: Pubkey,            // 32 bytes - User charged
+    pub payer: Pubkey,           // 32 bytes - Paid the rent; refunded on close
+    pub batch_id: u64,           // 8 bytes  - Platform batch id, in the seeds
+    pub cost: u64,               // 8 bytes  - GOR price after discount
+    pub attempts: u64,           // 8 bytes  - Attempts charged for
+    pub slot: u64,               // 8 bytes  - Slot of the charge
+    pub timestamp: i64,          // 8 bytes  - When it was charged
+    pub bump: u8,                // 1 byte   - PDA bump seed
+}
+
+impl BatchReceipt {
+    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1; // 105 bytes
+
+    /// Receipts are kept `BATCH_RECEIPT_RETENTION` past their charge
+    pub fn require_expired(&self, now: i64) -> Result<()> {
+        let expires = self
+            .timestamp
+            .checked_add(BATCH_RECEIPT_RETENTION)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(now >= expires, ErrorCode::ReceiptNotExpired);
+        Ok(())
+    }
+}
+
+/// GOR pooled by up to `MAX_TEAM_MEMBERS` members. Deposits mint share
+/// units at the current balance per unit; each member's units set their
+/// share of whatever balance is left.
+#[account]
+pub struct TeamAccount {
+    pub founder: Pubkey,         // 32 bytes - Created the team; manages members
+    pub team_id: u64,            // 8 bytes  - Founder-chosen id, in the seeds
+    pub balance: u64,            // 8 bytes  - Pooled GOR lamports, held on this PDA
+    pub total_spent: u64,        // 8 bytes  - Lifetime spent
+    pub matches_found: u32,      // 4 bytes  - Total matches
+    pub member_count: u8,        // 1 byte   - Members in use, founder first
+    pub members: [TeamMember; MAX_TEAM_MEMBERS], // 320 bytes
+    pub bump: u8,                // 1 byte   - PDA bump seed
+}
+
+impl TeamAccount {
+    pub const SIZE: usize =
+        32 + 8 + 8 + 8 + 4 + 1 + TeamMember::SIZE * MAX_TEAM_MEMBERS + 1; // 382 bytes
+
+    pub fn position(&self, member: &Pubkey) -> Option<usize> {
+        self.members[..self.member_count as usize]
+            .iter()
+            .position(|m| m.member == *member)
+    }
+
+    pub fn add(&mut self, member: Pubkey) -> Result<()> {
+        require!(self.position(&member).is_none(), ErrorCode::AlreadyTeamMember);
+        let count = self.member_count as usize;
+        require!(count < MAX_TEAM_MEMBERS, ErrorCode::TeamFull);
+        self.members[count] = TeamMember { member, units: 0 };
+        self.member_count += 1;
+        Ok(())
+    }
+
+    /// Drop member `index`, keeping the rest in order
+    pub fn remove(&mut self, index: usize) {
+        let count = self.member_count as usize;
+        self.members.copy_within(index + 1..count, index);
+        self.members[count - 1] = TeamMember::default();
+        self.member_count -= 1;
+    }
+
+    /// Share units held across all members
+    pub fn total_units(&self) -> Result<u64> {
+        let mut total: u64 = 0;
+        for m in self.members[..self.member_count as usize].iter() {
+            total = total.checked_add(m.units).ok_or(ErrorCode::Overflow)?;
+        }
+        Ok(total)
+    }
+
+    /// Credit member `index` with units for depositing `amount`, priced
+    /// against the balance before it lands: `amount * total units / balance`,
+    /// rounded down, or `amount` itself when there are no units or no
+    /// balance. Units left over once the balance is spent to zero are worth
+    /// nothing, so they are cleared rather than diluting the deposit.
+    pub fn mint_units(&mut self, index: usize, amount: u64) -> Result<()> {
+        if self.balance == 0 {
+            for m in self.members.iter_mut() {
+                m.units = 0;
+            }
+        }
+        let total = self.total_units()?;
+        let units = if total == 0 {
+            amount
+        } else {
+            let units = (amount as u128)
+                .checked_mul(total as u128)
+                .ok_or(ErrorCode::Overflow)?
+                / self.balance as u128;
+            u64::try_from(units).map_err(|_| ErrorCode::Overflow)?
+        };
+        self.members[index].units = self.members[index]
+            .units
+            .checked_add(units)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Member `index`'s pro-rata share of the balance, rounded down
+    pub fn share_of(&self, index: usize) -> Result<u64> {
+        let total = self.total_units()?;
+        if total == 0 {
+            return Ok(0);
+        }
+        let share = (self.balance as u128)
+            .checked_mul(self.members[index].units as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / total as u128;
+        Ok(u64::try_from(share).map_err(|_| ErrorCode::Overflow)?)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TeamMember {
+    pub member: Pubkey,      // 32 bytes
+    pub units: u64,          // 8 bytes - Share units, minted on deposit
+}
+
+impl TeamMember {
+    pub const SIZE: usize = 32 + 8; // 40 bytes
+}
+
+/// A match found under a team, kept at ["team_match", team, index]
+#[account]
+pub struct TeamMatchRecord {
+    pub team: Pubkey,            // 32 bytes - Team the match counts for
+    pub member: Pubkey,          // 32 bytes - Member who submitted it
+    pub index: u32,              // 4 bytes  - Team's match count at the time
+    pub address: Pubkey,         // 32 bytes - Matching address
+    pub timestamp: i64,          // 8 bytes  - When it was recorded
+    pub pattern: VanityPattern,  // 25 bytes - Pattern it matched
+    pub batch_id: u64,           // 8 bytes  - Mining batch that found it
+    pub bump: u8,                // 1 byte   - PDA bump seed
+}
+
+impl TeamMatchRecord {
+    pub const SIZE: usize = 32 + 32 + 4 + 32 + 8 + VanityPattern::SIZE + 8 + 1; // 142 bytes
+}
+
+/// Protocol-wide totals, so a stats endpoint needs no event indexing.
+/// Deposits and withdrawals count native GOR only; charges count at their
+/// GOR price whichever balance pays.
+/// Every user-facing instruction writes this one account, so transactions
+/// touching it are serialized by the runtime; if that contention starts to
+/// limit throughput, the updates should become best-effort behind a config
+/// flag rather than be spread over sharded counters.
+#[account]
+pub struct GlobalStats {
+    pub total_users: u64,        // 8 bytes  - Open mining accounts
+    pub active_miners: u64,      // 8 bytes  - Accounts with is_active set
+    pub total_deposited: u128,   // 16 bytes - Lifetime GOR lamports deposited
+    pub total_withdrawn: u128,   // 16 bytes - Lifetime GOR lamports withdrawn
+    pub total_spent: u128,       // 16 bytes - Lifetime GOR lamports charged
+    pub total_matches: u64,      // 8 bytes  - Lifetime recorded matches
+    pub bump: u8,                // 1 byte   - PDA bump seed
+}
+
+impl GlobalStats {
+    pub const SIZE: usize = 8 + 8 + 16 + 16 + 16 + 8 + 1; // 73 bytes
+}
+
+/// Top miners by matches found, best first. Ties keep the miner who got
+/// there first. `total_spent` is as of the miner's latest match, since
+/// charges do not touch the board. Entries are not dropped when an account
+/// closes or changes hands; `rebuild_leaderboard` clears such drift.
+#[account]
+pub struct Leaderboard {
+    pub count: u8,                                       // 1 byte      - Filled entries
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],   // 1100 bytes  - Ranked, first `count` used
+    pub bump: u8,                                        // 1 byte      - PDA bump seed
+}
+
+impl Leaderboard {
+    pub const SIZE: usize = 1 + LeaderboardEntry::SIZE * LEADERBOARD_SIZE + 1; // 1102 bytes
+
+    /// Put `user` at its rank for `matches_found`, replacing any entry it
+    /// already has. When the board is full the last entry drops off, unless
+    /// `user` does not beat it. One pass to remove and one to insert, so
+    /// the cost stays linear in `LEADERBOARD_SIZE`.
+    pub fn update(&mut self, user: Pubkey, matches_found: u32, total_spent: u64) {
+        let mut len = self.count as usize;
+        if let Some(i) = self.entries[..len].iter().position(|e| e.user == user) {
+            self.entries.copy_within(i + 1..len, i);
+            len -= 1;
+        }
+
+        let pos = self.entries[..len]
+            .iter()
+            .position(|e| e.matches_found < matches_found)
+            .unwrap_or(len);
+        if pos < LEADERBOARD_SIZE {
+            let end = len.min(LEADERBOARD_SIZE - 1);
+            self.entries.copy_within(pos..end, pos + 1);
+            self.entries[pos] = LeaderboardEntry {
+                user,
+                matches_found,
+                total_spent,
+            };
+            len = (len + 1).min(LEADERBOARD_SIZE);
+        }
+        self.count = len as u8;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LeaderboardEntry {
+    pub user: Pubkey,          // 32 bytes - Mining account owner
+    pub matches_found: u32,    // 4 bytes  - Rank key
+    pub total_spent: u64,      // 8 bytes  - Lifetime GOR lamports charged
+}
 
-        emit!(MatchFound {
-            user: ctx.accounts.user.key(),
-            address,
-            timestamp: Clock::get()?.unix_timestamp,
-            total_matches: mining_account.matches_found,
-        });
+impl LeaderboardEntry {
+    pub const SIZE: usize = 32 + 4 + 8; // 44 bytes
+}
+
+/// Prefix and/or suffix an address must have in base58
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VanityPattern {
+    pub prefix: String,
+    pub suffix: String,
+    pub case_sensitive: bool,
+}
+
+impl VanityPattern {
+    pub const SIZE: usize = (4 + MAX_PATTERN_LEN) * 2 + 1; // 25 bytes
 
+    /// At least one part, each at most `MAX_PATTERN_LEN` characters that
+    /// can appear in an address (either case, when case-insensitive).
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.prefix.is_empty() || !self.suffix.is_empty(),
+            ErrorCode::InvalidPattern
+        );
+        for part in [&self.prefix, &self.suffix] {
+            require!(part.len() <= MAX_PATTERN_LEN, ErrorCode::InvalidPattern);
+            let valid = part.bytes().all(|c| {
+                BASE58_ALPHABET.contains(&c)
+                    || (!self.case_sensitive
+                        && (BASE58_ALPHABET.contains(&c.to_ascii_lowercase())
+                            || BASE58_ALPHABET.contains(&c.to_ascii_uppercase())))
+            });
+            require!(valid, ErrorCode::InvalidPattern);
+        }
         Ok(())
     }
+
+    /// Whether the base58 form of `address` has the prefix and suffix.
+    pub fn matches(&self, address: &Pubkey) -> bool {
+        let mut buf = [0u8; MAX_BASE58_LEN];
+        let len = base58_encode(&address.to_bytes(), &mut buf);
+        let encoded = &buf[..len];
+
+        let (prefix, suffix) = (self.prefix.as_bytes(), self.suffix.as_bytes());
+        if prefix.len() > len || suffix.len() > len {
+            return false;
+        }
+        let head = &encoded[..prefix.len()];
+        let tail = &encoded[len - suffix.len()..];
+        if self.case_sensitive {
+            head == prefix && tail == suffix
+        } else {
+            head.eq_ignore_ascii_case(prefix) && tail.eq_ignore_ascii_case(suffix)
+        }
+    }
 }
 
-// === Account Structs ===
+// === Events ===
 
-#[derive(Accounts)]
-pub struct InitializeUser<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+#[event]
+pub struct UserInitialized {
+    pub user: Pubkey,
+    pub mining_account: Pubkey,
+    pub timestamp: i64,
+}
 
-    #[account(
-        init,
-        payer = user,
-        space = 8 + MiningAccount::SIZE,
-        seeds = [b"mining", user.key().as_ref()],
-        bump
-    )]
-    pub mining_account: Account<'info, MiningAccount>,
+#[event]
+pub struct ConfigInitializedEvent {
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct MiningStarted {
+    pub user: Pubkey,
+    pub deadline: i64,
+    pub budget: u64,
 }
 
-#[derive(Accounts)]
-pub struct Deposit<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+/// `spent` is how much of the session's `budget` was charged
+#[event]
+pub struct MiningStopped {
+    pub user: Pubkey,
+    pub spent: u64,
+    pub budget: u64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"mining", user.key().as_ref()],
-        bump = mining_account.bump,
-        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
-    )]
-    pub mining_account: Account<'info, MiningAccount>,
+#[event]
+pub struct DepositEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
 
-    /// CHECK: PDA vault for holding deposited GOR. Validated by seeds.
-    #[account(
-        mut,
-        seeds = [b"vault"],
-        bump
-    )]
-    pub vault: UncheckedAccount<'info>,
+/// `gross_cost` is the GOR price of the batch before the volume discount
+/// and `cost` after it; `paid`, `referral` and `remaining_balance` are in
+/// the unit of the balance charged (sGOR base units when `from_spl`).
+#[event]
+pub struct BatchChargedEvent {
+    pub user: Pubkey,
+    pub batch_id: u64,
+    pub attempts: u64,
+    pub gross_cost: u64,
+    pub cost: u64,
+    pub from_spl: bool,
+    pub paid: u64,
+    pub referral: u64,
+    pub remaining_balance: u64,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct BatchRefunded {
+    pub user: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub reference_id: u64,
+    pub new_balance: u64,
+    pub total_spent: u64,
 }
 
-#[derive(Accounts)]
-pub struct ChargeForBatch<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+#[event]
+pub struct SplDepositEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"mining", user.key().as_ref()],
-        bump = mining_account.bump,
-        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
-    )]
-    pub mining_account: Account<'info, MiningAccount>,
+#[event]
+pub struct SplWithdrawEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+}
 
-    /// CHECK: PDA vault. Validated by seeds.
-    #[account(
-        mut,
-        seeds = [b"vault"],
-        bump
-    )]
-    pub vault: UncheckedAccount<'info>,
+#[event]
+pub struct WithdrawEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+}
 
-    /// CHECK: Platform treasury wallet. Hardcoded address check.
-    #[account(
-        mut,
-        constraint = treasury.key().to_string() == TREASURY @ ErrorCode::InvalidTreasury,
-    )]
-    pub treasury: UncheckedAccount<'info>,
+#[event]
+pub struct AuthorityUpdatedEvent {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct ProgramPausedEvent {
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct Withdraw<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+#[event]
+pub struct ProgramUnpausedEvent {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"mining", user.key().as_ref()],
-        bump = mining_account.bump,
-        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
-    )]
-    pub mining_account: Account<'info, MiningAccount>,
+#[event]
+pub struct TreasuryUpdatedEvent {
+    pub authority: Pubkey,
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+}
 
-    /// CHECK: PDA vault. Validated by seeds.
-    #[account(
-        mut,
-        seeds = [b"vault"],
-        bump
-    )]
-    pub vault: UncheckedAccount<'info>,
+#[event]
+pub struct VaultMigratedEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+}
 
-    pub system_program: Program<'info, System>,
+/// `swept` is the balance the treasury took; `inactive_secs` is how long the
+/// account had gone unused
+#[event]
+pub struct AccountSwept {
+    pub user: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub swept: u64,
+    pub last_activity_ts: i64,
+    pub inactive_secs: i64,
+    pub total_spent: u64,
+    pub matches_found: u32,
 }
 
-#[derive(Accounts)]
-pub struct RecordMatch<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+#[event]
+pub struct AccountClosed {
+    pub user: Pubkey,
+    pub refunded: u64,
+    pub total_spent: u64,
+    pub matches_found: u32,
+}
 
-    #[account(
-        mut,
-        seeds = [b"mining", user.key().as_ref()],
-        bump = mining_account.bump,
-        constraint = mining_account.owner == user.key() @ ErrorCode::Unauthorized,
-    )]
-    pub mining_account: Account<'info, MiningAccount>,
+#[event]
+pub struct PricingUpdatedEvent {
+    pub authority: Pubkey,
+    pub old_cost_per_million_attempts: u64,
+    pub old_flat_fee: u64,
+    pub old_referral_bps: u16,
+    pub cost_per_million_attempts: u64,
+    pub flat_fee: u64,
+    pub referral_bps: u16,
 }
 
-// === State ===
+#[event]
+pub struct MaxChargeUpdatedEvent {
+    pub authority: Pubkey,
+    pub old_max_charge_per_call: u64,
+    pub max_charge_per_call: u64,
+}
 
-#[account]
-pub struct MiningAccount {
-    pub owner: Pubkey,       // 32 bytes
-    pub balance: u64,        // 8 bytes - GOR lamports
-    pub total_spent: u64,    // 8 bytes - Lifetime spent
-    pub matches_found: u32,  // 4 bytes - Total matches
-    pub is_active: bool,     // 1 byte  - Currently mining
-    pub bump: u8,            // 1 byte  - PDA bump seed
+#[event]
+pub struct SweepPeriodUpdatedEvent {
+    pub authority: Pubkey,
+    pub old_sweep_after_secs: i64,
+    pub sweep_after_secs: i64,
 }
 
-impl MiningAccount {
-    pub const SIZE: usize = 32 + 8 + 8 + 4 + 1 + 1; // 54 bytes
+#[event]
+pub struct DepositLimitsUpdatedEvent {
+    pub authority: Pubkey,
+    pub old_min_deposit: u64,
+    pub old_max_deposit_per_tx: u64,
+    pub min_deposit: u64,
+    pub max_deposit_per_tx: u64,
 }
 
-// === Events ===
+#[event]
+pub struct LeaderboardRebuiltEvent {
+    pub authority: Pubkey,
+    pub reset: bool,
+    pub accounts: u32,
+    pub count: u8,
+}
 
 #[event]
-pub struct DepositEvent {
+pub struct DiscountTiersUpdatedEvent {
+    pub authority: Pubkey,
+    pub old_tiers: Vec<DiscountTier>,
+    pub tiers: Vec<DiscountTier>,
+}
+
+#[event]
+pub struct SubscriptionTiersUpdatedEvent {
+    pub authority: Pubkey,
+    pub old_tiers: Vec<SubscriptionTier>,
+    pub tiers: Vec<SubscriptionTier>,
+}
+
+#[event]
+pub struct SubscriptionPurchased {
+    pub user: Pubkey,
+    pub tier: u8,
+    pub price: u64,
+    pub expiry_ts: i64,
+    pub remaining_balance: u64,
+}
+
+#[event]
+pub struct SplConfigUpdatedEvent {
+    pub authority: Pubkey,
+    pub sgor_mint: Pubkey,
+    pub old_spl_per_gor: u64,
+    pub spl_per_gor: u64,
+}
+
+#[event]
+pub struct TransferProposedEvent {
+    pub user: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct TransferCancelledEvent {
+    pub user: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnershipTransferredEvent {
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub balance: u64,
+}
+
+#[event]
+pub struct SessionKeySetEvent {
+    pub user: Pubkey,
+    pub key: Pubkey,
+    pub max_spend: u64,
+    pub expiry_ts: i64,
+}
+
+#[event]
+pub struct SessionKeyRevokedEvent {
+    pub user: Pubkey,
+    pub key: Pubkey,
+}
+
+#[event]
+pub struct MatchFound {
     pub user: Pubkey,
+    pub address: String,
+    pub timestamp: i64,
+    pub total_matches: u32,
+}
+
+#[event]
+pub struct JobCreated {
+    pub job: Pubkey,
+    pub creator: Pubkey,
+    pub reward: u64,
+    pub expiry_slot: u64,
+    pub delivery_timeout_slots: u64,
+}
+
+#[event]
+pub struct MatchCommitted {
+    pub job: Pubkey,
+    pub miner: Pubkey,
+    pub matched: Pubkey,
+    pub encrypted_key_hash: [u8; 32],
+    pub release_slot: u64,
+}
+
+#[event]
+pub struct DeliveryDisputed {
+    pub job: Pubkey,
+    pub creator: Pubkey,
+    pub miner: Pubkey,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub job: Pubkey,
+    pub authority: Pubkey,
+    pub to_miner: bool,
+    pub amount: u64,
+}
+
+#[event]
+pub struct JobFilled {
+    pub job: Pubkey,
+    pub miner: Pubkey,
+    pub matched: Pubkey,
+    pub reward: u64,
+}
+
+#[event]
+pub struct JobCancelled {
+    pub job: Pubkey,
+    pub creator: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct TeamCreated {
+    pub team: Pubkey,
+    pub founder: Pubkey,
+    pub members: Vec<Pubkey>,
+}
+
+#[event]
+pub struct TeamMemberAdded {
+    pub team: Pubkey,
+    pub member: Pubkey,
+}
+
+#[event]
+pub struct TeamMemberRemoved {
+    pub team: Pubkey,
+    pub member: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct TeamDeposit {
+    pub team: Pubkey,
+    pub member: Pubkey,
     pub amount: u64,
     pub new_balance: u64,
 }
 
 #[event]
-pub struct BatchChargedEvent {
-    pub user: Pubkey,
+pub struct TeamBatchCharged {
+    pub team: Pubkey,
+    pub batch_id: u64,
+    pub attempts: u64,
+    pub gross_cost: u64,
     pub cost: u64,
     pub remaining_balance: u64,
 }
 
 #[event]
-pub struct WithdrawEvent {
-    pub user: Pubkey,
+pub struct TeamWithdrawal {
+    pub team: Pubkey,
+    pub member: Pubkey,
     pub amount: u64,
+    pub remaining_balance: u64,
 }
 
 #[event]
-pub struct MatchFound {
-    pub user: Pubkey,
+pub struct TeamMatchFound {
+    pub team: Pubkey,
+    pub member: Pubkey,
     pub address: String,
     pub timestamp: i64,
     pub total_matches: u32,
@@ -329,4 +4509,116 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Invalid treasury address")]
     InvalidTreasury,
+    #[msg("Unauthorized: not the platform authority")]
+    UnauthorizedAuthority,
+    #[msg("Mining account already uses a per-user vault")]
+    AlreadyMigrated,
+    #[msg("Batch cost exceeds the per-call cap")]
+    CostAboveCap,
+    #[msg("Pattern needs a prefix or suffix of up to 8 base58 characters")]
+    InvalidPattern,
+    #[msg("Expiry slot must be in the future")]
+    InvalidExpiry,
+    #[msg("Job has expired")]
+    JobExpired,
+    #[msg("Job has not expired yet")]
+    JobNotExpired,
+    #[msg("Job has already been filled")]
+    JobAlreadyFilled,
+    #[msg("Address does not match the pattern")]
+    PatternMismatch,
+    #[msg("Match rejected: address longer than 44 characters")]
+    MatchAddressTooLong,
+    #[msg("Match rejected: address is not base58 of a 32-byte key")]
+    MatchAddressInvalid,
+    #[msg("Already mining")]
+    AlreadyMining,
+    #[msg("Not mining")]
+    NotMining,
+    #[msg("Users cannot refer themselves")]
+    SelfReferral,
+    #[msg("Referrer account does not match the mining account")]
+    InvalidReferrer,
+    #[msg("Referral share cannot exceed 10000 bps")]
+    InvalidReferralBps,
+    #[msg("Mint is not the configured sGOR mint")]
+    InvalidMint,
+    #[msg("The sGOR mint cannot be changed once set")]
+    SgorMintLocked,
+    #[msg("SPL accounts are required to charge the sGOR balance")]
+    SplAccountMissing,
+    #[msg("Withdraw the sGOR balance first")]
+    SplBalanceNotEmpty,
+    #[msg("Deposit is below the minimum")]
+    DepositTooSmall,
+    #[msg("Deposit is above the per-transaction cap")]
+    DepositTooLarge,
+    #[msg("Minimum deposit cannot exceed the cap")]
+    InvalidDepositLimits,
+    #[msg("Too many discount tiers")]
+    TooManyDiscountTiers,
+    #[msg("Discount tiers must have increasing thresholds and non-decreasing discounts of at most 10000 bps")]
+    InvalidDiscountTiers,
+    #[msg("Invalid new owner")]
+    InvalidNewOwner,
+    #[msg("Invalid session key")]
+    InvalidSessionKey,
+    #[msg("No session key is set")]
+    NoSessionKey,
+    #[msg("Session key has expired")]
+    SessionExpired,
+    #[msg("Session key spend cap reached")]
+    SessionSpendExceeded,
+    #[msg("Withdrawal destination cannot be the vault")]
+    InvalidDestination,
+    #[msg("Vault holds less than its tracked balance plus rent reserve")]
+    VaultInsolvent,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Program is not paused")]
+    ProgramNotPaused,
+    #[msg("Refund exceeds the user's lifetime spend")]
+    RefundExceedsSpend,
+    #[msg("Batch was already charged")]
+    DuplicateBatch,
+    #[msg("Batch receipt is not old enough to close")]
+    ReceiptNotExpired,
+    #[msg("Mining session has expired")]
+    MiningSessionExpired,
+    #[msg("Mining session budget spent")]
+    SessionBudgetExceeded,
+    #[msg("Batch cost exceeds the configured per-call charge cap")]
+    ChargeTooLarge,
+    #[msg("Charge cap must be between 1 lamport and the hard batch cost cap")]
+    InvalidChargeCap,
+    #[msg("Delivery timeout must be at least one slot")]
+    InvalidDeliveryTimeout,
+    #[msg("Job is not in the right state for this")]
+    InvalidJobStatus,
+    #[msg("Job reward is held pending delivery")]
+    JobInEscrow,
+    #[msg("Not the job's miner")]
+    InvalidMiner,
+    #[msg("Creator can still confirm or dispute delivery")]
+    DeliveryWindowOpen,
+    #[msg("Delivery window has closed")]
+    DeliveryWindowClosed,
+    #[msg("Too many subscription tiers")]
+    TooManySubscriptionTiers,
+    #[msg("Subscription tiers need a price and a duration")]
+    InvalidSubscriptionTiers,
+    #[msg("No such subscription tier")]
+    InvalidSubscriptionTier,
+    #[msg("Team already has the most members allowed")]
+    TeamFull,
+    #[msg("Already a member of the team")]
+    AlreadyTeamMember,
+    #[msg("Not a member of the team")]
+    NotTeamMember,
+    #[msg("The founder cannot be removed from the team")]
+    CannotRemoveFounder,
+    #[msg("Sweep period is below the hard-coded minimum")]
+    SweepPeriodTooShort,
+    #[msg("Account has been active within the sweep period")]
+    AccountNotAbandoned,
 }
@@ -0,0 +1,3139 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { VanityMiner } from "../target/types/vanity_miner";
+
+const TREASURY = new PublicKey("TMABDMgLHfmmRNyHgbHTP9P5XP1zrAMFfbRAef69o9f");
+const BPF_LOADER_UPGRADEABLE = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+describe("vanity_miner", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.VanityMiner as Program<VanityMiner>;
+
+  // Platform authority = provider wallet
+  const user = Keypair.generate();
+  const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+  const [programDataPDA] = PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE);
+  const [pricingPDA] = PublicKey.findProgramAddressSync([Buffer.from("pricing")], program.programId);
+  const [statsPDA] = PublicKey.findProgramAddressSync([Buffer.from("stats")], program.programId);
+  const [leaderboardPDA] = PublicKey.findProgramAddressSync([Buffer.from("leaderboard")], program.programId);
+  const [miningPDA] = miningPDAOf(user.publicKey);
+  const [vaultPDA] = vaultPDAOf(user.publicKey);
+
+  function miningPDAOf(owner: PublicKey) {
+    return PublicKey.findProgramAddressSync([Buffer.from("mining"), owner.toBuffer()], program.programId);
+  }
+
+  function vaultPDAOf(owner: PublicKey) {
+    return PublicKey.findProgramAddressSync([Buffer.from("vault"), owner.toBuffer()], program.programId);
+  }
+
+  /// Open a mining account; with `mining`, also start a day-long session
+  /// with room for any charge the suite makes, so the account can be charged
+  async function initializeUser(owner: Keypair, referrer: PublicKey | null = null, mining = true) {
+    await program.methods
+      .initializeUser(referrer)
+      .accounts({
+        user: owner.publicKey,
+        miningAccount: miningPDAOf(owner.publicKey)[0],
+        vault: vaultPDAOf(owner.publicKey)[0],
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+    if (mining) await startSession(owner);
+  }
+
+  function startSession(owner: Keypair, maxDurationSecs = 86_400, maxSpend = 100 * LAMPORTS_PER_SOL) {
+    return program.methods
+      .startSession(new anchor.BN(maxDurationSecs), new anchor.BN(maxSpend))
+      .accounts({ user: owner.publicKey, miningAccount: miningPDAOf(owner.publicKey)[0], stats: statsPDA })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  function stopSession(owner: Keypair) {
+    return program.methods
+      .stopSession()
+      .accounts({ user: owner.publicKey, miningAccount: miningPDAOf(owner.publicKey)[0], stats: statsPDA })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  function deposit(owner: Keypair, amount: anchor.BN) {
+    return program.methods
+      .deposit(amount)
+      .accounts({
+        user: owner.publicKey,
+        config: configPDA,
+        miningAccount: miningPDAOf(owner.publicKey)[0],
+        vault: vaultPDAOf(owner.publicKey)[0],
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  function withdraw(owner: Keypair, destination: PublicKey | null = null) {
+    return program.methods
+      .withdraw()
+      .accounts({
+        user: owner.publicKey,
+        destination,
+        miningAccount: miningPDAOf(owner.publicKey)[0],
+        vault: vaultPDAOf(owner.publicKey)[0],
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+  }
+
+  function chargeUser(owner: PublicKey, attempts = ATTEMPTS, referrer: PublicKey | null = null) {
+    return program.methods
+      .chargeForBatch(attempts, false, nextBatchId())
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPDA,
+        pricing: pricingPDA,
+        user: owner,
+        miningAccount: miningPDAOf(owner)[0],
+        vault: vaultPDAOf(owner)[0],
+        treasury: TREASURY,
+        referrer,
+        ...NO_SPL_ACCOUNTS,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  // Charges paid in GOR leave the optional sGOR accounts out
+  const NO_SPL_ACCOUNTS = {
+    splConfig: null,
+    sgorMint: null,
+    splVault: null,
+    treasuryTokenAccount: null,
+    referrerTokenAccount: null,
+    tokenProgram: null,
+  };
+
+  // Priced at 1 lamport per attempt; above the rent-exempt minimum, so the
+  // first charge can open the treasury
+  const ATTEMPTS = new anchor.BN(1_000_000);
+  const COST = new anchor.BN(1_000_000);
+
+  // Each charge needs its own batch id, or it is rejected as a retry
+  let lastBatchId = 0;
+  const nextBatchId = () => new anchor.BN(++lastBatchId);
+
+  const parser = new anchor.EventParser(program.programId, program.coder);
+
+  /// Run `attempt` and require it to fail with `error`
+  async function expectRejected(attempt: () => Promise<unknown>, error: string) {
+    try {
+      await attempt();
+      assert.fail(`Should fail with ${error}`);
+    } catch (e: any) {
+      assert.include(e.message, error);
+    }
+  }
+
+  /// Unix time of the latest slot, the clock the program checks against
+  async function chainTime() {
+    const slot = await provider.connection.getSlot();
+    return (await provider.connection.getBlockTime(slot))!;
+  }
+
+  /// Decode the program events emitted by a confirmed transaction
+  async function eventsOf(signature: string) {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return [...parser.parseLogs(tx!.meta!.logMessages!)];
+  }
+
+  /// The data of the single `name` event emitted by a confirmed transaction
+  async function onlyEvent(signature: string, name: string) {
+    const events = (await eventsOf(signature)).filter((e) => e.name === name);
+    assert.equal(events.length, 1, `expected one ${name}`);
+    return events[0].data as any;
+  }
+
+  /// Generate keypairs until one's address satisfies `accept`
+  function grind(accept: (address: string) => boolean): Keypair {
+    for (;;) {
+      const kp = Keypair.generate();
+      if (accept(kp.publicKey.toBase58())) return kp;
+    }
+  }
+
+  function updatePricing(costPerMillion: number, flatFee: number, referralBps = 0) {
+    return program.methods
+      .updatePricing(new anchor.BN(costPerMillion), new anchor.BN(flatFee), referralBps)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPDA,
+        pricing: pricingPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function setPaused(paused: boolean, authority: Keypair | null = null) {
+    const builder = paused ? program.methods.pause() : program.methods.unpause();
+    return builder
+      .accounts({ authority: authority ? authority.publicKey : provider.wallet.publicKey, config: configPDA })
+      .signers(authority ? [authority] : [])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  function chargeForBatch(
+    authority: PublicKey,
+    signers: Keypair[] = [],
+    treasury = TREASURY,
+    vault = vaultPDA
+  ) {
+    return program.methods
+      .chargeForBatch(ATTEMPTS, false, nextBatchId())
+      .accounts({
+        authority,
+        config: configPDA,
+        pricing: pricingPDA,
+        user: user.publicKey,
+        miningAccount: miningPDA,
+        vault,
+        treasury,
+        referrer: null,
+        ...NO_SPL_ACCOUNTS,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers(signers)
+      .rpc();
+  }
+
+  function initializeConfig(authority: PublicKey, treasury: PublicKey, payer?: Keypair) {
+    const builder = program.methods.initializeConfig(authority, treasury).accounts({
+      payer: payer ? payer.publicKey : provider.wallet.publicKey,
+      config: configPDA,
+      program: program.programId,
+      programData: programDataPDA,
+      systemProgram: SystemProgram.programId,
+    });
+    return payer ? builder.signers([payer]).rpc() : builder.rpc();
+  }
+
+  // The config can only be created once, so attempts by anyone but the
+  // upgrade authority are made before it exists and checked below
+  let userInitError = "";
+  let treasuryInitError = "";
+
+  before(async () => {
+    const sig = await provider.connection.requestAirdrop(user.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+
+    try {
+      await initializeConfig(user.publicKey, TREASURY, user);
+    } catch (e: any) {
+      userInitError = e.message;
+    }
+    try {
+      await initializeConfig(provider.wallet.publicKey, user.publicKey, user);
+    } catch (e: any) {
+      treasuryInitError = e.message;
+    }
+    await initializeConfig(provider.wallet.publicKey, TREASURY);
+    await updatePricing(COST.toNumber(), 0);
+    await program.methods
+      .initializeStats()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPDA,
+        stats: statsPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .initializeLeaderboard()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        config: configPDA,
+        leaderboard: leaderboardPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await initializeUser(user);
+    await deposit(user, new anchor.BN(LAMPORTS_PER_SOL));
+  });
+
+  it("stores the platform authority and treasury", async () => {
+    const config = await program.account.config.fetch(configPDA);
+    assert.ok(config.authority.equals(provider.wallet.publicKey));
+    assert.ok(config.treasury.equals(TREASURY));
+  });
+
+  it("only lets the upgrade authority create the config", async () => {
+    assert.include(userInitError, "UnauthorizedAuthority");
+  });
+
+  it("does not let anyone else pick the first treasury", async () => {
+    assert.include(treasuryInitError, "UnauthorizedAuthority");
+    const config = await program.account.config.fetch(configPDA);
+    assert.ok(config.treasury.equals(TREASURY));
+  });
+
+  it("does not let the user charge their own balance", async () => {
+    try {
+      await chargeForBatch(user.publicKey, [user]);
+      assert.fail("Should require the platform authority");
+    } catch (e: any) {
+      assert.include(e.message, "UnauthorizedAuthority");
+    }
+    const account = await program.account.miningAccount.fetch(miningPDA);
+    assert.equal(account.balance.toString(), LAMPORTS_PER_SOL.toString());
+  });
+
+  it("lets the authority charge without the user's signature", async () => {
+    const treasuryBefore = await provider.connection.getBalance(TREASURY);
+    await chargeForBatch(provider.wallet.publicKey);
+
+    const account = await program.account.miningAccount.fetch(miningPDA);
+    assert.equal(account.balance.toString(), new anchor.BN(LAMPORTS_PER_SOL).sub(COST).toString());
+    assert.equal(account.totalSpent.toString(), COST.toString());
+    assert.equal(await provider.connection.getBalance(TREASURY), treasuryBefore + COST.toNumber());
+  });
+
+  it("rejects a mining account that belongs to another user", async () => {
+    const other = Keypair.generate();
+    try {
+      await program.methods
+        .chargeForBatch(ATTEMPTS, false, nextBatchId())
+        .accounts({
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          user: other.publicKey,
+          miningAccount: miningPDA,
+          vault: vaultPDA,
+          treasury: TREASURY,
+          referrer: null,
+          ...NO_SPL_ACCOUNTS,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Should reject a mismatched user");
+    } catch (e: any) {
+      assert.include(e.message, "ConstraintSeeds");
+    }
+  });
+
+  it("hands the authority over", async () => {
+    const next = Keypair.generate();
+    await program.methods
+      .setAuthority(next.publicKey)
+      .accounts({ authority: provider.wallet.publicKey, config: configPDA })
+      .rpc();
+    try {
+      await chargeForBatch(provider.wallet.publicKey);
+      assert.fail("Old authority should be rejected");
+    } catch (e: any) {
+      assert.include(e.message, "UnauthorizedAuthority");
+    }
+    await program.methods
+      .setAuthority(provider.wallet.publicKey)
+      .accounts({ authority: next.publicKey, config: configPDA })
+      .signers([next])
+      .rpc();
+  });
+
+  it("rejects a charge paid to a treasury other than the config's", async () => {
+    try {
+      await chargeForBatch(provider.wallet.publicKey, [], Keypair.generate().publicKey);
+      assert.fail("Should reject the wrong treasury");
+    } catch (e: any) {
+      assert.include(e.message, "InvalidTreasury");
+    }
+  });
+
+  it("rotates the treasury", async () => {
+    const next = Keypair.generate().publicKey;
+    await program.methods
+      .updateTreasury(next)
+      .accounts({ authority: provider.wallet.publicKey, config: configPDA })
+      .rpc();
+    try {
+      await chargeForBatch(provider.wallet.publicKey);
+      assert.fail("Old treasury should be rejected");
+    } catch (e: any) {
+      assert.include(e.message, "InvalidTreasury");
+    }
+
+    await chargeForBatch(provider.wallet.publicKey, [], next);
+    assert.equal(await provider.connection.getBalance(next), COST.toNumber());
+
+    await program.methods
+      .updateTreasury(TREASURY)
+      .accounts({ authority: provider.wallet.publicKey, config: configPDA })
+      .rpc();
+  });
+
+  it("only lets the authority rotate the treasury", async () => {
+    try {
+      await program.methods
+        .updateTreasury(user.publicKey)
+        .accounts({ authority: user.publicKey, config: configPDA })
+        .signers([user])
+        .rpc();
+      assert.fail("Should require the platform authority");
+    } catch (e: any) {
+      assert.include(e.message, "UnauthorizedAuthority");
+    }
+  });
+
+  it("keeps two users' deposits in separate vaults", async () => {
+    const alice = Keypair.generate();
+    const bob = Keypair.generate();
+    for (const kp of [alice, bob]) {
+      const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(kp);
+    }
+    const reserve = await provider.connection.getMinimumBalanceForRentExemption(0);
+    const vaultOf = (kp: Keypair) => provider.connection.getBalance(vaultPDAOf(kp.publicKey)[0]);
+    assert.equal(await vaultOf(alice), reserve);
+
+    await deposit(alice, new anchor.BN(2 * LAMPORTS_PER_SOL));
+    await deposit(bob, new anchor.BN(LAMPORTS_PER_SOL));
+    await chargeUser(alice.publicKey);
+    await chargeUser(bob.publicKey);
+    await deposit(bob, new anchor.BN(LAMPORTS_PER_SOL));
+
+    // Alice leaves first; Bob's vault is untouched
+    await withdraw(alice);
+    assert.equal(await vaultOf(alice), reserve);
+    assert.equal(await vaultOf(bob), reserve + 2 * LAMPORTS_PER_SOL - COST.toNumber());
+
+    // Bob can still be charged down to zero and withdraw the rest
+    await chargeUser(bob.publicKey);
+    const bobAccount = await program.account.miningAccount.fetch(miningPDAOf(bob.publicKey)[0]);
+    assert.equal(
+      bobAccount.balance.toString(),
+      new anchor.BN(2 * LAMPORTS_PER_SOL).sub(COST).sub(COST).toString()
+    );
+    await withdraw(bob);
+    assert.equal(await vaultOf(bob), reserve);
+
+    const aliceAccount = await program.account.miningAccount.fetch(miningPDAOf(alice.publicKey)[0]);
+    assert.equal(aliceAccount.balance.toString(), "0");
+    assert.equal(aliceAccount.totalSpent.toString(), COST.toString());
+  });
+
+  it("cannot charge one user through another user's vault", async () => {
+    const other = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(other.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    await initializeUser(other);
+    await deposit(other, new anchor.BN(LAMPORTS_PER_SOL));
+    try {
+      await chargeForBatch(provider.wallet.publicKey, [], TREASURY, vaultPDAOf(other.publicKey)[0]);
+      assert.fail("Should reject a foreign vault");
+    } catch (e: any) {
+      assert.include(e.message, "ConstraintSeeds");
+    }
+  });
+
+  it("refuses to migrate an account that already has its own vault", async () => {
+    try {
+      await program.methods
+        .migrateVault()
+        .accounts({
+          user: user.publicKey,
+          miningAccount: miningPDA,
+          legacyVault: PublicKey.findProgramAddressSync([Buffer.from("vault")], program.programId)[0],
+          vault: vaultPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+      assert.fail("Should reject a migrated account");
+    } catch (e: any) {
+      assert.include(e.message, "AlreadyMigrated");
+    }
+  });
+
+  it("ends a migrated account's legacy session and leaves active_miners balanced", async () => {
+    // Preloaded from tests/fixtures/legacy-mining-account.json: a pre-vault
+    // account with a session running and 0.002 GOR in the shared vault
+    const legacy = Keypair.fromSeed(new Uint8Array(32).fill(46));
+    const sig = await provider.connection.requestAirdrop(legacy.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    const before = await program.account.globalStats.fetch(statsPDA);
+
+    await program.methods
+      .migrateVault()
+      .accounts({
+        user: legacy.publicKey,
+        miningAccount: miningPDAOf(legacy.publicKey)[0],
+        legacyVault: PublicKey.findProgramAddressSync([Buffer.from("vault")], program.programId)[0],
+        vault: vaultPDAOf(legacy.publicKey)[0],
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([legacy])
+      .rpc({ commitment: "confirmed" });
+
+    const migrated = await program.account.miningAccount.fetch(miningPDAOf(legacy.publicKey)[0]);
+    assert.isFalse(migrated.isActive);
+    assert.equal(migrated.miningDeadline.toNumber(), 0);
+    assert.equal(migrated.balance.toNumber(), 2_000_000);
+    let stats = await program.account.globalStats.fetch(statsPDA);
+    assert.equal(stats.totalUsers.toNumber(), before.totalUsers.toNumber() + 1);
+    assert.equal(stats.activeMiners.toNumber(), before.activeMiners.toNumber());
+
+    try {
+      await stopSession(legacy);
+      assert.fail("Should have no session to stop");
+    } catch (e: any) {
+      assert.include(e.message, "NotMining");
+    }
+
+    await startSession(legacy);
+    await stopSession(legacy);
+    stats = await program.account.globalStats.fetch(statsPDA);
+    assert.equal(stats.activeMiners.toNumber(), before.activeMiners.toNumber());
+  });
+
+  it("closes the mining account and lets the user start over", async () => {
+    const owner = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(owner.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    await initializeUser(owner);
+    await deposit(owner, new anchor.BN(LAMPORTS_PER_SOL));
+    await chargeUser(owner.publicKey);
+
+    const [mining] = miningPDAOf(owner.publicKey);
+    const [vault] = vaultPDAOf(owner.publicKey);
+    const reclaimable =
+      (await provider.connection.getBalance(mining)) + (await provider.connection.getBalance(vault));
+    const ownerBefore = await provider.connection.getBalance(owner.publicKey);
+
+    let event: any = null;
+    const listener = program.addEventListener("accountClosed", (e) => {
+      if (e.user.equals(owner.publicKey)) event = e;
+    });
+    try {
+      await program.methods
+        .closeAccount()
+        .accounts({ user: owner.publicKey, miningAccount: mining, vault, systemProgram: SystemProgram.programId })
+        .signers([owner])
+        .rpc();
+      await new Promise((r) => setTimeout(r, 1000));
+    } finally {
+      await program.removeEventListener(listener);
+    }
+
+    assert.isNull(await provider.connection.getAccountInfo(mining));
+    assert.equal(await provider.connection.getBalance(vault), 0);
+    // Everything comes back except the transaction fee
+    const ownerAfter = await provider.connection.getBalance(owner.publicKey);
+    assert.isAtLeast(ownerAfter - ownerBefore, reclaimable - 10_000);
+    assert.equal(event.refunded.toString(), new anchor.BN(LAMPORTS_PER_SOL).sub(COST).toString());
+    assert.equal(event.totalSpent.toString(), COST.toString());
+    assert.equal(event.matchesFound, 0);
+
+    await initializeUser(owner);
+    const fresh = await program.account.miningAccount.fetch(mining);
+    assert.equal(fresh.balance.toString(), "0");
+    assert.equal(fresh.totalSpent.toString(), "0");
+    assert.equal(
+      await provider.connection.getBalance(vault),
+      await provider.connection.getMinimumBalanceForRentExemption(0)
+    );
+  });
+
+  it("prices a batch on-chain with a flat fee, rounding up", async () => {
+    await updatePricing(1_500, 100_000);
+    try {
+      const before = await program.account.miningAccount.fetch(miningPDA);
+      let event: any = null;
+      const listener = program.addEventListener("batchChargedEvent", (e) => {
+        if (e.user.equals(user.publicKey)) event = e;
+      });
+      try {
+        // 1_001 attempts * 1_500 / 1e6 = 1.5015 -> 2 lamports, plus the fee
+        await chargeUser(user.publicKey, new anchor.BN(1_001));
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+      const after = await program.account.miningAccount.fetch(miningPDA);
+      assert.equal(before.balance.sub(after.balance).toString(), "100002");
+      assert.equal(event.attempts.toString(), "1001");
+      assert.equal(event.cost.toString(), "100002");
+    } finally {
+      await updatePricing(COST.toNumber(), 0);
+    }
+  });
+
+  it("rejects an empty batch", async () => {
+    try {
+      await chargeUser(user.publicKey, new anchor.BN(0));
+      assert.fail("Should reject zero attempts");
+    } catch (e: any) {
+      assert.include(e.message, "InvalidAmount");
+    }
+  });
+
+  it("caps the cost of a single charge", async () => {
+    // 1_000_000_001 attempts at 1 lamport each is just over 1 GOR
+    try {
+      await chargeUser(user.publicKey, new anchor.BN(1_000_000_001));
+      assert.fail("Should reject a charge above the cap");
+    } catch (e: any) {
+      assert.include(e.message, "CostAboveCap");
+    }
+  });
+
+  it("only lets the authority set pricing", async () => {
+    try {
+      await program.methods
+        .updatePricing(new anchor.BN(0), new anchor.BN(0), 0)
+        .accounts({
+          authority: user.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+      assert.fail("Should require the platform authority");
+    } catch (e: any) {
+      assert.include(e.message, "UnauthorizedAuthority");
+    }
+  });
+
+  // ─── Job bounties ─────────────────────────────────────────────────
+  describe("jobs", () => {
+    const REWARD = new anchor.BN(LAMPORTS_PER_SOL / 2);
+    const KEY_HASH = [...Buffer.alloc(32, 7)];
+    const miner = Keypair.generate();
+
+    function jobPDAOf(jobId: number) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("job"), provider.wallet.publicKey.toBuffer(), new anchor.BN(jobId).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    }
+
+    async function createJob(
+      jobId: number,
+      prefix: string,
+      suffix: string,
+      caseSensitive: boolean,
+      slots = 1_000,
+      deliveryTimeoutSlots = 1_000
+    ) {
+      const slot = await provider.connection.getSlot();
+      await program.methods
+        .createJob(
+          new anchor.BN(jobId),
+          { prefix, suffix, caseSensitive },
+          REWARD,
+          new anchor.BN(slot + slots),
+          new anchor.BN(deliveryTimeoutSlots)
+        )
+        .accounts({
+          creator: provider.wallet.publicKey,
+          job: jobPDAOf(jobId),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      return jobPDAOf(jobId);
+    }
+
+    function submitMatch(job: PublicKey, candidate: Keypair) {
+      return program.methods
+        .submitMatchCommitment(KEY_HASH)
+        .accounts({ miner: miner.publicKey, candidate: candidate.publicKey, job })
+        .signers([miner, candidate])
+        .rpc();
+    }
+
+    function confirmDelivery(job: PublicKey) {
+      return program.methods
+        .confirmDelivery()
+        .accounts({ creator: provider.wallet.publicKey, miner: miner.publicKey, job })
+        .rpc();
+    }
+
+    function dispute(job: PublicKey) {
+      return program.methods.dispute().accounts({ creator: provider.wallet.publicKey, job }).rpc();
+    }
+
+    function releaseAfterTimeout(job: PublicKey) {
+      return program.methods.releaseAfterTimeout().accounts({ miner: miner.publicKey, job }).rpc();
+    }
+
+    function resolveDispute(job: PublicKey, toMiner: boolean, authority: Keypair | null = null) {
+      return program.methods
+        .resolveDispute(toMiner)
+        .accounts({
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+          creator: provider.wallet.publicKey,
+          miner: miner.publicKey,
+          job,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+    }
+
+    function cancelJob(job: PublicKey) {
+      return program.methods.cancelJob().accounts({ creator: provider.wallet.publicKey, job }).rpc();
+    }
+
+    async function waitForSlot(target: number) {
+      while ((await provider.connection.getSlot()) < target) {
+        await new Promise((resolve) => setTimeout(resolve, 200));
+      }
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(miner.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+    });
+
+    it("locks the reward to the miner of a matching address", async () => {
+      const job = await createJob(1, "A", "z", true);
+      const candidate = grind((a) => a.startsWith("A") && a.endsWith("z"));
+      const held = await provider.connection.getBalance(job);
+
+      await submitMatch(job, candidate);
+
+      const committed = await program.account.job.fetch(job);
+      assert.deepEqual(committed.status, { committed: {} });
+      assert.ok(committed.miner.equals(miner.publicKey));
+      assert.ok(committed.matched.equals(candidate.publicKey));
+      assert.deepEqual([...committed.keyHash], KEY_HASH);
+      assert.isAbove(committed.committedSlot.toNumber(), 0);
+      // Nothing moves until delivery is settled
+      assert.equal(await provider.connection.getBalance(job), held);
+
+      await expectRejected(() => submitMatch(job, candidate), "JobAlreadyFilled");
+    });
+
+    it("pays the miner when the creator confirms delivery", async () => {
+      const job = jobPDAOf(1);
+      const minerBefore = await provider.connection.getBalance(miner.publicKey);
+
+      await confirmDelivery(job);
+
+      assert.deepEqual((await program.account.job.fetch(job)).status, { released: {} });
+      assert.equal((await provider.connection.getBalance(miner.publicKey)) - minerBefore, REWARD.toNumber());
+      await expectRejected(() => confirmDelivery(job), "InvalidJobStatus");
+      await expectRejected(() => dispute(job), "InvalidJobStatus");
+
+      // A paid-out job can be closed for its rent straight away
+      await cancelJob(job);
+      assert.isNull(await provider.connection.getAccountInfo(job));
+    });
+
+    it("rejects a near miss", async () => {
+      const job = await createJob(2, "ab", "", true);
+      const candidate = grind((a) => a.startsWith("a") && !a.startsWith("ab"));
+      await expectRejected(() => submitMatch(job, candidate), "PatternMismatch");
+    });
+
+    it("respects the case-sensitivity flag", async () => {
+      const candidate = grind((a) => a.startsWith("B"));
+
+      const strict = await createJob(3, "b", "", true);
+      await expectRejected(() => submitMatch(strict, candidate), "PatternMismatch");
+
+      const relaxed = await createJob(4, "b", "", false);
+      await submitMatch(relaxed, candidate);
+      assert.deepEqual((await program.account.job.fetch(relaxed)).status, { committed: {} });
+    });
+
+    it("rejects patterns that cannot appear in an address", async () => {
+      for (const [jobId, prefix] of [[5, "0"], [6, "ABCDEFGHJ"], [7, ""]] as [number, string][]) {
+        await expectRejected(() => createJob(jobId, prefix, "", true), "InvalidPattern");
+      }
+    });
+
+    it("refunds an unclaimed job only after expiry", async () => {
+      const job = await createJob(8, "zzzzzzzz", "", true, 5);
+      await expectRejected(() => cancelJob(job), "JobNotExpired");
+
+      const expiry = (await program.account.job.fetch(job)).expirySlot.toNumber();
+      await waitForSlot(expiry + 1);
+      await expectRejected(() => submitMatch(job, grind((a) => a.startsWith("z"))), "JobExpired");
+
+      const held = await provider.connection.getBalance(job);
+      const before = await provider.connection.getBalance(provider.wallet.publicKey);
+      await cancelJob(job);
+      assert.isNull(await provider.connection.getAccountInfo(job));
+      const after = await provider.connection.getBalance(provider.wallet.publicKey);
+      assert.isAtLeast(after - before, held - 10_000);
+    });
+
+    it("releases the reward to the miner once the creator goes silent", async () => {
+      const job = await createJob(9, "C", "", true, 1_000, 8);
+      await submitMatch(job, grind((a) => a.startsWith("C")));
+      const { committedSlot, deliveryTimeoutSlots } = await program.account.job.fetch(job);
+      const releaseSlot = committedSlot.add(deliveryTimeoutSlots).toNumber();
+
+      await expectRejected(() => releaseAfterTimeout(job), "DeliveryWindowOpen");
+      await expectRejected(() => cancelJob(job), "JobInEscrow");
+
+      await waitForSlot(releaseSlot);
+      await expectRejected(() => dispute(job), "DeliveryWindowClosed");
+      const minerBefore = await provider.connection.getBalance(miner.publicKey);
+      await releaseAfterTimeout(job);
+      assert.equal((await provider.connection.getBalance(miner.publicKey)) - minerBefore, REWARD.toNumber());
+      assert.deepEqual((await program.account.job.fetch(job)).status, { released: {} });
+    });
+
+    it("freezes a disputed reward until the authority settles it", async () => {
+      const refunded = await createJob(10, "D", "", true, 1_000, 8);
+      await submitMatch(refunded, grind((a) => a.startsWith("D")));
+      await dispute(refunded);
+      assert.deepEqual((await program.account.job.fetch(refunded)).status, { disputed: {} });
+
+      // Neither side can move the reward on their own, even after the timeout
+      const { committedSlot, deliveryTimeoutSlots } = await program.account.job.fetch(refunded);
+      await waitForSlot(committedSlot.add(deliveryTimeoutSlots).toNumber());
+      await expectRejected(() => releaseAfterTimeout(refunded), "InvalidJobStatus");
+      await expectRejected(() => confirmDelivery(refunded), "InvalidJobStatus");
+      await expectRejected(() => cancelJob(refunded), "JobInEscrow");
+      await expectRejected(() => resolveDispute(refunded, true, user), "UnauthorizedAuthority");
+
+      const creatorBefore = await provider.connection.getBalance(provider.wallet.publicKey);
+      await resolveDispute(refunded, false);
+      assert.deepEqual((await program.account.job.fetch(refunded)).status, { refunded: {} });
+      assert.isAtLeast(
+        (await provider.connection.getBalance(provider.wallet.publicKey)) - creatorBefore,
+        REWARD.toNumber() - 10_000
+      );
+      await expectRejected(() => resolveDispute(refunded, true), "InvalidJobStatus");
+
+      const released = await createJob(11, "E", "", true);
+      await submitMatch(released, grind((a) => a.startsWith("E")));
+      await dispute(released);
+      const minerBefore = await provider.connection.getBalance(miner.publicKey);
+      await resolveDispute(released, true);
+      assert.equal((await provider.connection.getBalance(miner.publicKey)) - minerBefore, REWARD.toNumber());
+      assert.deepEqual((await program.account.job.fetch(released)).status, { released: {} });
+    });
+  });
+
+  // ─── Recorded matches ─────────────────────────────────────────────
+  describe("record_match", () => {
+    const pattern = { prefix: "C", suffix: "", caseSensitive: true };
+
+    function matchRecordPDAOf(owner: PublicKey, index: number) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("match"), owner.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 4)],
+        program.programId
+      )[0];
+    }
+
+    async function recordMatch(address: string, authority: Keypair | null = null, batchId = 0) {
+      const index = (await program.account.miningAccount.fetch(miningPDA)).matchesFound;
+      return program.methods
+        .recordMatch(address, pattern, new anchor.BN(batchId))
+        .accounts({
+          signer: user.publicKey,
+          user: user.publicKey,
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+          miningAccount: miningPDA,
+          matchRecord: matchRecordPDAOf(user.publicKey, index),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(authority ? [user, authority] : [user])
+        .rpc();
+    }
+
+    async function expectMatchRejected(address: string, error: string) {
+      const before = (await program.account.miningAccount.fetch(miningPDA)).matchesFound;
+      await expectRejected(() => recordMatch(address), error);
+      assert.equal((await program.account.miningAccount.fetch(miningPDA)).matchesFound, before);
+    }
+
+    it("records a matching address co-signed by the authority", async () => {
+      const before = (await program.account.miningAccount.fetch(miningPDA)).matchesFound;
+      await recordMatch(grind((a) => a.startsWith("C")).publicKey.toBase58());
+      const after = (await program.account.miningAccount.fetch(miningPDA)).matchesFound;
+      assert.equal(after, before + 1);
+    });
+
+    it("rejects a match the authority did not co-sign", async () => {
+      try {
+        await recordMatch(grind((a) => a.startsWith("C")).publicKey.toBase58(), user);
+        assert.fail("Should require the platform authority");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAuthority");
+      }
+    });
+
+    it("rejects an oversized address", async () => {
+      await expectMatchRejected("C".repeat(45), "MatchAddressTooLong");
+    });
+
+    it("rejects strings that are not a 32-byte key", async () => {
+      await expectMatchRejected("C0OIl", "MatchAddressInvalid");
+      await expectMatchRejected("C".repeat(20), "MatchAddressInvalid");
+      await expectMatchRejected("z".repeat(44), "MatchAddressInvalid");
+    });
+
+    it("rejects an address that does not match the pattern", async () => {
+      await expectMatchRejected(grind((a) => !a.startsWith("C")).publicKey.toBase58(), "PatternMismatch");
+    });
+
+    it("keeps each match in its own record, enumerable by index", async () => {
+      const first = (await program.account.miningAccount.fetch(miningPDA)).matchesFound;
+      const found = [grind((a) => a.startsWith("C")), grind((a) => a.startsWith("C"))];
+      await recordMatch(found[0].publicKey.toBase58(), null, 41);
+      await recordMatch(found[1].publicKey.toBase58(), null, 42);
+
+      const total = (await program.account.miningAccount.fetch(miningPDA)).matchesFound;
+      assert.equal(total, first + 2);
+      for (let i = 0; i < 2; i++) {
+        const record = await program.account.matchRecord.fetch(matchRecordPDAOf(user.publicKey, first + i));
+        assert.ok(record.user.equals(user.publicKey));
+        assert.equal(record.index, first + i);
+        assert.ok(record.address.equals(found[i].publicKey));
+        assert.equal(record.batchId.toNumber(), 41 + i);
+        assert.equal(record.pattern.prefix, "C");
+        assert.isAbove(record.timestamp.toNumber(), 0);
+      }
+
+      // Every index below matchesFound has a record
+      const all = await program.account.matchRecord.fetchMultiple(
+        Array.from({ length: total }, (_, i) => matchRecordPDAOf(user.publicKey, i))
+      );
+      assert.isTrue(all.every((record) => record !== null));
+    });
+
+    it("closes a match record and returns its rent", async () => {
+      const record = matchRecordPDAOf(user.publicKey, 0);
+      const rent = await provider.connection.getBalance(record);
+      const before = await provider.connection.getBalance(user.publicKey);
+      await program.methods
+        .closeMatchRecord()
+        .accounts({ user: user.publicKey, matchRecord: record })
+        .signers([user])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(record));
+      const after = await provider.connection.getBalance(user.publicKey);
+      assert.isAtLeast(after - before, rent - 10_000);
+    });
+  });
+
+  // ─── Global stats ─────────────────────────────────────────────────
+  describe("stats", () => {
+    function setMining(owner: Keypair, active: boolean) {
+      return active ? startSession(owner) : stopSession(owner);
+    }
+
+    it("accounts exactly for a scripted multi-user session", async () => {
+      const before = await program.account.globalStats.fetch(statsPDA);
+      const [a, b, c] = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+      for (const kp of [a, b, c]) {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, 10 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+        await initializeUser(kp, null, false);
+      }
+
+      await setMining(a, true);
+      await setMining(b, true);
+      await deposit(a, new anchor.BN(3 * LAMPORTS_PER_SOL));
+      await deposit(b, new anchor.BN(LAMPORTS_PER_SOL));
+      await deposit(c, new anchor.BN(2 * LAMPORTS_PER_SOL));
+      await chargeUser(a.publicKey);
+      await chargeUser(a.publicKey, new anchor.BN(2_500_000));
+      await chargeUser(b.publicKey);
+      await withdraw(c);
+
+      const found = grind((addr) => addr.startsWith("D"));
+      const [aMining] = miningPDAOf(a.publicKey);
+      await program.methods
+        .recordMatch(found.publicKey.toBase58(), { prefix: "D", suffix: "", caseSensitive: true }, new anchor.BN(1))
+        .accounts({
+          signer: a.publicKey,
+          user: a.publicKey,
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          miningAccount: aMining,
+          matchRecord: PublicKey.findProgramAddressSync(
+            [Buffer.from("match"), a.publicKey.toBuffer(), Buffer.alloc(4)],
+            program.programId
+          )[0],
+          stats: statsPDA,
+          leaderboard: leaderboardPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([a])
+        .rpc();
+
+      await setMining(a, false);
+      try {
+        await setMining(a, false);
+        assert.fail("Should not stop twice");
+      } catch (e: any) {
+        assert.include(e.message, "NotMining");
+      }
+
+      // b closes while still mining, taking its remaining balance with it
+      await program.methods
+        .closeAccount()
+        .accounts({
+          user: b.publicKey,
+          miningAccount: miningPDAOf(b.publicKey)[0],
+          vault: vaultPDAOf(b.publicKey)[0],
+          stats: statsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([b])
+        .rpc();
+
+      const after = await program.account.globalStats.fetch(statsPDA);
+      const delta = (field: keyof typeof after) => (after[field] as anchor.BN).sub(before[field] as anchor.BN).toString();
+      const spent = 1_000_000 + 2_500_000 + 1_000_000;
+      assert.equal(delta("totalUsers"), "2"); // three opened, one closed
+      assert.equal(delta("activeMiners"), "0"); // a stopped, b closed
+      assert.equal(delta("totalDeposited"), (6 * LAMPORTS_PER_SOL).toString());
+      assert.equal(delta("totalSpent"), spent.toString());
+      // c withdrew everything, b's remainder left on close
+      assert.equal(delta("totalWithdrawn"), (2 * LAMPORTS_PER_SOL + LAMPORTS_PER_SOL - 1_000_000).toString());
+      assert.equal(delta("totalMatches"), "1");
+    });
+  });
+
+  describe("referrals", () => {
+    const referrer = Keypair.generate();
+    const referred = Keypair.generate();
+
+    before(async () => {
+      for (const kp of [referrer, referred]) {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+      await initializeUser(referred, referrer.publicKey);
+      await deposit(referred, new anchor.BN(LAMPORTS_PER_SOL));
+    });
+
+    it("rejects self-referral", async () => {
+      const kp = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      try {
+        await initializeUser(kp, kp.publicKey);
+        assert.fail("Should reject self-referral");
+      } catch (e: any) {
+        assert.include(e.message, "SelfReferral");
+      }
+    });
+
+    it("stores the referrer", async () => {
+      const account = await program.account.miningAccount.fetch(miningPDAOf(referred.publicKey)[0]);
+      assert.ok(account.referrer.equals(referrer.publicKey));
+      const plain = await program.account.miningAccount.fetch(miningPDA);
+      assert.ok(plain.referrer.equals(PublicKey.default));
+    });
+
+    it("pays the referral share to the referrer", async () => {
+      await updatePricing(COST.toNumber(), 0, 2_500);
+      try {
+        const referrerBefore = await provider.connection.getBalance(referrer.publicKey);
+        const treasuryBefore = await provider.connection.getBalance(TREASURY);
+        let event: any = null;
+        const listener = program.addEventListener("batchChargedEvent", (e) => {
+          if (e.user.equals(referred.publicKey)) event = e;
+        });
+        try {
+          await chargeUser(referred.publicKey, ATTEMPTS, referrer.publicKey);
+          await new Promise((r) => setTimeout(r, 1000));
+        } finally {
+          await program.removeEventListener(listener);
+        }
+
+        const share = COST.muln(2_500).divn(10_000);
+        assert.equal(
+          (await provider.connection.getBalance(referrer.publicKey)) - referrerBefore,
+          share.toNumber()
+        );
+        assert.equal(
+          (await provider.connection.getBalance(TREASURY)) - treasuryBefore,
+          COST.sub(share).toNumber()
+        );
+        assert.equal(event.cost.toString(), COST.toString());
+        assert.equal(event.referral.toString(), share.toString());
+      } finally {
+        await updatePricing(COST.toNumber(), 0);
+      }
+    });
+
+    it("requires the stored referrer account", async () => {
+      await updatePricing(COST.toNumber(), 0, 2_500);
+      try {
+        for (const wrong of [null, Keypair.generate().publicKey]) {
+          try {
+            await chargeUser(referred.publicKey, ATTEMPTS, wrong);
+            assert.fail("Should require the stored referrer");
+          } catch (e: any) {
+            assert.include(e.message, "InvalidReferrer");
+          }
+        }
+      } finally {
+        await updatePricing(COST.toNumber(), 0);
+      }
+    });
+
+    it("does not need the referrer account when there is no share", async () => {
+      const treasuryBefore = await provider.connection.getBalance(TREASURY);
+      await chargeUser(referred.publicKey, ATTEMPTS, null);
+      assert.equal(
+        (await provider.connection.getBalance(TREASURY)) - treasuryBefore,
+        COST.toNumber()
+      );
+    });
+
+    it("keeps a share too small to fund a new referrer in the treasury", async () => {
+      const unfunded = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(unfunded.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      const owner = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(owner.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+      await initializeUser(owner, unfunded.publicKey);
+      await deposit(owner, new anchor.BN(LAMPORTS_PER_SOL));
+
+      // Drain the referrer so the share alone can't make it rent-exempt
+      const balance = await provider.connection.getBalance(unfunded.publicKey);
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: unfunded.publicKey,
+            toPubkey: provider.wallet.publicKey,
+            lamports: balance,
+          })
+        ),
+        [unfunded]
+      );
+
+      await updatePricing(COST.toNumber(), 0, 2_500);
+      try {
+        const treasuryBefore = await provider.connection.getBalance(TREASURY);
+        await chargeUser(owner.publicKey, ATTEMPTS, unfunded.publicKey);
+        assert.equal(await provider.connection.getBalance(unfunded.publicKey), 0);
+        assert.equal(
+          (await provider.connection.getBalance(TREASURY)) - treasuryBefore,
+          COST.toNumber()
+        );
+      } finally {
+        await updatePricing(COST.toNumber(), 0);
+      }
+    });
+
+    it("sends the full cost to the treasury without a referrer", async () => {
+      await updatePricing(COST.toNumber(), 0, 2_500);
+      try {
+        const treasuryBefore = await provider.connection.getBalance(TREASURY);
+        await chargeUser(user.publicKey);
+        assert.equal(
+          (await provider.connection.getBalance(TREASURY)) - treasuryBefore,
+          COST.toNumber()
+        );
+      } finally {
+        await updatePricing(COST.toNumber(), 0);
+      }
+    });
+
+    it("rejects a referral share above 100%", async () => {
+      try {
+        await updatePricing(COST.toNumber(), 0, 10_001);
+        assert.fail("Should reject more than 10000 bps");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidReferralBps");
+      }
+    });
+  });
+
+  describe("sGOR balance", () => {
+    const owner = Keypair.generate();
+    const [splConfigPDA] = PublicKey.findProgramAddressSync([Buffer.from("spl_config")], program.programId);
+    const [splVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("spl_vault"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    let mint: PublicKey;
+    let ownerTokens: PublicKey;
+    let treasuryTokens: PublicKey;
+
+    function updateSplConfig(sgorMint: PublicKey, splPerGor: number) {
+      return program.methods
+        .updateSplConfig(new anchor.BN(splPerGor))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          splConfig: splConfigPDA,
+          sgorMint,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    function depositSpl(amount: number, sgorMint = mint) {
+      return program.methods
+        .depositSpl(new anchor.BN(amount))
+        .accounts({
+          user: owner.publicKey,
+          miningAccount: miningPDAOf(owner.publicKey)[0],
+          vault: vaultPDAOf(owner.publicKey)[0],
+          splConfig: splConfigPDA,
+          sgorMint,
+          splVault,
+          userTokenAccount: ownerTokens,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    }
+
+    function chargeSpl() {
+      return program.methods
+        .chargeForBatch(ATTEMPTS, true, nextBatchId())
+        .accounts({
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          user: owner.publicKey,
+          miningAccount: miningPDAOf(owner.publicKey)[0],
+          vault: vaultPDAOf(owner.publicKey)[0],
+          treasury: TREASURY,
+          referrer: null,
+          splConfig: splConfigPDA,
+          sgorMint: mint,
+          splVault,
+          treasuryTokenAccount: treasuryTokens,
+          referrerTokenAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(owner.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(owner);
+
+      mint = await createMint(provider.connection, payer, payer.publicKey, null, 9);
+      ownerTokens = (await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, owner.publicKey))
+        .address;
+      treasuryTokens = (await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, TREASURY))
+        .address;
+      await mintTo(provider.connection, payer, mint, ownerTokens, payer, 10_000_000);
+
+      // 2 sGOR base units per lamport
+      await updateSplConfig(mint, 2 * LAMPORTS_PER_SOL);
+    });
+
+    it("credits sGOR deposits to the SPL balance only", async () => {
+      await depositSpl(5_000_000);
+      const account = await program.account.miningAccount.fetch(miningPDAOf(owner.publicKey)[0]);
+      assert.equal(account.splBalance.toString(), "5000000");
+      assert.equal(account.balance.toString(), "0");
+      assert.equal((await getAccount(provider.connection, splVault)).amount.toString(), "5000000");
+    });
+
+    it("rejects a mint other than the configured one", async () => {
+      const other = await createMint(provider.connection, payer, payer.publicKey, null, 9);
+      try {
+        await depositSpl(1, other);
+        assert.fail("Should reject a foreign mint");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidMint");
+      }
+      try {
+        await updateSplConfig(other, LAMPORTS_PER_SOL);
+        assert.fail("Should not change the mint once set");
+      } catch (e: any) {
+        assert.include(e.message, "SgorMintLocked");
+      }
+    });
+
+    it("charges the sGOR balance at the configured rate", async () => {
+      const treasuryBefore = Number((await getAccount(provider.connection, treasuryTokens)).amount);
+      const statsBefore = await program.account.globalStats.fetch(statsPDA);
+      await chargeSpl();
+
+      const paid = COST.muln(2);
+      const account = await program.account.miningAccount.fetch(miningPDAOf(owner.publicKey)[0]);
+      assert.equal(account.splBalance.toString(), new anchor.BN(5_000_000).sub(paid).toString());
+      assert.equal(account.balance.toString(), "0");
+      assert.equal(account.totalSpent.toString(), COST.toString());
+      const treasuryAfter = Number((await getAccount(provider.connection, treasuryTokens)).amount);
+      assert.equal(treasuryAfter - treasuryBefore, paid.toNumber());
+      const statsAfter = await program.account.globalStats.fetch(statsPDA);
+      assert.equal(statsAfter.totalSpent.sub(statsBefore.totalSpent).toString(), COST.toString());
+    });
+
+    it("rejects a charge above the SPL balance", async () => {
+      // 3_000_000 left; each batch costs 2_000_000
+      await chargeSpl();
+      try {
+        await chargeSpl();
+        assert.fail("Should reject a charge above the balance");
+      } catch (e: any) {
+        assert.include(e.message, "InsufficientBalance");
+      }
+    });
+
+    it("rejects sGOR deposits and charges while paused", async () => {
+      await setPaused(true);
+      try {
+        for (const attempt of [() => depositSpl(1_000_000), chargeSpl]) {
+          try {
+            await attempt();
+            assert.fail("Should be paused");
+          } catch (e: any) {
+            assert.include(e.message, "ProgramPaused");
+          }
+        }
+      } finally {
+        await setPaused(false);
+      }
+      const account = await program.account.miningAccount.fetch(miningPDAOf(owner.publicKey)[0]);
+      assert.equal(account.splBalance.toString(), "1000000");
+    });
+
+    it("keeps the account open until the sGOR balance is withdrawn", async () => {
+      const closeAccount = () =>
+        program.methods
+          .closeAccount()
+          .accounts({
+            user: owner.publicKey,
+            miningAccount: miningPDAOf(owner.publicKey)[0],
+            vault: vaultPDAOf(owner.publicKey)[0],
+            stats: statsPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+
+      try {
+        await closeAccount();
+        assert.fail("Should require withdrawing sGOR first");
+      } catch (e: any) {
+        assert.include(e.message, "SplBalanceNotEmpty");
+      }
+
+      const before = Number((await getAccount(provider.connection, ownerTokens)).amount);
+      await program.methods
+        .withdrawSpl()
+        .accounts({
+          user: owner.publicKey,
+          miningAccount: miningPDAOf(owner.publicKey)[0],
+          vault: vaultPDAOf(owner.publicKey)[0],
+          splConfig: splConfigPDA,
+          sgorMint: mint,
+          splVault,
+          userTokenAccount: ownerTokens,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc();
+      const after = Number((await getAccount(provider.connection, ownerTokens)).amount);
+      assert.equal(after - before, 1_000_000);
+      assert.isNull(await provider.connection.getAccountInfo(splVault));
+
+      await closeAccount();
+    });
+  });
+
+  describe("deposit limits", () => {
+    const MIN = 10_000;
+    const MAX = 50_000;
+
+    function updateDepositLimits(
+      min: number,
+      max: number,
+      authority = provider.wallet.publicKey,
+      signers: Keypair[] = []
+    ) {
+      return program.methods
+        .updateDepositLimits(new anchor.BN(min), new anchor.BN(max))
+        .accounts({ authority, config: configPDA })
+        .signers(signers)
+        .rpc();
+    }
+
+    before(async () => {
+      await updateDepositLimits(MIN, MAX);
+    });
+
+    after(async () => {
+      await updateDepositLimits(0, 0);
+    });
+
+    it("accepts deposits of exactly the minimum and the cap", async () => {
+      const before = await program.account.miningAccount.fetch(miningPDA);
+      await deposit(user, new anchor.BN(MIN));
+      await deposit(user, new anchor.BN(MAX));
+      const after = await program.account.miningAccount.fetch(miningPDA);
+      assert.equal(after.balance.sub(before.balance).toNumber(), MIN + MAX);
+    });
+
+    it("rejects a deposit just below the minimum", async () => {
+      try {
+        await deposit(user, new anchor.BN(MIN - 1));
+        assert.fail("Should reject a deposit below the minimum");
+      } catch (e: any) {
+        assert.include(e.message, "DepositTooSmall");
+      }
+    });
+
+    it("rejects a deposit just above the cap", async () => {
+      try {
+        await deposit(user, new anchor.BN(MAX + 1));
+        assert.fail("Should reject a deposit above the cap");
+      } catch (e: any) {
+        assert.include(e.message, "DepositTooLarge");
+      }
+    });
+
+    it("treats a zero cap as unlimited", async () => {
+      await updateDepositLimits(MIN, 0);
+      try {
+        await deposit(user, new anchor.BN(MAX + 1));
+      } finally {
+        await updateDepositLimits(MIN, MAX);
+      }
+    });
+
+    it("rejects a minimum above the cap", async () => {
+      try {
+        await updateDepositLimits(MAX + 1, MAX);
+        assert.fail("Should reject inverted limits");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidDepositLimits");
+      }
+    });
+
+    it("only lets the authority set the limits", async () => {
+      try {
+        await updateDepositLimits(0, 0, user.publicKey, [user]);
+        assert.fail("Should require the platform authority");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAuthority");
+      }
+    });
+  });
+
+  describe("discount tiers", () => {
+    const spender = Keypair.generate();
+
+    function updateDiscountTiers(tiers: { threshold: anchor.BN; discountBps: number }[]) {
+      return program.methods
+        .updateDiscountTiers(tiers)
+        .accounts({ authority: provider.wallet.publicKey, config: configPDA, pricing: pricingPDA })
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(spender.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(spender);
+      await deposit(spender, new anchor.BN(LAMPORTS_PER_SOL));
+    });
+
+    after(async () => {
+      await updateDiscountTiers([]);
+    });
+
+    it("prices the charge that crosses a threshold at the tier below", async () => {
+      // Half price from 1.5 batches of lifetime spend
+      await updateDiscountTiers([
+        { threshold: COST.muln(3).divn(2), discountBps: 5_000 },
+      ]);
+
+      const events: any[] = [];
+      const listener = program.addEventListener("batchChargedEvent", (e) => {
+        if (e.user.equals(spender.publicKey)) events.push(e);
+      });
+      try {
+        await chargeUser(spender.publicKey); // 0 -> 1 batch spent
+        await chargeUser(spender.publicKey); // 1 -> 2, crosses the threshold
+        await chargeUser(spender.publicKey); // discounted
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      assert.equal(events.length, 3);
+      assert.deepEqual(
+        events.map((e) => e.cost.toString()),
+        [COST.toString(), COST.toString(), COST.divn(2).toString()]
+      );
+      for (const e of events) assert.equal(e.grossCost.toString(), COST.toString());
+      const account = await program.account.miningAccount.fetch(miningPDAOf(spender.publicKey)[0]);
+      assert.equal(account.totalSpent.toString(), COST.muln(5).divn(2).toString());
+    });
+
+    it("applies the highest tier reached", async () => {
+      await updateDiscountTiers([
+        { threshold: new anchor.BN(0), discountBps: 1_000 },
+        { threshold: COST, discountBps: 2_000 },
+        { threshold: COST.muln(1_000), discountBps: 9_000 },
+      ]);
+      const before = await program.account.miningAccount.fetch(miningPDAOf(spender.publicKey)[0]);
+      await chargeUser(spender.publicKey);
+      const after = await program.account.miningAccount.fetch(miningPDAOf(spender.publicKey)[0]);
+      assert.equal(before.balance.sub(after.balance).toString(), COST.muln(8).divn(10).toString());
+    });
+
+    it("rejects tiers that are not monotonic", async () => {
+      for (const tiers of [
+        [
+          { threshold: COST, discountBps: 1_000 },
+          { threshold: COST, discountBps: 2_000 },
+        ],
+        [
+          { threshold: COST, discountBps: 2_000 },
+          { threshold: COST.muln(2), discountBps: 1_000 },
+        ],
+        [{ threshold: COST, discountBps: 10_001 }],
+      ]) {
+        try {
+          await updateDiscountTiers(tiers);
+          assert.fail("Should reject non-monotonic tiers");
+        } catch (e: any) {
+          assert.include(e.message, "InvalidDiscountTiers");
+        }
+      }
+    });
+
+    it("holds at most four tiers", async () => {
+      const tiers = [1, 2, 3, 4, 5].map((i) => ({ threshold: COST.muln(i), discountBps: i * 100 }));
+      try {
+        await updateDiscountTiers(tiers);
+        assert.fail("Should reject a fifth tier");
+      } catch (e: any) {
+        assert.include(e.message, "TooManyDiscountTiers");
+      }
+    });
+  });
+
+  describe("ownership transfer", () => {
+    const oldOwner = Keypair.generate();
+    const newOwner = Keypair.generate();
+    const transferPDAOf = (owner: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("transfer"), owner.toBuffer()], program.programId)[0];
+
+    function proposeTransfer(to: PublicKey) {
+      return program.methods
+        .proposeTransfer(to)
+        .accounts({
+          user: oldOwner.publicKey,
+          miningAccount: miningPDAOf(oldOwner.publicKey)[0],
+          transfer: transferPDAOf(oldOwner.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([oldOwner])
+        .rpc();
+    }
+
+    function acceptTransfer(signer: Keypair) {
+      return program.methods
+        .acceptTransfer()
+        .accounts({
+          newOwner: signer.publicKey,
+          user: oldOwner.publicKey,
+          transfer: transferPDAOf(oldOwner.publicKey),
+          miningAccount: miningPDAOf(oldOwner.publicKey)[0],
+          vault: vaultPDAOf(oldOwner.publicKey)[0],
+          newMiningAccount: miningPDAOf(signer.publicKey)[0],
+          newVault: vaultPDAOf(signer.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    before(async () => {
+      for (const kp of [oldOwner, newOwner]) {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+      await initializeUser(oldOwner);
+      await deposit(oldOwner, new anchor.BN(LAMPORTS_PER_SOL));
+      await chargeUser(oldOwner.publicKey);
+    });
+
+    it("rejects a transfer to the current owner", async () => {
+      try {
+        await proposeTransfer(oldOwner.publicKey);
+        assert.fail("Should reject a transfer to self");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidNewOwner");
+      }
+    });
+
+    it("cancels a pending proposal", async () => {
+      await proposeTransfer(newOwner.publicKey);
+      await program.methods
+        .cancelTransfer()
+        .accounts({ user: oldOwner.publicKey, transfer: transferPDAOf(oldOwner.publicKey) })
+        .signers([oldOwner])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(transferPDAOf(oldOwner.publicKey)));
+
+      try {
+        await acceptTransfer(newOwner);
+        assert.fail("Should not accept a cancelled proposal");
+      } catch (e: any) {
+        assert.include(e.message, "AccountNotInitialized");
+      }
+    });
+
+    it("only lets the proposed owner accept", async () => {
+      await proposeTransfer(newOwner.publicKey);
+      const stranger = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(stranger.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      try {
+        await acceptTransfer(stranger);
+        assert.fail("Should require the proposed owner");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+    });
+
+    it("moves the account, vault and history to the new owner", async () => {
+      const oldMining = miningPDAOf(oldOwner.publicKey)[0];
+      const before = await program.account.miningAccount.fetch(oldMining);
+      const vaultBefore = await provider.connection.getBalance(vaultPDAOf(oldOwner.publicKey)[0]);
+      const ownerBefore = await provider.connection.getBalance(oldOwner.publicKey);
+      const reclaimable =
+        (await provider.connection.getBalance(oldMining)) +
+        (await provider.connection.getBalance(transferPDAOf(oldOwner.publicKey)));
+
+      await acceptTransfer(newOwner);
+
+      const after = await program.account.miningAccount.fetch(miningPDAOf(newOwner.publicKey)[0]);
+      assert.ok(after.owner.equals(newOwner.publicKey));
+      assert.equal(after.balance.toString(), before.balance.toString());
+      assert.equal(after.totalSpent.toString(), before.totalSpent.toString());
+      assert.equal(after.matchesFound, before.matchesFound);
+      assert.equal(await provider.connection.getBalance(vaultPDAOf(newOwner.publicKey)[0]), vaultBefore);
+
+      assert.isNull(await provider.connection.getAccountInfo(oldMining));
+      assert.equal(await provider.connection.getBalance(vaultPDAOf(oldOwner.publicKey)[0]), 0);
+      assert.isNull(await provider.connection.getAccountInfo(transferPDAOf(oldOwner.publicKey)));
+      assert.equal((await provider.connection.getBalance(oldOwner.publicKey)) - ownerBefore, reclaimable);
+
+      // The moved balance is still spendable and withdrawable
+      await chargeUser(newOwner.publicKey);
+      await withdraw(newOwner);
+      assert.equal(
+        await provider.connection.getBalance(vaultPDAOf(newOwner.publicKey)[0]),
+        await provider.connection.getMinimumBalanceForRentExemption(0)
+      );
+    });
+  });
+
+  describe("session keys", () => {
+    const owner = Keypair.generate();
+    const session = Keypair.generate();
+    const [mining] = miningPDAOf(owner.publicKey);
+
+    async function setSessionKey(maxSpend: anchor.BN, expiryTs: number) {
+      await program.methods
+        .setSessionKey(session.publicKey, maxSpend, new anchor.BN(expiryTs))
+        .accounts({ user: owner.publicKey, miningAccount: mining })
+        .signers([owner])
+        .rpc();
+    }
+
+    function sessionCharge() {
+      return program.methods
+        .chargeForBatch(ATTEMPTS, false, nextBatchId())
+        .accounts({
+          authority: session.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          user: owner.publicKey,
+          miningAccount: mining,
+          vault: vaultPDAOf(owner.publicKey)[0],
+          treasury: TREASURY,
+          referrer: null,
+          ...NO_SPL_ACCOUNTS,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([session])
+        .rpc();
+    }
+
+    before(async () => {
+      for (const kp of [owner, session]) {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+      await initializeUser(owner);
+      await deposit(owner, new anchor.BN(LAMPORTS_PER_SOL));
+    });
+
+    it("charges up to the spend cap, then stops", async () => {
+      await setSessionKey(COST.muln(2), (await chainTime()) + 3_600);
+      await sessionCharge();
+      await sessionCharge();
+      try {
+        await sessionCharge();
+        assert.fail("Should stop at the spend cap");
+      } catch (e: any) {
+        assert.include(e.message, "SessionSpendExceeded");
+      }
+
+      const account = await program.account.miningAccount.fetch(mining);
+      assert.equal(account.sessionSpent.toString(), COST.muln(2).toString());
+      assert.equal(account.totalSpent.toString(), COST.muln(2).toString());
+
+      // The platform authority is not bound by the session's cap
+      await chargeUser(owner.publicKey);
+    });
+
+    it("rejects the session key after expiry", async () => {
+      await setSessionKey(COST.muln(10), (await chainTime()) + 2);
+      await new Promise((r) => setTimeout(r, 4_000));
+      try {
+        await sessionCharge();
+        assert.fail("Should reject an expired session key");
+      } catch (e: any) {
+        assert.include(e.message, "SessionExpired");
+      }
+    });
+
+    it("never lets the session key withdraw", async () => {
+      await setSessionKey(COST.muln(10), (await chainTime()) + 3_600);
+      try {
+        await program.methods
+          .withdraw()
+          .accounts({
+            user: session.publicKey,
+            destination: null,
+            miningAccount: mining,
+            vault: vaultPDAOf(owner.publicKey)[0],
+            stats: statsPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([session])
+          .rpc();
+        assert.fail("Should not withdraw with the session key");
+      } catch (e: any) {
+        assert.include(e.message, "ConstraintSeeds");
+      }
+    });
+
+    it("stops charging once revoked", async () => {
+      await program.methods
+        .revokeSessionKey()
+        .accounts({ user: owner.publicKey, miningAccount: mining })
+        .signers([owner])
+        .rpc();
+      try {
+        await sessionCharge();
+        assert.fail("Should reject a revoked session key");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAuthority");
+      }
+    });
+
+    it("rejects the owner's own key as a session key", async () => {
+      try {
+        await program.methods
+          .setSessionKey(owner.publicKey, COST, new anchor.BN((await chainTime()) + 3_600))
+          .accounts({ user: owner.publicKey, miningAccount: mining })
+          .signers([owner])
+          .rpc();
+        assert.fail("Should reject the owner as its own session key");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidSessionKey");
+      }
+    });
+  });
+
+  describe("withdrawal destination", () => {
+    const owner = Keypair.generate();
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(owner.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(owner);
+    });
+
+    it("sends the balance to the destination, not the signer", async () => {
+      const destination = Keypair.generate().publicKey;
+      await deposit(owner, new anchor.BN(LAMPORTS_PER_SOL));
+      const ownerBefore = await provider.connection.getBalance(owner.publicKey);
+
+      let event: any = null;
+      const listener = program.addEventListener("withdrawEvent", (e) => {
+        if (e.user.equals(owner.publicKey)) event = e;
+      });
+      try {
+        await withdraw(owner, destination);
+        await new Promise((r) => setTimeout(r, 1000));
+      } finally {
+        await program.removeEventListener(listener);
+      }
+
+      assert.equal(await provider.connection.getBalance(destination), LAMPORTS_PER_SOL);
+      assert.isAtMost(await provider.connection.getBalance(owner.publicKey), ownerBefore);
+      assert.ok(event.destination.equals(destination));
+      assert.equal(event.amount.toNumber(), LAMPORTS_PER_SOL);
+    });
+
+    it("rejects the vault as the destination", async () => {
+      await deposit(owner, new anchor.BN(LAMPORTS_PER_SOL));
+      try {
+        await withdraw(owner, vaultPDAOf(owner.publicKey)[0]);
+        assert.fail("Should reject the vault as destination");
+      } catch (e: any) {
+        assert.include(e.message, "InvalidDestination");
+      }
+    });
+
+    it("rejects a program-owned destination", async () => {
+      try {
+        await withdraw(owner, miningPDAOf(owner.publicKey)[0]);
+        assert.fail("Should reject a program-owned destination");
+      } catch (e: any) {
+        assert.include(e.message, "AccountNotSystemOwned");
+      }
+    });
+
+    it("defaults to the signer", async () => {
+      const ownerBefore = await provider.connection.getBalance(owner.publicKey);
+      await withdraw(owner);
+      // Less the transaction fee
+      assert.isAbove(
+        (await provider.connection.getBalance(owner.publicKey)) - ownerBefore,
+        LAMPORTS_PER_SOL - 10_000
+      );
+    });
+  });
+
+  describe("events", () => {
+
+    it("announces new users", async () => {
+      const newcomer = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(newcomer.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdrop);
+
+      const sig = await program.methods
+        .initializeUser(null)
+        .accounts({
+          user: newcomer.publicKey,
+          miningAccount: miningPDAOf(newcomer.publicKey)[0],
+          vault: vaultPDAOf(newcomer.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([newcomer])
+        .rpc({ commitment: "confirmed" });
+
+      const event = await onlyEvent(sig, "userInitialized");
+      assert.ok(event.user.equals(newcomer.publicKey));
+      assert.ok(event.miningAccount.equals(miningPDAOf(newcomer.publicKey)[0]));
+      assert.isAbove(event.timestamp.toNumber(), 0);
+    });
+
+    it("reports old and new pricing with the admin key", async () => {
+      const sig = await program.methods
+        .updatePricing(new anchor.BN(2_000_000), new anchor.BN(5), 100)
+        .accounts({ authority: provider.wallet.publicKey, config: configPDA, pricing: pricingPDA })
+        .rpc({ commitment: "confirmed" });
+      try {
+        const event = await onlyEvent(sig, "pricingUpdatedEvent");
+        assert.ok(event.authority.equals(provider.wallet.publicKey));
+        assert.equal(event.oldCostPerMillionAttempts.toString(), COST.toString());
+        assert.equal(event.oldFlatFee.toString(), "0");
+        assert.equal(event.oldReferralBps, 0);
+        assert.equal(event.costPerMillionAttempts.toString(), "2000000");
+        assert.equal(event.flatFee.toString(), "5");
+        assert.equal(event.referralBps, 100);
+      } finally {
+        await updatePricing(COST.toNumber(), 0);
+      }
+    });
+
+    it("reports treasury rotation with the admin key", async () => {
+      const next = Keypair.generate().publicKey;
+      const rotate = (to: PublicKey) =>
+        program.methods
+          .updateTreasury(to)
+          .accounts({ authority: provider.wallet.publicKey, config: configPDA })
+          .rpc({ commitment: "confirmed" });
+
+      const sig = await rotate(next);
+      try {
+        const event = await onlyEvent(sig, "treasuryUpdatedEvent");
+        assert.ok(event.authority.equals(provider.wallet.publicKey));
+        assert.ok(event.oldTreasury.equals(TREASURY));
+        assert.ok(event.newTreasury.equals(next));
+      } finally {
+        await rotate(TREASURY);
+      }
+    });
+
+    it("reports old and new deposit limits", async () => {
+      const setLimits = (min: number, max: number) =>
+        program.methods
+          .updateDepositLimits(new anchor.BN(min), new anchor.BN(max))
+          .accounts({ authority: provider.wallet.publicKey, config: configPDA })
+          .rpc({ commitment: "confirmed" });
+
+      await setLimits(1, 2);
+      const event = await onlyEvent(await setLimits(0, 0), "depositLimitsUpdatedEvent");
+      assert.ok(event.authority.equals(provider.wallet.publicKey));
+      assert.equal(event.oldMinDeposit.toNumber(), 1);
+      assert.equal(event.oldMaxDepositPerTx.toNumber(), 2);
+      assert.equal(event.minDeposit.toNumber(), 0);
+      assert.equal(event.maxDepositPerTx.toNumber(), 0);
+    });
+  });
+
+  describe("vault solvency", () => {
+    // Preloaded from tests/fixtures (see Anchor.toml): a mining account
+    // claiming 1 GOR over a vault that holds only its rent reserve
+    const skewed = Keypair.fromSeed(new Uint8Array(32).fill(42));
+    const [mining] = miningPDAOf(skewed.publicKey);
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(skewed.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await startSession(skewed);
+
+      const account = await program.account.miningAccount.fetch(mining);
+      assert.equal(account.balance.toNumber(), LAMPORTS_PER_SOL);
+      assert.equal(
+        await provider.connection.getBalance(vaultPDAOf(skewed.publicKey)[0]),
+        await provider.connection.getMinimumBalanceForRentExemption(0)
+      );
+    });
+
+    it("refuses a withdrawal the vault cannot cover", async () => {
+      try {
+        await withdraw(skewed);
+        assert.fail("Should refuse to pay out of the rent reserve");
+      } catch (e: any) {
+        assert.include(e.message, "VaultInsolvent");
+      }
+    });
+
+    it("refuses to charge an insolvent vault", async () => {
+      try {
+        await chargeUser(skewed.publicKey);
+        assert.fail("Should refuse to charge an insolvent vault");
+      } catch (e: any) {
+        assert.include(e.message, "VaultInsolvent");
+      }
+    });
+
+    it("refuses deposits that leave the vault insolvent", async () => {
+      // Half the shortfall is not enough to restore the invariant
+      try {
+        await deposit(skewed, new anchor.BN(LAMPORTS_PER_SOL / 2));
+        assert.fail("Should refuse a deposit into an insolvent vault");
+      } catch (e: any) {
+        assert.include(e.message, "VaultInsolvent");
+      }
+      const account = await program.account.miningAccount.fetch(mining);
+      assert.equal(account.balance.toNumber(), LAMPORTS_PER_SOL);
+    });
+  });
+
+  // ─── Leaderboard ──────────────────────────────────────────────────
+  describe("leaderboard", () => {
+    const pattern = { prefix: "E", suffix: "", caseSensitive: true };
+
+    async function newMiner() {
+      const kp = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(kp);
+      return kp;
+    }
+
+    async function recordMatchFor(owner: Keypair) {
+      const [mining] = miningPDAOf(owner.publicKey);
+      const index = (await program.account.miningAccount.fetch(mining)).matchesFound;
+      return program.methods
+        .recordMatch(grind((a) => a.startsWith("E")).publicKey.toBase58(), pattern, new anchor.BN(0))
+        .accounts({
+          signer: owner.publicKey,
+          user: owner.publicKey,
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          miningAccount: mining,
+          matchRecord: PublicKey.findProgramAddressSync(
+            [Buffer.from("match"), owner.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 4)],
+            program.programId
+          )[0],
+          stats: statsPDA,
+          leaderboard: leaderboardPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    async function ranking() {
+      const board = await program.account.leaderboard.fetch(leaderboardPDA);
+      return board.entries.slice(0, board.count);
+    }
+
+    function rebuild(reset: boolean, accounts: PublicKey[], authority: Keypair | null = null) {
+      return program.methods
+        .rebuildLeaderboard(reset)
+        .accounts({
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+          leaderboard: leaderboardPDA,
+        })
+        .remainingAccounts(accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+        .signers(authority ? [authority] : [])
+        .rpc();
+    }
+
+    it("ranks miners by matches found, best first", async () => {
+      const entries = await ranking();
+      assert.isAbove(entries.length, 0);
+      const mine = entries.find((e) => e.user.equals(user.publicKey));
+      const account = await program.account.miningAccount.fetch(miningPDA);
+      assert.equal(mine.matchesFound, account.matchesFound);
+      for (let i = 1; i < entries.length; i++) {
+        assert.isAtLeast(entries[i - 1].matchesFound, entries[i].matchesFound);
+      }
+    });
+
+    it("lets a new miner displace the lowest incumbent once the board is full", async () => {
+      while ((await ranking()).length < 25) {
+        await recordMatchFor(await newMiner());
+      }
+      const full = await ranking();
+      const lowest = full[24];
+      assert.equal(lowest.matchesFound, 1);
+
+      // Tying the lowest entry is not enough
+      const challenger = await newMiner();
+      const sig = await recordMatchFor(challenger);
+      let entries = await ranking();
+      assert.equal(entries.length, 25);
+      assert.isFalse(entries.some((e) => e.user.equals(challenger.publicKey)));
+      assert.ok(entries[24].user.equals(lowest.user));
+
+      // Worst case for the update: a full board scanned to the end
+      const tx = await provider.connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      console.log("      record_match on a full leaderboard:", tx.meta.computeUnitsConsumed, "CU");
+      assert.isBelow(tx.meta.computeUnitsConsumed, 200_000);
+
+      await recordMatchFor(challenger);
+      entries = await ranking();
+      assert.equal(entries.length, 25);
+      const rank = entries.findIndex((e) => e.user.equals(challenger.publicKey));
+      assert.notEqual(rank, -1);
+      assert.equal(entries[rank].matchesFound, 2);
+      assert.isAtMost(entries[rank + 1].matchesFound, 2);
+      assert.isFalse(entries.some((e) => e.user.equals(lowest.user)));
+    });
+
+    it("rebuilds from mining accounts, clearing or merging", async () => {
+      const before = await ranking();
+      const top = before.slice(0, 3).map((e) => e.user);
+
+      await rebuild(true, [miningPDAOf(top[2])[0], miningPDAOf(top[0])[0]]);
+      let entries = await ranking();
+      assert.equal(entries.length, 2);
+      assert.ok(entries[0].user.equals(top[0]));
+      assert.ok(entries[1].user.equals(top[2]));
+
+      // Merging the rest back, duplicates included, restores the board
+      const users = before.map((e) => e.user);
+      await rebuild(false, users.slice(0, 13).map((u) => miningPDAOf(u)[0]));
+      await rebuild(false, users.slice(12).map((u) => miningPDAOf(u)[0]));
+      entries = await ranking();
+      assert.equal(entries.length, before.length);
+      for (let i = 0; i < entries.length; i++) {
+        assert.equal(entries[i].matchesFound, before[i].matchesFound);
+      }
+    });
+
+    it("rejects accounts that are not mining accounts", async () => {
+      try {
+        await rebuild(false, [configPDA]);
+        assert.fail("Should reject a non-mining account");
+      } catch (e: any) {
+        assert.include(e.message, "AccountDiscriminatorMismatch");
+      }
+      try {
+        await rebuild(false, [user.publicKey]);
+        assert.fail("Should reject an account the program does not own");
+      } catch (e: any) {
+        assert.include(e.message, "AccountOwnedByWrongProgram");
+      }
+    });
+
+    it("only lets the authority rebuild", async () => {
+      try {
+        await rebuild(true, [], user);
+        assert.fail("Should require the platform authority");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAuthority");
+      }
+    });
+  });
+
+  // ─── Emergency pause ──────────────────────────────────────────────
+  describe("pause", () => {
+    const miner = Keypair.generate();
+    const [minerMining] = miningPDAOf(miner.publicKey);
+
+    async function expectPaused(attempt: () => Promise<unknown>) {
+      try {
+        await attempt();
+        assert.fail("Should be paused");
+      } catch (e: any) {
+        assert.include(e.message, "ProgramPaused");
+      }
+    }
+
+    function recordMatch() {
+      return program.methods
+        .recordMatch(grind((a) => a.startsWith("F")).publicKey.toBase58(), { prefix: "F", suffix: "", caseSensitive: true }, new anchor.BN(0))
+        .accounts({
+          signer: miner.publicKey,
+          user: miner.publicKey,
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          miningAccount: minerMining,
+          matchRecord: PublicKey.findProgramAddressSync(
+            [Buffer.from("match"), miner.publicKey.toBuffer(), Buffer.alloc(4)],
+            program.programId
+          )[0],
+          stats: statsPDA,
+          leaderboard: leaderboardPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([miner])
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(miner.publicKey, 5 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(miner);
+      await deposit(miner, new anchor.BN(LAMPORTS_PER_SOL));
+    });
+
+    afterEach(async () => {
+      if ((await program.account.config.fetch(configPDA)).paused) await setPaused(false);
+    });
+
+    it("only lets the authority pause", async () => {
+      try {
+        await setPaused(true, user);
+        assert.fail("Should require the platform authority");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAuthority");
+      }
+      assert.isFalse((await program.account.config.fetch(configPDA)).paused);
+    });
+
+    it("halts deposits, charges and match recording", async () => {
+      const event = await onlyEvent(await setPaused(true), "programPausedEvent");
+      assert.ok(event.authority.equals(provider.wallet.publicKey));
+      assert.isTrue((await program.account.config.fetch(configPDA)).paused);
+
+      const before = await program.account.miningAccount.fetch(minerMining);
+      await expectPaused(() => deposit(miner, new anchor.BN(LAMPORTS_PER_SOL)));
+      await expectPaused(() => chargeUser(miner.publicKey));
+      await expectPaused(() => recordMatch());
+      await expectPaused(() => setPaused(true));
+
+      const after = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(after.balance.toString(), before.balance.toString());
+      assert.equal(after.matchesFound, before.matchesFound);
+    });
+
+    it("still lets users withdraw", async () => {
+      await setPaused(true);
+      const balanceBefore = await provider.connection.getBalance(miner.publicKey);
+      await withdraw(miner);
+      const account = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(account.balance.toString(), "0");
+      assert.isAbove(
+        (await provider.connection.getBalance(miner.publicKey)) - balanceBefore,
+        LAMPORTS_PER_SOL - 10_000
+      );
+    });
+
+    it("resumes everything on unpause", async () => {
+      await setPaused(true);
+      const event = await onlyEvent(await setPaused(false), "programUnpausedEvent");
+      assert.ok(event.authority.equals(provider.wallet.publicKey));
+      try {
+        await setPaused(false);
+        assert.fail("Should reject unpausing twice");
+      } catch (e: any) {
+        assert.include(e.message, "ProgramNotPaused");
+      }
+
+      await deposit(miner, new anchor.BN(LAMPORTS_PER_SOL));
+      await chargeUser(miner.publicKey);
+      await recordMatch();
+      const account = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(account.balance.toString(), (LAMPORTS_PER_SOL - COST.toNumber()).toString());
+      assert.equal(account.matchesFound, 1);
+    });
+  });
+
+  // ─── Refunds ──────────────────────────────────────────────────────
+  describe("refunds", () => {
+    // The suite's TREASURY has no keypair here, so point the config at one
+    // that can co-sign for the duration
+    const treasury = Keypair.generate();
+    const miner = Keypair.generate();
+    const [minerMining] = miningPDAOf(miner.publicKey);
+    const [minerVault] = vaultPDAOf(miner.publicKey);
+
+    function refund(amount: number, referenceId = 0, treasurySigner = treasury, authority: Keypair | null = null) {
+      return program.methods
+        .refundBatch(new anchor.BN(amount), new anchor.BN(referenceId))
+        .accounts({
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          treasury: treasurySigner.publicKey,
+          config: configPDA,
+          user: miner.publicKey,
+          miningAccount: minerMining,
+          vault: minerVault,
+          stats: statsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(authority ? [treasurySigner, authority] : [treasurySigner])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    async function expectRefundRejected(attempt: () => Promise<unknown>, error: string) {
+      const before = await program.account.miningAccount.fetch(minerMining);
+      await expectRejected(attempt, error);
+      const after = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(after.balance.toString(), before.balance.toString());
+      assert.equal(after.totalSpent.toString(), before.totalSpent.toString());
+    }
+
+    before(async () => {
+      for (const kp of [treasury, miner]) {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+      await initializeUser(miner);
+      await deposit(miner, new anchor.BN(LAMPORTS_PER_SOL));
+      await chargeUser(miner.publicKey);
+      await chargeUser(miner.publicKey);
+
+      await program.methods
+        .updateTreasury(treasury.publicKey)
+        .accounts({ authority: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .updateTreasury(TREASURY)
+        .accounts({ authority: provider.wallet.publicKey, config: configPDA })
+        .rpc();
+    });
+
+    it("moves a refund from the treasury back into the balance", async () => {
+      const before = await program.account.miningAccount.fetch(minerMining);
+      const statsBefore = await program.account.globalStats.fetch(statsPDA);
+      const treasuryBefore = await provider.connection.getBalance(treasury.publicKey);
+      const vaultBefore = await provider.connection.getBalance(minerVault);
+
+      const sig = await refund(400_000, 7);
+
+      const after = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(after.balance.sub(before.balance).toString(), "400000");
+      assert.equal(before.totalSpent.sub(after.totalSpent).toString(), "400000");
+      assert.equal((await provider.connection.getBalance(minerVault)) - vaultBefore, 400_000);
+      // The provider wallet pays the fee, so the treasury moves by the refund alone
+      assert.equal(treasuryBefore - (await provider.connection.getBalance(treasury.publicKey)), 400_000);
+      const statsAfter = await program.account.globalStats.fetch(statsPDA);
+      assert.equal(statsBefore.totalSpent.sub(statsAfter.totalSpent).toString(), "400000");
+
+      const event = await onlyEvent(sig, "batchRefunded");
+      assert.ok(event.user.equals(miner.publicKey));
+      assert.ok(event.authority.equals(provider.wallet.publicKey));
+      assert.equal(event.amount.toNumber(), 400_000);
+      assert.equal(event.referenceId.toNumber(), 7);
+      assert.equal(event.newBalance.toString(), after.balance.toString());
+      assert.equal(event.totalSpent.toString(), after.totalSpent.toString());
+    });
+
+    it("caps refunds at the user's lifetime spend", async () => {
+      const spent = (await program.account.miningAccount.fetch(minerMining)).totalSpent.toNumber();
+      await expectRefundRejected(() => refund(spent + 1), "RefundExceedsSpend");
+
+      await refund(spent);
+      assert.equal((await program.account.miningAccount.fetch(minerMining)).totalSpent.toNumber(), 0);
+      await expectRefundRejected(() => refund(1), "RefundExceedsSpend");
+    });
+
+    it("requires the configured treasury to co-sign", async () => {
+      const other = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(other.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await expectRefundRejected(() => refund(1, 0, other), "InvalidTreasury");
+    });
+
+    it("only lets the authority refund", async () => {
+      await expectRefundRejected(() => refund(1, 0, treasury, user), "UnauthorizedAuthority");
+    });
+  });
+
+  // ─── Batch receipts ───────────────────────────────────────────────
+  describe("batch receipts", () => {
+    const miner = Keypair.generate();
+    const [minerMining] = miningPDAOf(miner.publicKey);
+
+    function receiptPDAOf(owner: PublicKey, batchId: anchor.BN) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("batch"), owner.toBuffer(), batchId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    }
+
+    function charge(batchId: anchor.BN) {
+      return program.methods
+        .chargeForBatch(ATTEMPTS, false, batchId)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          user: miner.publicKey,
+          miningAccount: minerMining,
+          vault: vaultPDAOf(miner.publicKey)[0],
+          treasury: TREASURY,
+          referrer: null,
+          ...NO_SPL_ACCOUNTS,
+          receipt: receiptPDAOf(miner.publicKey, batchId),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    function closeReceipt(owner: Keypair, receipt: PublicKey, payer: PublicKey) {
+      return program.methods
+        .closeBatchReceipt()
+        .accounts({ user: owner.publicKey, receipt, payer })
+        .signers([owner])
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(miner.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(miner);
+      await deposit(miner, new anchor.BN(LAMPORTS_PER_SOL));
+    });
+
+    it("writes a receipt for each charge", async () => {
+      const batchId = nextBatchId();
+      const sig = await charge(batchId);
+
+      const receipt = await program.account.batchReceipt.fetch(receiptPDAOf(miner.publicKey, batchId));
+      const account = await program.account.miningAccount.fetch(minerMining);
+      assert.ok(receipt.user.equals(miner.publicKey));
+      assert.ok(receipt.payer.equals(provider.wallet.publicKey));
+      assert.equal(receipt.batchId.toString(), batchId.toString());
+      assert.equal(receipt.cost.toString(), account.totalSpent.toString());
+      assert.equal(receipt.attempts.toString(), ATTEMPTS.toString());
+      assert.isAbove(receipt.slot.toNumber(), 0);
+
+      const event = await onlyEvent(sig, "batchChargedEvent");
+      assert.equal(event.batchId.toString(), batchId.toString());
+    });
+
+    it("rejects a retried batch id without charging again", async () => {
+      const batchId = nextBatchId();
+      await charge(batchId);
+      const before = await program.account.miningAccount.fetch(minerMining);
+      try {
+        await charge(batchId);
+        assert.fail("Should reject a duplicate batch");
+      } catch (e: any) {
+        assert.include(e.message, "DuplicateBatch");
+      }
+      const after = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(after.balance.toString(), before.balance.toString());
+      assert.equal(after.totalSpent.toString(), before.totalSpent.toString());
+    });
+
+    it("keeps receipts for 30 days", async () => {
+      const batchId = nextBatchId();
+      await charge(batchId);
+      try {
+        await closeReceipt(miner, receiptPDAOf(miner.publicKey, batchId), provider.wallet.publicKey);
+        assert.fail("Should keep a fresh receipt");
+      } catch (e: any) {
+        assert.include(e.message, "ReceiptNotExpired");
+      }
+    });
+
+    it("refunds an expired receipt's rent to whoever paid it", async () => {
+      // Preloaded from tests/fixtures/expired-batch-receipt.json: batch 7,
+      // charged in 2020 with the rent paid by `payer`
+      const owner = Keypair.fromSeed(new Uint8Array(32).fill(43));
+      const payer = Keypair.fromSeed(new Uint8Array(32).fill(45)).publicKey;
+      const sig = await provider.connection.requestAirdrop(owner.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      const receipt = receiptPDAOf(owner.publicKey, new anchor.BN(7));
+      const rent = await provider.connection.getBalance(receipt);
+      assert.isAbove(rent, 0);
+
+      try {
+        await closeReceipt(owner, receipt, owner.publicKey);
+        assert.fail("Should not refund the user for rent they never paid");
+      } catch (e: any) {
+        assert.include(e.message, "Unauthorized");
+      }
+
+      const ownerBefore = await provider.connection.getBalance(owner.publicKey);
+      const payerBefore = await provider.connection.getBalance(payer);
+      await closeReceipt(owner, receipt, payer);
+      assert.isNull(await provider.connection.getAccountInfo(receipt));
+      assert.equal((await provider.connection.getBalance(payer)) - payerBefore, rent);
+      assert.isAtMost(await provider.connection.getBalance(owner.publicKey), ownerBefore);
+    });
+  });
+
+  // ─── Mining sessions ──────────────────────────────────────────────
+  describe("mining sessions", () => {
+    const miner = Keypair.generate();
+    const [minerMining] = miningPDAOf(miner.publicKey);
+
+    async function expectChargeRejected(error: string) {
+      const before = await program.account.miningAccount.fetch(minerMining);
+      await expectRejected(() => chargeUser(miner.publicKey), error);
+      const after = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(after.balance.toString(), before.balance.toString());
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(miner.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(miner, null, false);
+      await deposit(miner, new anchor.BN(LAMPORTS_PER_SOL));
+    });
+
+    it("refuses charges outside a session", async () => {
+      await expectChargeRejected("NotMining");
+    });
+
+    it("rejects an empty session", async () => {
+      for (const [duration, spend] of [
+        [0, COST.toNumber()],
+        [3_600, 0],
+      ]) {
+        try {
+          await startSession(miner, duration, spend);
+          assert.fail("Should reject an empty session");
+        } catch (e: any) {
+          assert.include(e.message, "InvalidAmount");
+        }
+      }
+    });
+
+    it("charges up to the session budget, then stops", async () => {
+      const now = await chainTime();
+      const started = await onlyEvent(await startSession(miner, 3_600, COST.muln(2).toNumber()), "miningStarted");
+      assert.ok(started.user.equals(miner.publicKey));
+      assert.equal(started.budget.toString(), COST.muln(2).toString());
+      assert.approximately(started.deadline.toNumber(), now + 3_600, 30);
+
+      let account = await program.account.miningAccount.fetch(minerMining);
+      assert.isTrue(account.isActive);
+      assert.equal(account.miningDeadline.toString(), started.deadline.toString());
+
+      try {
+        await startSession(miner);
+        assert.fail("Should not start over a running session");
+      } catch (e: any) {
+        assert.include(e.message, "AlreadyMining");
+      }
+
+      await chargeUser(miner.publicKey);
+      await chargeUser(miner.publicKey);
+      await expectChargeRejected("SessionBudgetExceeded");
+      account = await program.account.miningAccount.fetch(minerMining);
+      assert.equal(account.miningSpent.toString(), COST.muln(2).toString());
+    });
+
+    it("reports the consumed budget when stopped", async () => {
+      const stopped = await onlyEvent(await stopSession(miner), "miningStopped");
+      assert.ok(stopped.user.equals(miner.publicKey));
+      assert.equal(stopped.spent.toString(), COST.muln(2).toString());
+      assert.equal(stopped.budget.toString(), COST.muln(2).toString());
+
+      const account = await program.account.miningAccount.fetch(minerMining);
+      assert.isFalse(account.isActive);
+      assert.equal(account.miningSpent.toNumber(), 0);
+      await expectChargeRejected("NotMining");
+    });
+
+    it("refuses charges past the deadline until a new session starts", async () => {
+      await startSession(miner, 2, COST.muln(10).toNumber());
+      await chargeUser(miner.publicKey);
+      // Wait for the validator clock to pass the deadline
+      await new Promise((r) => setTimeout(r, 4_000));
+      await expectChargeRejected("MiningSessionExpired");
+
+      // An expired session is replaced without stopping it, and counts once
+      const statsBefore = await program.account.globalStats.fetch(statsPDA);
+      await startSession(miner, 3_600, COST.toNumber());
+      const statsAfter = await program.account.globalStats.fetch(statsPDA);
+      assert.equal(statsAfter.activeMiners.toString(), statsBefore.activeMiners.toString());
+      await chargeUser(miner.publicKey);
+    });
+  });
+
+  // ─── Per-call charge cap ──────────────────────────────────────────
+  describe("charge cap", () => {
+    const miner = Keypair.generate();
+    const [minerMining] = miningPDAOf(miner.publicKey);
+
+    function updateMaxCharge(maxChargePerCall: number, authority: Keypair | null = null) {
+      return program.methods
+        .updateMaxCharge(new anchor.BN(maxChargePerCall))
+        .accounts({ authority: authority ? authority.publicKey : provider.wallet.publicKey, config: configPDA })
+        .signers(authority ? [authority] : [])
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(miner.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(miner);
+      await deposit(miner, new anchor.BN(LAMPORTS_PER_SOL));
+    });
+
+    it("defaults to 1 GOR", async () => {
+      const config = await program.account.config.fetch(configPDA);
+      assert.equal(config.maxChargePerCall.toString(), LAMPORTS_PER_SOL.toString());
+    });
+
+    it("allows a charge at the cap and rejects one a lamport above", async () => {
+      // Priced at 1 lamport per attempt, so attempts == cost
+      const cap = COST.muln(2);
+      await updateMaxCharge(cap.toNumber());
+      try {
+        await chargeUser(miner.publicKey, cap);
+        const before = await program.account.miningAccount.fetch(minerMining);
+        try {
+          await chargeUser(miner.publicKey, cap.addn(1));
+          assert.fail("Should reject a charge above the cap");
+        } catch (e: any) {
+          assert.include(e.message, "ChargeTooLarge");
+        }
+        const after = await program.account.miningAccount.fetch(minerMining);
+        assert.equal(after.balance.toString(), before.balance.toString());
+      } finally {
+        await updateMaxCharge(LAMPORTS_PER_SOL);
+      }
+    });
+
+    it("keeps the cap between 1 lamport and the hard batch cap", async () => {
+      for (const cap of [0, LAMPORTS_PER_SOL + 1]) {
+        try {
+          await updateMaxCharge(cap);
+          assert.fail(`Should reject a cap of ${cap}`);
+        } catch (e: any) {
+          assert.include(e.message, "InvalidChargeCap");
+        }
+      }
+    });
+
+    it("only lets the authority change the cap", async () => {
+      try {
+        await updateMaxCharge(1, user);
+        assert.fail("Should require the platform authority");
+      } catch (e: any) {
+        assert.include(e.message, "UnauthorizedAuthority");
+      }
+    });
+  });
+
+  // ─── Subscriptions ────────────────────────────────────────────────
+  describe("subscriptions", () => {
+    const subscriber = Keypair.generate();
+    const lapsing = Keypair.generate();
+    const PRICE = COST.muln(5);
+    const DAY = 86_400;
+    // Short enough for tier 1 to lapse during the test
+    const SHORT = 2;
+
+    function updateSubscriptionTiers(
+      tiers: { price: anchor.BN; durationSecs: anchor.BN }[],
+      authority: Keypair | null = null
+    ) {
+      return program.methods
+        .updateSubscriptionTiers(tiers)
+        .accounts({
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+    }
+
+    function purchase(owner: Keypair, tier: number) {
+      return program.methods
+        .purchaseSubscription(tier)
+        .accounts({
+          user: owner.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          miningAccount: miningPDAOf(owner.publicKey)[0],
+          vault: vaultPDAOf(owner.publicKey)[0],
+          treasury: TREASURY,
+          stats: statsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    function charge(owner: Keypair) {
+      return program.methods
+        .chargeForBatch(ATTEMPTS, false, nextBatchId())
+        .accounts({
+          authority: provider.wallet.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          user: owner.publicKey,
+          miningAccount: miningPDAOf(owner.publicKey)[0],
+          vault: vaultPDAOf(owner.publicKey)[0],
+          treasury: TREASURY,
+          referrer: null,
+          ...NO_SPL_ACCOUNTS,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    before(async () => {
+      for (const owner of [subscriber, lapsing]) {
+        const sig = await provider.connection.requestAirdrop(owner.publicKey, 2 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+        await initializeUser(owner);
+        await deposit(owner, new anchor.BN(LAMPORTS_PER_SOL));
+      }
+      await updateSubscriptionTiers([
+        { price: PRICE, durationSecs: new anchor.BN(DAY) },
+        { price: PRICE, durationSecs: new anchor.BN(SHORT) },
+      ]);
+    });
+
+    after(async () => {
+      await updateSubscriptionTiers([]);
+    });
+
+    it("only takes tiers with a price and a duration from the authority", async () => {
+      const valid = { price: PRICE, durationSecs: new anchor.BN(DAY) };
+      await expectRejected(() => updateSubscriptionTiers([valid], user), "UnauthorizedAuthority");
+      await expectRejected(
+        () => updateSubscriptionTiers([{ ...valid, durationSecs: new anchor.BN(0) }]),
+        "InvalidSubscriptionTiers"
+      );
+      await expectRejected(
+        () => updateSubscriptionTiers([{ ...valid, price: new anchor.BN(0) }]),
+        "InvalidSubscriptionTiers"
+      );
+      await expectRejected(
+        () => updateSubscriptionTiers([valid, valid, valid, valid, valid]),
+        "TooManySubscriptionTiers"
+      );
+
+      const pricing = await program.account.pricingConfig.fetch(pricingPDA);
+      assert.equal(pricing.subscriptionTierCount, 2);
+      assert.equal(pricing.subscriptionTiers[1].durationSecs.toNumber(), SHORT);
+    });
+
+    it("rejects a tier that is not on sale", async () => {
+      await expectRejected(() => purchase(subscriber, 2), "InvalidSubscriptionTier");
+    });
+
+    it("takes the flat price from the balance to the treasury", async () => {
+      const [subscriberMining] = miningPDAOf(subscriber.publicKey);
+      const before = await program.account.miningAccount.fetch(subscriberMining);
+      const treasuryBefore = await provider.connection.getBalance(TREASURY);
+      const now = await chainTime();
+
+      const purchased = await onlyEvent(await purchase(subscriber, 0), "subscriptionPurchased");
+      assert.equal(purchased.tier, 0);
+      assert.equal(purchased.price.toString(), PRICE.toString());
+      assert.approximately(purchased.expiryTs.toNumber(), now + DAY, 30);
+
+      const after = await program.account.miningAccount.fetch(subscriberMining);
+      assert.equal(before.balance.sub(after.balance).toString(), PRICE.toString());
+      assert.equal(after.totalSpent.sub(before.totalSpent).toString(), PRICE.toString());
+      assert.equal(after.subscriptionExpiryTs.toString(), purchased.expiryTs.toString());
+      assert.equal(after.subscriptionTier, 0);
+      assert.equal((await provider.connection.getBalance(TREASURY)) - treasuryBefore, PRICE.toNumber());
+    });
+
+    it("charges nothing for batches while subscribed", async () => {
+      const [subscriberMining] = miningPDAOf(subscriber.publicKey);
+      const before = await program.account.miningAccount.fetch(subscriberMining);
+
+      const charged = await onlyEvent(await charge(subscriber), "batchChargedEvent");
+      assert.equal(charged.attempts.toString(), ATTEMPTS.toString());
+      assert.equal(charged.grossCost.toString(), COST.toString());
+      assert.equal(charged.cost.toNumber(), 0);
+      assert.equal(charged.paid.toNumber(), 0);
+
+      const after = await program.account.miningAccount.fetch(subscriberMining);
+      assert.equal(after.balance.toString(), before.balance.toString());
+      assert.equal(after.totalSpent.toString(), before.totalSpent.toString());
+    });
+
+    it("extends a running subscription instead of overlapping it", async () => {
+      const [subscriberMining] = miningPDAOf(subscriber.publicKey);
+      const before = await program.account.miningAccount.fetch(subscriberMining);
+
+      await purchase(subscriber, 1);
+
+      const after = await program.account.miningAccount.fetch(subscriberMining);
+      assert.equal(after.subscriptionExpiryTs.toNumber(), before.subscriptionExpiryTs.toNumber() + SHORT);
+      assert.equal(after.subscriptionTier, 1);
+    });
+
+    it("bills again once the subscription lapses, and restarts it from now", async () => {
+      const [lapsingMining] = miningPDAOf(lapsing.publicKey);
+      await purchase(lapsing, 1);
+      const lapsed = (await program.account.miningAccount.fetch(lapsingMining)).subscriptionExpiryTs.toNumber();
+      // Wait for the validator clock to pass the expiry
+      await new Promise((r) => setTimeout(r, 4_000));
+
+      const charged = await onlyEvent(await charge(lapsing), "batchChargedEvent");
+      assert.equal(charged.cost.toString(), COST.toString());
+
+      const now = await chainTime();
+      const purchased = await onlyEvent(await purchase(lapsing, 1), "subscriptionPurchased");
+      assert.isAtLeast(purchased.expiryTs.toNumber(), lapsed + SHORT);
+      assert.approximately(purchased.expiryTs.toNumber(), now + SHORT, 30);
+    });
+
+    it("rejects a purchase the balance cannot cover", async () => {
+      const broke = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(broke.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(broke, null, false);
+      await expectRejected(() => purchase(broke, 0), "InsufficientBalance");
+    });
+  });
+
+  // ─── Team accounts ────────────────────────────────────────────────
+  describe("teams", () => {
+    const founder = provider.wallet.publicKey;
+    const alice = Keypair.generate();
+    const bob = Keypair.generate();
+    const TEAM_ID = new anchor.BN(1);
+    const teamPDA = teamPDAOf(TEAM_ID);
+
+    function teamPDAOf(teamId: anchor.BN) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("team"), founder.toBuffer(), teamId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    }
+
+    function createTeam(teamId: anchor.BN, members: PublicKey[]) {
+      return program.methods
+        .createTeam(teamId, members)
+        .accounts({ founder, team: teamPDAOf(teamId), systemProgram: SystemProgram.programId })
+        .rpc();
+    }
+
+    function depositToTeam(member: Keypair, amount: anchor.BN) {
+      return program.methods
+        .depositToTeam(amount)
+        .accounts({
+          member: member.publicKey,
+          config: configPDA,
+          team: teamPDA,
+          stats: statsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+    }
+
+    function chargeTeam(batchId: anchor.BN, authority: Keypair | null = null) {
+      return program.methods
+        .chargeTeamBatch(ATTEMPTS, batchId)
+        .accounts({
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+          pricing: pricingPDA,
+          team: teamPDA,
+          treasury: TREASURY,
+          receipt: PublicKey.findProgramAddressSync(
+            [Buffer.from("batch"), teamPDA.toBuffer(), batchId.toArrayLike(Buffer, "le", 8)],
+            program.programId
+          )[0],
+          stats: statsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+    }
+
+    function withdrawTeam(member: Keypair) {
+      return program.methods
+        .withdrawTeam()
+        .accounts({ member: member.publicKey, team: teamPDA, stats: statsPDA })
+        .signers([member])
+        .rpc();
+    }
+
+    before(async () => {
+      for (const member of [alice, bob]) {
+        const sig = await provider.connection.requestAirdrop(member.publicKey, 2 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+    });
+
+    it("creates a team with the founder as first member", async () => {
+      await createTeam(TEAM_ID, [alice.publicKey]);
+
+      const team = await program.account.teamAccount.fetch(teamPDA);
+      assert.ok(team.founder.equals(founder));
+      assert.equal(team.memberCount, 2);
+      assert.ok(team.members[0].member.equals(founder));
+      assert.ok(team.members[1].member.equals(alice.publicKey));
+      assert.equal(team.balance.toNumber(), 0);
+    });
+
+    it("rejects duplicate members and more than eight", async () => {
+      await expectRejected(() => createTeam(new anchor.BN(2), [alice.publicKey, alice.publicKey]), "AlreadyTeamMember");
+      await expectRejected(() => createTeam(new anchor.BN(2), [founder]), "AlreadyTeamMember");
+      const crowd = Array.from({ length: 8 }, () => Keypair.generate().publicKey);
+      await expectRejected(() => createTeam(new anchor.BN(2), crowd), "TeamFull");
+    });
+
+    it("lets only the founder manage members", async () => {
+      await expectRejected(
+        () =>
+          program.methods
+            .addMember(bob.publicKey)
+            .accounts({ founder: alice.publicKey, team: teamPDA })
+            .signers([alice])
+            .rpc(),
+        "ConstraintSeeds"
+      );
+      await program.methods.addMember(bob.publicKey).accounts({ founder, team: teamPDA }).rpc();
+      await expectRejected(
+        () => program.methods.addMember(bob.publicKey).accounts({ founder, team: teamPDA }).rpc(),
+        "AlreadyTeamMember"
+      );
+      await expectRejected(
+        () =>
+          program.methods
+            .removeMember()
+            .accounts({ founder, member: founder, team: teamPDA, stats: statsPDA })
+            .rpc(),
+        "CannotRemoveFounder"
+      );
+      assert.equal((await program.account.teamAccount.fetch(teamPDA)).memberCount, 3);
+    });
+
+    it("pools deposits and mints each member share units", async () => {
+      const tenth = new anchor.BN(LAMPORTS_PER_SOL / 10);
+      await program.methods
+        .depositToTeam(tenth.muln(2))
+        .accounts({ member: founder, config: configPDA, team: teamPDA, stats: statsPDA, systemProgram: SystemProgram.programId })
+        .rpc();
+      await depositToTeam(alice, tenth);
+      await depositToTeam(bob, tenth);
+      await expectRejected(() => depositToTeam(user, tenth), "NotTeamMember");
+
+      const team = await program.account.teamAccount.fetch(teamPDA);
+      assert.equal(team.balance.toString(), tenth.muln(4).toString());
+      assert.deepEqual(
+        team.members.slice(0, 3).map((m: any) => m.units.toString()),
+        [tenth.muln(2), tenth, tenth].map((c) => c.toString())
+      );
+    });
+
+    it("charges batches to the team balance once per batch id", async () => {
+      const before = await program.account.teamAccount.fetch(teamPDA);
+      const treasuryBefore = await provider.connection.getBalance(TREASURY);
+      const batchId = nextBatchId();
+
+      await expectRejected(() => chargeTeam(batchId, user), "UnauthorizedAuthority");
+      await chargeTeam(batchId);
+      await expectRejected(() => chargeTeam(batchId), "DuplicateBatch");
+
+      const after = await program.account.teamAccount.fetch(teamPDA);
+      assert.equal(before.balance.sub(after.balance).toString(), COST.toString());
+      assert.equal(after.totalSpent.toString(), COST.toString());
+      assert.equal((await provider.connection.getBalance(TREASURY)) - treasuryBefore, COST.toNumber());
+    });
+
+    it("keeps team receipts for 30 days and lets only the founder close them", async () => {
+      const batchId = nextBatchId();
+      await chargeTeam(batchId);
+      const receipt = PublicKey.findProgramAddressSync(
+        [Buffer.from("batch"), teamPDA.toBuffer(), batchId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+      assert.ok((await program.account.batchReceipt.fetch(receipt)).payer.equals(provider.wallet.publicKey));
+
+      const close = (signer: Keypair | null) =>
+        program.methods
+          .closeTeamBatchReceipt()
+          .accounts({
+            founder: signer ? signer.publicKey : founder,
+            team: teamPDA,
+            receipt,
+            payer: provider.wallet.publicKey,
+          })
+          .signers(signer ? [signer] : [])
+          .rpc();
+      await expectRejected(() => close(alice), "ConstraintSeeds");
+      await expectRejected(() => close(null), "ReceiptNotExpired");
+    });
+
+    it("records matches against the team, noting the member", async () => {
+      const candidate = grind((a) => a.startsWith("T"));
+      const pattern = { prefix: "T", suffix: "", caseSensitive: true };
+      const recordPDA = PublicKey.findProgramAddressSync(
+        [Buffer.from("team_match"), teamPDA.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 4)],
+        program.programId
+      )[0];
+      const record = (member: Keypair) =>
+        program.methods
+          .recordTeamMatch(candidate.publicKey.toBase58(), pattern, new anchor.BN(0))
+          .accounts({
+            member: member.publicKey,
+            authority: provider.wallet.publicKey,
+            config: configPDA,
+            team: teamPDA,
+            matchRecord: recordPDA,
+            stats: statsPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([member])
+          .rpc();
+
+      await expectRejected(() => record(user), "NotTeamMember");
+      await record(alice);
+
+      const recorded = await program.account.teamMatchRecord.fetch(recordPDA);
+      assert.ok(recorded.team.equals(teamPDA));
+      assert.ok(recorded.member.equals(alice.publicKey));
+      assert.ok(recorded.address.equals(candidate.publicKey));
+      assert.equal((await program.account.teamAccount.fetch(teamPDA)).matchesFound, 1);
+    });
+
+    it("prices later deposits at the balance per unit", async () => {
+      // The charge above cost every member alike; a deposit made after it
+      // must not pick up part of that loss
+      const before = await program.account.teamAccount.fetch(teamPDA);
+      const totalUnits = before.members
+        .slice(0, before.memberCount)
+        .reduce((sum: anchor.BN, m: any) => sum.add(m.units), new anchor.BN(0));
+      const shareBefore = before.balance.mul(before.members[1].units).div(totalUnits);
+      const amount = new anchor.BN(LAMPORTS_PER_SOL / 10);
+
+      await depositToTeam(alice, amount);
+
+      const after = await program.account.teamAccount.fetch(teamPDA);
+      const minted = after.members[1].units.sub(before.members[1].units);
+      assert.equal(minted.toString(), amount.mul(totalUnits).div(before.balance).toString());
+      assert.isTrue(minted.lt(amount));
+      const shareAfter = after.balance.mul(after.members[1].units).div(totalUnits.add(minted));
+      assert.approximately(shareAfter.sub(shareBefore).toNumber(), amount.toNumber(), 1);
+    });
+
+    it("returns funds pro-rata to share units", async () => {
+      let team = await program.account.teamAccount.fetch(teamPDA);
+      const totalUnits = team.members
+        .slice(0, team.memberCount)
+        .reduce((sum: anchor.BN, m: any) => sum.add(m.units), new anchor.BN(0));
+      const shareOf = (index: number) => team.balance.mul(team.members[index].units).div(totalUnits);
+      const aliceShare = shareOf(1);
+      const bobShare = shareOf(2);
+
+      const aliceBefore = await provider.connection.getBalance(alice.publicKey);
+      await withdrawTeam(alice);
+      assert.equal((await provider.connection.getBalance(alice.publicKey)) - aliceBefore, aliceShare.toNumber());
+      await expectRejected(() => withdrawTeam(alice), "NoBalance");
+
+      // Removing a member pays out their share first; alice's redeemed
+      // units leave it unchanged
+      const bobBefore = await provider.connection.getBalance(bob.publicKey);
+      await program.methods
+        .removeMember()
+        .accounts({ founder, member: bob.publicKey, team: teamPDA, stats: statsPDA })
+        .rpc();
+      const bobPaid = (await provider.connection.getBalance(bob.publicKey)) - bobBefore;
+      assert.approximately(bobPaid, bobShare.toNumber(), 1);
+      await expectRejected(() => depositToTeam(bob, new anchor.BN(LAMPORTS_PER_SOL / 10)), "NotTeamMember");
+
+      // The last member holding units takes whatever is left, rounding dust
+      // included
+      team = await program.account.teamAccount.fetch(teamPDA);
+      assert.equal(team.memberCount, 2);
+      const rest = team.balance;
+      const teamLamports = await provider.connection.getBalance(teamPDA);
+      await program.methods
+        .withdrawTeam()
+        .accounts({ member: founder, team: teamPDA, stats: statsPDA })
+        .rpc();
+      team = await program.account.teamAccount.fetch(teamPDA);
+      assert.equal(team.balance.toNumber(), 0);
+      assert.equal(teamLamports - (await provider.connection.getBalance(teamPDA)), rest.toNumber());
+    });
+  });
+
+  // ─── Abandoned account sweep ──────────────────────────────────────
+  describe("abandoned account sweep", () => {
+    const YEAR = 365 * 24 * 60 * 60;
+    const active = Keypair.generate();
+
+    function updateSweepPeriod(sweepAfterSecs: number, authority: Keypair | null = null) {
+      return program.methods
+        .updateSweepPeriod(new anchor.BN(sweepAfterSecs))
+        .accounts({ authority: authority ? authority.publicKey : provider.wallet.publicKey, config: configPDA })
+        .signers(authority ? [authority] : [])
+        .rpc();
+    }
+
+    function sweep(owner: PublicKey, authority: Keypair | null = null) {
+      return program.methods
+        .sweepAbandoned()
+        .accounts({
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          config: configPDA,
+          user: owner,
+          miningAccount: miningPDAOf(owner)[0],
+          vault: vaultPDAOf(owner)[0],
+          treasury: TREASURY,
+          stats: statsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(active.publicKey, 2 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await initializeUser(active);
+    });
+
+    it("waits two years by default and can only be shortened to one", async () => {
+      assert.equal((await program.account.config.fetch(configPDA)).sweepAfterSecs.toNumber(), 2 * YEAR);
+
+      await expectRejected(() => updateSweepPeriod(YEAR - 1), "SweepPeriodTooShort");
+      await expectRejected(() => updateSweepPeriod(YEAR, user), "UnauthorizedAuthority");
+      await updateSweepPeriod(YEAR);
+      assert.equal((await program.account.config.fetch(configPDA)).sweepAfterSecs.toNumber(), YEAR);
+      await updateSweepPeriod(2 * YEAR);
+    });
+
+    it("stamps activity on the user's own instructions", async () => {
+      const [activeMining] = miningPDAOf(active.publicKey);
+      const opened = (await program.account.miningAccount.fetch(activeMining)).lastActivityTs.toNumber();
+      assert.approximately(opened, await chainTime(), 30);
+
+      // Wait for the validator clock to move on
+      await new Promise((r) => setTimeout(r, 2_000));
+      await deposit(active, new anchor.BN(LAMPORTS_PER_SOL / 10));
+      const deposited = (await program.account.miningAccount.fetch(activeMining)).lastActivityTs.toNumber();
+      assert.isAbove(deposited, opened);
+    });
+
+    it("leaves activity alone on authority charges", async () => {
+      const [activeMining] = miningPDAOf(active.publicKey);
+      const before = (await program.account.miningAccount.fetch(activeMining)).lastActivityTs.toNumber();
+
+      await new Promise((r) => setTimeout(r, 2_000));
+      await chargeUser(active.publicKey);
+      const after = await program.account.miningAccount.fetch(activeMining);
+      assert.equal(after.lastActivityTs.toNumber(), before);
+    });
+
+    it("refuses to sweep a recently active account", async () => {
+      await updateSweepPeriod(YEAR);
+      try {
+        await expectRejected(() => sweep(active.publicKey), "AccountNotAbandoned");
+      } finally {
+        await updateSweepPeriod(2 * YEAR);
+      }
+      assert.isNotNull(await provider.connection.getAccountInfo(miningPDAOf(active.publicKey)[0]));
+    });
+
+    it("sweeps an abandoned account's balance into the treasury", async () => {
+      // Preloaded from tests/fixtures/abandoned-mining-account.json: a
+      // 0.005 GOR balance, last used at 1_600_000_000 (September 2020)
+      const owner = Keypair.fromSeed(new Uint8Array(32).fill(44)).publicKey;
+      const [abandonedMining] = miningPDAOf(owner);
+      const [abandonedVault] = vaultPDAOf(owner);
+      const account = await program.account.miningAccount.fetch(abandonedMining);
+      assert.equal(account.lastActivityTs.toNumber(), 1_600_000_000);
+
+      await expectRejected(() => sweep(owner, user), "UnauthorizedAuthority");
+
+      const rent =
+        (await provider.connection.getBalance(abandonedMining)) +
+        (await provider.connection.getBalance(abandonedVault)) -
+        account.balance.toNumber();
+      const treasuryBefore = await provider.connection.getBalance(TREASURY);
+      const ownerBefore = await provider.connection.getBalance(owner);
+
+      const signature = await sweep(owner);
+
+      assert.equal((await provider.connection.getBalance(TREASURY)) - treasuryBefore, account.balance.toNumber());
+      assert.equal((await provider.connection.getBalance(owner)) - ownerBefore, rent);
+      assert.isNull(await provider.connection.getAccountInfo(abandonedMining));
+      assert.equal(await provider.connection.getBalance(abandonedVault), 0);
+
+      const event = await onlyEvent(signature, "accountSwept");
+      assert.ok(event.user.equals(owner));
+      assert.equal(event.swept.toString(), account.balance.toString());
+      assert.equal(event.lastActivityTs.toNumber(), 1_600_000_000);
+      assert.isAtLeast(event.inactiveSecs.toNumber(), 2 * YEAR);
+    });
+  });
+});